@@ -0,0 +1,1058 @@
+//! Audit log storage and queries.
+
+use crate::db::{parse_datetime, DbPool};
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub timestamp: DateTime<Utc>,
+    pub server_name: String,
+    pub tool_name: String,
+    pub tool_args: Option<serde_json::Value>,
+    pub result: Option<serde_json::Value>,
+    pub blocked: bool,
+    pub block_reason: Option<String>,
+    pub duration_ms: u64,
+    /// Whether sensitive data was redacted from the tool's response
+    #[serde(default)]
+    pub redacted: bool,
+    /// Arguments as the client sent them, when rules rewrote them before
+    /// forwarding; `tool_args` then holds what the server received
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_args: Option<serde_json::Value>,
+    /// Whether the request was rejected for exceeding the proxy's size limit
+    #[serde(default)]
+    pub oversized: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolStats {
+    pub tool_name: String,
+    pub call_count: i64,
+    pub blocked_count: i64,
+    pub last_seen: DateTime<Utc>,
+    pub servers: Vec<String>,
+}
+
+/// Number of calls to one tool on one server, split by whether they were blocked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallCount {
+    pub server_name: String,
+    pub tool_name: String,
+    pub blocked: bool,
+    pub count: u64,
+}
+
+/// Totals over the audit log, from [`AuditLog::stats`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuditStats {
+    pub total_calls: u64,
+    pub blocked_calls: u64,
+    pub unique_tools: usize,
+    pub unique_servers: usize,
+    /// Calls to the [`STATS_TOP_TOOLS`] most called tools
+    pub calls_per_tool: HashMap<String, u64>,
+    /// Blocked calls to every tool with any
+    pub blocked_per_tool: HashMap<String, u64>,
+    pub avg_duration_ms: f64,
+    pub p99_duration_ms: u64,
+}
+
+/// Number of tools counted in [`AuditStats::calls_per_tool`].
+pub const STATS_TOP_TOOLS: usize = 10;
+
+pub struct AuditLog {
+    pool: DbPool,
+}
+
+/// Columns read by [`entry_from_row`], in order.
+const ENTRY_COLUMNS: &str =
+    "id, timestamp, server_name, tool_name, tool_args, result, blocked, block_reason, duration_ms, redacted, original_args, oversized";
+
+fn entry_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<AuditEntry> {
+    Ok(AuditEntry {
+        id: row.get(0)?,
+        timestamp: parse_datetime(row.get::<_, String>(1)?),
+        server_name: row.get(2)?,
+        tool_name: row.get(3)?,
+        tool_args: row
+            .get::<_, Option<String>>(4)?
+            .and_then(|s| serde_json::from_str(&s).ok()),
+        result: row
+            .get::<_, Option<String>>(5)?
+            .and_then(|s| serde_json::from_str(&s).ok()),
+        blocked: row.get::<_, i32>(6)? != 0,
+        block_reason: row.get(7)?,
+        duration_ms: row.get::<_, i64>(8)? as u64,
+        redacted: row.get::<_, i32>(9)? != 0,
+        original_args: row
+            .get::<_, Option<String>>(10)?
+            .and_then(|s| serde_json::from_str(&s).ok()),
+        oversized: row.get::<_, i32>(11)? != 0,
+    })
+}
+
+/// `text` with a `'` in front if it starts like a spreadsheet formula, so
+/// that opening an export can't run one planted in a tool argument.
+fn csv_text(text: String) -> String {
+    if text.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", text)
+    } else {
+        text
+    }
+}
+
+/// `AND` conditions, and their parameters, for the `search` filters.
+fn filter_clause(
+    server: Option<&str>,
+    tool: Option<&str>,
+    blocked_only: bool,
+) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+    let mut clause = String::new();
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(s) = server {
+        clause.push_str(" AND server_name = ?");
+        params.push(Box::new(s.to_string()));
+    }
+    if let Some(t) = tool {
+        clause.push_str(" AND tool_name = ?");
+        params.push(Box::new(t.to_string()));
+    }
+    if blocked_only {
+        clause.push_str(" AND blocked = 1");
+    }
+
+    (clause, params)
+}
+
+impl AuditLog {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub fn insert(&self, entry: &AuditEntry) -> Result<i64> {
+        let conn = self.pool.get()?;
+
+        conn.execute(
+            r#"
+            INSERT INTO audit_log (timestamp, server_name, tool_name, tool_args, result, blocked, block_reason, duration_ms, redacted, original_args, oversized)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+            "#,
+            rusqlite::params![
+                entry.timestamp.to_rfc3339(),
+                entry.server_name,
+                entry.tool_name,
+                entry.tool_args.as_ref().map(|v| v.to_string()),
+                entry.result.as_ref().map(|v| v.to_string()),
+                entry.blocked as i32,
+                entry.block_reason,
+                entry.duration_ms as i64,
+                entry.redacted as i32,
+                entry.original_args.as_ref().map(|v| v.to_string()),
+                entry.oversized as i32,
+            ],
+        )?;
+
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub fn get(&self, id: i64) -> Result<AuditEntry> {
+        let conn = self.pool.get()?;
+
+        let entry = conn
+            .query_row(
+                &format!("SELECT {} FROM audit_log WHERE id = ?1", ENTRY_COLUMNS),
+                [id],
+                entry_from_row,
+            )
+            .optional()?;
+
+        entry.ok_or_else(|| Error::Other(format!("Audit entry {} not found", id)))
+    }
+
+    /// Offset pagination, kept for existing callers. Prefer `list_after`,
+    /// which doesn't scan past every skipped row.
+    pub fn list(&self, limit: usize, offset: usize) -> Result<Vec<AuditEntry>> {
+        let conn = self.pool.get()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM audit_log ORDER BY timestamp DESC LIMIT ?1 OFFSET ?2",
+            ENTRY_COLUMNS
+        ))?;
+
+        let entries = stmt
+            .query_map([limit as i64, offset as i64], entry_from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Keyset pagination: returns up to `limit` entries older than `cursor_id`
+    /// (newest first when `None`), plus the cursor for the next page if any.
+    ///
+    /// Unlike `list`, the cost doesn't grow with the page number and pages
+    /// stay stable while new entries are being inserted.
+    pub fn list_after(
+        &self,
+        cursor_id: Option<i64>,
+        limit: usize,
+    ) -> Result<(Vec<AuditEntry>, Option<i64>)> {
+        let conn = self.pool.get()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM audit_log WHERE id < ?1 ORDER BY id DESC LIMIT ?2",
+            ENTRY_COLUMNS
+        ))?;
+
+        // Fetch one extra row to know whether another page follows
+        let mut entries: Vec<AuditEntry> = stmt
+            .query_map(
+                [cursor_id.unwrap_or(i64::MAX), limit as i64 + 1],
+                entry_from_row,
+            )?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let next_cursor = if entries.len() > limit {
+            entries.truncate(limit);
+            entries.last().map(|e| e.id)
+        } else {
+            None
+        };
+
+        Ok((entries, next_cursor))
+    }
+
+    /// Keyset pagination like [`list_after`](Self::list_after), over the
+    /// entries matching the filters of [`search`](Self::search).
+    pub fn search_after(
+        &self,
+        cursor_id: Option<i64>,
+        server: Option<&str>,
+        tool: Option<&str>,
+        blocked_only: bool,
+        limit: usize,
+    ) -> Result<(Vec<AuditEntry>, Option<i64>)> {
+        let conn = self.pool.get()?;
+
+        let (filters, mut params) = filter_clause(server, tool, blocked_only);
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM audit_log WHERE id < ?{} ORDER BY id DESC LIMIT ?",
+            ENTRY_COLUMNS, filters
+        ))?;
+        params.insert(0, Box::new(cursor_id.unwrap_or(i64::MAX)));
+        // Fetch one extra row to know whether another page follows
+        params.push(Box::new(limit as i64 + 1));
+        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+
+        let mut entries: Vec<AuditEntry> = stmt
+            .query_map(param_refs.as_slice(), entry_from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let next_cursor = if entries.len() > limit {
+            entries.truncate(limit);
+            entries.last().map(|e| e.id)
+        } else {
+            None
+        };
+
+        Ok((entries, next_cursor))
+    }
+
+    pub fn search(
+        &self,
+        server: Option<&str>,
+        tool: Option<&str>,
+        blocked_only: bool,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>> {
+        self.query(None, server, tool, blocked_only, Some(limit))
+    }
+
+    /// Like [`search`](Self::search), limited to entries logged between
+    /// `start` and `end` inclusive.
+    pub fn search_with_time_range(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        server: Option<&str>,
+        tool: Option<&str>,
+        blocked_only: bool,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>> {
+        self.query(Some((start, end)), server, tool, blocked_only, Some(limit))
+    }
+
+    /// Write matching entries to `writer` as CSV, oldest first, with a header
+    /// row. `tool_args`, `result` and `original_args` are JSON strings.
+    /// Entries are written as they're read rather than loaded first, and
+    /// text cells that a spreadsheet would run as a formula are prefixed
+    /// with `'`. Returns the number of entries written.
+    pub fn export_csv(
+        &self,
+        writer: impl Write,
+        server: Option<&str>,
+        blocked_only: bool,
+    ) -> Result<usize> {
+        let conn = self.pool.get()?;
+        let (filters, params) = filter_clause(server, None, blocked_only);
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM audit_log WHERE 1=1{} ORDER BY timestamp ASC, id ASC",
+            ENTRY_COLUMNS, filters
+        ))?;
+        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let entries = stmt.query_map(param_refs.as_slice(), entry_from_row)?;
+
+        let csv_error = |e: csv::Error| Error::Other(format!("CSV export failed: {}", e));
+        let json = |value: &Option<serde_json::Value>| {
+            value
+                .as_ref()
+                .map(|v| csv_text(v.to_string()))
+                .unwrap_or_default()
+        };
+
+        let mut csv = csv::Writer::from_writer(writer);
+        csv.write_record([
+            "id",
+            "timestamp",
+            "server_name",
+            "tool_name",
+            "tool_args",
+            "result",
+            "blocked",
+            "block_reason",
+            "duration_ms",
+            "redacted",
+            "original_args",
+            "oversized",
+        ])
+        .map_err(csv_error)?;
+        let mut count = 0;
+        for entry in entries {
+            let entry = entry?;
+            csv.write_record([
+                entry.id.to_string(),
+                entry.timestamp.to_rfc3339(),
+                csv_text(entry.server_name),
+                csv_text(entry.tool_name),
+                json(&entry.tool_args),
+                json(&entry.result),
+                entry.blocked.to_string(),
+                csv_text(entry.block_reason.unwrap_or_default()),
+                entry.duration_ms.to_string(),
+                entry.redacted.to_string(),
+                json(&entry.original_args),
+                entry.oversized.to_string(),
+            ])
+            .map_err(csv_error)?;
+            count += 1;
+        }
+        csv.flush()?;
+
+        Ok(count)
+    }
+
+    fn query(
+        &self,
+        range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+        server: Option<&str>,
+        tool: Option<&str>,
+        blocked_only: bool,
+        limit: Option<usize>,
+    ) -> Result<Vec<AuditEntry>> {
+        let conn = self.pool.get()?;
+
+        let mut query = format!("SELECT {} FROM audit_log WHERE 1=1", ENTRY_COLUMNS);
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some((start, end)) = range {
+            // Timestamps are stored as UTC RFC 3339, so they compare as strings
+            query.push_str(" AND timestamp >= ? AND timestamp <= ?");
+            params.push(Box::new(start.to_rfc3339()));
+            params.push(Box::new(end.to_rfc3339()));
+        }
+        let (filters, filter_params) = filter_clause(server, tool, blocked_only);
+        query.push_str(&filters);
+        params.extend(filter_params);
+
+        query.push_str(" ORDER BY timestamp DESC, id DESC");
+        if let Some(limit) = limit {
+            query.push_str(" LIMIT ?");
+            params.push(Box::new(limit as i64));
+        }
+
+        let mut stmt = conn.prepare(&query)?;
+        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+
+        let entries = stmt
+            .query_map(param_refs.as_slice(), entry_from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(entries)
+    }
+
+    pub fn tool_stats(&self, limit: usize) -> Result<Vec<ToolStats>> {
+        let conn = self.pool.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT tool_name, COUNT(*) AS call_count, SUM(blocked), MAX(timestamp), GROUP_CONCAT(DISTINCT server_name)
+            FROM audit_log
+            GROUP BY tool_name
+            ORDER BY call_count DESC
+            LIMIT ?1
+            "#,
+        )?;
+
+        let stats = stmt
+            .query_map([limit as i64], |row| {
+                let servers: Option<String> = row.get(4)?;
+                Ok(ToolStats {
+                    tool_name: row.get(0)?,
+                    call_count: row.get(1)?,
+                    blocked_count: row.get::<_, Option<i64>>(2)?.unwrap_or(0),
+                    last_seen: parse_datetime(row.get::<_, String>(3)?),
+                    servers: servers
+                        .map(|s| s.split(',').map(String::from).collect())
+                        .unwrap_or_default(),
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(stats)
+    }
+
+    /// Calls logged after entry `after_id`, grouped by server, tool, and
+    /// whether they were blocked, along with the highest ID counted. Pass
+    /// that ID back in to count only what's been logged since; IDs are never
+    /// reused, so purged entries don't affect later counts.
+    pub fn call_counts_since(&self, after_id: i64) -> Result<(Vec<CallCount>, i64)> {
+        let conn = self.pool.get()?;
+        // Fixed first, so entries logged during the count are left for next time
+        let last_id: i64 = conn.query_row(
+            "SELECT COALESCE(MAX(id), ?1) FROM audit_log",
+            [after_id],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT server_name, tool_name, blocked, COUNT(*)
+            FROM audit_log
+            WHERE id > ?1 AND id <= ?2
+            GROUP BY server_name, tool_name, blocked
+            ORDER BY server_name, tool_name, blocked
+            "#,
+        )?;
+
+        let counts = stmt
+            .query_map([after_id, last_id], |row| {
+                Ok(CallCount {
+                    server_name: row.get(0)?,
+                    tool_name: row.get(1)?,
+                    blocked: row.get(2)?,
+                    count: row.get::<_, i64>(3)? as u64,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok((counts, last_id))
+    }
+
+    /// Totals over the entries logged at or after `since`, or all of them.
+    pub fn stats(&self, since: Option<DateTime<Utc>>) -> Result<AuditStats> {
+        let conn = self.pool.get()?;
+        // RFC 3339 timestamps in UTC sort as text
+        let since = since.unwrap_or(DateTime::UNIX_EPOCH).to_rfc3339();
+
+        let (total_calls, blocked_calls, unique_tools, unique_servers, avg_duration_ms) = conn
+            .query_row(
+                r#"
+                SELECT COUNT(*), COALESCE(SUM(blocked), 0), COUNT(DISTINCT tool_name),
+                       COUNT(DISTINCT server_name), COALESCE(AVG(duration_ms), 0.0)
+                FROM audit_log
+                WHERE timestamp >= ?1
+                "#,
+                [&since],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)? as u64,
+                        row.get::<_, i64>(1)? as u64,
+                        row.get::<_, i64>(2)? as usize,
+                        row.get::<_, i64>(3)? as usize,
+                        row.get::<_, f64>(4)?,
+                    ))
+                },
+            )?;
+
+        let per_tool = |sql: &str, limit: i64| -> Result<HashMap<String, u64>> {
+            let mut stmt = conn.prepare(sql)?;
+            let counts = stmt
+                .query_map(rusqlite::params![since, limit], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64))
+                })?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(counts)
+        };
+        let calls_per_tool = per_tool(
+            r#"
+            SELECT tool_name, COUNT(*) AS calls
+            FROM audit_log
+            WHERE timestamp >= ?1
+            GROUP BY tool_name
+            ORDER BY calls DESC, tool_name
+            LIMIT ?2
+            "#,
+            STATS_TOP_TOOLS as i64,
+        )?;
+        let blocked_per_tool = per_tool(
+            r#"
+            SELECT tool_name, COUNT(*)
+            FROM audit_log
+            WHERE timestamp >= ?1 AND blocked = 1
+            GROUP BY tool_name
+            LIMIT ?2
+            "#,
+            -1,
+        )?;
+
+        // Nearest-rank percentile: the smallest duration that at least 99%
+        // of calls took no longer than
+        let p99_duration_ms = if total_calls == 0 {
+            0
+        } else {
+            let rank = (total_calls * 99).div_ceil(100);
+            conn.query_row(
+                r#"
+                SELECT duration_ms
+                FROM audit_log
+                WHERE timestamp >= ?1
+                ORDER BY duration_ms
+                LIMIT 1 OFFSET ?2
+                "#,
+                rusqlite::params![since, rank as i64 - 1],
+                |row| row.get::<_, i64>(0),
+            )? as u64
+        };
+
+        Ok(AuditStats {
+            total_calls,
+            blocked_calls,
+            unique_tools,
+            unique_servers,
+            calls_per_tool,
+            blocked_per_tool,
+            avg_duration_ms,
+            p99_duration_ms,
+        })
+    }
+
+    /// Flag an entry as redacted and store the filtered result that was
+    /// forwarded to the client.
+    pub fn mark_redacted(&self, id: i64, result: Option<&serde_json::Value>) -> Result<()> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "UPDATE audit_log SET redacted = 1, result = ?1 WHERE id = ?2",
+            rusqlite::params![result.map(|v| v.to_string()), id],
+        )?;
+        Ok(())
+    }
+
+    /// Delete entries logged before `cutoff`, returning how many were
+    /// deleted. The database is vacuumed afterwards if any were.
+    pub fn purge_before(&self, cutoff: DateTime<Utc>) -> Result<u64> {
+        let conn = self.pool.get()?;
+        // RFC 3339 timestamps in UTC sort as text
+        let deleted = conn.execute(
+            "DELETE FROM audit_log WHERE timestamp < ?1",
+            [cutoff.to_rfc3339()],
+        )?;
+        if deleted > 0 {
+            super::vacuum(&conn)?;
+        }
+        Ok(deleted as u64)
+    }
+
+    /// Store the server's response for an entry.
+    pub fn set_result(&self, id: i64, result: &serde_json::Value) -> Result<()> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "UPDATE audit_log SET result = ?1 WHERE id = ?2",
+            rusqlite::params![result.to_string(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Store the arguments the client originally sent for an entry whose
+    /// arguments were rewritten before forwarding.
+    pub fn set_original_args(&self, id: i64, original: &serde_json::Value) -> Result<()> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "UPDATE audit_log SET original_args = ?1 WHERE id = ?2",
+            rusqlite::params![original.to_string(), id],
+        )?;
+        Ok(())
+    }
+
+    pub fn count(&self) -> Result<i64> {
+        let conn = self.pool.get()?;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM audit_log", [], |row| row.get(0))?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::create_pool;
+    use tempfile::tempdir;
+
+    fn test_pool() -> (tempfile::TempDir, DbPool) {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let pool = create_pool(&db_path).unwrap();
+        (dir, pool)
+    }
+
+    #[test]
+    fn insert_and_list_entries() {
+        let (_dir, pool) = test_pool();
+        let log = AuditLog::new(pool);
+
+        let entry = AuditEntry {
+            id: 0,
+            timestamp: Utc::now(),
+            server_name: "test-server".to_string(),
+            tool_name: "read_file".to_string(),
+            tool_args: Some(serde_json::json!({"path": "/tmp/test"})),
+            result: Some(serde_json::json!({"content": "hello"})),
+            blocked: false,
+            block_reason: None,
+            duration_ms: 100,
+            redacted: false,
+            original_args: None,
+            oversized: false,
+        };
+
+        let id = log.insert(&entry).unwrap();
+        assert!(id > 0);
+
+        let entries = log.list(10, 0).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].server_name, "test-server");
+
+        let fetched = log.get(id).unwrap();
+        assert_eq!(fetched.tool_args, entry.tool_args);
+        assert!(log.get(id + 1).is_err());
+    }
+
+    #[test]
+    fn search_after_pages_through_matching_entries() {
+        let (_dir, pool) = test_pool();
+        let log = AuditLog::new(pool);
+
+        for i in 0..30 {
+            log.insert(&AuditEntry {
+                id: 0,
+                timestamp: Utc::now(),
+                server_name: "test-server".to_string(),
+                tool_name: format!("tool_{}", i),
+                tool_args: None,
+                result: None,
+                blocked: i % 3 == 0,
+                block_reason: None,
+                duration_ms: 0,
+                redacted: false,
+                original_args: None,
+                oversized: false,
+            })
+            .unwrap();
+        }
+
+        let (first, next) = log
+            .search_after(None, Some("test-server"), None, true, 6)
+            .unwrap();
+        assert_eq!(first.len(), 6);
+        assert!(first.iter().all(|e| e.blocked));
+        let (second, next) = log
+            .search_after(next, Some("test-server"), None, true, 6)
+            .unwrap();
+        assert_eq!(second.len(), 4);
+        assert!(next.is_none());
+        assert!(second.iter().all(|e| e.blocked));
+        assert!(first.last().unwrap().id > second[0].id);
+    }
+
+    #[test]
+    fn list_after_pages_through_every_entry() {
+        let (_dir, pool) = test_pool();
+        let log = AuditLog::new(pool);
+
+        for i in 0..100 {
+            log.insert(&AuditEntry {
+                id: 0,
+                timestamp: Utc::now(),
+                server_name: "test-server".to_string(),
+                tool_name: format!("tool_{}", i),
+                tool_args: None,
+                result: None,
+                blocked: false,
+                block_reason: None,
+                duration_ms: 0,
+                redacted: false,
+                original_args: None,
+                oversized: false,
+            })
+            .unwrap();
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        let mut cursor = None;
+        let mut pages = 0;
+        loop {
+            let (entries, next) = log.list_after(cursor, 10).unwrap();
+            assert_eq!(entries.len(), 10);
+            assert!(entries.windows(2).all(|w| w[0].id > w[1].id));
+            seen.extend(entries.iter().map(|e| e.id));
+            pages += 1;
+            match next {
+                Some(id) => cursor = Some(id),
+                None => break,
+            }
+        }
+
+        assert_eq!(pages, 10);
+        assert_eq!(seen.len(), 100);
+    }
+
+    #[test]
+    fn search_by_server() {
+        let (_dir, pool) = test_pool();
+        let log = AuditLog::new(pool);
+
+        for server in ["server-a", "server-b"] {
+            let entry = AuditEntry {
+                id: 0,
+                timestamp: Utc::now(),
+                server_name: server.to_string(),
+                tool_name: "test".to_string(),
+                tool_args: None,
+                result: None,
+                blocked: false,
+                block_reason: None,
+                duration_ms: 50,
+                redacted: false,
+                original_args: None,
+                oversized: false,
+            };
+            log.insert(&entry).unwrap();
+        }
+
+        let results = log.search(Some("server-a"), None, false, 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].server_name, "server-a");
+    }
+
+    #[test]
+    fn search_with_time_range_filters_by_timestamp() {
+        let (_dir, pool) = test_pool();
+        let log = AuditLog::new(pool);
+        let now = Utc::now();
+
+        for hours_ago in [1, 5, 30] {
+            let entry = AuditEntry {
+                id: 0,
+                timestamp: now - chrono::Duration::hours(hours_ago),
+                server_name: "server-a".to_string(),
+                tool_name: format!("tool-{}", hours_ago),
+                tool_args: None,
+                result: None,
+                blocked: false,
+                block_reason: None,
+                duration_ms: 10,
+                redacted: false,
+                original_args: None,
+                oversized: false,
+            };
+            log.insert(&entry).unwrap();
+        }
+
+        let results = log
+            .search_with_time_range(
+                now - chrono::Duration::hours(6),
+                now - chrono::Duration::hours(2),
+                None,
+                None,
+                false,
+                10,
+            )
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].tool_name, "tool-5");
+
+        let results = log
+            .search_with_time_range(
+                now - chrono::Duration::days(2),
+                now,
+                Some("server-a"),
+                None,
+                false,
+                10,
+            )
+            .unwrap();
+        assert_eq!(results.len(), 3);
+    }
+
+    #[test]
+    fn tool_stats_groups_by_tool() {
+        let (_dir, pool) = test_pool();
+        let log = AuditLog::new(pool);
+
+        for (server, tool, blocked) in [
+            ("server-a", "read_file", false),
+            ("server-b", "read_file", true),
+            ("server-a", "read_file", false),
+            ("server-a", "write_file", false),
+        ] {
+            let entry = AuditEntry {
+                id: 0,
+                timestamp: Utc::now(),
+                server_name: server.to_string(),
+                tool_name: tool.to_string(),
+                tool_args: None,
+                result: None,
+                blocked,
+                block_reason: None,
+                duration_ms: 10,
+                redacted: false,
+                original_args: None,
+                oversized: false,
+            };
+            log.insert(&entry).unwrap();
+        }
+
+        let stats = log.tool_stats(50).unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].tool_name, "read_file");
+        assert_eq!(stats[0].call_count, 3);
+        assert_eq!(stats[0].blocked_count, 1);
+        let mut servers = stats[0].servers.clone();
+        servers.sort();
+        assert_eq!(servers, vec!["server-a", "server-b"]);
+
+        let (counts, last_id) = log.call_counts_since(0).unwrap();
+        assert_eq!(counts.len(), 3);
+        assert_eq!(
+            counts[0],
+            CallCount {
+                server_name: "server-a".to_string(),
+                tool_name: "read_file".to_string(),
+                blocked: false,
+                count: 2,
+            }
+        );
+        assert!(counts[2].blocked);
+        assert_eq!(log.call_counts_since(last_id).unwrap(), (vec![], last_id));
+    }
+
+    #[test]
+    fn export_csv_writes_header_and_rows() {
+        let (_dir, pool) = test_pool();
+        let log = AuditLog::new(pool);
+
+        for i in 0..5 {
+            log.insert(&AuditEntry {
+                id: 0,
+                timestamp: Utc::now(),
+                server_name: if i < 3 { "server-a" } else { "server-b" }.to_string(),
+                tool_name: "read_file".to_string(),
+                tool_args: Some(serde_json::json!({"path": format!("/tmp/{}", i)})),
+                result: None,
+                blocked: i == 4,
+                block_reason: (i == 4).then(|| "Blocked by policy, with a comma".to_string()),
+                duration_ms: i,
+                redacted: false,
+                original_args: None,
+                oversized: false,
+            })
+            .unwrap();
+        }
+
+        let mut out = Vec::new();
+        assert_eq!(log.export_csv(&mut out, None, false).unwrap(), 5);
+        let mut reader = csv::Reader::from_reader(out.as_slice());
+        assert_eq!(
+            reader.headers().unwrap(),
+            vec![
+                "id",
+                "timestamp",
+                "server_name",
+                "tool_name",
+                "tool_args",
+                "result",
+                "blocked",
+                "block_reason",
+                "duration_ms",
+                "redacted",
+                "original_args",
+                "oversized",
+            ]
+        );
+        let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+        assert_eq!(rows.len(), 5);
+        assert_eq!(&rows[0][4], r#"{"path":"/tmp/0"}"#);
+        assert_eq!(&rows[0][5], "");
+        assert_eq!(&rows[4][6], "true");
+        assert_eq!(&rows[4][7], "Blocked by policy, with a comma");
+
+        let mut out = Vec::new();
+        assert_eq!(
+            log.export_csv(&mut out, Some("server-a"), false).unwrap(),
+            3
+        );
+        let mut out = Vec::new();
+        assert_eq!(log.export_csv(&mut out, None, true).unwrap(), 1);
+    }
+
+    #[test]
+    fn export_csv_neutralizes_formulas() {
+        let (_dir, pool) = test_pool();
+        let log = AuditLog::new(pool);
+        log.insert(&AuditEntry {
+            id: 0,
+            timestamp: Utc::now(),
+            server_name: "=HYPERLINK(\"http://evil\")".to_string(),
+            tool_name: "@SUM(A1)".to_string(),
+            tool_args: Some(serde_json::json!("+cmd|' /C calc'!A0")),
+            result: None,
+            blocked: true,
+            block_reason: Some("-2+3".to_string()),
+            duration_ms: 0,
+            redacted: false,
+            original_args: None,
+            oversized: false,
+        })
+        .unwrap();
+
+        let mut out = Vec::new();
+        log.export_csv(&mut out, None, false).unwrap();
+        let mut reader = csv::Reader::from_reader(out.as_slice());
+        let row = reader.records().next().unwrap().unwrap();
+        assert_eq!(&row[2], "'=HYPERLINK(\"http://evil\")");
+        assert_eq!(&row[3], "'@SUM(A1)");
+        assert_eq!(&row[4], "\"+cmd|' /C calc'!A0\"");
+        assert_eq!(&row[7], "'-2+3");
+    }
+
+    #[test]
+    fn stats_summarize_calls_since() {
+        let (_dir, pool) = test_pool();
+        let log = AuditLog::new(pool);
+        let now = Utc::now();
+
+        let calls = [
+            ("server-a", "read_file", false, 10, 1),
+            ("server-a", "read_file", false, 20, 1),
+            ("server-a", "read_file", true, 0, 1),
+            ("server-b", "shell_exec", true, 0, 1),
+            ("server-b", "fetch_url", false, 970, 1),
+            // Before the window
+            ("server-c", "old_tool", true, 5000, 48),
+        ];
+        for (server, tool, blocked, duration_ms, hours_ago) in calls {
+            log.insert(&AuditEntry {
+                id: 0,
+                timestamp: now - chrono::Duration::hours(hours_ago),
+                server_name: server.to_string(),
+                tool_name: tool.to_string(),
+                tool_args: None,
+                result: None,
+                blocked,
+                block_reason: None,
+                duration_ms,
+                redacted: false,
+                original_args: None,
+                oversized: false,
+            })
+            .unwrap();
+        }
+
+        let stats = log.stats(Some(now - chrono::Duration::hours(24))).unwrap();
+        assert_eq!(stats.total_calls, 5);
+        assert_eq!(stats.blocked_calls, 2);
+        assert_eq!(stats.unique_tools, 3);
+        assert_eq!(stats.unique_servers, 2);
+        assert_eq!(stats.calls_per_tool["read_file"], 3);
+        assert_eq!(stats.calls_per_tool.len(), 3);
+        assert_eq!(
+            stats.blocked_per_tool,
+            HashMap::from([("read_file".to_string(), 1), ("shell_exec".to_string(), 1)])
+        );
+        assert_eq!(stats.avg_duration_ms, 200.0);
+        assert_eq!(stats.p99_duration_ms, 970);
+
+        let all = log.stats(None).unwrap();
+        assert_eq!(all.total_calls, 6);
+        assert_eq!(all.p99_duration_ms, 5000);
+
+        let (_empty_dir, empty_pool) = test_pool();
+        let empty = AuditLog::new(empty_pool).stats(None).unwrap();
+        assert_eq!(empty, AuditStats::default());
+    }
+
+    #[test]
+    fn purge_before_keeps_entries_in_retention_window() {
+        let (_dir, pool) = test_pool();
+        let log = AuditLog::new(pool);
+        let now = Utc::now();
+
+        for days_ago in [1, 10, 89, 91, 400] {
+            let entry = AuditEntry {
+                id: 0,
+                timestamp: now - chrono::Duration::days(days_ago),
+                server_name: "server-a".to_string(),
+                tool_name: format!("tool-{}", days_ago),
+                tool_args: None,
+                result: None,
+                blocked: false,
+                block_reason: None,
+                duration_ms: 10,
+                redacted: false,
+                original_args: None,
+                oversized: false,
+            };
+            log.insert(&entry).unwrap();
+        }
+
+        assert_eq!(
+            log.purge_before(now - chrono::Duration::days(90)).unwrap(),
+            2
+        );
+        let mut kept: Vec<String> = log
+            .list_after(None, 10)
+            .unwrap()
+            .0
+            .into_iter()
+            .map(|e| e.tool_name)
+            .collect();
+        kept.sort();
+        assert_eq!(kept, ["tool-1", "tool-10", "tool-89"]);
+
+        assert_eq!(
+            log.purge_before(now - chrono::Duration::days(90)).unwrap(),
+            0
+        );
+    }
+}
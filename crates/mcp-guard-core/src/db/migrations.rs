@@ -0,0 +1,232 @@
+//! Database schema migrations.
+
+use crate::error::Result;
+use rusqlite::Connection;
+
+pub fn run_migrations(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS snapshots (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            server_name TEXT NOT NULL,
+            tool_name TEXT NOT NULL,
+            description TEXT,
+            description_hash TEXT NOT NULL,
+            input_schema TEXT,
+            scanned_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS snapshot_servers (
+            server_name TEXT PRIMARY KEY,
+            server_capabilities TEXT,
+            protocol_version TEXT,
+            scanned_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS scan_results (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            server_name TEXT NOT NULL,
+            server_source TEXT NOT NULL,
+            tool_count INTEGER NOT NULL,
+            resource_count INTEGER NOT NULL,
+            threat_count INTEGER NOT NULL,
+            threats_json TEXT NOT NULL,
+            scan_duration_ms INTEGER NOT NULL,
+            scanned_at TEXT NOT NULL DEFAULT (datetime('now')),
+            result_json TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            timestamp TEXT NOT NULL DEFAULT (datetime('now')),
+            server_name TEXT NOT NULL,
+            tool_name TEXT NOT NULL,
+            tool_args TEXT,
+            result TEXT,
+            blocked INTEGER NOT NULL DEFAULT 0,
+            block_reason TEXT,
+            duration_ms INTEGER NOT NULL,
+            redacted INTEGER NOT NULL DEFAULT 0,
+            original_args TEXT,
+            oversized INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE TABLE IF NOT EXISTS proxy_rules (
+            id TEXT PRIMARY KEY,
+            tool_pattern TEXT NOT NULL,
+            action TEXT NOT NULL,
+            priority INTEGER NOT NULL DEFAULT 0,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            expires_at TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS scan_schedules (
+            id TEXT PRIMARY KEY,
+            server_name TEXT NOT NULL,
+            cron_expression TEXT NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            last_run TEXT,
+            next_run TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_audit_timestamp ON audit_log(timestamp);
+        CREATE INDEX IF NOT EXISTS idx_audit_server ON audit_log(server_name);
+        CREATE INDEX IF NOT EXISTS idx_audit_tool ON audit_log(tool_name);
+        CREATE INDEX IF NOT EXISTS idx_snapshots_server_tool ON snapshots(server_name, tool_name);
+        CREATE INDEX IF NOT EXISTS idx_scan_results_server ON scan_results(server_name);
+        "#,
+    )?;
+
+    // Columns added after the first release; CREATE TABLE IF NOT EXISTS
+    // leaves older databases without them.
+    add_column_if_missing(conn, "audit_log", "redacted", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "proxy_rules", "expires_at", "TEXT")?;
+    add_column_if_missing(conn, "audit_log", "original_args", "TEXT")?;
+    add_column_if_missing(conn, "scan_results", "result_json", "TEXT")?;
+    add_column_if_missing(conn, "audit_log", "oversized", "INTEGER NOT NULL DEFAULT 0")?;
+
+    Ok(())
+}
+
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let exists = conn
+        .prepare(&format!("PRAGMA table_info({})", table))?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|r| r.ok())
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute_batch(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {}",
+            table, column, definition
+        ))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_migrations_creates_tables() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+
+        let tables: Vec<String> = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type='table' ORDER BY name")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .filter_map(|r| r.ok())
+            .collect();
+
+        assert!(tables.contains(&"audit_log".to_string()));
+        assert!(tables.contains(&"proxy_rules".to_string()));
+        assert!(tables.contains(&"scan_results".to_string()));
+        assert!(tables.contains(&"snapshots".to_string()));
+        assert!(tables.contains(&"scan_schedules".to_string()));
+    }
+
+    #[test]
+    fn run_migrations_is_idempotent() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        run_migrations(&conn).unwrap(); // Should not fail
+    }
+
+    #[test]
+    fn run_migrations_adds_columns_to_old_tables() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE audit_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp TEXT NOT NULL DEFAULT (datetime('now')),
+                server_name TEXT NOT NULL,
+                tool_name TEXT NOT NULL,
+                tool_args TEXT,
+                result TEXT,
+                blocked INTEGER NOT NULL DEFAULT 0,
+                block_reason TEXT,
+                duration_ms INTEGER NOT NULL
+            );
+            CREATE TABLE proxy_rules (
+                id TEXT PRIMARY KEY,
+                tool_pattern TEXT NOT NULL,
+                action TEXT NOT NULL,
+                priority INTEGER NOT NULL DEFAULT 0,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+            CREATE TABLE scan_results (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                server_name TEXT NOT NULL,
+                server_source TEXT NOT NULL,
+                tool_count INTEGER NOT NULL,
+                resource_count INTEGER NOT NULL,
+                threat_count INTEGER NOT NULL,
+                threats_json TEXT NOT NULL,
+                scan_duration_ms INTEGER NOT NULL,
+                scanned_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );",
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        let redacted: i32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('audit_log') WHERE name = 'redacted'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(redacted, 1);
+
+        let expires_at: i32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('proxy_rules') WHERE name = 'expires_at'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(expires_at, 1);
+
+        let original_args: i32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('audit_log') WHERE name = 'original_args'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(original_args, 1);
+
+        let result_json: i32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('scan_results') WHERE name = 'result_json'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(result_json, 1);
+
+        let oversized: i32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('audit_log') WHERE name = 'oversized'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(oversized, 1);
+    }
+}
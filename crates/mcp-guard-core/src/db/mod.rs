@@ -2,12 +2,16 @@
 
 mod audit;
 mod migrations;
+mod scan_results;
 mod snapshots;
 
-pub use audit::{AuditEntry, AuditLog};
+pub use audit::{AuditEntry, AuditLog, AuditStats, CallCount, ToolStats, STATS_TOP_TOOLS};
 pub use migrations::run_migrations;
+pub use scan_results::{ScanResultStore, SeverityCounts, StoredScanResult};
+pub use snapshots::SnapshotDb;
 
 use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
 use std::path::PathBuf;
@@ -38,6 +42,20 @@ pub fn default_db_path() -> Result<PathBuf> {
     Ok(home.join(".mcp-guard").join("mcp-guard.db"))
 }
 
+/// Rebuild the database file to return space freed by deleted rows to the
+/// filesystem.
+pub(crate) fn vacuum(conn: &rusqlite::Connection) -> Result<()> {
+    conn.execute_batch("VACUUM")?;
+    Ok(())
+}
+
+/// Parse an RFC 3339 timestamp column, falling back to now for bad data.
+pub(crate) fn parse_datetime(s: String) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(&s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,487 @@
+//! Scan result history storage.
+
+use crate::db::{parse_datetime, DbPool};
+use crate::error::{Error, Result};
+use crate::scanner::{ScanResult, Severity, Threat};
+use chrono::{DateTime, Utc};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Number of threats at each severity level.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeverityCounts {
+    pub critical: usize,
+    pub high: usize,
+    pub medium: usize,
+    pub low: usize,
+    pub info: usize,
+}
+
+impl SeverityCounts {
+    pub fn from_threats(threats: &[Threat]) -> Self {
+        let mut counts = Self::default();
+        for threat in threats {
+            match threat.severity {
+                Severity::Critical => counts.critical += 1,
+                Severity::High => counts.high += 1,
+                Severity::Medium => counts.medium += 1,
+                Severity::Low => counts.low += 1,
+                Severity::Info => counts.info += 1,
+            }
+        }
+        counts
+    }
+}
+
+/// A scan result as recorded in the history table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredScanResult {
+    pub id: i64,
+    pub server_name: String,
+    pub server_source: String,
+    pub tool_count: i64,
+    pub resource_count: i64,
+    pub threat_count: i64,
+    pub threats_by_severity: SeverityCounts,
+    pub scan_duration_ms: i64,
+    pub scanned_at: DateTime<Utc>,
+}
+
+const RESULT_COLUMNS: &str = "id, server_name, server_source, tool_count, resource_count, \
+     threat_count, threats_json, scan_duration_ms, scanned_at";
+
+fn result_from_row(row: &rusqlite::Row) -> rusqlite::Result<(StoredScanResult, Vec<Threat>)> {
+    let threats: Vec<Threat> = serde_json::from_str(&row.get::<_, String>(6)?).unwrap_or_default();
+    let result = StoredScanResult {
+        id: row.get(0)?,
+        server_name: row.get(1)?,
+        server_source: row.get(2)?,
+        tool_count: row.get(3)?,
+        resource_count: row.get(4)?,
+        threat_count: row.get(5)?,
+        threats_by_severity: SeverityCounts::from_threats(&threats),
+        scan_duration_ms: row.get(7)?,
+        scanned_at: parse_datetime(row.get::<_, String>(8)?),
+    };
+    Ok((result, threats))
+}
+
+#[derive(Clone)]
+pub struct ScanResultStore {
+    pool: DbPool,
+}
+
+impl ScanResultStore {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store `result`. The server's env values are redacted in the stored
+    /// copy.
+    pub fn insert(&self, result: &ScanResult) -> Result<i64> {
+        let conn = self.pool.get()?;
+        let mut stored = serde_json::to_value(result)?;
+        stored["server"] = serde_json::to_value(result.server.redacted())?;
+
+        conn.execute(
+            r#"
+            INSERT INTO scan_results (server_name, server_source, tool_count, resource_count, threat_count, threats_json, scan_duration_ms, scanned_at, result_json)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#,
+            rusqlite::params![
+                result.server.name,
+                result.server.display_source(),
+                result.tools.len() as i64,
+                result.resources.len() as i64,
+                result.threats.len() as i64,
+                serde_json::to_string(&result.threats)?,
+                result.scan_duration.as_millis() as i64,
+                result.scanned_at.to_rfc3339(),
+                stored.to_string(),
+            ],
+        )?;
+
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// The full result of a stored scan. Scans stored before full results
+    /// were kept only have their threats, and are reported as an error.
+    pub fn get(&self, id: i64) -> Result<ScanResult> {
+        let conn = self.pool.get()?;
+
+        let json = conn
+            .query_row(
+                "SELECT result_json FROM scan_results WHERE id = ?1",
+                [id],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()?
+            .ok_or_else(|| Error::Other(format!("Scan result {} not found", id)))?
+            .ok_or_else(|| {
+                Error::Other(format!(
+                    "Scan result {} was stored by an older version without its tools",
+                    id
+                ))
+            })?;
+
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// List stored results, newest first, optionally for a single server.
+    pub fn list(
+        &self,
+        server: Option<&str>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<StoredScanResult>> {
+        let conn = self.pool.get()?;
+
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT {}
+            FROM scan_results
+            WHERE ?1 IS NULL OR server_name = ?1
+            ORDER BY scanned_at DESC, id DESC
+            LIMIT ?2 OFFSET ?3
+            "#,
+            RESULT_COLUMNS
+        ))?;
+
+        let results = stmt
+            .query_map(
+                rusqlite::params![server, limit as i64, offset as i64],
+                |row| Ok(result_from_row(row)?.0),
+            )?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Every stored scan with its threats, oldest first, optionally for a
+    /// single server and only from `since` onward.
+    pub fn history(
+        &self,
+        server: Option<&str>,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(StoredScanResult, Vec<Threat>)>> {
+        let conn = self.pool.get()?;
+
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT {}
+            FROM scan_results
+            WHERE (?1 IS NULL OR server_name = ?1) AND (?2 IS NULL OR scanned_at >= ?2)
+            ORDER BY scanned_at ASC, id ASC
+            "#,
+            RESULT_COLUMNS
+        ))?;
+
+        let results = stmt
+            .query_map(
+                rusqlite::params![server, since.map(|t| t.to_rfc3339())],
+                result_from_row,
+            )?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Delete results of scans before `cutoff`, returning how many were
+    /// deleted. The database is vacuumed afterwards if any were.
+    pub fn purge_before(&self, cutoff: DateTime<Utc>) -> Result<u64> {
+        let conn = self.pool.get()?;
+        let deleted = conn.execute(
+            "DELETE FROM scan_results WHERE scanned_at < ?1",
+            [cutoff.to_rfc3339()],
+        )?;
+        if deleted > 0 {
+            super::vacuum(&conn)?;
+        }
+        Ok(deleted as u64)
+    }
+
+    /// The most recent stored scan of a server, if it was ever scanned.
+    pub fn latest_for_server(&self, server_name: &str) -> Result<Option<StoredScanResult>> {
+        Ok(self.list(Some(server_name), 1, 0)?.into_iter().next())
+    }
+
+    /// Threats and timestamp from the most recent stored scan of a server.
+    pub fn latest_threats(&self, server: &str) -> Result<Option<(DateTime<Utc>, Vec<Threat>)>> {
+        let conn = self.pool.get()?;
+
+        let row = conn
+            .query_row(
+                r#"
+            SELECT scanned_at, threats_json
+            FROM scan_results
+            WHERE server_name = ?1
+            ORDER BY scanned_at DESC, id DESC
+            LIMIT 1
+            "#,
+                [server],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+            )
+            .optional()?;
+
+        match row {
+            Some((scanned_at, threats_json)) => Ok(Some((
+                parse_datetime(scanned_at),
+                serde_json::from_str(&threats_json)?,
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    /// Number of threats each detector has reported across every stored
+    /// scan. Threats stored before detectors were recorded aren't counted.
+    pub fn threat_counts_by_detector(&self) -> Result<HashMap<String, usize>> {
+        let mut counts = HashMap::new();
+        for (_, threats) in self.history(None, None)? {
+            for detector in threats.into_iter().filter_map(|t| t.detector) {
+                *counts.entry(detector).or_insert(0) += 1;
+            }
+        }
+        Ok(counts)
+    }
+
+    pub fn count(&self, server: Option<&str>) -> Result<i64> {
+        let conn = self.pool.get()?;
+        let count = conn.query_row(
+            "SELECT COUNT(*) FROM scan_results WHERE ?1 IS NULL OR server_name = ?1",
+            [server],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::create_pool;
+    use crate::discovery::ServerConfig;
+    use crate::scanner::report::ThreatCategory;
+    use chrono::Utc;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    fn scan_result(server: &str, threats: Vec<Threat>) -> ScanResult {
+        ScanResult {
+            server: ServerConfig::new(server, "npx"),
+            tools: vec![],
+            resources: vec![],
+            prompts: vec![],
+            threats,
+            snapshot_diff: None,
+            scan_duration: Duration::from_millis(120),
+            scanned_at: Utc::now(),
+        }
+    }
+
+    fn threat(severity: Severity) -> Threat {
+        Threat::new("TEST", severity, ThreatCategory::NoAuth, "Test threat")
+    }
+
+    #[test]
+    fn insert_scan_result() {
+        let dir = tempdir().unwrap();
+        let pool = create_pool(&dir.path().join("test.db")).unwrap();
+        let store = ScanResultStore::new(pool.clone());
+
+        let result = scan_result(
+            "test-server",
+            vec![Threat::new(
+                "NO-AUTH-LOCAL",
+                Severity::Info,
+                ThreatCategory::NoAuth,
+                "No authentication configured",
+            )],
+        );
+
+        let id = store.insert(&result).unwrap();
+        assert!(id > 0);
+
+        let conn = pool.get().unwrap();
+        let (name, threat_count): (String, i64) = conn
+            .query_row(
+                "SELECT server_name, threat_count FROM scan_results WHERE id = ?1",
+                [id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(name, "test-server");
+        assert_eq!(threat_count, 1);
+    }
+
+    #[test]
+    fn list_filters_by_server_and_paginates() {
+        let dir = tempdir().unwrap();
+        let pool = create_pool(&dir.path().join("test.db")).unwrap();
+        let store = ScanResultStore::new(pool);
+
+        store
+            .insert(&scan_result("alpha", vec![threat(Severity::Critical)]))
+            .unwrap();
+        store
+            .insert(&scan_result(
+                "alpha",
+                vec![threat(Severity::High), threat(Severity::Info)],
+            ))
+            .unwrap();
+        store.insert(&scan_result("beta", vec![])).unwrap();
+
+        assert_eq!(store.count(None).unwrap(), 3);
+        assert_eq!(store.count(Some("alpha")).unwrap(), 2);
+
+        let alpha = store.list(Some("alpha"), 10, 0).unwrap();
+        assert_eq!(alpha.len(), 2);
+        // Newest first
+        assert_eq!(alpha[0].threats_by_severity.high, 1);
+        assert_eq!(alpha[0].threats_by_severity.info, 1);
+        assert_eq!(alpha[1].threats_by_severity.critical, 1);
+
+        let page = store.list(None, 1, 1).unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].server_name, "alpha");
+    }
+
+    #[test]
+    fn history_is_oldest_first_and_filters_by_time() {
+        let dir = tempdir().unwrap();
+        let pool = create_pool(&dir.path().join("test.db")).unwrap();
+        let store = ScanResultStore::new(pool);
+
+        let mut old = scan_result("alpha", vec![threat(Severity::Critical)]);
+        old.scanned_at = Utc::now() - chrono::Duration::days(10);
+        store.insert(&scan_result("alpha", vec![])).unwrap();
+        store.insert(&old).unwrap();
+        store.insert(&scan_result("beta", vec![])).unwrap();
+
+        let history = store.history(Some("alpha"), None).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].1.len(), 1);
+        assert!(history[1].1.is_empty());
+
+        let recent = store
+            .history(None, Some(Utc::now() - chrono::Duration::days(1)))
+            .unwrap();
+        assert_eq!(recent.len(), 2);
+        assert!(recent.iter().all(|(r, _)| r.threat_count == 0));
+    }
+
+    #[test]
+    fn threat_counts_by_detector_sums_history() {
+        let dir = tempdir().unwrap();
+        let pool = create_pool(&dir.path().join("test.db")).unwrap();
+        let store = ScanResultStore::new(pool);
+
+        let tagged = || threat(Severity::High).with_detector("NoAuth");
+        store
+            .insert(&scan_result("alpha", vec![tagged(), threat(Severity::Low)]))
+            .unwrap();
+        store.insert(&scan_result("beta", vec![tagged()])).unwrap();
+
+        let counts = store.threat_counts_by_detector().unwrap();
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts["NoAuth"], 2);
+    }
+
+    #[test]
+    fn get_returns_full_result() {
+        let dir = tempdir().unwrap();
+        let pool = create_pool(&dir.path().join("test.db")).unwrap();
+        let store = ScanResultStore::new(pool.clone());
+
+        let id = store
+            .insert(&scan_result("alpha", vec![threat(Severity::High)]))
+            .unwrap();
+        let result = store.get(id).unwrap();
+        assert_eq!(result.server.name, "alpha");
+        assert_eq!(result.threats.len(), 1);
+        assert_eq!(result.scan_duration, Duration::from_millis(120));
+
+        assert!(store.get(id + 1).is_err());
+
+        pool.get()
+            .unwrap()
+            .execute("UPDATE scan_results SET result_json = NULL", [])
+            .unwrap();
+        assert!(store.get(id).is_err());
+    }
+
+    #[test]
+    fn stored_results_redact_env_values() {
+        let dir = tempdir().unwrap();
+        let pool = create_pool(&dir.path().join("test.db")).unwrap();
+        let store = ScanResultStore::new(pool.clone());
+
+        let mut result = scan_result("alpha", vec![]);
+        result.server.env = HashMap::from([("API_KEY".to_string(), "sk-live-1234".to_string())]);
+        let id = store.insert(&result).unwrap();
+
+        let json: String = pool
+            .get()
+            .unwrap()
+            .query_row(
+                "SELECT result_json FROM scan_results WHERE id = ?1",
+                [id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(!json.contains("sk-live-1234"));
+        assert_eq!(store.get(id).unwrap().server.env["API_KEY"], "[REDACTED]");
+    }
+
+    #[test]
+    fn latest_threats_returns_most_recent_scan() {
+        let dir = tempdir().unwrap();
+        let pool = create_pool(&dir.path().join("test.db")).unwrap();
+        let store = ScanResultStore::new(pool);
+
+        assert!(store.latest_threats("alpha").unwrap().is_none());
+
+        store
+            .insert(&scan_result("alpha", vec![threat(Severity::Critical)]))
+            .unwrap();
+        store.insert(&scan_result("alpha", vec![])).unwrap();
+
+        let (_, threats) = store.latest_threats("alpha").unwrap().unwrap();
+        assert!(threats.is_empty());
+
+        let latest = store.latest_for_server("alpha").unwrap().unwrap();
+        assert_eq!(latest.threat_count, 0);
+        assert!(store.latest_for_server("beta").unwrap().is_none());
+    }
+
+    #[test]
+    fn purge_before_keeps_results_in_retention_window() {
+        let dir = tempdir().unwrap();
+        let pool = create_pool(&dir.path().join("test.db")).unwrap();
+        let store = ScanResultStore::new(pool);
+        let now = Utc::now();
+
+        for days_ago in [0, 364, 366] {
+            let mut result = scan_result(&format!("server-{}", days_ago), vec![]);
+            result.scanned_at = now - chrono::Duration::days(days_ago);
+            store.insert(&result).unwrap();
+        }
+
+        assert_eq!(
+            store
+                .purge_before(now - chrono::Duration::days(365))
+                .unwrap(),
+            1
+        );
+        let mut kept: Vec<String> = store
+            .list(None, 10, 0)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.server_name)
+            .collect();
+        kept.sort();
+        assert_eq!(kept, ["server-0", "server-364"]);
+    }
+}
@@ -0,0 +1,327 @@
+//! Snapshot storage in SQLite.
+
+use crate::db::{parse_datetime, DbPool};
+use crate::error::Result;
+use crate::scanner::report::ToolInfo;
+use crate::scanner::snapshot::{hash_description, Snapshot, ToolSnapshot};
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::HashMap;
+
+/// Tool name, description, description hash, and scan time.
+type SnapshotRow = (String, Option<String>, String, String);
+
+/// Scan time, server capabilities, and protocol version.
+type ServerRow = (String, Option<String>, Option<String>);
+
+/// Rows of the `snapshots` table, the latest tool descriptions per server,
+/// and of `snapshot_servers`, the server info from the same scan.
+#[derive(Clone)]
+pub struct SnapshotDb {
+    pool: DbPool,
+}
+
+impl SnapshotDb {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Replace the stored snapshot for `server_name` with `tools`.
+    pub fn save(&self, server_name: &str, tools: &[ToolInfo]) -> Result<()> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        let scanned_at = Utc::now().to_rfc3339();
+
+        delete_server(&tx, server_name)?;
+        insert_server(&tx, server_name, &scanned_at, None, None)?;
+
+        for tool in tools {
+            let desc = tool.description.as_deref().unwrap_or("");
+            let hash = hash_description(desc);
+
+            tx.execute(
+                r#"
+                INSERT INTO snapshots (server_name, tool_name, description, description_hash, input_schema, scanned_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+                rusqlite::params![
+                    server_name,
+                    tool.name,
+                    tool.description,
+                    hash,
+                    serde_json::to_string(&tool.input_schema).ok(),
+                    scanned_at,
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Replace the stored snapshot for `server_name` with `snapshot`, keeping
+    /// its creation time and server info.
+    pub fn import(&self, server_name: &str, snapshot: &Snapshot) -> Result<()> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        let scanned_at = snapshot.created_at.to_rfc3339();
+        let capabilities = (!snapshot.server_capabilities.is_null())
+            .then(|| snapshot.server_capabilities.to_string());
+
+        delete_server(&tx, server_name)?;
+        insert_server(
+            &tx,
+            server_name,
+            &scanned_at,
+            capabilities,
+            snapshot.protocol_version.as_deref(),
+        )?;
+
+        for (name, tool) in &snapshot.tools {
+            tx.execute(
+                r#"
+                INSERT INTO snapshots (server_name, tool_name, description, description_hash, scanned_at)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                "#,
+                rusqlite::params![
+                    server_name,
+                    name,
+                    tool.description,
+                    tool.description_hash,
+                    scanned_at,
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Remove the stored snapshot for `server_name`. Returns whether one existed.
+    pub fn delete(&self, server_name: &str) -> Result<bool> {
+        let conn = self.pool.get()?;
+        delete_server(&conn, server_name)
+    }
+
+    /// Every stored snapshot, sorted by server name.
+    pub fn list(&self) -> Result<Vec<(String, Snapshot)>> {
+        let servers: Vec<String> = {
+            let conn = self.pool.get()?;
+            // Databases from before `snapshot_servers` only have tool rows
+            let mut stmt = conn.prepare(
+                "SELECT server_name FROM snapshot_servers UNION SELECT server_name FROM snapshots ORDER BY server_name",
+            )?;
+            let names = stmt
+                .query_map([], |row| row.get(0))?
+                .filter_map(|r| r.ok())
+                .collect();
+            names
+        };
+
+        let mut snapshots = Vec::new();
+        for server in servers {
+            if let Some(snapshot) = self.load(&server)? {
+                snapshots.push((server, snapshot));
+            }
+        }
+        Ok(snapshots)
+    }
+
+    /// The stored snapshot for `server_name`, or `None` if it was never saved.
+    pub fn load(&self, server_name: &str) -> Result<Option<Snapshot>> {
+        let conn = self.pool.get()?;
+
+        let server: Option<ServerRow> = conn
+            .query_row(
+                "SELECT scanned_at, server_capabilities, protocol_version FROM snapshot_servers WHERE server_name = ?1",
+                [server_name],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT tool_name, description, description_hash, scanned_at FROM snapshots WHERE server_name = ?1",
+        )?;
+
+        let rows: Vec<SnapshotRow> = stmt
+            .query_map([server_name], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let (created_at, capabilities, protocol_version) = match server {
+            Some(server) => server,
+            None => match rows.first() {
+                Some((_, _, _, scanned_at)) => (scanned_at.clone(), None, None),
+                None => return Ok(None),
+            },
+        };
+        let created_at = parse_datetime(created_at);
+        let server_capabilities = capabilities
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or(serde_json::Value::Null);
+
+        let tools: HashMap<String, ToolSnapshot> = rows
+            .into_iter()
+            .map(|(name, description, description_hash, _)| {
+                (
+                    name,
+                    ToolSnapshot {
+                        description,
+                        description_hash,
+                    },
+                )
+            })
+            .collect();
+
+        Ok(Some(Snapshot {
+            tools,
+            created_at,
+            server_capabilities,
+            protocol_version,
+        }))
+    }
+}
+
+/// Delete the tool and server rows of `server_name`, returning whether
+/// there were any.
+fn delete_server(conn: &Connection, server_name: &str) -> Result<bool> {
+    let tools = conn.execute(
+        "DELETE FROM snapshots WHERE server_name = ?1",
+        [server_name],
+    )?;
+    let server = conn.execute(
+        "DELETE FROM snapshot_servers WHERE server_name = ?1",
+        [server_name],
+    )?;
+    Ok(tools + server > 0)
+}
+
+fn insert_server(
+    conn: &Connection,
+    server_name: &str,
+    scanned_at: &str,
+    capabilities: Option<String>,
+    protocol_version: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO snapshot_servers (server_name, server_capabilities, protocol_version, scanned_at) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![server_name, capabilities, protocol_version, scanned_at],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::create_pool;
+    use tempfile::tempdir;
+
+    fn test_pool() -> (tempfile::TempDir, DbPool) {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let pool = create_pool(&db_path).unwrap();
+        (dir, pool)
+    }
+
+    fn make_tool(name: &str, desc: &str) -> ToolInfo {
+        ToolInfo {
+            name: name.to_string(),
+            description: Some(desc.to_string()),
+            input_schema: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let (_dir, pool) = test_pool();
+        let db = SnapshotDb::new(pool);
+
+        let tools = vec![make_tool("tool1", "desc1"), make_tool("tool2", "desc2")];
+        db.save("server1", &tools).unwrap();
+
+        let snapshot = db.load("server1").unwrap().unwrap();
+        assert_eq!(snapshot.tools.len(), 2);
+        assert_eq!(
+            snapshot.tools["tool1"].description_hash,
+            hash_description("desc1")
+        );
+
+        let diff = snapshot.diff(&Snapshot::from_tools(&tools));
+        assert!(diff.added_tools.is_empty());
+        assert!(diff.removed_tools.is_empty());
+        assert!(diff.changed_descriptions.is_empty());
+    }
+
+    #[test]
+    fn save_replaces_previous_snapshot() {
+        let (_dir, pool) = test_pool();
+        let db = SnapshotDb::new(pool);
+
+        db.save("server1", &[make_tool("tool1", "desc1")]).unwrap();
+        db.save("server1", &[make_tool("tool2", "desc2")]).unwrap();
+
+        let snapshot = db.load("server1").unwrap().unwrap();
+        assert_eq!(snapshot.tools.len(), 1);
+        assert!(snapshot.tools.contains_key("tool2"));
+    }
+
+    #[test]
+    fn import_list_and_delete() {
+        let (_dir, pool) = test_pool();
+        let db = SnapshotDb::new(pool);
+
+        let capabilities: crate::protocol::mcp::ServerCapabilities =
+            serde_json::from_value(serde_json::json!({ "tools": {} })).unwrap();
+        let snapshot = Snapshot::from_tools(&[make_tool("tool1", "desc1")])
+            .with_server_info(&capabilities, "2025-03-26");
+        db.import("server2", &snapshot).unwrap();
+        db.save("server1", &[make_tool("tool2", "desc2")]).unwrap();
+
+        let listed = db.list().unwrap();
+        let names: Vec<&str> = listed.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["server1", "server2"]);
+        assert_eq!(
+            listed[1].1.tools["tool1"].description_hash,
+            snapshot.tools["tool1"].description_hash
+        );
+        assert_eq!(
+            listed[1].1.server_capabilities,
+            snapshot.server_capabilities
+        );
+        assert_eq!(listed[1].1.protocol_version.as_deref(), Some("2025-03-26"));
+
+        assert!(db.delete("server2").unwrap());
+        assert!(!db.delete("server2").unwrap());
+        assert!(db.load("server2").unwrap().is_none());
+    }
+
+    #[test]
+    fn servers_without_tools_keep_their_info() {
+        let (_dir, pool) = test_pool();
+        let db = SnapshotDb::new(pool);
+
+        let capabilities: crate::protocol::mcp::ServerCapabilities =
+            serde_json::from_value(serde_json::json!({ "resources": {} })).unwrap();
+        let snapshot = Snapshot::from_tools(&[]).with_server_info(&capabilities, "2025-03-26");
+        db.import("resources-only", &snapshot).unwrap();
+
+        let loaded = db.load("resources-only").unwrap().unwrap();
+        assert!(loaded.tools.is_empty());
+        assert_eq!(loaded.server_capabilities, snapshot.server_capabilities);
+        assert_eq!(loaded.protocol_version.as_deref(), Some("2025-03-26"));
+        assert_eq!(db.list().unwrap().len(), 1);
+
+        assert!(db.delete("resources-only").unwrap());
+        assert!(db.load("resources-only").unwrap().is_none());
+    }
+
+    #[test]
+    fn load_returns_none_for_new_server() {
+        let (_dir, pool) = test_pool();
+        let db = SnapshotDb::new(pool);
+
+        assert!(db.load("never-seen").unwrap().is_none());
+    }
+}
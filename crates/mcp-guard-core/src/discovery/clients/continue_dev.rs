@@ -0,0 +1,240 @@
+//! Continue.dev MCP configuration discovery.
+//!
+//! Continue's `config.json` can list servers in three ways: an `mcpServers`
+//! array of named servers, the `mcpServers` map other clients use, or as
+//! `contextProviders` entries named `mcp` with the server under
+//! `params.server`.
+
+use crate::discovery::clients::generic::parse_mcp_servers_at;
+use crate::discovery::config::{ConfigSource, ServerConfig, TransportType};
+use crate::discovery::traits::McpClientDiscovery;
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContinueConfig {
+    #[serde(default)]
+    mcp_servers: Option<Value>,
+    #[serde(default)]
+    context_providers: Vec<ContextProvider>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContextProvider {
+    name: String,
+    #[serde(default)]
+    params: Option<McpProviderParams>,
+}
+
+#[derive(Debug, Deserialize)]
+struct McpProviderParams {
+    name: Option<String>,
+    server: Option<ContinueServer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContinueServer {
+    name: Option<String>,
+    command: Option<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    env: HashMap<String, String>,
+}
+
+pub struct ContinueDiscovery;
+
+impl ContinueDiscovery {
+    fn server(
+        &self,
+        name: String,
+        raw: ContinueServer,
+        config_path: &Path,
+    ) -> Result<ServerConfig> {
+        let command = raw.command.ok_or_else(|| {
+            Error::invalid_server_config(format!("Server '{}' is missing 'command' field", name))
+        })?;
+
+        Ok(ServerConfig {
+            name,
+            command,
+            args: raw.args,
+            env: raw.env,
+            transport: TransportType::Stdio,
+            source: ConfigSource::Client {
+                name: self.name().to_string(),
+                config_path: config_path.to_path_buf(),
+            },
+        })
+    }
+}
+
+impl McpClientDiscovery for ContinueDiscovery {
+    fn name(&self) -> &str {
+        "Continue"
+    }
+
+    fn config_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+
+        if let Some(home) = dirs::home_dir() {
+            // ~/.continue/config.json
+            paths.push(home.join(".continue/config.json"));
+        }
+
+        paths
+    }
+
+    fn parse(&self, content: &str, config_path: &Path) -> Result<Vec<ServerConfig>> {
+        let root: Value = serde_json::from_str(content)
+            .map_err(|e| Error::config_parse(config_path, e.to_string()))?;
+        let config: ContinueConfig = serde_json::from_value(root.clone())
+            .map_err(|e| Error::config_parse(config_path, e.to_string()))?;
+
+        let mut servers = match config.mcp_servers {
+            Some(Value::Array(entries)) => entries
+                .into_iter()
+                .enumerate()
+                .map(|(i, entry)| {
+                    let raw: ContinueServer = serde_json::from_value(entry).map_err(|e| {
+                        Error::config_parse(config_path, format!("mcpServers[{}]: {}", i, e))
+                    })?;
+                    let name = raw.name.clone().unwrap_or_else(|| format!("mcp-{}", i + 1));
+                    self.server(name, raw, config_path)
+                })
+                .collect::<Result<Vec<_>>>()?,
+            Some(_) => parse_mcp_servers_at(&root, "mcpServers", config_path, self.name())?,
+            None => Vec::new(),
+        };
+
+        let providers = config
+            .context_providers
+            .into_iter()
+            .filter(|provider| provider.name == "mcp")
+            .enumerate();
+        for (i, provider) in providers {
+            let params = provider.params.ok_or_else(|| {
+                Error::config_parse(config_path, "mcp context provider is missing 'params'")
+            })?;
+            let raw = params.server.ok_or_else(|| {
+                Error::config_parse(
+                    config_path,
+                    "mcp context provider is missing 'params.server'",
+                )
+            })?;
+            let name = params
+                .name
+                .or_else(|| raw.name.clone())
+                .unwrap_or_else(|| format!("mcp-provider-{}", i + 1));
+            servers.push(self.server(name, raw, config_path)?);
+        }
+
+        Ok(servers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Result<Vec<ServerConfig>> {
+        ContinueDiscovery.parse(content, Path::new("/tmp/config.json"))
+    }
+
+    #[test]
+    fn has_correct_name() {
+        let discovery = ContinueDiscovery;
+        assert_eq!(discovery.name(), "Continue");
+    }
+
+    #[test]
+    fn config_paths_correct() {
+        let discovery = ContinueDiscovery;
+        let paths = discovery.config_paths();
+        assert!(!paths.is_empty());
+        assert!(paths[0].ends_with(".continue/config.json"));
+    }
+
+    #[test]
+    fn parses_mcp_context_providers() {
+        let servers = parse(
+            r#"{
+                "models": [{"title": "GPT-4", "provider": "openai", "model": "gpt-4"}],
+                "contextProviders": [
+                    {"name": "code"},
+                    {
+                        "name": "mcp",
+                        "params": {
+                            "server": {
+                                "command": "npx",
+                                "args": ["-y", "@modelcontextprotocol/server-filesystem", "/"],
+                                "env": {"DEBUG": "1"}
+                            }
+                        }
+                    },
+                    {
+                        "name": "mcp",
+                        "params": {
+                            "name": "sqlite",
+                            "server": {"command": "uvx", "args": ["mcp-server-sqlite"]}
+                        }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[0].name, "mcp-provider-1");
+        assert_eq!(servers[0].command, "npx");
+        assert_eq!(servers[0].args.len(), 3);
+        assert_eq!(servers[0].env.get("DEBUG").unwrap(), "1");
+        assert_eq!(servers[1].name, "sqlite");
+        assert_eq!(servers[1].command, "uvx");
+        assert!(matches!(
+            &servers[1].source,
+            ConfigSource::Client { name, .. } if name == "Continue"
+        ));
+    }
+
+    #[test]
+    fn parses_mcp_servers_array() {
+        let servers = parse(
+            r#"{"mcpServers": [
+                {"name": "fs", "command": "npx", "args": ["server-fs"]},
+                {"command": "node", "args": ["server.js"]}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[0].name, "fs");
+        assert_eq!(servers[1].name, "mcp-2");
+    }
+
+    #[test]
+    fn parses_mcp_servers_map() {
+        let servers = parse(r#"{"mcpServers": {"fs": {"command": "npx"}}}"#).unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].name, "fs");
+    }
+
+    #[test]
+    fn config_without_servers_is_empty() {
+        let servers = parse(r#"{"models": [], "contextProviders": [{"name": "diff"}]}"#).unwrap();
+        assert!(servers.is_empty());
+    }
+
+    #[test]
+    fn mcp_provider_without_server_is_an_error() {
+        assert!(parse(r#"{"contextProviders": [{"name": "mcp", "params": {}}]}"#).is_err());
+        assert!(parse(
+            r#"{"contextProviders": [{"name": "mcp", "params": {"server": {"args": []}}}]}"#
+        )
+        .is_err());
+    }
+}
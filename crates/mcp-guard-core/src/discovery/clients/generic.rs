@@ -0,0 +1,356 @@
+//! Generic MCP config parser for any JSON or YAML file with an mcpServers map.
+
+use crate::discovery::config::{ConfigSource, ServerConfig, TransportType};
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct McpConfig {
+    #[serde(default)]
+    mcp_servers: HashMap<String, RawServerConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawServerConfig {
+    command: Option<String>,
+    args: Option<Vec<String>>,
+    #[serde(default)]
+    env: HashMap<String, String>,
+    url: Option<String>,
+    transport: Option<String>,
+}
+
+pub fn parse_mcp_servers(
+    content: &str,
+    config_path: &Path,
+    client_name: &str,
+) -> Result<Vec<ServerConfig>> {
+    let config: McpConfig = serde_json::from_str(content)
+        .map_err(|e| Error::config_parse(config_path, e.to_string()))?;
+
+    build_servers(config.mcp_servers, config_path, client_name)
+}
+
+/// Parses the server map found at a dot-separated key path such as
+/// `ai.mcpServers`, for configs that don't keep `mcpServers` at the top level.
+pub fn parse_mcp_servers_at(
+    root: &serde_json::Value,
+    json_path: &str,
+    config_path: &Path,
+    client_name: &str,
+) -> Result<Vec<ServerConfig>> {
+    let mut node = root;
+    for key in json_path.split('.').filter(|k| !k.is_empty()) {
+        node = node.get(key).ok_or_else(|| {
+            Error::config_parse(
+                config_path,
+                format!("key '{}' not found while resolving '{}'", key, json_path),
+            )
+        })?;
+    }
+
+    let raw_servers: HashMap<String, RawServerConfig> = serde_json::from_value(node.clone())
+        .map_err(|e| Error::config_parse(config_path, format!("{}: {}", json_path, e)))?;
+
+    build_servers(raw_servers, config_path, client_name)
+}
+
+fn build_servers(
+    raw_servers: HashMap<String, RawServerConfig>,
+    config_path: &Path,
+    client_name: &str,
+) -> Result<Vec<ServerConfig>> {
+    let mut servers = Vec::new();
+
+    for (name, raw) in raw_servers {
+        let transport = determine_transport(&raw);
+
+        // Remote servers don't need a command
+        let command = match &transport {
+            TransportType::Stdio => raw.command.ok_or_else(|| {
+                Error::invalid_server_config(format!(
+                    "Server '{}' is missing 'command' field",
+                    name
+                ))
+            })?,
+            TransportType::Sse { .. } | TransportType::StreamableHttp { .. } => {
+                raw.command.unwrap_or_default()
+            }
+        };
+
+        let server = ServerConfig {
+            name,
+            command,
+            args: raw.args.unwrap_or_default(),
+            env: raw.env,
+            transport,
+            source: ConfigSource::Client {
+                name: client_name.to_string(),
+                config_path: config_path.to_path_buf(),
+            },
+        };
+
+        servers.push(server);
+    }
+
+    Ok(servers)
+}
+
+/// The `{"mcpServers": {...}}` document that [`parse_mcp_servers`] reads back
+/// into `servers`.
+pub fn to_mcp_servers_json(servers: &[ServerConfig]) -> serde_json::Value {
+    let entries = servers
+        .iter()
+        .map(|server| (server.name.clone(), server.to_mcp_json_entry()))
+        .collect::<serde_json::Map<_, _>>();
+
+    serde_json::json!({ "mcpServers": entries })
+}
+
+fn determine_transport(raw: &RawServerConfig) -> TransportType {
+    if let Some(url) = raw.url.clone() {
+        match raw.transport.as_deref() {
+            Some("sse") => TransportType::Sse { url },
+            Some("streamable-http") | Some("http") => TransportType::StreamableHttp { url },
+            _ => {
+                // Default to SSE if URL is provided but transport not specified
+                TransportType::Sse { url }
+            }
+        }
+    } else {
+        TransportType::Stdio
+    }
+}
+
+pub struct GenericDiscovery {
+    pub path: PathBuf,
+    /// Dot-separated path to the server map, e.g. `ai.mcpServers`.
+    /// Defaults to a top-level `mcpServers` key.
+    pub json_path: Option<String>,
+}
+
+impl GenericDiscovery {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            json_path: None,
+        }
+    }
+
+    pub fn with_json_path(mut self, json_path: impl Into<String>) -> Self {
+        self.json_path = Some(json_path.into());
+        self
+    }
+
+    pub fn parse_file(&self) -> Result<Vec<ServerConfig>> {
+        if !self.path.exists() {
+            return Err(Error::ConfigNotFound {
+                path: self.path.clone(),
+            });
+        }
+
+        let content = std::fs::read_to_string(&self.path).map_err(|e| Error::ConfigRead {
+            path: self.path.clone(),
+            source: e,
+        })?;
+
+        let is_yaml = matches!(
+            self.path.extension().and_then(|e| e.to_str()),
+            Some("yaml") | Some("yml")
+        );
+
+        if !is_yaml && self.json_path.is_none() {
+            return parse_mcp_servers(&content, &self.path, "generic");
+        }
+
+        let root: serde_json::Value = if is_yaml {
+            serde_yaml::from_str(&content)
+                .map_err(|e| Error::config_parse(&self.path, e.to_string()))?
+        } else {
+            serde_json::from_str(&content)
+                .map_err(|e| Error::config_parse(&self.path, e.to_string()))?
+        };
+
+        let json_path = self.json_path.as_deref().unwrap_or("mcpServers");
+        parse_mcp_servers_at(&root, json_path, &self.path, "generic")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_mcp_servers_json_round_trips() {
+        let mut remote = ServerConfig::new("remote", "");
+        remote.transport = TransportType::StreamableHttp {
+            url: "https://example.com/mcp".into(),
+        };
+        let servers = vec![
+            ServerConfig::new("fs", "npx")
+                .with_args(vec!["-y".into(), "server-fs".into()])
+                .with_env(HashMap::from([
+                    ("DEBUG".into(), "1".into()),
+                    ("API_KEY".into(), "${API_KEY}".into()),
+                ])),
+            remote,
+        ];
+
+        let json = to_mcp_servers_json(&servers).to_string();
+        let mut parsed = parse_mcp_servers(&json, Path::new("out.json"), "Test").unwrap();
+        parsed.sort_by(|a, b| a.name.cmp(&b.name));
+
+        assert_eq!(parsed[0].name, "fs");
+        assert_eq!(parsed[0].args, vec!["-y", "server-fs"]);
+        assert_eq!(parsed[0].env.get("DEBUG").unwrap(), "1");
+        assert_eq!(parsed[0].env.get("API_KEY").unwrap(), "${API_KEY}");
+        assert_eq!(parsed[1].transport, servers[1].transport);
+    }
+
+    #[test]
+    fn parse_basic_config() {
+        let json = r#"{
+            "mcpServers": {
+                "filesystem": {
+                    "command": "npx",
+                    "args": ["-y", "@modelcontextprotocol/server-filesystem", "/"]
+                }
+            }
+        }"#;
+
+        let servers = parse_mcp_servers(json, &PathBuf::from("/test/config.json"), "test").unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].name, "filesystem");
+        assert_eq!(servers[0].command, "npx");
+        assert_eq!(servers[0].args.len(), 3);
+    }
+
+    #[test]
+    fn parse_config_with_env() {
+        let json = r#"{
+            "mcpServers": {
+                "github": {
+                    "command": "npx",
+                    "args": ["-y", "@modelcontextprotocol/server-github"],
+                    "env": {
+                        "GITHUB_TOKEN": "secret"
+                    }
+                }
+            }
+        }"#;
+
+        let servers = parse_mcp_servers(json, &PathBuf::from("/test/config.json"), "test").unwrap();
+        assert_eq!(
+            servers[0].env.get("GITHUB_TOKEN"),
+            Some(&"secret".to_string())
+        );
+    }
+
+    #[test]
+    fn keeps_env_var_references_until_started() {
+        let json = r#"{
+            "mcpServers": {
+                "fs": {
+                    "command": "npx",
+                    "args": ["server-fs", "${HOME}"],
+                    "env": {
+                        "TOKEN": "${MCP_GUARD_TEST_UNSET_VAR}"
+                    }
+                }
+            }
+        }"#;
+
+        let servers = parse_mcp_servers(json, &PathBuf::from("/test/config.json"), "test").unwrap();
+        assert_eq!(servers[0].args[1], "${HOME}");
+        assert_eq!(servers[0].env["TOKEN"], "${MCP_GUARD_TEST_UNSET_VAR}");
+    }
+
+    #[test]
+    fn parse_remote_server() {
+        let json = r#"{
+            "mcpServers": {
+                "remote": {
+                    "url": "http://localhost:8080",
+                    "transport": "sse"
+                }
+            }
+        }"#;
+
+        let servers = parse_mcp_servers(json, &PathBuf::from("/test/config.json"), "test").unwrap();
+        assert!(
+            matches!(&servers[0].transport, TransportType::Sse { url } if url == "http://localhost:8080")
+        );
+    }
+
+    #[test]
+    fn missing_command_for_stdio_fails() {
+        let json = r#"{
+            "mcpServers": {
+                "bad": {
+                    "args": ["test"]
+                }
+            }
+        }"#;
+
+        let result = parse_mcp_servers(json, &PathBuf::from("/test/config.json"), "test");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_nested_server_map() {
+        let root = serde_json::json!({
+            "ai": {
+                "mcpServers": {
+                    "filesystem": { "command": "npx", "args": ["-y", "server-fs"] }
+                }
+            }
+        });
+
+        let servers = parse_mcp_servers_at(
+            &root,
+            "ai.mcpServers",
+            &PathBuf::from("/test/config.json"),
+            "test",
+        )
+        .unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].name, "filesystem");
+    }
+
+    #[test]
+    fn missing_json_path_key_fails() {
+        let root = serde_json::json!({ "ai": {} });
+
+        let err = parse_mcp_servers_at(
+            &root,
+            "ai.mcpServers",
+            &PathBuf::from("/test/config.json"),
+            "test",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("mcpServers"));
+    }
+
+    #[test]
+    fn parse_yaml_file_with_json_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.yaml");
+        std::fs::write(
+            &path,
+            "assistant:\n  context_servers:\n    git:\n      command: uvx\n      args: [mcp-server-git]\n",
+        )
+        .unwrap();
+
+        let servers = GenericDiscovery::new(path)
+            .with_json_path("assistant.context_servers")
+            .parse_file()
+            .unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].command, "uvx");
+        assert_eq!(servers[0].args, vec!["mcp-server-git"]);
+    }
+}
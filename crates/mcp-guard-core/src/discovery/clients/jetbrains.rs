@@ -0,0 +1,142 @@
+//! JetBrains IDE (IntelliJ IDEA, PyCharm, ...) MCP configuration discovery.
+//!
+//! Each product and version keeps its own config directory, e.g.
+//! `JetBrains/IntelliJIdea2025.1`, so every one of them is checked.
+
+use crate::discovery::clients::generic::parse_mcp_servers;
+use crate::discovery::config::ServerConfig;
+use crate::discovery::traits::McpClientDiscovery;
+use crate::error::{Error, Result};
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE: &str = "mcpServers.json";
+
+pub struct JetBrainsDiscovery {
+    product: Option<String>,
+}
+
+impl JetBrainsDiscovery {
+    pub fn new() -> Self {
+        Self { product: None }
+    }
+
+    /// Only discover servers for one product directory, e.g. `PyCharm2025.1`.
+    pub fn with_product(product: &str) -> Self {
+        Self {
+            product: Some(product.to_string()),
+        }
+    }
+
+    fn paths_in(&self, jetbrains_dir: &Path) -> Vec<PathBuf> {
+        if let Some(ref product) = self.product {
+            return vec![jetbrains_dir.join(product).join(CONFIG_FILE)];
+        }
+
+        let Ok(entries) = std::fs::read_dir(jetbrains_dir) else {
+            return Vec::new();
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.path().join(CONFIG_FILE))
+            .collect();
+        paths.sort();
+        paths
+    }
+}
+
+impl Default for JetBrainsDiscovery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl McpClientDiscovery for JetBrainsDiscovery {
+    fn name(&self) -> &str {
+        "JetBrains"
+    }
+
+    fn config_paths(&self) -> Vec<PathBuf> {
+        match dirs::config_dir() {
+            // ~/.config/JetBrains/<PRODUCT>/mcpServers.json, %APPDATA%\JetBrains\<PRODUCT>\...
+            Some(config) => self.paths_in(&config.join("JetBrains")),
+            None => Vec::new(),
+        }
+    }
+
+    fn parse(&self, content: &str, config_path: &Path) -> Result<Vec<ServerConfig>> {
+        parse_mcp_servers(content, config_path, self.name())
+    }
+
+    fn discover(&self) -> Result<Vec<ServerConfig>> {
+        // Unlike single-config clients, every installed product contributes servers
+        let mut servers = Vec::new();
+        for path in self.config_paths() {
+            if path.exists() {
+                let content = std::fs::read_to_string(&path).map_err(|e| Error::ConfigRead {
+                    path: path.clone(),
+                    source: e,
+                })?;
+                servers.extend(self.parse(&content, &path)?);
+            }
+        }
+        Ok(servers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn has_correct_name() {
+        assert_eq!(JetBrainsDiscovery::new().name(), "JetBrains");
+    }
+
+    #[test]
+    fn finds_config_for_each_product() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("PyCharm2025.1")).unwrap();
+        std::fs::create_dir(dir.path().join("IntelliJIdea2025.1")).unwrap();
+        std::fs::write(dir.path().join("stray.txt"), "").unwrap();
+
+        let paths = JetBrainsDiscovery::new().paths_in(dir.path());
+        assert_eq!(
+            paths,
+            vec![
+                dir.path().join("IntelliJIdea2025.1/mcpServers.json"),
+                dir.path().join("PyCharm2025.1/mcpServers.json"),
+            ]
+        );
+    }
+
+    #[test]
+    fn with_product_targets_one_directory() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("PyCharm2025.1")).unwrap();
+
+        let paths = JetBrainsDiscovery::with_product("GoLand2025.1").paths_in(dir.path());
+        assert_eq!(paths, vec![dir.path().join("GoLand2025.1/mcpServers.json")]);
+    }
+
+    #[test]
+    fn missing_jetbrains_dir_has_no_paths() {
+        let dir = TempDir::new().unwrap();
+        let paths = JetBrainsDiscovery::new().paths_in(&dir.path().join("JetBrains"));
+        assert!(paths.is_empty());
+    }
+
+    #[test]
+    fn parses_mcp_servers_format() {
+        let content = r#"{"mcpServers": {"fs": {"command": "npx", "args": ["server-fs"]}}}"#;
+        let servers = JetBrainsDiscovery::new()
+            .parse(content, Path::new("/tmp/mcpServers.json"))
+            .unwrap();
+
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].name, "fs");
+        assert_eq!(servers[0].command, "npx");
+    }
+}
@@ -6,6 +6,8 @@ mod cline;
 mod continue_dev;
 mod cursor;
 mod generic;
+mod jetbrains;
+mod neovim;
 mod roo_code;
 mod vscode;
 mod windsurf;
@@ -16,7 +18,9 @@ pub use claude_desktop::ClaudeDesktopDiscovery;
 pub use cline::ClineDiscovery;
 pub use continue_dev::ContinueDiscovery;
 pub use cursor::CursorDiscovery;
-pub use generic::GenericDiscovery;
+pub use generic::{to_mcp_servers_json, GenericDiscovery};
+pub use jetbrains::JetBrainsDiscovery;
+pub use neovim::NeovimDiscovery;
 pub use roo_code::RooCodeDiscovery;
 pub use vscode::VsCodeDiscovery;
 pub use windsurf::WindsurfDiscovery;
@@ -0,0 +1,307 @@
+//! Neovim MCP configuration discovery.
+//!
+//! Neovim MCP plugins read `mcp.json` from the project's `.nvim/` directory or
+//! from `~/.config/nvim/`. Lua configs (`mcp.lua`) are also supported, as long
+//! as servers are written as a plain table literal:
+//!
+//! ```lua
+//! mcpServers = {
+//!   ["filesystem"] = { command = "npx", args = { "-y", "server-fs" } },
+//! }
+//! ```
+//!
+//! Servers built with function calls or variables aren't picked up.
+
+use crate::discovery::clients::generic::parse_mcp_servers;
+use crate::discovery::config::{ConfigSource, ServerConfig, TransportType};
+use crate::discovery::traits::McpClientDiscovery;
+use crate::error::{Error, Result};
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+static SERVERS_TABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"mcpServers\s*=\s*\{").unwrap());
+
+/// A table field whose value is itself a table: `["name"] = {` or `name = {`.
+static TABLE_FIELD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:\[\s*["']([^"']+)["']\s*\]|([A-Za-z_][A-Za-z0-9_]*))\s*=\s*\{"#).unwrap()
+});
+
+/// A table field with a string value: `["KEY"] = "value"` or `key = 'value'`.
+static STRING_FIELD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?:\[\s*["']([^"']+)["']\s*\]|([A-Za-z_][A-Za-z0-9_]*))\s*=\s*(?:"([^"]*)"|'([^']*)')"#,
+    )
+    .unwrap()
+});
+
+static STRING: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#""([^"]*)"|'([^']*)'"#).unwrap());
+
+pub struct NeovimDiscovery;
+
+impl NeovimDiscovery {
+    /// Extract servers from an `mcpServers = { ... }` table in a Lua config.
+    pub fn parse_lua_config(content: &str, config_path: &Path) -> Result<Vec<ServerConfig>> {
+        let content = strip_lua_comments(content);
+        let Some(start) = SERVERS_TABLE.find(&content) else {
+            return Ok(Vec::new());
+        };
+        let servers_table = table_body(&content, start.end() - 1)
+            .ok_or_else(|| Error::config_parse(config_path, "unclosed mcpServers table"))?;
+
+        let mut servers = Vec::new();
+        for (name, range) in table_fields(servers_table) {
+            let body = &servers_table[range];
+            let fields = string_fields(&top_level(body));
+            let command = fields.get("command").ok_or_else(|| {
+                Error::invalid_server_config(format!(
+                    "Server '{}' is missing 'command' field",
+                    name
+                ))
+            })?;
+
+            let nested: HashMap<&str, &str> = table_fields(body)
+                .into_iter()
+                .map(|(key, range)| (key, &body[range]))
+                .collect();
+            let args = nested
+                .get("args")
+                .map(|args| {
+                    STRING
+                        .captures_iter(args)
+                        .filter_map(|c| c.get(1).or_else(|| c.get(2)))
+                        .map(|m| m.as_str().to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            let env = nested
+                .get("env")
+                .map(|env| string_fields(env).into_iter().collect())
+                .unwrap_or_default();
+
+            servers.push(ServerConfig {
+                name: name.to_string(),
+                command: command.to_string(),
+                args,
+                env,
+                transport: TransportType::Stdio,
+                source: ConfigSource::Client {
+                    name: "Neovim".to_string(),
+                    config_path: config_path.to_path_buf(),
+                },
+            });
+        }
+
+        Ok(servers)
+    }
+}
+
+impl McpClientDiscovery for NeovimDiscovery {
+    fn name(&self) -> &str {
+        "Neovim"
+    }
+
+    fn config_paths(&self) -> Vec<PathBuf> {
+        // Project-level .nvim/ takes precedence
+        let mut paths = vec![
+            PathBuf::from(".nvim/mcp.json"),
+            PathBuf::from(".nvim/mcp.lua"),
+        ];
+
+        if let Some(home) = dirs::home_dir() {
+            // Neovim uses ~/.config/nvim on every platform but Windows
+            let nvim = home.join(".config/nvim");
+            paths.push(nvim.join("mcp.json"));
+            paths.push(nvim.join("mcp.lua"));
+        }
+
+        paths
+    }
+
+    fn parse(&self, content: &str, config_path: &Path) -> Result<Vec<ServerConfig>> {
+        if config_path.extension().is_some_and(|ext| ext == "lua") {
+            Self::parse_lua_config(content, config_path)
+        } else {
+            parse_mcp_servers(content, config_path, self.name())
+        }
+    }
+}
+
+/// Remove `--` line comments, keeping `--` inside strings.
+fn strip_lua_comments(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| {
+            let mut quote = None;
+            let bytes = line.as_bytes();
+            for (i, &b) in bytes.iter().enumerate() {
+                match (quote, b) {
+                    (None, b'"' | b'\'') => quote = Some(b),
+                    (Some(q), _) if b == q => quote = None,
+                    (None, b'-') if bytes.get(i + 1) == Some(&b'-') => return &line[..i],
+                    _ => {}
+                }
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The contents of the table whose `{` is at `open`, without the braces.
+fn table_body(content: &str, open: usize) -> Option<&str> {
+    let mut depth = 0;
+    let mut quote = None;
+    for (i, b) in content.bytes().enumerate().skip(open) {
+        match (quote, b) {
+            (Some(q), _) if b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') => quote = Some(b),
+            (None, b'{') => depth += 1,
+            (None, b'}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&content[open + 1..i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Fields of `table` whose values are tables, with the byte range of each
+/// body. Fields of nested tables are skipped.
+fn table_fields(table: &str) -> Vec<(&str, Range<usize>)> {
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    while let Some(caps) = TABLE_FIELD.captures_at(table, pos) {
+        let whole = caps.get(0).unwrap();
+        let name = caps.get(1).or_else(|| caps.get(2)).unwrap().as_str();
+        let Some(body) = table_body(table, whole.end() - 1) else {
+            break;
+        };
+        let range = whole.end()..whole.end() + body.len();
+        pos = range.end + 1;
+        fields.push((name, range));
+    }
+    fields
+}
+
+/// `table` with the bodies of nested tables removed.
+fn top_level(table: &str) -> String {
+    let mut out = String::new();
+    let mut pos = 0;
+    for (_, body) in table_fields(table) {
+        out.push_str(&table[pos..body.start]);
+        pos = body.end;
+    }
+    out.push_str(&table[pos..]);
+    out
+}
+
+fn string_fields(table: &str) -> HashMap<String, String> {
+    STRING_FIELD
+        .captures_iter(table)
+        .map(|c| {
+            let key = c.get(1).or_else(|| c.get(2)).unwrap().as_str();
+            let value = c.get(3).or_else(|| c.get(4)).unwrap().as_str();
+            (key.to_string(), value.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn has_correct_name() {
+        assert_eq!(NeovimDiscovery.name(), "Neovim");
+    }
+
+    #[test]
+    fn config_paths_include_json_and_lua() {
+        let paths = NeovimDiscovery.config_paths();
+        assert_eq!(paths[0], PathBuf::from(".nvim/mcp.json"));
+        assert_eq!(paths[1], PathBuf::from(".nvim/mcp.lua"));
+        assert!(paths.iter().any(|p| p.ends_with(".config/nvim/mcp.lua")));
+    }
+
+    #[test]
+    fn parses_json_config() {
+        let content = r#"{"mcpServers": {"fs": {"command": "npx", "args": ["server-fs"]}}}"#;
+        let servers = NeovimDiscovery
+            .parse(content, Path::new("/tmp/mcp.json"))
+            .unwrap();
+
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].command, "npx");
+    }
+
+    #[test]
+    fn parses_lua_config() {
+        let content = r#"
+-- MCP servers for the current project
+return {
+  mcpServers = {
+    ["filesystem"] = {
+      command = "npx",
+      args = { "-y", "@modelcontextprotocol/server-filesystem", "/tmp" },
+    },
+    github = {
+      command = 'docker', -- run in a container
+      args = { 'run', '-i', 'ghcr.io/github/github-mcp-server' },
+      env = { GITHUB_TOKEN = "ghp_x", ["GH-HOST"] = "github.com" },
+    },
+  },
+}
+"#;
+        let mut servers = NeovimDiscovery
+            .parse(content, Path::new("/tmp/mcp.lua"))
+            .unwrap();
+        servers.sort_by(|a, b| a.name.cmp(&b.name));
+
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[0].name, "filesystem");
+        assert_eq!(servers[0].command, "npx");
+        assert_eq!(
+            servers[0].args,
+            vec!["-y", "@modelcontextprotocol/server-filesystem", "/tmp"]
+        );
+        assert!(servers[0].env.is_empty());
+
+        assert_eq!(servers[1].name, "github");
+        assert_eq!(servers[1].command, "docker");
+        assert_eq!(servers[1].args.len(), 3);
+        assert_eq!(servers[1].env.get("GITHUB_TOKEN").unwrap(), "ghp_x");
+        assert_eq!(servers[1].env.get("GH-HOST").unwrap(), "github.com");
+        assert!(matches!(
+            &servers[1].source,
+            ConfigSource::Client { name, .. } if name == "Neovim"
+        ));
+    }
+
+    #[test]
+    fn lua_config_without_servers_is_empty() {
+        let servers =
+            NeovimDiscovery::parse_lua_config("vim.opt.number = true", Path::new("mcp.lua"))
+                .unwrap();
+        assert!(servers.is_empty());
+    }
+
+    #[test]
+    fn lua_server_without_command_is_an_error() {
+        let content = r#"mcpServers = { broken = { args = { "x" } } }"#;
+        assert!(NeovimDiscovery::parse_lua_config(content, Path::new("mcp.lua")).is_err());
+    }
+
+    #[test]
+    fn unclosed_lua_table_is_an_error() {
+        let content = r#"mcpServers = { fs = { command = "npx" }"#;
+        assert!(NeovimDiscovery::parse_lua_config(content, Path::new("mcp.lua")).is_err());
+    }
+}
@@ -0,0 +1,438 @@
+//! Server configuration types shared across all discovery clients.
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use tracing::warn;
+
+/// `${VAR}` or `$VAR`.
+static ENV_VAR_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\$(?:\{([A-Za-z_][A-Za-z0-9_]*)\}|([A-Za-z_][A-Za-z0-9_]*))").unwrap()
+});
+
+/// Replace `${VAR}` and `$VAR` with values from the environment. Variables
+/// that aren't set are left as written.
+pub fn expand_env_vars(s: &str) -> String {
+    ENV_VAR_PATTERN
+        .replace_all(s, |caps: &Captures| {
+            let name = caps
+                .get(1)
+                .or_else(|| caps.get(2))
+                .map_or("", |m| m.as_str());
+            std::env::var(name).unwrap_or_else(|_| {
+                warn!(variable = name, "Environment variable in config is not set");
+                caps[0].to_string()
+            })
+        })
+        .into_owned()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerConfig {
+    pub name: String,
+    pub command: String,
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    #[serde(default)]
+    pub transport: TransportType,
+    /// Client config the server was discovered in
+    #[serde(default)]
+    pub source: ConfigSource,
+}
+
+impl ServerConfig {
+    pub fn new(name: impl Into<String>, command: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            command: command.into(),
+            args: Vec::new(),
+            env: HashMap::new(),
+            transport: TransportType::Stdio,
+            source: ConfigSource::Manual,
+        }
+    }
+
+    pub fn with_args(mut self, args: Vec<String>) -> Self {
+        self.args = args;
+        self
+    }
+
+    #[cfg(test)]
+    pub fn with_env(mut self, env: HashMap<String, String>) -> Self {
+        self.env = env;
+        self
+    }
+
+    #[cfg(test)]
+    pub fn is_remote(&self) -> bool {
+        matches!(
+            self.transport,
+            TransportType::Sse { .. } | TransportType::StreamableHttp { .. }
+        )
+    }
+
+    /// A copy with `${VAR}` and `$VAR` in the command, arguments, env
+    /// values, and URL replaced from the environment, for starting or
+    /// connecting to the server. Discovery keeps the references as written,
+    /// so the secrets they point at stay out of reports, stored scans, and
+    /// generated configs.
+    pub fn expanded(&self) -> Self {
+        let transport = match &self.transport {
+            TransportType::Stdio => TransportType::Stdio,
+            TransportType::Sse { url } => TransportType::Sse {
+                url: expand_env_vars(url),
+            },
+            TransportType::StreamableHttp { url } => TransportType::StreamableHttp {
+                url: expand_env_vars(url),
+            },
+        };
+
+        Self {
+            name: self.name.clone(),
+            command: expand_env_vars(&self.command),
+            args: self.args.iter().map(|arg| expand_env_vars(arg)).collect(),
+            env: self
+                .env
+                .iter()
+                .map(|(key, value)| (key.clone(), expand_env_vars(value)))
+                .collect(),
+            transport,
+            source: self.source.clone(),
+        }
+    }
+
+    /// A copy with every env value replaced by `[REDACTED]`, for storing.
+    /// Client configs often hold API keys inline in `env`.
+    pub fn redacted(&self) -> Self {
+        Self {
+            env: self
+                .env
+                .keys()
+                .map(|key| (key.clone(), "[REDACTED]".to_string()))
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    pub fn display_source(&self) -> String {
+        match &self.source {
+            ConfigSource::Client { name, config_path } => {
+                format!("{} ({})", name, config_path.display())
+            }
+            ConfigSource::Manual => "manual".to_string(),
+        }
+    }
+
+    pub fn config_path(&self) -> Option<PathBuf> {
+        match &self.source {
+            ConfigSource::Client { config_path, .. } => Some(config_path.clone()),
+            ConfigSource::Manual => None,
+        }
+    }
+
+    /// The server's entry in an `mcpServers` map, as client configs and
+    /// `scan --config` expect it.
+    pub fn to_mcp_json_entry(&self) -> serde_json::Value {
+        let mut entry = serde_json::Map::new();
+        match &self.transport {
+            TransportType::Stdio => {
+                entry.insert("command".into(), self.command.clone().into());
+                entry.insert("args".into(), self.args.clone().into());
+            }
+            TransportType::Sse { url } => {
+                entry.insert("url".into(), url.clone().into());
+                entry.insert("transport".into(), "sse".into());
+            }
+            TransportType::StreamableHttp { url } => {
+                entry.insert("url".into(), url.clone().into());
+                entry.insert("transport".into(), "streamable-http".into());
+            }
+        }
+        if !self.env.is_empty() {
+            entry.insert("env".into(), serde_json::json!(self.env));
+        }
+        serde_json::Value::Object(entry)
+    }
+
+    /// Check the configuration without starting the server: the command is
+    /// on `PATH`, remote URLs are `http(s)://`, and env entries aren't empty.
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        let mut warnings = Vec::new();
+        let expanded = self.expanded();
+
+        match &expanded.transport {
+            TransportType::Stdio => {
+                if expanded.command.trim().is_empty() {
+                    warnings.push(ValidationWarning::error("No command configured"));
+                } else if which::which(&expanded.command).is_err() {
+                    warnings.push(ValidationWarning::error(format!(
+                        "Command '{}' not found in PATH",
+                        expanded.command
+                    )));
+                }
+            }
+            TransportType::Sse { url } | TransportType::StreamableHttp { url } => {
+                match reqwest::Url::parse(url) {
+                    Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+                    Ok(parsed) => warnings.push(ValidationWarning::error(format!(
+                        "URL '{}' uses unsupported scheme '{}'",
+                        url,
+                        parsed.scheme()
+                    ))),
+                    Err(e) => warnings.push(ValidationWarning::error(format!(
+                        "Invalid URL '{}': {}",
+                        url, e
+                    ))),
+                }
+            }
+        }
+
+        let mut keys: Vec<&String> = self.env.keys().collect();
+        keys.sort();
+        for key in keys {
+            if key.trim().is_empty() {
+                warnings.push(ValidationWarning::error(
+                    "Environment variable with an empty name",
+                ));
+            } else if self.env[key].is_empty() {
+                warnings.push(ValidationWarning::warning(format!(
+                    "Environment variable '{}' is empty",
+                    key
+                )));
+            }
+        }
+
+        warnings
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ValidationLevel {
+    Warning,
+    Error,
+}
+
+/// A problem found by [`ServerConfig::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationWarning {
+    pub level: ValidationLevel,
+    pub message: String,
+}
+
+impl ValidationWarning {
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            level: ValidationLevel::Warning,
+            message: message.into(),
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            level: ValidationLevel::Error,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum TransportType {
+    #[default]
+    Stdio,
+    Sse {
+        url: String,
+    },
+    StreamableHttp {
+        url: String,
+    },
+}
+
+/// Where a server's configuration came from. Serialized as
+/// `{"type": "client", "name": "Claude Desktop", "config_path": "..."}` or
+/// `{"type": "manual"}`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ConfigSource {
+    Client {
+        name: String,
+        config_path: PathBuf,
+    },
+    #[default]
+    Manual,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn expand_env_vars_substitutes_set_variables() {
+        let home = std::env::var("HOME").unwrap();
+
+        assert_eq!(
+            expand_env_vars("${HOME}/projects"),
+            format!("{}/projects", home)
+        );
+        assert_eq!(expand_env_vars("$HOME"), home);
+        assert_eq!(expand_env_vars("no variables"), "no variables");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn expanded_substitutes_every_field() {
+        let home = std::env::var("HOME").unwrap();
+        let mut server = ServerConfig::new("fs", "$HOME/bin/server")
+            .with_args(vec!["${HOME}".to_string()])
+            .with_env(HashMap::from([
+                ("ROOT".to_string(), "${HOME}".to_string()),
+                (
+                    "TOKEN".to_string(),
+                    "${MCP_GUARD_TEST_UNSET_VAR}".to_string(),
+                ),
+            ]));
+
+        let expanded = server.expanded();
+        assert_eq!(expanded.command, format!("{}/bin/server", home));
+        assert_eq!(expanded.args[0], home);
+        assert_eq!(expanded.env["ROOT"], home);
+        assert_eq!(expanded.env["TOKEN"], "${MCP_GUARD_TEST_UNSET_VAR}");
+        // The original keeps the references
+        assert_eq!(server.env["ROOT"], "${HOME}");
+
+        server.transport = TransportType::Sse {
+            url: "http://${MCP_GUARD_TEST_UNSET_VAR}/$HOME".to_string(),
+        };
+        assert_eq!(
+            server.expanded().transport,
+            TransportType::Sse {
+                url: format!("http://${{MCP_GUARD_TEST_UNSET_VAR}}/{}", home)
+            }
+        );
+    }
+
+    #[test]
+    fn source_round_trips_through_json() {
+        let mut config = ServerConfig::new("fs", "npx");
+        config.source = ConfigSource::Client {
+            name: "Claude Desktop".to_string(),
+            config_path: PathBuf::from("/home/me/.config/Claude/claude_desktop_config.json"),
+        };
+
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(
+            json["source"],
+            serde_json::json!({
+                "type": "client",
+                "name": "Claude Desktop",
+                "config_path": "/home/me/.config/Claude/claude_desktop_config.json"
+            })
+        );
+        let parsed: ServerConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.source, config.source);
+
+        let manual = serde_json::to_value(ServerConfig::new("local", "cmd")).unwrap();
+        assert_eq!(manual["source"], serde_json::json!({ "type": "manual" }));
+
+        // Results stored before sources were serialized still load
+        let legacy: ServerConfig =
+            serde_json::from_str(r#"{"name": "old", "command": "cmd", "args": []}"#).unwrap();
+        assert_eq!(legacy.source, ConfigSource::Manual);
+    }
+
+    #[test]
+    fn expand_env_vars_leaves_missing_variables() {
+        let value = "${MCP_GUARD_TEST_UNSET_VAR}:$MCP_GUARD_TEST_UNSET_VAR";
+        assert_eq!(expand_env_vars(value), value);
+        assert_eq!(expand_env_vars("costs $5"), "costs $5");
+    }
+
+    #[test]
+    fn server_config_builder_pattern() {
+        let config = ServerConfig::new("test", "npx")
+            .with_args(vec!["-y".to_string(), "server".to_string()])
+            .with_env(HashMap::from([("KEY".to_string(), "value".to_string())]));
+
+        assert_eq!(config.name, "test");
+        assert_eq!(config.command, "npx");
+        assert_eq!(config.args, vec!["-y", "server"]);
+        assert_eq!(config.env.get("KEY"), Some(&"value".to_string()));
+    }
+
+    #[test]
+    fn transport_type_serialization() {
+        let stdio = TransportType::Stdio;
+        let json = serde_json::to_string(&stdio).unwrap();
+        assert!(json.contains("\"type\":\"stdio\""));
+
+        let sse = TransportType::Sse {
+            url: "http://localhost:8080".to_string(),
+        };
+        let json = serde_json::to_string(&sse).unwrap();
+        assert!(json.contains("\"type\":\"sse\""));
+        assert!(json.contains("\"url\":\"http://localhost:8080\""));
+    }
+
+    #[test]
+    fn is_remote() {
+        let stdio_server = ServerConfig::new("test", "cmd");
+        assert!(!stdio_server.is_remote());
+
+        let mut sse_server = ServerConfig::new("test", "");
+        sse_server.transport = TransportType::Sse {
+            url: "http://localhost".to_string(),
+        };
+        assert!(sse_server.is_remote());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn validate_checks_command_on_path() {
+        assert!(ServerConfig::new("test", "sh").validate().is_empty());
+
+        let warnings = ServerConfig::new("test", "no-such-mcp-server-binary").validate();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].level, ValidationLevel::Error);
+        assert!(warnings[0].message.contains("not found in PATH"));
+    }
+
+    #[test]
+    fn validate_checks_remote_urls() {
+        let mut server = ServerConfig::new("test", "");
+        server.transport = TransportType::Sse {
+            url: "https://example.com/sse".to_string(),
+        };
+        assert!(server.validate().is_empty());
+
+        server.transport = TransportType::StreamableHttp {
+            url: "ftp://example.com".to_string(),
+        };
+        assert_eq!(server.validate()[0].level, ValidationLevel::Error);
+
+        server.transport = TransportType::Sse {
+            url: "not a url".to_string(),
+        };
+        assert!(server.validate()[0].message.starts_with("Invalid URL"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn validate_warns_on_empty_env_values() {
+        let server = ServerConfig::new("test", "sh").with_env(HashMap::from([
+            ("API_KEY".to_string(), String::new()),
+            ("HOME".to_string(), "/home".to_string()),
+        ]));
+
+        let warnings = server.validate();
+        assert_eq!(
+            warnings,
+            vec![ValidationWarning::warning(
+                "Environment variable 'API_KEY' is empty"
+            )]
+        );
+    }
+}
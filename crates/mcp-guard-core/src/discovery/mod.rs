@@ -0,0 +1,193 @@
+//! MCP server discovery across all supported clients.
+
+pub mod clients;
+pub mod config;
+pub mod traits;
+
+pub use config::{ConfigSource, ServerConfig, TransportType, ValidationLevel, ValidationWarning};
+pub use traits::McpClientDiscovery;
+
+use crate::error::Result;
+use std::collections::HashMap;
+use tracing::warn;
+
+pub fn all_clients() -> Vec<Box<dyn McpClientDiscovery>> {
+    vec![
+        Box::new(clients::ClaudeDesktopDiscovery),
+        Box::new(clients::CursorDiscovery),
+        Box::new(clients::WindsurfDiscovery),
+        Box::new(clients::ZedDiscovery),
+        Box::new(clients::ClineDiscovery),
+        Box::new(clients::ContinueDiscovery),
+        Box::new(clients::VsCodeDiscovery),
+        Box::new(clients::RooCodeDiscovery),
+        Box::new(clients::ClaudeCodeDiscovery),
+        Box::new(clients::JetBrainsDiscovery::new()),
+        Box::new(clients::NeovimDiscovery),
+    ]
+}
+
+pub fn discover_all() -> Result<Vec<ServerConfig>> {
+    let mut servers: Vec<ServerConfig> = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for client in all_clients() {
+        match client.discover() {
+            Ok(client_servers) => {
+                for server in client_servers {
+                    // Dedupe by server name - first discovery wins
+                    if let Some(&idx) = seen.get(&server.name) {
+                        warn!(
+                            server_name = %server.name,
+                            existing_source = %servers[idx].display_source(),
+                            duplicate_source = %server.display_source(),
+                            "Duplicate server name found, using first discovery"
+                        );
+                    } else {
+                        seen.insert(server.name.clone(), servers.len());
+                        servers.push(server);
+                    }
+                }
+            }
+            Err(e) => {
+                warn!(
+                    client = %client.name(),
+                    error = %e,
+                    "Failed to discover servers from client"
+                );
+            }
+        }
+    }
+
+    Ok(servers)
+}
+
+/// Every server from every client, unlike [`discover_all`], which keeps
+/// only the first server with each name. Servers configured the same way
+/// in several clients appear once; a differently configured server with a
+/// name already taken is renamed `<name>-<client>`, e.g. `github-cursor`.
+pub fn discover_merged() -> Result<Vec<ServerConfig>> {
+    let mut per_client = Vec::new();
+    for client in all_clients() {
+        match client.discover() {
+            Ok(servers) => per_client.push((client.name().to_string(), servers)),
+            Err(e) => warn!(
+                client = %client.name(),
+                error = %e,
+                "Failed to discover servers from client"
+            ),
+        }
+    }
+    Ok(merge_servers(per_client))
+}
+
+fn merge_servers(per_client: Vec<(String, Vec<ServerConfig>)>) -> Vec<ServerConfig> {
+    let mut merged: Vec<ServerConfig> = Vec::new();
+
+    for (client, servers) in per_client {
+        for mut server in servers {
+            let entry = server.to_mcp_json_entry();
+            if merged
+                .iter()
+                .any(|m| m.name == server.name && m.to_mcp_json_entry() == entry)
+            {
+                continue;
+            }
+
+            if merged.iter().any(|m| m.name == server.name) {
+                let suffix: String = client
+                    .chars()
+                    .map(|c| match c {
+                        c if c.is_alphanumeric() => c.to_ascii_lowercase(),
+                        _ => '-',
+                    })
+                    .collect();
+                let base = format!("{}-{}", server.name, suffix);
+                let mut name = base.clone();
+                let mut n = 2;
+                while merged.iter().any(|m| m.name == name) {
+                    name = format!("{}-{}", base, n);
+                    n += 1;
+                }
+                server.name = name;
+            }
+            merged.push(server);
+        }
+    }
+
+    merged
+}
+
+pub fn discover_from_client(client_name: &str) -> Result<Vec<ServerConfig>> {
+    find_client(client_name)?.discover()
+}
+
+/// Look up a client by name, ignoring case, spaces, `-`, and `_`, so
+/// `claude-desktop` finds "Claude Desktop".
+pub fn find_client(client_name: &str) -> Result<Box<dyn McpClientDiscovery>> {
+    let normalize = |name: &str| -> String {
+        name.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let wanted = normalize(client_name);
+
+    all_clients()
+        .into_iter()
+        .find(|c| normalize(c.name()) == wanted)
+        .ok_or_else(|| crate::error::Error::Other(format!("Unknown client: {}", client_name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_clients_returns_expected_count() {
+        let clients = all_clients();
+        assert_eq!(clients.len(), 11);
+    }
+
+    #[test]
+    fn find_client_ignores_case_and_separators() {
+        for name in ["Claude Desktop", "claude-desktop", "CLAUDE_DESKTOP"] {
+            assert_eq!(find_client(name).unwrap().name(), "Claude Desktop");
+        }
+        assert_eq!(find_client("roo code").unwrap().name(), "Roo Code");
+        assert!(find_client("claude").is_err());
+    }
+
+    #[test]
+    fn merge_servers_keeps_every_distinct_server() {
+        let fs = || ServerConfig::new("fs", "npx").with_args(vec!["server-fs".into()]);
+        let merged = merge_servers(vec![
+            (
+                "Claude Desktop".to_string(),
+                vec![fs(), ServerConfig::new("github", "npx")],
+            ),
+            (
+                "Roo Code".to_string(),
+                vec![
+                    fs(),
+                    ServerConfig::new("github", "docker"),
+                    ServerConfig::new("git", "uvx"),
+                ],
+            ),
+        ]);
+
+        let names: Vec<_> = merged.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["fs", "github", "github-roo-code", "git"]);
+        assert_eq!(merged[2].command, "docker");
+    }
+
+    #[test]
+    fn all_clients_have_unique_names() {
+        let clients = all_clients();
+        let names: Vec<_> = clients.iter().map(|c| c.name()).collect();
+        let mut unique_names = names.clone();
+        unique_names.sort();
+        unique_names.dedup();
+        assert_eq!(names.len(), unique_names.len());
+    }
+}
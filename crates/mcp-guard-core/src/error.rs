@@ -20,6 +20,9 @@ pub enum Error {
     #[error("HTTP transport error: {0}")]
     HttpTransport(#[from] reqwest::Error),
 
+    #[error("MCP server closed the connection")]
+    ConnectionClosed,
+
     #[error("Invalid JSON-RPC message: {0}")]
     JsonRpcParse(#[source] serde_json::Error),
 
@@ -30,6 +33,15 @@ pub enum Error {
         data: Option<serde_json::Value>,
     },
 
+    #[error(
+        "Server uses unsupported MCP protocol version {server_version} (supported: {})",
+        client_supported.join(", ")
+    )]
+    IncompatibleProtocol {
+        server_version: String,
+        client_supported: Vec<String>,
+    },
+
     #[error("Config file not found: {path}")]
     ConfigNotFound { path: PathBuf },
 
@@ -52,9 +64,11 @@ pub enum Error {
         message: String,
     },
 
+    #[cfg(feature = "db")]
     #[error("Database error: {0}")]
     Database(#[from] rusqlite::Error),
 
+    #[cfg(feature = "db")]
     #[error("Database pool error: {0}")]
     DatabasePool(#[from] r2d2::Error),
 
@@ -70,6 +84,9 @@ pub enum Error {
     #[error("TOML parse error: {0}")]
     Toml(#[from] toml::de::Error),
 
+    #[error("Invalid cron expression '{expression}': {message}")]
+    InvalidCron { expression: String, message: String },
+
     #[error("{0}")]
     Other(String),
 }
@@ -0,0 +1,44 @@
+//! Core library for mcp-scanner: MCP server discovery, security scanning,
+//! and the building blocks of the filtering proxy.
+//!
+//! This crate has no CLI or web dependencies, so it can be embedded in CI
+//! tools and IDE plugins that only need the scanner:
+//!
+//! ```no_run
+//! use mcp_guard_core::discovery::discover_all;
+//! use mcp_guard_core::scanner::Scanner;
+//!
+//! # async fn run() -> mcp_guard_core::error::Result<()> {
+//! let scanner = Scanner::new();
+//! for server in discover_all()? {
+//!     let result = scanner.scan(&server).await?;
+//!     println!("{}: {} threats", result.server.name, result.threats.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Scanner::with_custom_detectors`](scanner::Scanner::with_custom_detectors)
+//! runs only the given detectors, and
+//! [`Scanner::with_additional_detectors`](scanner::Scanner::with_additional_detectors)
+//! adds them to the built-in ones from [`scanner::all_detectors`].
+//!
+//! # Features
+//!
+//! - `db` (default): SQLite storage for audit logs, proxy rules, and scan history.
+//! - `proxy` (default): STDIO proxy with rule-based filtering. Implies `db`.
+//! - `schedule` (default): Cron-scheduled scans stored in the database. Implies `db`.
+//! - `plugins` (default): Threat detectors loaded from dynamic libraries.
+
+#[cfg(feature = "db")]
+pub mod db;
+pub mod discovery;
+pub mod error;
+pub mod notifications;
+pub mod protocol;
+#[cfg(feature = "proxy")]
+pub mod proxy;
+pub mod scanner;
+#[cfg(feature = "schedule")]
+pub mod schedule;
+pub mod watch;
@@ -0,0 +1,5 @@
+//! Notifications sent when scans find threats.
+
+mod webhook;
+
+pub use webhook::{NotificationConfig, NotifyEvent, SlackConfig, WebhookNotifier, WebhookPayload};
@@ -0,0 +1,516 @@
+//! Webhook notifications for scan findings.
+//!
+//! Threats are POSTed as JSON to `webhook_url`, and to a Slack incoming
+//! webhook as Slack blocks when `[notifications.slack]` is configured. Failed
+//! deliveries are retried with exponential backoff.
+
+use crate::error::{Error, Result};
+use crate::scanner::{Severity, Threat, ThreatCategory};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+const MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_BACKOFF: Duration = Duration::from_millis(500);
+/// Per attempt, so an endpoint that never answers can't hold up a scan.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Slack rejects messages with more than 50 blocks.
+const MAX_SLACK_THREATS: usize = 45;
+
+/// The `[notifications]` section of the user config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// Receives a JSON [`WebhookPayload`] per event
+    pub webhook_url: Option<String>,
+    /// Threats less severe than this are not sent
+    pub min_severity: Severity,
+    /// Events to send; all of them by default
+    pub events: Vec<NotifyEvent>,
+    pub slack: Option<SlackConfig>,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            min_severity: Severity::High,
+            events: vec![NotifyEvent::NewThreat, NotifyEvent::DriftDetected],
+            slack: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SlackConfig {
+    pub webhook_url: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyEvent {
+    /// A scan found threats, other than description drift
+    NewThreat,
+    /// A tool description changed since the last snapshot
+    DriftDetected,
+}
+
+/// Body POSTed to `webhook_url`.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload<'a> {
+    pub event: NotifyEvent,
+    pub server: &'a str,
+    pub threats: Vec<&'a Threat>,
+}
+
+pub struct WebhookNotifier {
+    config: NotificationConfig,
+    client: reqwest::Client,
+    backoff: Duration,
+    timeout: Duration,
+}
+
+impl WebhookNotifier {
+    pub fn new(config: NotificationConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+            backoff: DEFAULT_BACKOFF,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// How long each delivery attempt may take, including connecting.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Delay before the first retry; it doubles on each further retry.
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Whether any webhook is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.config.webhook_url.is_some() || self.config.slack.is_some()
+    }
+
+    /// Send `threats` found on `server`: drift findings as `drift_detected`,
+    /// everything else as `new_threat`. Both events are sent even if one
+    /// fails.
+    pub async fn notify_threats(&self, server: &str, threats: &[Threat]) -> Result<()> {
+        let (drift, other): (Vec<&Threat>, Vec<&Threat>) = threats
+            .iter()
+            .partition(|t| t.category == ThreatCategory::DescriptionDrift);
+
+        combine(vec![
+            self.notify(NotifyEvent::NewThreat, server, other).await,
+            self.notify(NotifyEvent::DriftDetected, server, drift).await,
+        ])
+    }
+
+    /// Send one event to every configured webhook, skipping it if it's
+    /// disabled or no threat reaches `min_severity`. A failed delivery
+    /// doesn't stop the others; the failures are returned together.
+    pub async fn notify(
+        &self,
+        event: NotifyEvent,
+        server: &str,
+        threats: Vec<&Threat>,
+    ) -> Result<()> {
+        let threats: Vec<&Threat> = threats
+            .into_iter()
+            .filter(|t| t.severity <= self.config.min_severity)
+            .collect();
+        if threats.is_empty() || !self.config.events.contains(&event) {
+            return Ok(());
+        }
+
+        let mut results = Vec::new();
+        if let Some(url) = &self.config.webhook_url {
+            let payload = WebhookPayload {
+                event,
+                server,
+                threats: threats.clone(),
+            };
+            results.push(
+                self.post("webhook", url, &serde_json::to_value(&payload)?)
+                    .await,
+            );
+        }
+        if let Some(slack) = &self.config.slack {
+            results.push(
+                self.post(
+                    "slack",
+                    &slack.webhook_url,
+                    &slack_payload(event, server, &threats),
+                )
+                .await,
+            );
+        }
+        combine(results)
+    }
+
+    /// POST `body` to `url`, retrying on failure. Logs and errors name the
+    /// endpoint by `target` only: a webhook URL is often its own credential.
+    async fn post(&self, target: &str, url: &str, body: &Value) -> Result<()> {
+        let mut last_error = String::new();
+        for attempt in 0..MAX_ATTEMPTS {
+            if attempt > 0 {
+                tokio::time::sleep(self.backoff * 2u32.pow(attempt - 1)).await;
+            }
+
+            match self
+                .client
+                .post(url)
+                .timeout(self.timeout)
+                .json(body)
+                .send()
+                .await
+            {
+                Ok(response) if response.status().is_success() => {
+                    debug!(target, "Webhook delivered");
+                    return Ok(());
+                }
+                Ok(response) => last_error = format!("HTTP {}", response.status()),
+                Err(e) => last_error = e.without_url().to_string(),
+            }
+            warn!(target, attempt = attempt + 1, error = %last_error, "Webhook delivery failed");
+        }
+
+        Err(Error::Other(format!(
+            "Delivery to {} failed after {} attempts: {}",
+            target, MAX_ATTEMPTS, last_error
+        )))
+    }
+}
+
+/// Ok if every delivery succeeded, otherwise one error listing each failure.
+fn combine(results: Vec<Result<()>>) -> Result<()> {
+    let errors: Vec<String> = results
+        .into_iter()
+        .filter_map(|r| r.err())
+        .map(|e| e.to_string())
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Other(errors.join("; ")))
+    }
+}
+
+fn slack_payload(event: NotifyEvent, server: &str, threats: &[&Threat]) -> Value {
+    let summary = match event {
+        NotifyEvent::NewThreat => format!("{} threat(s) found in {}", threats.len(), server),
+        NotifyEvent::DriftDetected => format!("Tool descriptions changed in {}", server),
+    };
+
+    let mut blocks = vec![json!({
+        "type": "header",
+        "text": { "type": "plain_text", "text": summary }
+    })];
+    for threat in threats.iter().take(MAX_SLACK_THREATS) {
+        let mut text = format!("*[{}]* {}", threat.severity, threat.title);
+        if let Some(tool) = &threat.tool_name {
+            text.push_str(&format!("\nTool: `{}`", tool));
+        }
+        if !threat.message.is_empty() {
+            text.push_str(&format!("\n{}", threat.message));
+        }
+        blocks.push(json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": text }
+        }));
+    }
+    if threats.len() > MAX_SLACK_THREATS {
+        blocks.push(json!({
+            "type": "context",
+            "elements": [{
+                "type": "mrkdwn",
+                "text": format!("…and {} more", threats.len() - MAX_SLACK_THREATS)
+            }]
+        }));
+    }
+
+    json!({ "text": summary, "blocks": blocks })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn threat(id: &str, severity: Severity, category: ThreatCategory) -> Threat {
+        Threat::new(id, severity, category, id).with_tool("read_file")
+    }
+
+    fn notifier(config: NotificationConfig) -> WebhookNotifier {
+        WebhookNotifier::new(config).with_backoff(Duration::from_millis(10))
+    }
+
+    #[tokio::test]
+    async fn posts_threats_at_or_above_min_severity() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let notifier = notifier(NotificationConfig {
+            webhook_url: Some(format!("{}/hook", server.uri())),
+            ..Default::default()
+        });
+        let threats = vec![
+            threat(
+                "INJ-1",
+                Severity::Critical,
+                ThreatCategory::DescriptionInjection,
+            ),
+            threat("NO-AUTH", Severity::Info, ThreatCategory::NoAuth),
+        ];
+        notifier.notify_threats("fs", &threats).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["event"], "new_threat");
+        assert_eq!(body["server"], "fs");
+        assert_eq!(body["threats"].as_array().unwrap().len(), 1);
+        assert_eq!(body["threats"][0]["id"], "INJ-1");
+    }
+
+    #[tokio::test]
+    async fn drift_is_sent_as_its_own_event() {
+        let server = MockServer::start().await;
+        Mock::given(body_partial_json(json!({"event": "drift_detected"})))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let notifier = notifier(NotificationConfig {
+            webhook_url: Some(server.uri()),
+            events: vec![NotifyEvent::DriftDetected],
+            ..Default::default()
+        });
+        let threats = vec![
+            threat("DRIFT-1", Severity::High, ThreatCategory::DescriptionDrift),
+            threat(
+                "INJ-1",
+                Severity::Critical,
+                ThreatCategory::DescriptionInjection,
+            ),
+        ];
+        notifier.notify_threats("fs", &threats).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn nothing_is_sent_below_min_severity() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let notifier = notifier(NotificationConfig {
+            webhook_url: Some(server.uri()),
+            ..Default::default()
+        });
+        let threats = vec![threat("LOW", Severity::Medium, ThreatCategory::NoAuth)];
+        notifier.notify_threats("fs", &threats).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn retries_failed_deliveries() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let notifier = notifier(NotificationConfig {
+            webhook_url: Some(server.uri()),
+            ..Default::default()
+        });
+        let threats = vec![threat(
+            "INJ",
+            Severity::High,
+            ThreatCategory::PermissionScope,
+        )];
+        notifier.notify_threats("fs", &threats).await.unwrap();
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_three_attempts() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let notifier = notifier(NotificationConfig {
+            webhook_url: Some(server.uri()),
+            ..Default::default()
+        });
+        let threats = vec![threat(
+            "INJ",
+            Severity::High,
+            ThreatCategory::PermissionScope,
+        )];
+        assert!(notifier.notify_threats("fs", &threats).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn failed_endpoint_does_not_stop_the_others() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/down"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/slack"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let notifier = notifier(NotificationConfig {
+            webhook_url: Some(format!("{}/down", server.uri())),
+            slack: Some(SlackConfig {
+                webhook_url: format!("{}/slack", server.uri()),
+            }),
+            ..Default::default()
+        });
+        let threats = vec![
+            threat(
+                "INJ-1",
+                Severity::Critical,
+                ThreatCategory::DescriptionInjection,
+            ),
+            threat("DRIFT-1", Severity::High, ThreatCategory::DescriptionDrift),
+        ];
+        let err = notifier.notify_threats("fs", &threats).await.unwrap_err();
+        assert!(err.to_string().contains("webhook"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn failures_do_not_reveal_webhook_urls() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+        // Nothing listens on the reserved port, so the connection fails
+        let notifier = notifier(NotificationConfig {
+            webhook_url: Some("http://127.0.0.1:1/hooks/secret-token".to_string()),
+            slack: Some(SlackConfig {
+                webhook_url: format!("{}/services/T000/B000/slack-secret", server.uri()),
+            }),
+            ..Default::default()
+        });
+        let threats = vec![threat(
+            "INJ",
+            Severity::High,
+            ThreatCategory::PermissionScope,
+        )];
+
+        let err = notifier
+            .notify_threats("fs", &threats)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("webhook") && err.contains("slack"), "{}", err);
+        assert!(!err.contains("secret-token"), "{}", err);
+        assert!(!err.contains("slack-secret"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn slow_endpoints_time_out() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let notifier = notifier(NotificationConfig {
+            webhook_url: Some(server.uri()),
+            ..Default::default()
+        })
+        .with_timeout(Duration::from_millis(50));
+        let threats = vec![threat(
+            "INJ",
+            Severity::High,
+            ThreatCategory::PermissionScope,
+        )];
+        assert!(notifier.notify_threats("fs", &threats).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn slack_receives_blocks() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let notifier = notifier(NotificationConfig {
+            slack: Some(SlackConfig {
+                webhook_url: server.uri(),
+            }),
+            ..Default::default()
+        });
+        let threats = vec![threat(
+            "INJ",
+            Severity::Critical,
+            ThreatCategory::DescriptionInjection,
+        )];
+        notifier.notify_threats("fs", &threats).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["text"], "1 threat(s) found in fs");
+        assert_eq!(body["blocks"][0]["type"], "header");
+        assert_eq!(
+            body["blocks"][1]["text"]["text"],
+            "*[critical]* INJ\nTool: `read_file`"
+        );
+    }
+
+    #[test]
+    fn config_defaults_and_parsing() {
+        let config: NotificationConfig = toml::from_str(
+            r#"
+            webhook_url = "https://example.com/hook"
+            min_severity = "medium"
+            events = ["new_threat"]
+
+            [slack]
+            webhook_url = "https://hooks.slack.com/services/x"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.min_severity, Severity::Medium);
+        assert_eq!(config.events, vec![NotifyEvent::NewThreat]);
+        assert!(config.slack.is_some());
+
+        let defaults: NotificationConfig = toml::from_str("").unwrap();
+        assert_eq!(defaults.min_severity, Severity::High);
+        assert_eq!(defaults.events.len(), 2);
+        assert!(!WebhookNotifier::new(defaults).is_enabled());
+    }
+}
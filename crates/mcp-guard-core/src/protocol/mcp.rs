@@ -1,10 +1,35 @@
 //! MCP (Model Context Protocol) message types.
 
+use crate::error::{Error, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 pub const MCP_PROTOCOL_VERSION: &str = "2025-11-25";
 
+/// Protocol versions a server may answer `initialize` with, newest first.
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &[
+    MCP_PROTOCOL_VERSION,
+    "2025-06-18",
+    "2025-03-26",
+    "2024-11-05",
+];
+
+/// Accept the protocol version a server chose in its `InitializeResult` if
+/// it's one we support, even when it differs from the one we requested.
+pub fn negotiate_protocol_version(server_version: &str) -> Result<&'static str> {
+    SUPPORTED_PROTOCOL_VERSIONS
+        .iter()
+        .find(|v| **v == server_version)
+        .copied()
+        .ok_or_else(|| Error::IncompatibleProtocol {
+            server_version: server_version.to_string(),
+            client_supported: SUPPORTED_PROTOCOL_VERSIONS
+                .iter()
+                .map(|v| v.to_string())
+                .collect(),
+        })
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InitializeParams {
@@ -76,6 +101,10 @@ impl ServerCapabilities {
     pub fn has_resources(&self) -> bool {
         self.resources.is_some()
     }
+
+    pub fn has_prompts(&self) -> bool {
+        self.prompts.is_some()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -120,12 +149,40 @@ pub struct Resource {
     pub mime_type: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptsListResult {
+    pub prompts: Vec<Prompt>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Prompt {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arguments: Vec<PromptArgument>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptArgument {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
 pub const NOTIFICATION_INITIALIZED: &str = "notifications/initialized";
 
 pub mod methods {
     pub const INITIALIZE: &str = "initialize";
     pub const TOOLS_LIST: &str = "tools/list";
+    pub const TOOLS_CALL: &str = "tools/call";
     pub const RESOURCES_LIST: &str = "resources/list";
+    pub const PROMPTS_LIST: &str = "prompts/list";
 }
 
 #[cfg(test)]
@@ -139,6 +196,29 @@ mod tests {
         assert_eq!(params.client_info.name, "mcp-guard");
     }
 
+    #[test]
+    fn negotiates_supported_versions() {
+        assert_eq!(
+            negotiate_protocol_version(MCP_PROTOCOL_VERSION).unwrap(),
+            MCP_PROTOCOL_VERSION
+        );
+        assert_eq!(
+            negotiate_protocol_version("2024-11-05").unwrap(),
+            "2024-11-05"
+        );
+
+        match negotiate_protocol_version("2023-01-01") {
+            Err(Error::IncompatibleProtocol {
+                server_version,
+                client_supported,
+            }) => {
+                assert_eq!(server_version, "2023-01-01");
+                assert_eq!(client_supported.len(), SUPPORTED_PROTOCOL_VERSIONS.len());
+            }
+            other => panic!("expected IncompatibleProtocol, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_tool_serialization() {
         let tool = Tool {
@@ -171,6 +251,26 @@ mod tests {
 
         assert!(caps.has_tools());
         assert!(!caps.has_resources());
+        assert!(!caps.has_prompts());
+    }
+
+    #[test]
+    fn test_prompts_list_deserialization() {
+        let json = r#"{
+            "prompts": [
+                {
+                    "name": "code_review",
+                    "description": "Review a diff",
+                    "arguments": [{"name": "diff", "required": true}]
+                },
+                {"name": "summarize"}
+            ]
+        }"#;
+
+        let result: PromptsListResult = serde_json::from_str(json).unwrap();
+        assert_eq!(result.prompts.len(), 2);
+        assert!(result.prompts[0].arguments[0].required);
+        assert!(result.prompts[1].arguments.is_empty());
     }
 
     #[test]
@@ -0,0 +1,166 @@
+//! Streamable HTTP transport for remote MCP servers.
+//!
+//! Every JSON-RPC message is sent as its own HTTP POST. The server replies
+//! either with a JSON body or with a short `text/event-stream` body carrying
+//! the response. Session IDs handed out by the server via `Mcp-Session-Id`
+//! are echoed on subsequent requests.
+
+use crate::error::{Error, Result};
+use crate::protocol::jsonrpc::{Message, Notification, Request, Response};
+use crate::protocol::transport::sse::SseParser;
+use crate::protocol::transport::{header_map, http_error, match_response, McpTransport};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, ACCEPT, CONTENT_TYPE};
+use reqwest::Url;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+pub(crate) const SESSION_HEADER: &str = "Mcp-Session-Id";
+
+pub struct StreamableHttpTransport {
+    client: reqwest::Client,
+    url: Url,
+    headers: HeaderMap,
+    session_id: Option<String>,
+    timeout: Duration,
+}
+
+impl StreamableHttpTransport {
+    /// Prepare a transport for the MCP endpoint at `url`. No connection is
+    /// made until the first request is sent.
+    pub fn connect(url: &str, headers: &HashMap<String, String>) -> Result<Self> {
+        let url = Url::parse(url).map_err(|e| {
+            Error::invalid_server_config(format!("Invalid HTTP URL '{}': {}", url, e))
+        })?;
+
+        Ok(Self {
+            client: reqwest::Client::new(),
+            url,
+            headers: header_map(headers)?,
+            session_id: None,
+            timeout: Duration::from_secs(30),
+        })
+    }
+
+    /// Give up on opening a connection to the server after `timeout`,
+    /// separately from the timeout of each request.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Result<Self> {
+        self.client = reqwest::Client::builder()
+            .connect_timeout(timeout)
+            .build()
+            .map_err(|e| http_error(e, timeout))?;
+        Ok(self)
+    }
+
+    async fn post(&mut self, body: &impl Serialize) -> Result<reqwest::Response> {
+        let mut request = self
+            .client
+            .post(self.url.clone())
+            .headers(self.headers.clone())
+            .header(ACCEPT, "application/json, text/event-stream")
+            .timeout(self.timeout)
+            .json(body);
+        if let Some(session_id) = &self.session_id {
+            request = request.header(SESSION_HEADER, session_id);
+        }
+
+        let response = request
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| http_error(e, self.timeout))?;
+
+        if let Some(session_id) = response
+            .headers()
+            .get(SESSION_HEADER)
+            .and_then(|v| v.to_str().ok())
+        {
+            self.session_id = Some(session_id.to_string());
+        }
+
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl McpTransport for StreamableHttpTransport {
+    async fn send(&mut self, request: Request) -> Result<Response> {
+        let expected_id = request.id.clone();
+        let response = self.post(&request).await?;
+
+        let is_event_stream = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+        let body = response
+            .text()
+            .await
+            .map_err(|e| http_error(e, self.timeout))?;
+
+        let payloads = if is_event_stream {
+            let mut parser = SseParser::default();
+            let mut events = parser.feed(body.as_bytes());
+            events.extend(parser.finish());
+            events
+                .into_iter()
+                .filter(|e| e.event == "message")
+                .map(|e| e.data)
+                .collect()
+        } else {
+            vec![body]
+        };
+
+        for payload in payloads {
+            let message = Message::parse(&payload).map_err(Error::JsonRpcParse)?;
+            if let Some(result) = match_response(message, &expected_id) {
+                return result;
+            }
+        }
+
+        Err(Error::ConnectionClosed)
+    }
+
+    async fn send_notification(&mut self, notification: Notification) -> Result<()> {
+        self.post(&notification).await?;
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        // Let the server drop the session; failures here don't matter
+        if let Some(session_id) = self.session_id.take() {
+            self.client
+                .delete(self.url.clone())
+                .headers(self.headers.clone())
+                .header(SESSION_HEADER, session_id)
+                .timeout(self.timeout)
+                .send()
+                .await
+                .ok();
+        }
+        Ok(())
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connect_rejects_invalid_url() {
+        let result = StreamableHttpTransport::connect("not a url", &HashMap::new());
+        assert!(matches!(result, Err(Error::InvalidServerConfig { .. })));
+    }
+
+    #[test]
+    fn connect_rejects_invalid_header() {
+        let headers = HashMap::from([("Authorization".to_string(), "bad\nvalue".to_string())]);
+        let result = StreamableHttpTransport::connect("http://localhost/mcp", &headers);
+        assert!(matches!(result, Err(Error::InvalidServerConfig { .. })));
+    }
+}
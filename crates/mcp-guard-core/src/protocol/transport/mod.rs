@@ -0,0 +1,140 @@
+//! Transport layer for MCP communication.
+
+mod http;
+mod sse;
+mod stdio;
+
+pub use http::StreamableHttpTransport;
+#[cfg(feature = "proxy")]
+pub(crate) use http::SESSION_HEADER;
+#[cfg(feature = "proxy")]
+pub(crate) use sse::SseParser;
+pub use sse::SseTransport;
+pub use stdio::StdioTransport;
+
+use crate::error::{Error, Result};
+use crate::protocol::jsonrpc::{Message, Request, RequestId, Response};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[async_trait]
+pub trait McpTransport: Send + Sync {
+    async fn send(&mut self, request: Request) -> Result<Response>;
+
+    async fn send_notification(
+        &mut self,
+        notification: crate::protocol::jsonrpc::Notification,
+    ) -> Result<()>;
+
+    async fn close(&mut self) -> Result<()>;
+
+    fn set_timeout(&mut self, timeout: Duration);
+}
+
+/// Build HTTP headers for a remote server from its configured environment.
+///
+/// The first variable (in name order) whose name contains `TOKEN`, `KEY`, or
+/// `BEARER` becomes an `Authorization: Bearer` header. Values that already
+/// carry a scheme (`Bearer ...`, `Basic ...`) are passed through unchanged.
+pub fn auth_headers(env: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut names: Vec<&String> = env.keys().collect();
+    names.sort();
+
+    let credential = names.into_iter().find(|name| {
+        let upper = name.to_uppercase();
+        ["TOKEN", "KEY", "BEARER"]
+            .iter()
+            .any(|marker| upper.contains(marker))
+    });
+
+    let mut headers = HashMap::new();
+    if let Some(value) = credential.and_then(|name| env.get(name)) {
+        let value = if value.starts_with("Bearer ") || value.starts_with("Basic ") {
+            value.clone()
+        } else {
+            format!("Bearer {}", value)
+        };
+        headers.insert("Authorization".to_string(), value);
+    }
+    headers
+}
+
+/// Convert configured header pairs into a reqwest header map.
+pub(crate) fn header_map(headers: &HashMap<String, String>) -> Result<reqwest::header::HeaderMap> {
+    let mut map = reqwest::header::HeaderMap::new();
+    for (name, value) in headers {
+        let name = reqwest::header::HeaderName::from_bytes(name.as_bytes()).map_err(|e| {
+            Error::invalid_server_config(format!("Invalid header name '{}': {}", name, e))
+        })?;
+        let value = reqwest::header::HeaderValue::from_str(value).map_err(|e| {
+            Error::invalid_server_config(format!("Invalid value for header '{}': {}", name, e))
+        })?;
+        map.insert(name, value);
+    }
+    Ok(map)
+}
+
+/// Report reqwest timeouts the same way the STDIO transport does.
+pub(crate) fn http_error(error: reqwest::Error, timeout: Duration) -> Error {
+    if error.is_timeout() {
+        Error::Timeout {
+            timeout_secs: timeout.as_secs(),
+        }
+    } else {
+        Error::HttpTransport(error)
+    }
+}
+
+/// Match an incoming message against the request we're waiting on.
+///
+/// Returns `None` for anything that isn't the reply to `expected_id`
+/// (notifications, server-initiated requests, replies to other requests).
+pub(crate) fn match_response(
+    message: Message,
+    expected_id: &RequestId,
+) -> Option<Result<Response>> {
+    match message {
+        Message::Response(response) if &response.id == expected_id => Some(Ok(response)),
+        Message::Error(error) if &error.id == expected_id => Some(Err(Error::JsonRpc {
+            code: error.error.code,
+            message: error.error.message,
+            data: error.error.data,
+        })),
+        Message::Request(req) => {
+            tracing::debug!(method = %req.method, "Ignoring server-initiated request");
+            None
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auth_headers_uses_token_variable() {
+        let env = HashMap::from([
+            ("HOME".to_string(), "/home/me".to_string()),
+            ("API_TOKEN".to_string(), "abc123".to_string()),
+        ]);
+
+        let headers = auth_headers(&env);
+        assert_eq!(headers.get("Authorization").unwrap(), "Bearer abc123");
+    }
+
+    #[test]
+    fn auth_headers_keeps_existing_scheme() {
+        let env = HashMap::from([("BEARER".to_string(), "Basic dXNlcjpwYXNz".to_string())]);
+
+        let headers = auth_headers(&env);
+        assert_eq!(headers.get("Authorization").unwrap(), "Basic dXNlcjpwYXNz");
+    }
+
+    #[test]
+    fn auth_headers_empty_without_credentials() {
+        let env = HashMap::from([("DEBUG".to_string(), "1".to_string())]);
+        assert!(auth_headers(&env).is_empty());
+    }
+}
@@ -0,0 +1,272 @@
+//! SSE transport for remote MCP servers.
+//!
+//! Implements the HTTP+SSE transport: the client holds open a
+//! `text/event-stream` GET, POSTs JSON-RPC requests, and receives replies as
+//! `message` events on the stream. Servers announce where requests should be
+//! POSTed with an initial `endpoint` event; if none arrives, requests go to
+//! the stream URL itself.
+
+use crate::error::{Error, Result};
+use crate::protocol::jsonrpc::{Message, Notification, Request, Response};
+use crate::protocol::transport::{header_map, http_error, match_response, McpTransport};
+use async_trait::async_trait;
+use futures::StreamExt;
+use reqwest::header::{HeaderMap, ACCEPT};
+use reqwest::Url;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+
+/// How long to wait for the server's `endpoint` event before falling back
+/// to POSTing at the stream URL.
+const ENDPOINT_WAIT: Duration = Duration::from_secs(5);
+
+/// A single server-sent event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SseEvent {
+    pub event: String,
+    pub data: String,
+}
+
+/// Incremental parser for `text/event-stream` bodies.
+#[derive(Debug, Default)]
+pub(crate) struct SseParser {
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    /// Feed a chunk of the stream, returning every event it completes.
+    pub(crate) fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut events = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let raw: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&raw);
+            if let Some(event) = self.process_line(line.trim_end_matches(['\n', '\r'])) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    /// Flush an event left at the end of the body without a trailing blank line.
+    pub(crate) fn finish(&mut self) -> Option<SseEvent> {
+        if !self.buffer.is_empty() {
+            let raw = std::mem::take(&mut self.buffer);
+            let line = String::from_utf8_lossy(&raw).into_owned();
+            if let Some(event) = self.process_line(line.trim_end_matches('\r')) {
+                return Some(event);
+            }
+        }
+        self.dispatch()
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            // Comment / keep-alive
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event = self.event.take();
+        if self.data.is_empty() {
+            return None;
+        }
+        Some(SseEvent {
+            event: event.unwrap_or_else(|| "message".to_string()),
+            data: std::mem::take(&mut self.data).join("\n"),
+        })
+    }
+}
+
+pub struct SseTransport {
+    client: reqwest::Client,
+    endpoint: Url,
+    headers: HeaderMap,
+    events: mpsc::UnboundedReceiver<SseEvent>,
+    reader: JoinHandle<()>,
+    timeout: Duration,
+}
+
+impl SseTransport {
+    /// Open the event stream at `url`, sending `headers` with every request.
+    pub async fn connect(url: &str, headers: &HashMap<String, String>) -> Result<Self> {
+        let base = Url::parse(url).map_err(|e| {
+            Error::invalid_server_config(format!("Invalid SSE URL '{}': {}", url, e))
+        })?;
+        let headers = header_map(headers)?;
+        let client = reqwest::Client::new();
+
+        let response = client
+            .get(base.clone())
+            .headers(headers.clone())
+            .header(ACCEPT, "text/event-stream")
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let (tx, mut events) = mpsc::unbounded_channel();
+        let reader = tokio::spawn(read_events(response, tx));
+
+        let endpoint = match timeout(ENDPOINT_WAIT, events.recv()).await {
+            Ok(Some(event)) if event.event == "endpoint" => {
+                base.join(event.data.trim()).map_err(|e| {
+                    Error::invalid_server_config(format!(
+                        "Invalid endpoint '{}' announced by {}: {}",
+                        event.data, url, e
+                    ))
+                })?
+            }
+            Ok(None) => {
+                reader.abort();
+                return Err(Error::ConnectionClosed);
+            }
+            _ => base,
+        };
+
+        tracing::debug!(endpoint = %endpoint, "SSE transport connected");
+
+        Ok(Self {
+            client,
+            endpoint,
+            headers,
+            events,
+            reader,
+            timeout: Duration::from_secs(30),
+        })
+    }
+
+    async fn post(&self, body: &impl Serialize) -> Result<()> {
+        self.client
+            .post(self.endpoint.clone())
+            .headers(self.headers.clone())
+            .timeout(self.timeout)
+            .json(body)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| http_error(e, self.timeout))?;
+        Ok(())
+    }
+}
+
+async fn read_events(response: reqwest::Response, tx: mpsc::UnboundedSender<SseEvent>) {
+    let mut stream = response.bytes_stream();
+    let mut parser = SseParser::default();
+
+    while let Some(Ok(chunk)) = stream.next().await {
+        for event in parser.feed(&chunk) {
+            if tx.send(event).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl McpTransport for SseTransport {
+    async fn send(&mut self, request: Request) -> Result<Response> {
+        let expected_id = request.id.clone();
+        self.post(&request).await?;
+
+        let timeout_secs = self.timeout.as_secs();
+        let events = &mut self.events;
+        timeout(self.timeout, async {
+            // Replies arrive on the event stream, possibly after other traffic
+            loop {
+                let event = events.recv().await.ok_or(Error::ConnectionClosed)?;
+                if event.event != "message" {
+                    continue;
+                }
+                let message = Message::parse(&event.data).map_err(Error::JsonRpcParse)?;
+                if let Some(result) = match_response(message, &expected_id) {
+                    return result;
+                }
+            }
+        })
+        .await
+        .map_err(|_| Error::Timeout { timeout_secs })?
+    }
+
+    async fn send_notification(&mut self, notification: Notification) -> Result<()> {
+        self.post(&notification).await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.reader.abort();
+        Ok(())
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+}
+
+impl Drop for SseTransport {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_events_across_chunks() {
+        let mut parser = SseParser::default();
+
+        assert!(parser.feed(b"event: endpoint\r\nda").is_empty());
+        let events = parser.feed(b"ta: /messages?session=1\r\n\r\n: ping\n\ndata: {\"a\":1}\n\n");
+
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: "endpoint".to_string(),
+                    data: "/messages?session=1".to_string(),
+                },
+                SseEvent {
+                    event: "message".to_string(),
+                    data: "{\"a\":1}".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn joins_multiline_data_and_flushes_trailing_event() {
+        let mut parser = SseParser::default();
+
+        assert!(parser.feed(b"data: line one\ndata: line two").is_empty());
+        let event = parser.finish().unwrap();
+        assert_eq!(event.data, "line one\nline two");
+    }
+
+    #[tokio::test]
+    async fn connect_to_unreachable_server_fails() {
+        let result = SseTransport::connect("http://127.0.0.1:1/sse", &HashMap::new()).await;
+        assert!(matches!(result, Err(Error::HttpTransport(_))));
+    }
+}
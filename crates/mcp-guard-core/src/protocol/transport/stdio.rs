@@ -5,7 +5,7 @@
 
 use crate::error::{Error, Result};
 use crate::protocol::jsonrpc::{Message, Notification, Request, Response};
-use crate::protocol::transport::McpTransport;
+use crate::protocol::transport::{match_response, McpTransport};
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::process::Stdio;
@@ -33,7 +33,9 @@ impl StdioTransport {
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::inherit())
-            .envs(env);
+            .envs(env)
+            // Reap the server if the transport is dropped early (e.g. after a timeout)
+            .kill_on_drop(true);
 
         let mut child = cmd.spawn().map_err(Error::ProcessSpawn)?;
 
@@ -105,28 +107,8 @@ impl McpTransport for StdioTransport {
             let response_line = self.read_line().await?;
             let message = Message::parse(&response_line).map_err(Error::JsonRpcParse)?;
 
-            match message {
-                Message::Response(response) => {
-                    if response.id == expected_id {
-                        return Ok(response);
-                    }
-                    // ID mismatch - could be response to a different request, skip it
-                }
-                Message::Error(error) => {
-                    if error.id == expected_id {
-                        return Err(Error::JsonRpc {
-                            code: error.error.code,
-                            message: error.error.message,
-                            data: error.error.data,
-                        });
-                    }
-                }
-                Message::Notification(_) => {
-                    // Server-initiated notification, skip it
-                }
-                Message::Request(req) => {
-                    tracing::debug!(method = %req.method, "Ignoring server-initiated request");
-                }
+            if let Some(result) = match_response(message, &expected_id) {
+                return result;
             }
         }
     }
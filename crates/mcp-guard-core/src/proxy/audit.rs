@@ -0,0 +1,385 @@
+//! Audit logging for proxy tool calls.
+//!
+//! Entries go to the database, and optionally also to a JSONL file that
+//! external log shippers can tail. Each line is an [`AuditEntry`] as first
+//! recorded; later updates (results, redacted results, original arguments
+//! of rewritten calls) only reach the database. The file is written by a
+//! background task, so a slow disk doesn't hold up proxied calls.
+
+use crate::db::{AuditEntry, AuditLog, DbPool};
+use crate::error::Result;
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::fs::File;
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::mpsc;
+
+/// Size at which the JSONL audit file is rotated unless overridden with
+/// [`ProxyAudit::with_log_max_bytes`].
+pub const DEFAULT_LOG_MAX_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Rotated files kept next to the JSONL audit file: `<path>.1` (newest)
+/// through `<path>.3`.
+const LOG_ROTATIONS: u32 = 3;
+
+/// Entries waiting to be written to the JSONL file before new ones are
+/// dropped from it.
+const LOG_QUEUE: usize = 1024;
+
+/// Recorded results longer than this, as JSON, are truncated.
+pub const MAX_RECORDED_RESULT_BYTES: usize = 4096;
+
+pub struct ProxyAudit {
+    log: AuditLog,
+    log_file: Option<mpsc::Sender<LogCommand>>,
+    log_max_bytes: u64,
+}
+
+impl ProxyAudit {
+    pub fn new(pool: DbPool) -> Self {
+        Self {
+            log: AuditLog::new(pool),
+            log_file: None,
+            log_max_bytes: DEFAULT_LOG_MAX_BYTES,
+        }
+    }
+
+    /// Also append each entry to `path` as a line of JSON, creating the
+    /// file, readable only by the current user, if needed. Must be called
+    /// from within a Tokio runtime, which runs the writer.
+    pub fn with_log_file(mut self, path: PathBuf) -> Result<Self> {
+        let file = open_log(&path, std::fs::OpenOptions::new().create(true).append(true))?;
+        let log = JsonlLog {
+            len: file.metadata()?.len(),
+            path,
+            writer: BufWriter::new(File::from_std(file)),
+        };
+        let (tx, rx) = mpsc::channel(LOG_QUEUE);
+        tokio::spawn(write_log(log, rx));
+        self.log_file = Some(tx);
+        Ok(self)
+    }
+
+    /// Rotate the JSONL file once it would grow past `bytes`. Defaults to
+    /// [`DEFAULT_LOG_MAX_BYTES`].
+    pub fn with_log_max_bytes(mut self, bytes: u64) -> Self {
+        self.log_max_bytes = bytes;
+        self
+    }
+
+    /// Record a tool call, returning the audit entry's ID if it was saved.
+    #[allow(clippy::too_many_arguments)] // All fields needed to construct audit entry
+    pub fn record_call(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        tool_args: Option<serde_json::Value>,
+        result: Option<serde_json::Value>,
+        blocked: bool,
+        block_reason: Option<String>,
+        duration: Duration,
+    ) -> Option<i64> {
+        let entry = AuditEntry {
+            id: 0,
+            timestamp: Utc::now(),
+            server_name: server_name.to_string(),
+            tool_name: tool_name.to_string(),
+            tool_args,
+            result,
+            blocked,
+            block_reason,
+            duration_ms: duration.as_millis() as u64,
+            redacted: false,
+            original_args: None,
+            oversized: false,
+        };
+        self.record(entry)
+    }
+
+    /// Record a request rejected for exceeding the size limit. Its arguments
+    /// aren't stored, since their size is the problem.
+    pub fn record_oversized(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        size: usize,
+        limit: usize,
+        duration: Duration,
+    ) -> Option<i64> {
+        let entry = AuditEntry {
+            id: 0,
+            timestamp: Utc::now(),
+            server_name: server_name.to_string(),
+            tool_name: tool_name.to_string(),
+            tool_args: None,
+            result: None,
+            blocked: true,
+            block_reason: Some(format!(
+                "Request too large: {} bytes (limit {})",
+                size, limit
+            )),
+            duration_ms: duration.as_millis() as u64,
+            redacted: false,
+            original_args: None,
+            oversized: true,
+        };
+        self.record(entry)
+    }
+
+    fn record(&self, mut entry: AuditEntry) -> Option<i64> {
+        let id = match self.log.insert(&entry) {
+            Ok(id) => Some(id),
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to record audit entry");
+                None
+            }
+        };
+
+        if let Some(log_file) = &self.log_file {
+            entry.id = id.unwrap_or(0);
+            let append = LogCommand::Append {
+                entry,
+                max_bytes: self.log_max_bytes,
+            };
+            if let Err(e) = log_file.try_send(append) {
+                tracing::error!(error = %e, "Audit entry not written to log file");
+            }
+        }
+        id
+    }
+
+    /// Wait until every entry recorded so far is in the JSONL file.
+    pub async fn flush_log_file(&self) {
+        if let Some(log_file) = &self.log_file {
+            let (done, written) = tokio::sync::oneshot::channel();
+            if log_file.send(LogCommand::Flush(done)).await.is_ok() {
+                let _ = written.await;
+            }
+        }
+    }
+
+    /// Record the arguments the client sent for a call whose arguments were
+    /// rewritten before forwarding.
+    pub fn record_rewrite(&self, id: i64, original_args: &serde_json::Value) {
+        if let Err(e) = self.log.set_original_args(id, original_args) {
+            tracing::error!(error = %e, "Failed to record original arguments");
+        }
+    }
+
+    /// Store the server's response to a recorded call. Results over
+    /// [`MAX_RECORDED_RESULT_BYTES`] are stored as a truncated JSON string.
+    pub fn record_result(&self, id: i64, result: &serde_json::Value) {
+        if let Err(e) = self.log.set_result(id, &truncate_result(result)) {
+            tracing::error!(error = %e, "Failed to record tool result");
+        }
+    }
+
+    /// Mark a recorded call as redacted, storing the filtered result.
+    pub fn record_redaction(&self, id: i64, result: &serde_json::Value) {
+        if let Err(e) = self.log.mark_redacted(id, Some(result)) {
+            tracing::error!(error = %e, "Failed to record redaction");
+        }
+    }
+}
+
+/// `result` unchanged if it's small enough to record, otherwise the start of
+/// its JSON text with a note of how long it was.
+fn truncate_result(result: &serde_json::Value) -> serde_json::Value {
+    let json = result.to_string();
+    if json.len() <= MAX_RECORDED_RESULT_BYTES {
+        return result.clone();
+    }
+    let mut end = MAX_RECORDED_RESULT_BYTES;
+    while !json.is_char_boundary(end) {
+        end -= 1;
+    }
+    serde_json::Value::String(format!(
+        "{}... [truncated from {} bytes]",
+        &json[..end],
+        json.len()
+    ))
+}
+
+enum LogCommand {
+    Append { entry: AuditEntry, max_bytes: u64 },
+    Flush(tokio::sync::oneshot::Sender<()>),
+}
+
+/// Write entries to `log` until every [`ProxyAudit`] sender is dropped.
+async fn write_log(mut log: JsonlLog, mut commands: mpsc::Receiver<LogCommand>) {
+    while let Some(command) = commands.recv().await {
+        match command {
+            LogCommand::Append { entry, max_bytes } => {
+                if let Err(e) = log.append(&entry, max_bytes).await {
+                    tracing::error!(error = %e, path = %log.path.display(), "Failed to write audit log file");
+                }
+            }
+            LogCommand::Flush(done) => {
+                let _ = done.send(());
+            }
+        }
+    }
+}
+
+/// Open `path` with `options`, creating it readable only by the current
+/// user, since entries include tool arguments.
+fn open_log(path: &Path, options: &mut std::fs::OpenOptions) -> std::io::Result<std::fs::File> {
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(options, 0o600);
+    options.open(path)
+}
+
+/// Append-only JSONL file of audit entries, rotated by size.
+struct JsonlLog {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    len: u64,
+}
+
+impl JsonlLog {
+    async fn append(&mut self, entry: &AuditEntry, max_bytes: u64) -> Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        if self.len > 0 && self.len + line.len() as u64 > max_bytes {
+            self.rotate().await?;
+        }
+
+        self.writer.write_all(&line).await?;
+        // Flush every entry so the file can be tailed as calls happen
+        self.writer.flush().await?;
+        self.len += line.len() as u64;
+        Ok(())
+    }
+
+    /// Shift `<path>.N` to `<path>.N+1`, dropping the oldest, and start a
+    /// fresh file.
+    async fn rotate(&mut self) -> Result<()> {
+        self.writer.flush().await?;
+        for n in (1..LOG_ROTATIONS).rev() {
+            let from = rotated_path(&self.path, n);
+            if tokio::fs::try_exists(&from).await? {
+                tokio::fs::rename(&from, rotated_path(&self.path, n + 1)).await?;
+            }
+        }
+        tokio::fs::rename(&self.path, rotated_path(&self.path, 1)).await?;
+
+        let mut options = tokio::fs::OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        self.writer = BufWriter::new(options.open(&self.path).await?);
+        self.len = 0;
+        Ok(())
+    }
+}
+
+fn rotated_path(path: &Path, n: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::create_pool;
+    use tempfile::tempdir;
+
+    fn read_lines(path: &Path) -> Vec<AuditEntry> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn log_file_gets_one_line_per_entry() {
+        let dir = tempdir().unwrap();
+        let pool = create_pool(&dir.path().join("test.db")).unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let audit = ProxyAudit::new(pool).with_log_file(path.clone()).unwrap();
+
+        for i in 0..10 {
+            audit.record_call(
+                "server",
+                &format!("tool_{}", i),
+                Some(serde_json::json!({ "i": i })),
+                None,
+                i % 2 == 0,
+                None,
+                Duration::from_millis(5),
+            );
+        }
+
+        audit.flush_log_file().await;
+        let entries = read_lines(&path);
+        assert_eq!(entries.len(), 10);
+        assert_eq!(entries[0].id, 1);
+        assert_eq!(entries[9].tool_name, "tool_9");
+        assert!(entries[0].blocked);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[tokio::test]
+    async fn log_file_rotates_and_keeps_three_old_files() {
+        let dir = tempdir().unwrap();
+        let pool = create_pool(&dir.path().join("test.db")).unwrap();
+        let path = dir.path().join("audit.jsonl");
+        // Small enough that every entry starts a new file
+        let audit = ProxyAudit::new(pool)
+            .with_log_file(path.clone())
+            .unwrap()
+            .with_log_max_bytes(10);
+
+        for i in 0..6 {
+            audit.record_call(
+                "server",
+                &format!("tool_{}", i),
+                None,
+                None,
+                false,
+                None,
+                Duration::ZERO,
+            );
+        }
+
+        audit.flush_log_file().await;
+        assert_eq!(read_lines(&path)[0].tool_name, "tool_5");
+        assert_eq!(read_lines(&rotated_path(&path, 1))[0].tool_name, "tool_4");
+        assert_eq!(read_lines(&rotated_path(&path, 3))[0].tool_name, "tool_2");
+        assert!(!rotated_path(&path, 4).exists());
+    }
+
+    #[test]
+    fn large_results_are_truncated() {
+        let dir = tempdir().unwrap();
+        let pool = create_pool(&dir.path().join("test.db")).unwrap();
+        let audit = ProxyAudit::new(pool.clone());
+        let small = audit
+            .record_call("server", "small", None, None, false, None, Duration::ZERO)
+            .unwrap();
+        let large = audit
+            .record_call("server", "large", None, None, false, None, Duration::ZERO)
+            .unwrap();
+
+        let result = serde_json::json!({ "content": [{ "type": "text", "text": "ok" }] });
+        audit.record_result(small, &result);
+        audit.record_result(large, &serde_json::json!({ "text": "x".repeat(10_000) }));
+
+        let entries = AuditLog::new(pool).list_after(None, 10).unwrap().0;
+        let entry = |id| entries.iter().find(|e| e.id == id).unwrap();
+        assert_eq!(entry(small).result.as_ref(), Some(&result));
+        let truncated = entry(large).result.as_ref().unwrap().as_str().unwrap();
+        assert!(truncated.len() < MAX_RECORDED_RESULT_BYTES + 64);
+        assert!(truncated.ends_with("[truncated from 10011 bytes]"));
+    }
+}
@@ -0,0 +1,189 @@
+//! Per-tool circuit breaker for the proxy.
+//!
+//! A tool that keeps failing floods the model with errors it can't act on.
+//! After `threshold` consecutive JSON-RPC errors from a tool within the
+//! window, its circuit opens and calls are refused without reaching the
+//! server. Once `recovery` has passed, one trial call is let through: success
+//! closes the circuit, another error opens it again.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Reason given to the client for calls refused by an open circuit.
+pub const CIRCUIT_OPEN_REASON: &str = "Circuit open: too many errors";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls go through; errors are being counted
+    Closed,
+    /// Calls are refused until the recovery time has passed
+    Open,
+    /// A trial call is allowed to decide whether to close again
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct Circuit {
+    state: CircuitState,
+    /// Consecutive errors since the last success
+    failures: u32,
+    /// When the current run of errors started
+    first_failure: Option<Instant>,
+    /// When the circuit opened, or when the trial call was let through
+    since: Instant,
+}
+
+impl Circuit {
+    fn new() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            failures: 0,
+            first_failure: None,
+            since: Instant::now(),
+        }
+    }
+}
+
+pub struct CircuitBreaker {
+    threshold: u32,
+    window: Duration,
+    recovery: Duration,
+    circuits: Mutex<HashMap<String, Circuit>>,
+}
+
+impl CircuitBreaker {
+    /// Open a tool's circuit after `threshold` consecutive errors within
+    /// `window`, and try it again after `recovery`. A threshold below 1 is
+    /// treated as 1.
+    pub fn new(threshold: u32, window: Duration, recovery: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            window,
+            recovery,
+            circuits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether a call to `tool` may go to the server. Moves an open circuit
+    /// whose recovery time has passed to half-open and lets that call
+    /// through as the trial. If a trial never reports back, for example
+    /// because a rule blocked it, another is allowed after `recovery`.
+    pub fn allow(&self, tool: &str) -> bool {
+        let mut circuits = self.circuits.lock().unwrap();
+        let Some(circuit) = circuits.get_mut(tool) else {
+            return true;
+        };
+
+        match circuit.state {
+            CircuitState::Closed => true,
+            CircuitState::Open | CircuitState::HalfOpen
+                if circuit.since.elapsed() >= self.recovery =>
+            {
+                circuit.state = CircuitState::HalfOpen;
+                circuit.since = Instant::now();
+                true
+            }
+            CircuitState::Open | CircuitState::HalfOpen => false,
+        }
+    }
+
+    /// Record a successful response from `tool`, closing its circuit.
+    pub fn record_success(&self, tool: &str) {
+        self.circuits.lock().unwrap().remove(tool);
+    }
+
+    /// Record an error response from `tool`. Returns whether this opened
+    /// the circuit.
+    pub fn record_failure(&self, tool: &str) -> bool {
+        let mut circuits = self.circuits.lock().unwrap();
+        let circuit = circuits
+            .entry(tool.to_string())
+            .or_insert_with(Circuit::new);
+        let now = Instant::now();
+
+        if circuit.state == CircuitState::HalfOpen {
+            circuit.state = CircuitState::Open;
+            circuit.since = now;
+            return true;
+        }
+
+        // Errors spread out further than the window don't add up
+        match circuit.first_failure {
+            Some(first) if now.duration_since(first) <= self.window => circuit.failures += 1,
+            _ => {
+                circuit.failures = 1;
+                circuit.first_failure = Some(now);
+            }
+        }
+
+        if circuit.state == CircuitState::Closed && circuit.failures >= self.threshold {
+            circuit.state = CircuitState::Open;
+            circuit.since = now;
+            return true;
+        }
+        false
+    }
+
+    pub fn state(&self, tool: &str) -> CircuitState {
+        self.circuits
+            .lock()
+            .unwrap()
+            .get(tool)
+            .map_or(CircuitState::Closed, |c| c.state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opens_after_consecutive_errors_and_recovers() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(60), Duration::from_millis(50));
+
+        assert!(!breaker.record_failure("flaky"));
+        assert!(!breaker.record_failure("flaky"));
+        assert!(breaker.allow("flaky"));
+        assert!(breaker.record_failure("flaky"));
+        assert_eq!(breaker.state("flaky"), CircuitState::Open);
+        assert!(!breaker.allow("flaky"));
+        assert!(breaker.allow("other"));
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(breaker.allow("flaky"));
+        assert_eq!(breaker.state("flaky"), CircuitState::HalfOpen);
+        // Only the one trial call until it reports back
+        assert!(!breaker.allow("flaky"));
+
+        // A failed trial opens the circuit again at once
+        assert!(breaker.record_failure("flaky"));
+        assert!(!breaker.allow("flaky"));
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(breaker.allow("flaky"));
+        breaker.record_success("flaky");
+        assert_eq!(breaker.state("flaky"), CircuitState::Closed);
+        assert!(breaker.allow("flaky"));
+    }
+
+    #[test]
+    fn success_resets_the_error_count() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60), Duration::from_secs(60));
+
+        breaker.record_failure("tool");
+        breaker.record_success("tool");
+        assert!(!breaker.record_failure("tool"));
+        assert_eq!(breaker.state("tool"), CircuitState::Closed);
+    }
+
+    #[test]
+    fn errors_outside_the_window_do_not_add_up() {
+        let breaker = CircuitBreaker::new(2, Duration::from_millis(20), Duration::from_secs(60));
+
+        breaker.record_failure("tool");
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(!breaker.record_failure("tool"));
+        assert!(breaker.record_failure("tool"));
+    }
+}
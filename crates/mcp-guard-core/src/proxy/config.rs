@@ -0,0 +1,495 @@
+//! TOML proxy config file loaded with `proxy --config`: the server to
+//! proxy, request limits, and rules. Every section is optional, and
+//! command-line flags take precedence over `[server]` and `[limits]`.
+//!
+//! ```toml
+//! [server]
+//! command = "npx"
+//! args = ["-y", "@modelcontextprotocol/server-filesystem", "/tmp"]
+//! name = "filesystem"
+//!
+//! [limits]
+//! max_request_bytes = 524288
+//! upstream_timeout_secs = 30
+//!
+//! [[rules]]
+//! id = "block-shell"
+//! tool_pattern = "shell_*"
+//! action = "block"
+//! reason = "Shell access is disabled"
+//! priority = 10
+//!
+//! [[rules]]
+//! tool_pattern = "write_*"
+//! server = "filesystem"
+//! action = "block"
+//!
+//! [[rules]]
+//! tool_pattern = "api_*"
+//! action = "rate_limit"
+//! max_calls = 10
+//! window_secs = 60
+//! per_client = true
+//!
+//! [[rules]]
+//! tool_pattern = "*"
+//! action = "redact"
+//!
+//! [[rules]]
+//! tool_pattern = "read_*"
+//! action = "sanitize"
+//! field = "path"
+//! transform = "strip_html"
+//!
+//! [[rules]]
+//! tool_pattern = "ask_*"
+//! action = "transform"
+//! field = "prompt"
+//! operation = { clamp_length = 4000 }
+//!
+//! [[rules]]
+//! tool_pattern = "upload_*"
+//! action = "size_limit"
+//! max_bytes = 65536
+//!
+//! [[rules]]
+//! tool_pattern = "debug_*"
+//! action = "hide"
+//! ```
+
+use crate::error::{Error, Result};
+use crate::proxy::rules::{ProxyRule, RuleAction, RuleEngine, SanitizeTransform, TransformOp};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ProxyConfig {
+    /// Server to proxy when neither `--server` nor `--server-url` is given
+    pub server: Option<ProxyServerConfig>,
+    pub limits: LimitsConfig,
+    pub rules: Vec<RuleConfig>,
+}
+
+/// The `[server]` table.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProxyServerConfig {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Server name in the audit log; defaults to the command's file name
+    pub name: Option<String>,
+}
+
+/// The `[limits]` table.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimitsConfig {
+    /// Largest request forwarded to the server; defaults to
+    /// [`DEFAULT_MAX_REQUEST_SIZE`](crate::proxy::interceptor::DEFAULT_MAX_REQUEST_SIZE)
+    pub max_request_bytes: Option<usize>,
+    /// Seconds to wait for the server to answer a request
+    pub upstream_timeout_secs: Option<u64>,
+}
+
+/// One `[[rules]]` entry. Which of the optional fields are required depends
+/// on `action`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleConfig {
+    /// Defaults to `rule-<n>` by position in the file
+    pub id: Option<String>,
+    pub tool_pattern: String,
+    /// Only apply when proxying the server with this name
+    pub server: Option<String>,
+    pub action: ActionKind,
+    /// Message returned to the client for `block`
+    pub reason: Option<String>,
+    /// Required for `rate_limit`
+    pub max_calls: Option<u32>,
+    /// Required for `rate_limit`
+    pub window_secs: Option<u64>,
+    /// Give each client its own `rate_limit` budget instead of sharing one
+    #[serde(default)]
+    pub per_client: bool,
+    /// Extra regex to redact for `redact`, on top of the built-in checks
+    pub pattern: Option<String>,
+    /// Argument to rewrite; required for `sanitize` and `transform`
+    pub field: Option<String>,
+    /// Required for `sanitize`, e.g. `"strip_html"` or `{ truncate_bytes = 1000 }`
+    pub transform: Option<SanitizeTransform>,
+    /// Required for `transform`, e.g. `{ clamp_length = 4000 }` or
+    /// `{ replace = { from = "\r\n", to = "\n" } }`
+    pub operation: Option<TransformOp>,
+    /// Required for `size_limit`
+    pub max_bytes: Option<usize>,
+    #[serde(default)]
+    pub priority: i32,
+    /// RFC 3339 time after which the rule stops applying
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionKind {
+    Allow,
+    Block,
+    RateLimit,
+    Log,
+    Redact,
+    Sanitize,
+    Transform,
+    SizeLimit,
+    Hide,
+}
+
+impl ProxyConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Err(Error::ConfigNotFound {
+                path: path.to_path_buf(),
+            });
+        }
+
+        let content = std::fs::read_to_string(path).map_err(|e| Error::ConfigRead {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        let config: Self =
+            toml::from_str(&content).map_err(|e| Error::config_parse(path, e.to_string()))?;
+
+        // Surface bad actions and patterns now rather than at the first tool call
+        config
+            .rule_engine()
+            .map_err(|e| Error::config_parse(path, e.to_string()))?;
+
+        Ok(config)
+    }
+
+    pub fn proxy_rules(&self) -> Result<Vec<ProxyRule>> {
+        self.rules
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| rule.to_proxy_rule(index))
+            .collect()
+    }
+
+    /// Build a rule engine containing every rule in the file.
+    pub fn rule_engine(&self) -> Result<RuleEngine> {
+        let mut engine = RuleEngine::new();
+        for rule in self.proxy_rules()? {
+            engine.add_rule(rule)?;
+        }
+        Ok(engine)
+    }
+
+    /// Build a rule engine containing the rules that apply when proxying
+    /// `server`: those without a `server` and those naming it.
+    pub fn rule_engine_for(&self, server: &str) -> Result<RuleEngine> {
+        let mut engine = RuleEngine::new();
+        for (index, rule) in self.rules.iter().enumerate() {
+            if rule.applies_to(server) {
+                engine.add_rule(rule.to_proxy_rule(index)?)?;
+            }
+        }
+        Ok(engine)
+    }
+}
+
+impl RuleConfig {
+    /// Whether the rule applies when proxying `server`.
+    pub fn applies_to(&self, server: &str) -> bool {
+        self.server.as_deref().is_none_or(|s| s == server)
+    }
+
+    fn to_proxy_rule(&self, index: usize) -> Result<ProxyRule> {
+        let id = self
+            .id
+            .clone()
+            .unwrap_or_else(|| format!("rule-{}", index + 1));
+
+        let action = match self.action {
+            ActionKind::Allow => RuleAction::Allow,
+            ActionKind::Block => RuleAction::Block {
+                reason: self
+                    .reason
+                    .clone()
+                    .unwrap_or_else(|| "Blocked by policy".to_string()),
+            },
+            ActionKind::RateLimit => match (self.max_calls, self.window_secs) {
+                (Some(max_calls), Some(window_secs)) => RuleAction::RateLimit {
+                    max_calls,
+                    window_secs,
+                    per_client: self.per_client,
+                },
+                _ => {
+                    return Err(Error::Other(format!(
+                        "Rule '{}': rate_limit requires max_calls and window_secs",
+                        id
+                    )))
+                }
+            },
+            ActionKind::Log => RuleAction::Log,
+            ActionKind::Hide => RuleAction::Hide,
+            ActionKind::Redact => RuleAction::Redact {
+                pattern: self.pattern.clone().unwrap_or_default(),
+            },
+            ActionKind::Sanitize => match (&self.field, &self.transform) {
+                (Some(field), Some(transform)) => RuleAction::Sanitize {
+                    field: field.clone(),
+                    transform: transform.clone(),
+                },
+                _ => {
+                    return Err(Error::Other(format!(
+                        "Rule '{}': sanitize requires field and transform",
+                        id
+                    )))
+                }
+            },
+            ActionKind::Transform => match (&self.field, &self.operation) {
+                (Some(field), Some(operation)) => RuleAction::Transform {
+                    field: field.clone(),
+                    operation: operation.clone(),
+                },
+                _ => {
+                    return Err(Error::Other(format!(
+                        "Rule '{}': transform requires field and operation",
+                        id
+                    )))
+                }
+            },
+            ActionKind::SizeLimit => match self.max_bytes {
+                Some(max_bytes) => RuleAction::SizeLimit { max_bytes },
+                None => {
+                    return Err(Error::Other(format!(
+                        "Rule '{}': size_limit requires max_bytes",
+                        id
+                    )))
+                }
+            },
+        };
+
+        Ok(ProxyRule {
+            id,
+            tool_pattern: self.tool_pattern.clone(),
+            action,
+            priority: self.priority,
+            expires_at: self.expires_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy::rules::RuleResult;
+    use tempfile::tempdir;
+
+    fn write_config(content: &str) -> (tempfile::TempDir, std::path::PathBuf) {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("rules.toml");
+        std::fs::write(&path, content).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn loads_all_action_types() {
+        let (_dir, path) = write_config(
+            r#"
+            [[rules]]
+            id = "allow-safe"
+            tool_pattern = "safe_*"
+            action = "allow"
+            priority = 10
+            expires_at = "2099-01-01T00:00:00Z"
+
+            [[rules]]
+            tool_pattern = "*"
+            action = "block"
+            reason = "Denied"
+
+            [[rules]]
+            tool_pattern = "api_*"
+            action = "rate_limit"
+            max_calls = 5
+            window_secs = 60
+            per_client = true
+
+            [[rules]]
+            tool_pattern = "read_*"
+            action = "log"
+
+            [[rules]]
+            tool_pattern = "read_*"
+            action = "sanitize"
+            field = "path"
+            transform = { truncate_bytes = 1000 }
+
+            [[rules]]
+            tool_pattern = "fetch_*"
+            action = "redact"
+            pattern = "emp-\\d+"
+
+            [[rules]]
+            tool_pattern = "write_*"
+            action = "transform"
+            field = "path"
+            operation = { replace = { from = "../", to = "" } }
+
+            [[rules]]
+            tool_pattern = "upload_*"
+            action = "size_limit"
+            max_bytes = 4096
+            "#,
+        );
+
+        let config = ProxyConfig::load(&path).unwrap();
+        let rules = config.proxy_rules().unwrap();
+        assert_eq!(rules.len(), 8);
+        assert!(matches!(
+            rules[7].action,
+            RuleAction::SizeLimit { max_bytes: 4096 }
+        ));
+        assert!(matches!(
+            &rules[6].action,
+            RuleAction::Transform {
+                operation: TransformOp::Replace { from, .. },
+                ..
+            } if from == "../"
+        ));
+        assert!(matches!(
+            &rules[4].action,
+            RuleAction::Sanitize {
+                transform: SanitizeTransform::TruncateBytes(1000),
+                ..
+            }
+        ));
+        assert_eq!(rules[0].id, "allow-safe");
+        assert_eq!(
+            rules[0].expires_at.map(|t| t.to_rfc3339()).as_deref(),
+            Some("2099-01-01T00:00:00+00:00")
+        );
+        assert!(rules[1].expires_at.is_none());
+        assert_eq!(rules[1].id, "rule-2");
+        assert!(matches!(
+            rules[2].action,
+            RuleAction::RateLimit {
+                max_calls: 5,
+                window_secs: 60,
+                per_client: true
+            }
+        ));
+
+        let engine = config.rule_engine().unwrap();
+        assert!(engine.redact_patterns("fetch_page").is_some());
+        assert!(matches!(engine.evaluate("safe_tool")[0], RuleResult::Allow));
+        match &engine.evaluate("other")[0] {
+            RuleResult::Block { reason } => assert_eq!(reason, "Denied"),
+            other => panic!("Expected block, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rate_limit_without_limits_is_rejected() {
+        let (_dir, path) = write_config(
+            r#"
+            [[rules]]
+            tool_pattern = "api_*"
+            action = "rate_limit"
+            "#,
+        );
+
+        let err = ProxyConfig::load(&path).unwrap_err();
+        assert!(err.to_string().contains("max_calls"), "{}", err);
+    }
+
+    #[test]
+    fn unknown_action_is_rejected() {
+        let (_dir, path) = write_config(
+            r#"
+            [[rules]]
+            tool_pattern = "*"
+            action = "quarantine"
+            "#,
+        );
+
+        assert!(matches!(
+            ProxyConfig::load(&path),
+            Err(Error::ConfigParse { .. })
+        ));
+    }
+
+    #[test]
+    fn loads_server_and_limits() {
+        let (_dir, path) = write_config(
+            r#"
+            [server]
+            command = "npx"
+            args = ["-y", "server-filesystem"]
+
+            [limits]
+            max_request_bytes = 4096
+            "#,
+        );
+
+        let config = ProxyConfig::load(&path).unwrap();
+        let server = config.server.unwrap();
+        assert_eq!(server.command, "npx");
+        assert_eq!(server.args, ["-y", "server-filesystem"]);
+        assert_eq!(server.name, None);
+        assert_eq!(config.limits.max_request_bytes, Some(4096));
+        assert_eq!(config.limits.upstream_timeout_secs, None);
+        assert!(config.rules.is_empty());
+
+        let (_dir, path) = write_config("[server]\nargs = [\"x\"]\n");
+        assert!(matches!(
+            ProxyConfig::load(&path),
+            Err(Error::ConfigParse { .. })
+        ));
+        let (_dir, path) = write_config("[limits]\ntimeout = 5\n");
+        assert!(matches!(
+            ProxyConfig::load(&path),
+            Err(Error::ConfigParse { .. })
+        ));
+    }
+
+    #[test]
+    fn server_scoped_rules_only_apply_to_their_server() {
+        let (_dir, path) = write_config(
+            r#"
+            [[rules]]
+            tool_pattern = "write_*"
+            server = "filesystem"
+            action = "block"
+
+            [[rules]]
+            tool_pattern = "*"
+            action = "log"
+            "#,
+        );
+
+        let config = ProxyConfig::load(&path).unwrap();
+        let blocks = |server: &str| {
+            config
+                .rule_engine_for(server)
+                .unwrap()
+                .evaluate("write_file")
+                .iter()
+                .any(|r| matches!(r, RuleResult::Block { .. }))
+        };
+        assert!(blocks("filesystem"));
+        assert!(!blocks("github"));
+        assert!(config.rules[1].applies_to("github"));
+    }
+
+    #[test]
+    fn missing_file_is_not_found() {
+        let dir = tempdir().unwrap();
+        assert!(matches!(
+            ProxyConfig::load(&dir.path().join("missing.toml")),
+            Err(Error::ConfigNotFound { .. })
+        ));
+    }
+}
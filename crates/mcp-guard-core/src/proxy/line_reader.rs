@@ -0,0 +1,205 @@
+//! Reads newline-delimited JSON-RPC messages without holding more than a
+//! fixed number of bytes of any one line in memory.
+
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Bytes of an oversized line's end kept by [`LineReader::skip_rest`], enough
+/// for a trailing `"id"`.
+const TAIL_BYTES: usize = 256;
+
+/// A line returned by [`LineReader::next_line`].
+pub(crate) enum Line {
+    /// A whole line, with its newline if it had one.
+    Complete(String),
+    /// The first `limit` bytes of a longer line. The rest is still unread
+    /// and must be passed on with [`LineReader::copy_rest`] or dropped with
+    /// [`LineReader::skip_rest`] before the next line is read.
+    Oversized(Vec<u8>),
+}
+
+pub(crate) struct LineReader<R> {
+    reader: R,
+    /// The line read so far, kept here so a cancelled read loses nothing
+    buf: Vec<u8>,
+    limit: usize,
+}
+
+impl<R: AsyncBufRead + Unpin> LineReader<R> {
+    /// Read lines from `reader`, returning those over `limit` bytes,
+    /// excluding the newline, as [`Line::Oversized`].
+    pub(crate) fn new(reader: R, limit: usize) -> Self {
+        Self {
+            reader,
+            buf: Vec::new(),
+            limit,
+        }
+    }
+
+    /// The next line, or `None` at end of input. Cancel safe, so it can be
+    /// used as a `select!` branch: part of a line read before cancellation
+    /// is kept for the next call.
+    pub(crate) async fn next_line(&mut self) -> std::io::Result<Option<Line>> {
+        loop {
+            let available = self.reader.fill_buf().await?;
+            if available.is_empty() {
+                if self.buf.is_empty() {
+                    return Ok(None);
+                }
+                return Ok(Some(Line::Complete(self.take_line())));
+            }
+
+            let room = self.limit - self.buf.len();
+            match available.iter().position(|&b| b == b'\n') {
+                Some(end) if end <= room => {
+                    self.buf.extend_from_slice(&available[..=end]);
+                    self.reader.consume(end + 1);
+                    return Ok(Some(Line::Complete(self.take_line())));
+                }
+                _ if available.len() > room => {
+                    self.buf.extend_from_slice(&available[..room]);
+                    self.reader.consume(room);
+                    return Ok(Some(Line::Oversized(std::mem::take(&mut self.buf))));
+                }
+                _ => {
+                    let len = available.len();
+                    self.buf.extend_from_slice(available);
+                    self.reader.consume(len);
+                }
+            }
+        }
+    }
+
+    /// Drop the rest of an oversized line, returning how many bytes were
+    /// dropped, newline excluded, and the last few of them.
+    pub(crate) async fn skip_rest(&mut self) -> std::io::Result<(usize, Vec<u8>)> {
+        let mut skipped = 0;
+        let mut tail = Vec::new();
+        self.pass_rest(|chunk| {
+            skipped += chunk.len();
+            tail.extend_from_slice(chunk);
+            let excess = tail.len().saturating_sub(TAIL_BYTES);
+            tail.drain(..excess);
+        })
+        .await?;
+        Ok((skipped, tail))
+    }
+
+    /// Write `head` and the rest of an oversized line to `writer` as it's
+    /// read, newline included. Returns the line's length, newline excluded.
+    pub(crate) async fn copy_rest(
+        &mut self,
+        head: &[u8],
+        writer: &mut (impl AsyncWrite + Unpin),
+    ) -> std::io::Result<usize> {
+        writer.write_all(head).await?;
+        let mut size = head.len();
+        loop {
+            let available = self.reader.fill_buf().await?;
+            if available.is_empty() {
+                return Ok(size);
+            }
+            let (chunk, done) = match available.iter().position(|&b| b == b'\n') {
+                Some(end) => (&available[..=end], true),
+                None => (available, false),
+            };
+            writer.write_all(chunk).await?;
+            let len = chunk.len();
+            self.reader.consume(len);
+            if done {
+                return Ok(size + len - 1);
+            }
+            size += len;
+        }
+    }
+
+    /// Read up to and including the next newline, passing each chunk before
+    /// it to `f`.
+    async fn pass_rest(&mut self, mut f: impl FnMut(&[u8])) -> std::io::Result<()> {
+        loop {
+            let available = self.reader.fill_buf().await?;
+            if available.is_empty() {
+                return Ok(());
+            }
+            match available.iter().position(|&b| b == b'\n') {
+                Some(end) => {
+                    f(&available[..end]);
+                    self.reader.consume(end + 1);
+                    return Ok(());
+                }
+                None => {
+                    let len = available.len();
+                    f(available);
+                    self.reader.consume(len);
+                }
+            }
+        }
+    }
+
+    fn take_line(&mut self) -> String {
+        String::from_utf8_lossy(&std::mem::take(&mut self.buf)).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::io::BufReader;
+
+    fn reader(input: &'static [u8], limit: usize) -> LineReader<BufReader<&'static [u8]>> {
+        // A tiny buffer, so lines arrive in several chunks
+        LineReader::new(BufReader::with_capacity(4, input), limit)
+    }
+
+    async fn complete(reader: &mut LineReader<impl AsyncBufRead + Unpin>) -> String {
+        match reader.next_line().await.unwrap() {
+            Some(Line::Complete(line)) => line,
+            _ => panic!("expected a complete line"),
+        }
+    }
+
+    #[tokio::test]
+    async fn reads_lines_up_to_the_limit() {
+        let mut lines = reader(b"0123456789\nabc\nlast", 10);
+        assert_eq!(complete(&mut lines).await, "0123456789\n");
+        assert_eq!(complete(&mut lines).await, "abc\n");
+        assert_eq!(complete(&mut lines).await, "last");
+        assert!(lines.next_line().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn longer_lines_keep_only_their_head() {
+        let mut lines = reader(b"0123456789abcdef\nnext\n", 10);
+        let Some(Line::Oversized(head)) = lines.next_line().await.unwrap() else {
+            panic!("expected an oversized line");
+        };
+        assert_eq!(head, b"0123456789");
+        assert_eq!(lines.skip_rest().await.unwrap(), (6, b"abcdef".to_vec()));
+        assert_eq!(complete(&mut lines).await, "next\n");
+    }
+
+    #[tokio::test]
+    async fn oversized_lines_can_be_passed_on() {
+        let mut lines = reader(b"0123456789abcdef\nnext\n", 10);
+        let Some(Line::Oversized(head)) = lines.next_line().await.unwrap() else {
+            panic!("expected an oversized line");
+        };
+        let mut out = Vec::new();
+        assert_eq!(lines.copy_rest(&head, &mut out).await.unwrap(), 16);
+        assert_eq!(out, b"0123456789abcdef\n");
+        assert_eq!(complete(&mut lines).await, "next\n");
+    }
+
+    #[tokio::test]
+    async fn cancelled_reads_keep_partial_lines() {
+        let (mut tx, rx) = tokio::io::duplex(64);
+        let mut lines = LineReader::new(BufReader::new(rx), 100);
+
+        tx.write_all(b"{\"id\":").await.unwrap();
+        let read = tokio::time::timeout(Duration::from_millis(20), lines.next_line()).await;
+        assert!(read.is_err());
+
+        tx.write_all(b"1}\n").await.unwrap();
+        assert_eq!(complete(&mut lines).await, "{\"id\":1}\n");
+    }
+}
@@ -0,0 +1,20 @@
+//! STDIO proxy for MCP servers with rule-based filtering and audit logging,
+//! for local servers or, through [`SseProxyInterceptor`], remote ones.
+
+pub mod audit;
+pub mod circuit_breaker;
+pub mod config;
+pub mod filter;
+pub mod interceptor;
+mod line_reader;
+pub mod rules;
+pub mod sse_proxy;
+pub mod store;
+
+pub use audit::ProxyAudit;
+pub use circuit_breaker::{CircuitBreaker, CircuitState};
+pub use config::ProxyConfig;
+pub use filter::{ResponseFilter, SensitiveDataFilter};
+pub use interceptor::ProxyInterceptor;
+pub use sse_proxy::SseProxyInterceptor;
+pub use store::{RuleStore, StoredRule};
@@ -0,0 +1,1059 @@
+//! Proxy rule engine for filtering and rate-limiting tool calls.
+
+use crate::error::{Error, Result};
+use crate::proxy::store::RuleStore;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use glob::Pattern;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, LazyLock, Mutex, RwLock, RwLockReadGuard};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyRule {
+    pub id: String,
+    pub tool_pattern: String,
+    pub action: RuleAction,
+    pub priority: i32,
+    /// After this time the rule no longer matches anything
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl ProxyRule {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|expires| expires < Utc::now())
+    }
+
+    /// Whether `tool_pattern` matches the tool name. Invalid patterns match nothing.
+    pub fn matches(&self, tool_name: &str) -> bool {
+        Pattern::new(&self.tool_pattern).is_ok_and(|p| p.matches(tool_name))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleAction {
+    Allow,
+    Block {
+        reason: String,
+    },
+    /// Allow at most `max_calls` calls per `window_secs`. With `per_client`,
+    /// each client connected through the proxy gets its own budget.
+    RateLimit {
+        max_calls: u32,
+        window_secs: u64,
+        #[serde(default)]
+        per_client: bool,
+    },
+    Log,
+    /// Replace sensitive data in matching tools' responses with `[REDACTED]`.
+    /// `pattern` is an extra regex to redact on top of the built-in secret,
+    /// private key, and card number checks; leave it empty for just those.
+    Redact {
+        pattern: String,
+    },
+    /// Rewrite the string argument `field` before the call is forwarded.
+    Sanitize {
+        field: String,
+        transform: SanitizeTransform,
+    },
+    /// Rewrite the string argument `field` with `operation` before the call
+    /// is forwarded, e.g. to cap the length of a prompt.
+    Transform {
+        field: String,
+        operation: TransformOp,
+    },
+    /// Reject calls to matching tools whose request line is longer than
+    /// `max_bytes`, without forwarding them.
+    SizeLimit {
+        max_bytes: usize,
+    },
+    /// Remove matching tools from `tools/list` responses, so clients never
+    /// see them. Calls to them are still evaluated by the other rules.
+    Hide,
+}
+
+/// Parses the short forms accepted on the command line: `allow`, `log`,
+/// `hide`, `block`, `block:<reason>`, `rate_limit:<max_calls>/<window_secs>`, and
+/// `size_limit:<max_bytes>`.
+impl FromStr for RuleAction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (kind, arg) = match s.split_once(':') {
+            Some((kind, arg)) => (kind, Some(arg)),
+            None => (s, None),
+        };
+
+        match (kind, arg) {
+            ("allow", None) => Ok(RuleAction::Allow),
+            ("log", None) => Ok(RuleAction::Log),
+            ("hide", None) => Ok(RuleAction::Hide),
+            ("block", reason) => Ok(RuleAction::Block {
+                reason: reason
+                    .filter(|r| !r.is_empty())
+                    .unwrap_or("Blocked by proxy rule")
+                    .to_string(),
+            }),
+            ("rate_limit", Some(limit)) => {
+                let parsed = limit
+                    .split_once('/')
+                    .and_then(|(max, window)| Some((max.parse().ok()?, window.parse().ok()?)));
+                match parsed {
+                    Some((max_calls, window_secs)) if window_secs > 0 => Ok(RuleAction::RateLimit {
+                        max_calls,
+                        window_secs,
+                        per_client: false,
+                    }),
+                    _ => Err(Error::Other(format!(
+                        "Invalid rate limit '{}': expected <max_calls>/<window_secs>",
+                        limit
+                    ))),
+                }
+            }
+            ("size_limit", Some(max_bytes)) => match max_bytes.parse() {
+                Ok(max_bytes) if max_bytes > 0 => Ok(RuleAction::SizeLimit { max_bytes }),
+                _ => Err(Error::Other(format!(
+                    "Invalid size limit '{}': expected a number of bytes",
+                    max_bytes
+                ))),
+            },
+            _ => Err(Error::Other(format!(
+                "Unknown rule action '{}': expected allow, log, hide, block[:<reason>], rate_limit:<max>/<window>, or size_limit:<bytes>",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for RuleAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleAction::Allow => write!(f, "allow"),
+            RuleAction::Block { reason } => write!(f, "block ({})", reason),
+            RuleAction::RateLimit {
+                max_calls,
+                window_secs,
+                per_client,
+            } => {
+                write!(f, "rate_limit ({} per {}s", max_calls, window_secs)?;
+                if *per_client {
+                    write!(f, " per client")?;
+                }
+                write!(f, ")")
+            }
+            RuleAction::Log => write!(f, "log"),
+            RuleAction::Redact { pattern } if pattern.is_empty() => write!(f, "redact"),
+            RuleAction::Redact { pattern } => write!(f, "redact ({})", pattern),
+            RuleAction::Sanitize { field, transform } => {
+                write!(f, "sanitize ({}: {:?})", field, transform)
+            }
+            RuleAction::Transform { field, operation } => {
+                write!(f, "transform ({}: {:?})", field, operation)
+            }
+            RuleAction::SizeLimit { max_bytes } => write!(f, "size_limit ({} bytes)", max_bytes),
+            RuleAction::Hide => write!(f, "hide"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SanitizeTransform {
+    /// Remove HTML tags, dropping `<script>` and `<style>` contents entirely
+    StripHtml,
+    /// Cut the value to at most this many bytes, on a character boundary
+    TruncateBytes(usize),
+    /// Keep only characters that appear in this string
+    AllowlistChars(String),
+    /// Decode base64 values; values that aren't valid base64 UTF-8 are kept
+    Base64Decode,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransformOp {
+    /// Add this text before the value
+    Prepend(String),
+    /// Add this text after the value
+    Append(String),
+    /// Replace every occurrence of `from` with `to`
+    Replace { from: String, to: String },
+    /// Keep at most this many characters
+    ClampLength(usize),
+}
+
+impl TransformOp {
+    pub fn apply(&self, value: &str) -> String {
+        match self {
+            TransformOp::Prepend(prefix) => format!("{}{}", prefix, value),
+            TransformOp::Append(suffix) => format!("{}{}", value, suffix),
+            TransformOp::Replace { from, to } if !from.is_empty() => value.replace(from, to),
+            TransformOp::Replace { .. } => value.to_string(),
+            TransformOp::ClampLength(max) => value.chars().take(*max).collect(),
+        }
+    }
+}
+
+static SCRIPT_OR_STYLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<(script|style)\b[^>]*>.*?(</(script|style)\s*>|$)").unwrap()
+});
+
+static HTML_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<[^>]*>").unwrap());
+
+impl SanitizeTransform {
+    pub fn apply(&self, value: &str) -> String {
+        match self {
+            SanitizeTransform::StripHtml => {
+                let without_scripts = SCRIPT_OR_STYLE.replace_all(value, "");
+                HTML_TAG.replace_all(&without_scripts, "").into_owned()
+            }
+            SanitizeTransform::TruncateBytes(max) => {
+                let mut end = (*max).min(value.len());
+                while !value.is_char_boundary(end) {
+                    end -= 1;
+                }
+                value[..end].to_string()
+            }
+            SanitizeTransform::AllowlistChars(allowed) => {
+                value.chars().filter(|c| allowed.contains(*c)).collect()
+            }
+            SanitizeTransform::Base64Decode => base64::engine::general_purpose::STANDARD
+                .decode(value.trim())
+                .ok()
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+                .unwrap_or_else(|| value.to_string()),
+        }
+    }
+}
+
+pub struct RuleEngine {
+    rules: Vec<CompiledRule>,
+    rate_limiters: Mutex<HashMap<String, RateLimiter>>,
+}
+
+struct CompiledRule {
+    rule: ProxyRule,
+    pattern: Pattern,
+    redact_pattern: Option<Regex>,
+}
+
+struct RateLimiter {
+    max_calls: u32,
+    window: Duration,
+    calls: Vec<Instant>,
+}
+
+impl RateLimiter {
+    fn new(max_calls: u32, window_secs: u64) -> Self {
+        Self {
+            max_calls,
+            window: Duration::from_secs(window_secs),
+            calls: Vec::new(),
+        }
+    }
+
+    fn check_and_record(&mut self) -> bool {
+        let now = Instant::now();
+        self.calls.retain(|&t| now.duration_since(t) < self.window);
+
+        if self.calls.len() >= self.max_calls as usize {
+            return false;
+        }
+
+        self.calls.push(now);
+        true
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleResult {
+    Allow,
+    Block {
+        reason: String,
+    },
+    RateLimited {
+        tool: String,
+    },
+    Sanitize {
+        field: String,
+        transform: SanitizeTransform,
+    },
+    Transform {
+        field: String,
+        operation: TransformOp,
+    },
+}
+
+impl RuleEngine {
+    pub fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            rate_limiters: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn add_rule(&mut self, rule: ProxyRule) -> Result<()> {
+        let pattern = Pattern::new(&rule.tool_pattern)
+            .map_err(|e| crate::error::Error::Other(format!("Invalid rule pattern: {}", e)))?;
+
+        let redact_pattern = match &rule.action {
+            RuleAction::Redact { pattern } if !pattern.is_empty() => {
+                Some(Regex::new(pattern).map_err(|e| {
+                    crate::error::Error::Other(format!("Invalid redact pattern: {}", e))
+                })?)
+            }
+            _ => None,
+        };
+
+        self.rules.push(CompiledRule {
+            rule,
+            pattern,
+            redact_pattern,
+        });
+        self.rules
+            .sort_by_key(|r| std::cmp::Reverse(r.rule.priority));
+
+        Ok(())
+    }
+
+    /// Evaluate every rule matching `tool_name`. The first entry is always
+    /// the verdict (`Allow`, `Block`, or `RateLimited`) from the highest
+    /// priority deciding rule; it's followed by the `Sanitize` and
+    /// `Transform` actions of all matching rules, in priority order.
+    pub fn evaluate(&self, tool_name: &str) -> Vec<RuleResult> {
+        self.evaluate_inner(tool_name, None)
+    }
+
+    /// Like [`evaluate`](Self::evaluate), but `per_client` rate limits are
+    /// counted separately for `client_id`.
+    pub fn evaluate_for_client(&self, tool_name: &str, client_id: &str) -> Vec<RuleResult> {
+        self.evaluate_inner(tool_name, Some(client_id))
+    }
+
+    fn evaluate_inner(&self, tool_name: &str, client_id: Option<&str>) -> Vec<RuleResult> {
+        let mut verdict = None;
+        let mut sanitizers = Vec::new();
+
+        for compiled in &self.rules {
+            if compiled.rule.is_expired() || !compiled.pattern.matches(tool_name) {
+                continue;
+            }
+
+            match &compiled.rule.action {
+                RuleAction::Sanitize { field, transform } => {
+                    sanitizers.push(RuleResult::Sanitize {
+                        field: field.clone(),
+                        transform: transform.clone(),
+                    });
+                }
+                RuleAction::Transform { field, operation } => {
+                    sanitizers.push(RuleResult::Transform {
+                        field: field.clone(),
+                        operation: operation.clone(),
+                    });
+                }
+                // Once decided, only argument rewrites are still collected
+                _ if verdict.is_some() => {}
+                RuleAction::Allow => verdict = Some(RuleResult::Allow),
+                RuleAction::Block { reason } => {
+                    verdict = Some(RuleResult::Block {
+                        reason: reason.clone(),
+                    })
+                }
+                RuleAction::RateLimit {
+                    max_calls,
+                    window_secs,
+                    per_client,
+                } => {
+                    let mut limiters = self.rate_limiters.lock().unwrap();
+                    let key = match client_id {
+                        Some(client) if *per_client => {
+                            format!("{}:{}:{}", compiled.rule.id, client, tool_name)
+                        }
+                        _ => format!("{}:{}", compiled.rule.id, tool_name),
+                    };
+                    let limiter = limiters
+                        .entry(key)
+                        .or_insert_with(|| RateLimiter::new(*max_calls, *window_secs));
+
+                    if !limiter.check_and_record() {
+                        verdict = Some(RuleResult::RateLimited {
+                            tool: tool_name.to_string(),
+                        });
+                    }
+                }
+                RuleAction::Log
+                | RuleAction::Redact { .. }
+                | RuleAction::SizeLimit { .. }
+                | RuleAction::Hide => {
+                    // Log action doesn't block, but marks for audit;
+                    // redaction applies to the response, size limits to
+                    // the raw request, and hiding to tool lists. Continue
+                    // checking other rules
+                }
+            }
+        }
+
+        // Default: allow
+        let mut results = vec![verdict.unwrap_or(RuleResult::Allow)];
+        results.extend(sanitizers);
+        results
+    }
+}
+
+impl RuleEngine {
+    /// Extra patterns to redact from a tool's responses, or `None` if no
+    /// redact rule matches the tool.
+    pub fn redact_patterns(&self, tool_name: &str) -> Option<Vec<Regex>> {
+        let mut matched = false;
+        let mut patterns = Vec::new();
+
+        for compiled in &self.rules {
+            if matches!(compiled.rule.action, RuleAction::Redact { .. })
+                && !compiled.rule.is_expired()
+                && compiled.pattern.matches(tool_name)
+            {
+                matched = true;
+                patterns.extend(compiled.redact_pattern.clone());
+            }
+        }
+
+        matched.then_some(patterns)
+    }
+
+    /// The smallest `max_bytes` of the size limit rules matching a tool, or
+    /// `None` if no size limit rule matches it.
+    pub fn max_request_size(&self, tool_name: &str) -> Option<usize> {
+        self.rules
+            .iter()
+            .filter(|compiled| !compiled.rule.is_expired() && compiled.pattern.matches(tool_name))
+            .filter_map(|compiled| match compiled.rule.action {
+                RuleAction::SizeLimit { max_bytes } => Some(max_bytes),
+                _ => None,
+            })
+            .min()
+    }
+
+    /// Whether a hide rule matches the tool, so it should be left out of
+    /// `tools/list` responses.
+    pub fn is_hidden(&self, tool_name: &str) -> bool {
+        self.rules.iter().any(|compiled| {
+            matches!(compiled.rule.action, RuleAction::Hide)
+                && !compiled.rule.is_expired()
+                && compiled.pattern.matches(tool_name)
+        })
+    }
+}
+
+impl Default for RuleEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How often [`HotReloadableRuleEngine::spawn_polling`] checks the
+/// `proxy_rules` table for changes.
+pub const RULE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A [`RuleEngine`] that can be rebuilt while the proxy is running.
+///
+/// With a [`RuleStore`], the engine holds the rules it was created with plus
+/// every enabled rule in the `proxy_rules` table, and is rebuilt whenever the
+/// table changes. Rate limit counters start over after a rebuild.
+#[derive(Clone)]
+pub struct HotReloadableRuleEngine {
+    engine: Arc<RwLock<RuleEngine>>,
+    reloader: Option<Arc<Reloader>>,
+}
+
+struct Reloader {
+    store: RuleStore,
+    /// Rules from the proxy config, kept across reloads
+    base_rules: Vec<ProxyRule>,
+    /// [`RuleStore::version`] when the engine was last built
+    version: Mutex<Option<String>>,
+}
+
+impl HotReloadableRuleEngine {
+    /// Load the stored rules on top of `base` and keep them in sync with
+    /// `store`.
+    pub fn with_store(base: RuleEngine, store: RuleStore) -> Result<Self> {
+        let reloader = Reloader {
+            store,
+            base_rules: base.rules.into_iter().map(|c| c.rule).collect(),
+            version: Mutex::new(None),
+        };
+        let engine = Self {
+            engine: Arc::new(RwLock::new(RuleEngine::new())),
+            reloader: Some(Arc::new(reloader)),
+        };
+        engine.reload()?;
+        Ok(engine)
+    }
+
+    /// The current rules. Hold the guard only as long as needed, since a
+    /// reload waits for it.
+    pub fn read(&self) -> RwLockReadGuard<'_, RuleEngine> {
+        self.engine.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Rebuild the engine from the base rules and the stored rules. Stored
+    /// rules with invalid patterns are skipped. Returns the number of rules
+    /// loaded; without a store this does nothing.
+    pub fn reload(&self) -> Result<usize> {
+        let Some(reloader) = &self.reloader else {
+            return Ok(self.read().rules.len());
+        };
+
+        let version = reloader.store.version()?;
+        let mut engine = RuleEngine::new();
+        for rule in &reloader.base_rules {
+            engine.add_rule(rule.clone())?;
+        }
+        for stored in reloader.store.list()?.into_iter().filter(|r| r.enabled) {
+            let id = stored.rule.id.clone();
+            if let Err(e) = engine.add_rule(stored.rule) {
+                tracing::warn!(rule = %id, error = %e, "Skipping invalid stored rule");
+            }
+        }
+
+        let count = engine.rules.len();
+        *self.engine.write().unwrap_or_else(|e| e.into_inner()) = engine;
+        *reloader.version.lock().unwrap() = Some(version);
+        Ok(count)
+    }
+
+    /// Reload if the stored rules changed since the last build. Returns
+    /// whether a reload happened.
+    pub fn reload_if_changed(&self) -> Result<bool> {
+        let Some(reloader) = &self.reloader else {
+            return Ok(false);
+        };
+
+        let current = reloader.store.version()?;
+        if reloader.version.lock().unwrap().as_ref() == Some(&current) {
+            return Ok(false);
+        }
+        self.reload()?;
+        Ok(true)
+    }
+
+    /// Check for rule changes every `interval` in a background task. Returns
+    /// `None` when there's no store to watch.
+    pub fn spawn_polling(&self, interval: Duration) -> Option<tokio::task::JoinHandle<()>> {
+        self.reloader.as_ref()?;
+        let engine = self.clone();
+
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                match engine.reload_if_changed() {
+                    Ok(true) => {
+                        tracing::info!(rules = engine.read().rules.len(), "Reloaded proxy rules")
+                    }
+                    Ok(false) => {}
+                    Err(e) => tracing::warn!(error = %e, "Failed to check proxy rules for changes"),
+                }
+            }
+        }))
+    }
+}
+
+impl From<RuleEngine> for HotReloadableRuleEngine {
+    /// An engine whose rules never change.
+    fn from(engine: RuleEngine) -> Self {
+        Self {
+            engine: Arc::new(RwLock::new(engine)),
+            reloader: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_rule() {
+        let mut engine = RuleEngine::new();
+        engine
+            .add_rule(ProxyRule {
+                id: "1".to_string(),
+                tool_pattern: "dangerous_*".to_string(),
+                action: RuleAction::Block {
+                    reason: "Blocked by policy".to_string(),
+                },
+                priority: 0,
+                expires_at: None,
+            })
+            .unwrap();
+
+        match &engine.evaluate("dangerous_tool")[0] {
+            RuleResult::Block { reason } => assert_eq!(reason, "Blocked by policy"),
+            _ => panic!("Expected block"),
+        }
+
+        assert!(matches!(engine.evaluate("safe_tool")[0], RuleResult::Allow));
+    }
+
+    #[test]
+    fn parses_cli_actions() {
+        assert!(matches!(
+            "allow".parse::<RuleAction>().unwrap(),
+            RuleAction::Allow
+        ));
+        assert!(matches!(
+            "block:No shell access".parse::<RuleAction>().unwrap(),
+            RuleAction::Block { reason } if reason == "No shell access"
+        ));
+        assert!(matches!(
+            "block".parse::<RuleAction>().unwrap(),
+            RuleAction::Block { reason } if reason == "Blocked by proxy rule"
+        ));
+        assert!(matches!(
+            "rate_limit:10/60".parse::<RuleAction>().unwrap(),
+            RuleAction::RateLimit {
+                max_calls: 10,
+                window_secs: 60,
+                per_client: false
+            }
+        ));
+
+        assert!(matches!(
+            "size_limit:4096".parse::<RuleAction>().unwrap(),
+            RuleAction::SizeLimit { max_bytes: 4096 }
+        ));
+        assert!(matches!(
+            "hide".parse::<RuleAction>().unwrap(),
+            RuleAction::Hide
+        ));
+
+        for invalid in [
+            "rate_limit:10",
+            "rate_limit:x/60",
+            "rate_limit:1/0",
+            "size_limit:0",
+            "size_limit:1mb",
+            "deny",
+            "allow:x",
+        ] {
+            assert!(invalid.parse::<RuleAction>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn rate_limit_rule() {
+        let mut engine = RuleEngine::new();
+        engine
+            .add_rule(ProxyRule {
+                id: "1".to_string(),
+                tool_pattern: "api_*".to_string(),
+                action: RuleAction::RateLimit {
+                    max_calls: 2,
+                    window_secs: 60,
+                    per_client: false,
+                },
+                priority: 0,
+                expires_at: None,
+            })
+            .unwrap();
+
+        // First two calls should succeed
+        assert!(matches!(engine.evaluate("api_call")[0], RuleResult::Allow));
+        assert!(matches!(engine.evaluate("api_call")[0], RuleResult::Allow));
+
+        // Third call should be rate limited
+        assert!(matches!(
+            engine.evaluate("api_call")[0],
+            RuleResult::RateLimited { .. }
+        ));
+    }
+
+    #[test]
+    fn per_client_rate_limit_counts_clients_separately() {
+        let mut engine = RuleEngine::new();
+        engine
+            .add_rule(ProxyRule {
+                id: "1".to_string(),
+                tool_pattern: "api_*".to_string(),
+                action: RuleAction::RateLimit {
+                    max_calls: 1,
+                    window_secs: 60,
+                    per_client: true,
+                },
+                priority: 0,
+                expires_at: None,
+            })
+            .unwrap();
+
+        assert_eq!(
+            engine.evaluate_for_client("api_call", "claude")[0],
+            RuleResult::Allow
+        );
+        assert_eq!(
+            engine.evaluate_for_client("api_call", "cursor")[0],
+            RuleResult::Allow
+        );
+        assert!(matches!(
+            engine.evaluate_for_client("api_call", "claude")[0],
+            RuleResult::RateLimited { .. }
+        ));
+    }
+
+    #[test]
+    fn rule_expired_when_added_never_applies() {
+        let mut engine = RuleEngine::new();
+        engine
+            .add_rule(ProxyRule {
+                id: "expired".to_string(),
+                tool_pattern: "*".to_string(),
+                action: RuleAction::Block {
+                    reason: "Temporary freeze".to_string(),
+                },
+                priority: 10,
+                expires_at: Some(Utc::now() - chrono::Duration::seconds(1)),
+            })
+            .unwrap();
+        engine
+            .add_rule(ProxyRule {
+                id: "redact".to_string(),
+                tool_pattern: "*".to_string(),
+                action: RuleAction::Redact {
+                    pattern: String::new(),
+                },
+                priority: 0,
+                expires_at: Some(Utc::now() - chrono::Duration::days(1)),
+            })
+            .unwrap();
+
+        assert_eq!(engine.evaluate("any_tool"), vec![RuleResult::Allow]);
+        assert_eq!(engine.evaluate("any_tool"), vec![RuleResult::Allow]);
+        assert!(engine.redact_patterns("any_tool").is_none());
+    }
+
+    #[test]
+    fn rule_applies_until_it_expires() {
+        let mut engine = RuleEngine::new();
+        engine
+            .add_rule(ProxyRule {
+                id: "1".to_string(),
+                tool_pattern: "*".to_string(),
+                action: RuleAction::Block {
+                    reason: "Temporary freeze".to_string(),
+                },
+                priority: 0,
+                expires_at: Some(Utc::now() + chrono::Duration::hours(1)),
+            })
+            .unwrap();
+
+        assert!(matches!(
+            engine.evaluate("any_tool")[0],
+            RuleResult::Block { .. }
+        ));
+    }
+
+    #[test]
+    fn priority_ordering() {
+        let mut engine = RuleEngine::new();
+
+        // Low priority block
+        engine
+            .add_rule(ProxyRule {
+                id: "1".to_string(),
+                tool_pattern: "*".to_string(),
+                action: RuleAction::Block {
+                    reason: "Default block".to_string(),
+                },
+                priority: 0,
+                expires_at: None,
+            })
+            .unwrap();
+
+        // High priority allow
+        engine
+            .add_rule(ProxyRule {
+                id: "2".to_string(),
+                tool_pattern: "safe_*".to_string(),
+                action: RuleAction::Allow,
+                priority: 10,
+                expires_at: None,
+            })
+            .unwrap();
+
+        // safe_* should be allowed due to higher priority
+        assert!(matches!(engine.evaluate("safe_tool")[0], RuleResult::Allow));
+
+        // Other tools should be blocked
+        assert!(matches!(
+            engine.evaluate("other_tool")[0],
+            RuleResult::Block { .. }
+        ));
+    }
+
+    #[test]
+    fn redact_rule_does_not_block_and_reports_patterns() {
+        let mut engine = RuleEngine::new();
+        engine
+            .add_rule(ProxyRule {
+                id: "1".to_string(),
+                tool_pattern: "fetch_*".to_string(),
+                action: RuleAction::Redact {
+                    pattern: r"internal-\d+".to_string(),
+                },
+                priority: 0,
+                expires_at: None,
+            })
+            .unwrap();
+
+        assert!(matches!(
+            engine.evaluate("fetch_page")[0],
+            RuleResult::Allow
+        ));
+        assert_eq!(engine.redact_patterns("fetch_page").unwrap().len(), 1);
+        assert!(engine.redact_patterns("read_file").is_none());
+    }
+
+    #[test]
+    fn invalid_redact_pattern_is_rejected() {
+        let mut engine = RuleEngine::new();
+        let result = engine.add_rule(ProxyRule {
+            id: "1".to_string(),
+            tool_pattern: "*".to_string(),
+            action: RuleAction::Redact {
+                pattern: "(".to_string(),
+            },
+            priority: 0,
+            expires_at: None,
+        });
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn sanitize_rules_are_collected_after_verdict() {
+        let mut engine = RuleEngine::new();
+        engine
+            .add_rule(ProxyRule {
+                id: "allow".to_string(),
+                tool_pattern: "*".to_string(),
+                action: RuleAction::Allow,
+                priority: 10,
+                expires_at: None,
+            })
+            .unwrap();
+        for (id, transform) in [
+            ("strip", SanitizeTransform::StripHtml),
+            ("truncate", SanitizeTransform::TruncateBytes(1000)),
+        ] {
+            engine
+                .add_rule(ProxyRule {
+                    id: id.to_string(),
+                    tool_pattern: "read_*".to_string(),
+                    action: RuleAction::Sanitize {
+                        field: "path".to_string(),
+                        transform,
+                    },
+                    priority: 0,
+                    expires_at: None,
+                })
+                .unwrap();
+        }
+
+        let results = engine.evaluate("read_file");
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0], RuleResult::Allow);
+        assert!(matches!(
+            &results[1],
+            RuleResult::Sanitize {
+                transform: SanitizeTransform::StripHtml,
+                ..
+            }
+        ));
+
+        assert_eq!(engine.evaluate("list_dir"), vec![RuleResult::Allow]);
+    }
+
+    #[test]
+    fn transform_ops_rewrite_values() {
+        assert_eq!(
+            TransformOp::Prepend("/sandbox".to_string()).apply("/etc/passwd"),
+            "/sandbox/etc/passwd"
+        );
+        assert_eq!(
+            TransformOp::Append(" (via proxy)".to_string()).apply("hello"),
+            "hello (via proxy)"
+        );
+        assert_eq!(
+            TransformOp::Replace {
+                from: "../".to_string(),
+                to: String::new(),
+            }
+            .apply("../../etc/passwd"),
+            "etc/passwd"
+        );
+        assert_eq!(
+            TransformOp::Replace {
+                from: String::new(),
+                to: "x".to_string(),
+            }
+            .apply("abc"),
+            "abc"
+        );
+        assert_eq!(TransformOp::ClampLength(3).apply("héllo"), "hél");
+        assert_eq!(TransformOp::ClampLength(10).apply("short"), "short");
+    }
+
+    #[test]
+    fn transform_rules_follow_verdict_in_priority_order() {
+        let mut engine = RuleEngine::new();
+        for (id, priority, operation) in [
+            ("clamp", 0, TransformOp::ClampLength(20)),
+            ("prefix", 5, TransformOp::Prepend("/sandbox".to_string())),
+        ] {
+            engine
+                .add_rule(ProxyRule {
+                    id: id.to_string(),
+                    tool_pattern: "read_*".to_string(),
+                    action: RuleAction::Transform {
+                        field: "path".to_string(),
+                        operation,
+                    },
+                    priority,
+                    expires_at: None,
+                })
+                .unwrap();
+        }
+
+        let results = engine.evaluate("read_file");
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0], RuleResult::Allow);
+        assert_eq!(
+            results[1],
+            RuleResult::Transform {
+                field: "path".to_string(),
+                operation: TransformOp::Prepend("/sandbox".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn strip_html_removes_script_tags() {
+        let path = "/tmp/<script>alert('x')</script>notes<b>.txt</b>";
+        assert_eq!(SanitizeTransform::StripHtml.apply(path), "/tmp/notes.txt");
+    }
+
+    #[test]
+    fn truncate_bytes_limits_oversized_input() {
+        let input = "é".repeat(1000);
+        let truncated = SanitizeTransform::TruncateBytes(1000).apply(&input);
+        assert_eq!(truncated.len(), 1000);
+
+        assert_eq!(
+            SanitizeTransform::TruncateBytes(1000).apply("short"),
+            "short"
+        );
+    }
+
+    #[test]
+    fn allowlist_and_base64_transforms() {
+        let allow = SanitizeTransform::AllowlistChars("abc/".to_string());
+        assert_eq!(allow.apply("a/b;rm -rf c"), "a/bc");
+
+        assert_eq!(
+            SanitizeTransform::Base64Decode.apply("aGVsbG8gd29ybGQ="),
+            "hello world"
+        );
+        assert_eq!(
+            SanitizeTransform::Base64Decode.apply("not base64!"),
+            "not base64!"
+        );
+    }
+
+    #[test]
+    fn sanitize_transform_serialization() {
+        let action = RuleAction::Sanitize {
+            field: "path".to_string(),
+            transform: SanitizeTransform::TruncateBytes(1000),
+        };
+        let json = serde_json::to_value(&action).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"type": "sanitize", "field": "path", "transform": {"truncate_bytes": 1000}})
+        );
+
+        let strip: SanitizeTransform = serde_json::from_str(r#""strip_html""#).unwrap();
+        assert_eq!(strip, SanitizeTransform::StripHtml);
+    }
+
+    fn block(id: &str, pattern: &str) -> ProxyRule {
+        ProxyRule {
+            id: id.to_string(),
+            tool_pattern: pattern.to_string(),
+            action: RuleAction::Block {
+                reason: format!("{} blocked", id),
+            },
+            priority: 0,
+            expires_at: None,
+        }
+    }
+
+    fn is_blocked(engine: &HotReloadableRuleEngine, tool: &str) -> bool {
+        matches!(engine.read().evaluate(tool)[0], RuleResult::Block { .. })
+    }
+
+    #[test]
+    fn hot_reload_picks_up_stored_rule_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RuleStore::new(crate::db::create_pool(&dir.path().join("test.db")).unwrap());
+        let mut base = RuleEngine::new();
+        base.add_rule(block("base", "delete_*")).unwrap();
+
+        let engine = HotReloadableRuleEngine::with_store(base, store.clone()).unwrap();
+        assert!(is_blocked(&engine, "delete_file"));
+        assert!(!is_blocked(&engine, "exec"));
+        assert!(!engine.reload_if_changed().unwrap());
+
+        store.insert(&block("stored", "exec")).unwrap();
+        assert!(engine.reload_if_changed().unwrap());
+        assert!(is_blocked(&engine, "exec"));
+        assert!(is_blocked(&engine, "delete_file"));
+        assert!(!engine.reload_if_changed().unwrap());
+
+        store.set_enabled("stored", false).unwrap();
+        assert!(engine.reload_if_changed().unwrap());
+        assert!(!is_blocked(&engine, "exec"));
+        assert_eq!(engine.reload().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn polling_reloads_in_the_background() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RuleStore::new(crate::db::create_pool(&dir.path().join("test.db")).unwrap());
+        let engine = HotReloadableRuleEngine::with_store(RuleEngine::new(), store.clone()).unwrap();
+        let poller = engine
+            .spawn_polling(Duration::from_millis(10))
+            .expect("engine has a store");
+
+        store.insert(&block("stored", "exec")).unwrap();
+        for _ in 0..100 {
+            if is_blocked(&engine, "exec") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(is_blocked(&engine, "exec"));
+        poller.abort();
+
+        let fixed = HotReloadableRuleEngine::from(RuleEngine::new());
+        assert!(fixed.spawn_polling(Duration::from_millis(10)).is_none());
+        assert!(!fixed.reload_if_changed().unwrap());
+    }
+}
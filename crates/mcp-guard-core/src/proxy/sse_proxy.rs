@@ -0,0 +1,534 @@
+//! STDIO proxy for a remote MCP server, bridging a local client to the
+//! server's HTTP endpoint.
+//!
+//! Each client message the rules let through is POSTed to the endpoint, in
+//! order, as the streamable HTTP transport does. The server answers with a
+//! JSON body or a `text/event-stream` body, whose `message` events are
+//! relayed to the client as they arrive, so a slow call doesn't hold up
+//! the others.
+
+use crate::error::{Error, Result};
+use crate::protocol::jsonrpc::Message;
+use crate::protocol::transport::{header_map, SseParser, SESSION_HEADER};
+use crate::proxy::interceptor::{
+    client_id_from_initialize, error_response, InterceptResult, ProxyInterceptor,
+};
+use crate::proxy::line_reader::{Line, LineReader};
+use futures::StreamExt;
+use reqwest::header::{HeaderMap, ACCEPT, CONTENT_TYPE};
+use reqwest::Url;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinSet;
+
+/// JSON-RPC error code returned when a request can't be delivered to the
+/// remote server.
+pub const UPSTREAM_ERROR: i32 = -32003;
+
+/// How long to wait for queued messages and the session `DELETE` once the
+/// client disconnects.
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct SseProxyInterceptor {
+    interceptor: ProxyInterceptor,
+    client: reqwest::Client,
+    url: Url,
+    headers: HeaderMap,
+}
+
+impl SseProxyInterceptor {
+    /// Bridge to the MCP endpoint at `url`, sending `headers` with every
+    /// request. Rules, audit logging, and limits come from `interceptor`;
+    /// its server command is never run.
+    pub fn new(
+        url: &str,
+        headers: &HashMap<String, String>,
+        interceptor: ProxyInterceptor,
+    ) -> Result<Self> {
+        let url = Url::parse(url).map_err(|e| {
+            Error::invalid_server_config(format!("Invalid HTTP URL '{}': {}", url, e))
+        })?;
+
+        Ok(Self {
+            interceptor,
+            client: reqwest::Client::new(),
+            url,
+            headers: header_map(headers)?,
+        })
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        let result = self
+            .relay(BufReader::new(tokio::io::stdin()), tokio::io::stdout())
+            .await;
+        self.interceptor.flush_audit().await;
+        result
+    }
+
+    async fn relay(
+        &self,
+        client_in: impl AsyncBufRead + Unpin,
+        mut client_out: impl AsyncWrite + Unpin,
+    ) -> Result<()> {
+        let interceptor = &self.interceptor;
+        let server_name = interceptor.server_name.clone();
+        let mut client_id = interceptor.client_id.clone();
+        // Keeps partial lines when another branch wins the select below
+        let mut client_in = LineReader::new(client_in, interceptor.max_request_size);
+
+        let (server_tx, mut server_rx) = mpsc::unbounded_channel();
+        let (upstream_tx, upstream_rx) = mpsc::unbounded_channel();
+        let upstream = Upstream {
+            client: self.client.clone(),
+            url: self.url.clone(),
+            headers: self.headers.clone(),
+            session_id: None,
+        };
+        let sender = tokio::spawn(upstream.send_all(upstream_rx, server_tx));
+
+        loop {
+            let deadline = interceptor.next_upstream_deadline();
+
+            tokio::select! {
+                result = client_in.next_line() => {
+                    match result {
+                        Ok(None) => break,
+                        Ok(Some(Line::Oversized(head))) => {
+                            let start = Instant::now();
+                            // Forwarded lines are POSTed whole, so they're
+                            // gathered here first
+                            let mut message = Vec::new();
+                            match interceptor.handle_oversized(&head, &mut client_in, &mut message, &server_name, start).await.map_err(Error::ProcessIo)? {
+                                Some(response) => {
+                                    client_out.write_all(response.as_bytes()).await.map_err(Error::ProcessIo)?;
+                                    client_out.flush().await.map_err(Error::ProcessIo)?;
+                                }
+                                None => {
+                                    if upstream_tx.send(String::from_utf8_lossy(&message).into_owned()).is_err() {
+                                        break;
+                                    }
+                                }
+                            }
+                        }
+                        Ok(Some(Line::Complete(client_line))) => {
+                            let start = Instant::now();
+                            if client_id.is_none() {
+                                client_id = client_id_from_initialize(&client_line);
+                            }
+                            match interceptor.intercept_client_message(&client_line, &server_name, client_id.as_deref(), start) {
+                                InterceptResult::Forward(msg) => {
+                                    interceptor.track_upstream(&msg);
+                                    if upstream_tx.send(msg).is_err() {
+                                        break;
+                                    }
+                                }
+                                InterceptResult::BlockWithResponse(response) => {
+                                    client_out.write_all(response.as_bytes()).await.map_err(Error::ProcessIo)?;
+                                    client_out.flush().await.map_err(Error::ProcessIo)?;
+                                }
+                            }
+                        }
+                        Err(e) => return Err(Error::ProcessIo(e)),
+                    }
+                }
+
+                Some(server_line) = server_rx.recv() => {
+                    if let Some(server_line) = interceptor.intercept_server_message(&server_line, &server_name) {
+                        client_out.write_all(server_line.as_bytes()).await.map_err(Error::ProcessIo)?;
+                        client_out.flush().await.map_err(Error::ProcessIo)?;
+                    }
+                }
+
+                _ = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now).into()), if deadline.is_some() => {
+                    for response in interceptor.expire_upstream(&server_name) {
+                        client_out.write_all(response.as_bytes()).await.map_err(Error::ProcessIo)?;
+                    }
+                    client_out.flush().await.map_err(Error::ProcessIo)?;
+                }
+            }
+        }
+
+        drop(upstream_tx);
+        tokio::time::timeout(CLOSE_TIMEOUT, sender).await.ok();
+        Ok(())
+    }
+}
+
+/// The remote endpoint and the session it handed out.
+struct Upstream {
+    client: reqwest::Client,
+    url: Url,
+    headers: HeaderMap,
+    session_id: Option<String>,
+}
+
+impl Upstream {
+    /// POST each message in turn, relaying the messages in every response
+    /// body to `server_tx` as lines. A request that can't be delivered is
+    /// answered with an [`UPSTREAM_ERROR`].
+    async fn send_all(
+        mut self,
+        mut messages: UnboundedReceiver<String>,
+        server_tx: UnboundedSender<String>,
+    ) {
+        let mut bodies = JoinSet::new();
+        while let Some(line) = messages.recv().await {
+            while bodies.try_join_next().is_some() {}
+
+            match self.post(&line).await {
+                Ok(response) => {
+                    bodies.spawn(relay_body(response, server_tx.clone()));
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to send message to server");
+                    if let Ok(Message::Request(request)) = Message::parse(&line) {
+                        let message = format!("Server request failed: {}", e);
+                        server_tx
+                            .send(error_response(request.id, UPSTREAM_ERROR, &message))
+                            .ok();
+                    }
+                }
+            }
+        }
+
+        // The client is gone; stop reading open response streams
+        bodies.abort_all();
+        self.close().await;
+    }
+
+    async fn post(&mut self, line: &str) -> Result<reqwest::Response> {
+        let mut request = self
+            .client
+            .post(self.url.clone())
+            .headers(self.headers.clone())
+            .header(ACCEPT, "application/json, text/event-stream")
+            .header(CONTENT_TYPE, "application/json")
+            .body(line.trim_end().to_string());
+        if let Some(session_id) = &self.session_id {
+            request = request.header(SESSION_HEADER, session_id);
+        }
+
+        let response = request
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(Error::HttpTransport)?;
+
+        if let Some(session_id) = response
+            .headers()
+            .get(SESSION_HEADER)
+            .and_then(|v| v.to_str().ok())
+        {
+            self.session_id = Some(session_id.to_string());
+        }
+        Ok(response)
+    }
+
+    /// Let the server drop the session; failures here don't matter.
+    async fn close(&mut self) {
+        if let Some(session_id) = self.session_id.take() {
+            self.client
+                .delete(self.url.clone())
+                .headers(self.headers.clone())
+                .header(SESSION_HEADER, session_id)
+                .send()
+                .await
+                .ok();
+        }
+    }
+}
+
+/// Send each message in a response body to `server_tx` as a line. Event
+/// stream bodies are relayed event by event as they arrive.
+async fn relay_body(response: reqwest::Response, server_tx: UnboundedSender<String>) {
+    let is_event_stream = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/event-stream"));
+
+    if !is_event_stream {
+        match response.text().await {
+            // Notifications and responses are acknowledged with an empty 202
+            Ok(body) if body.trim().is_empty() => {}
+            Ok(body) => {
+                server_tx.send(as_line(&body)).ok();
+            }
+            Err(e) => tracing::warn!(error = %e, "Failed to read server response"),
+        }
+        return;
+    }
+
+    let mut parser = SseParser::default();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                tracing::warn!(error = %e, "Server event stream failed");
+                break;
+            }
+        };
+        for event in parser.feed(&chunk) {
+            if event.event == "message" && server_tx.send(as_line(&event.data)).is_err() {
+                return;
+            }
+        }
+    }
+    if let Some(event) = parser.finish().filter(|e| e.event == "message") {
+        server_tx.send(as_line(&event.data)).ok();
+    }
+}
+
+/// `payload` as a single newline-terminated line, re-serializing JSON that
+/// spans several lines so the client can split messages on newlines.
+fn as_line(payload: &str) -> String {
+    let payload = payload.trim();
+    if payload.contains('\n') {
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(payload) {
+            return format!("{}\n", value);
+        }
+    }
+    format!("{}\n", payload)
+}
+
+/// Display name for a remote server, e.g. `https://mcp.example.com/sse` ->
+/// `mcp.example.com`.
+pub fn default_server_name(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy::interceptor::REQUEST_TOO_LARGE;
+    use crate::proxy::rules::{ProxyRule, RuleAction, RuleEngine};
+    use tokio::io::AsyncBufReadExt;
+    use wiremock::matchers::{body_partial_json, header, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn server_name_defaults_to_host() {
+        assert_eq!(
+            default_server_name("https://mcp.example.com/sse"),
+            "mcp.example.com"
+        );
+        assert_eq!(default_server_name("not a url"), "not a url");
+    }
+
+    #[test]
+    fn as_line_compacts_multiline_json() {
+        assert_eq!(as_line("{\n  \"id\": 1\n}\n"), "{\"id\":1}\n");
+        assert_eq!(as_line("{\"id\":1}"), "{\"id\":1}\n");
+    }
+
+    #[tokio::test]
+    async fn relays_responses_and_blocks_by_rule() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                serde_json::json!({"method": "initialize"}),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(SESSION_HEADER, "session-1")
+                    .set_body_raw(
+                        r#"{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-03-26"}}"#,
+                        "application/json",
+                    ),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(header(SESSION_HEADER, "session-1"))
+            .and(body_partial_json(
+                serde_json::json!({"params": {"name": "read_file"}}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                "event: message\ndata: {\"jsonrpc\":\"2.0\",\"id\":2,\"result\":{\"content\":[]}}\n\n",
+                "text/event-stream",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                serde_json::json!({"params": {"name": "delete_file"}}),
+            ))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let mut engine = RuleEngine::new();
+        engine
+            .add_rule(ProxyRule {
+                id: "block".to_string(),
+                tool_pattern: "delete_*".to_string(),
+                action: RuleAction::Block {
+                    reason: "Test block".to_string(),
+                },
+                priority: 0,
+                expires_at: None,
+            })
+            .unwrap();
+        let interceptor = ProxyInterceptor::new(String::new(), vec![]).with_rules(engine);
+        let proxy = SseProxyInterceptor::new(&server.uri(), &HashMap::new(), interceptor).unwrap();
+
+        let (mut client, proxy_end) = tokio::io::duplex(4096);
+        let (proxy_in, proxy_out) = tokio::io::split(proxy_end);
+        let relay =
+            tokio::spawn(async move { proxy.relay(BufReader::new(proxy_in), proxy_out).await });
+
+        let (client_out, client_in) = tokio::io::split(&mut client);
+        let mut client_out = BufReader::new(client_out).lines();
+        let mut client_in = client_in;
+
+        client_in
+            .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"initialize\",\"params\":{}}\n")
+            .await
+            .unwrap();
+        let line = client_out.next_line().await.unwrap().unwrap();
+        assert!(line.contains("protocolVersion"));
+
+        client_in
+            .write_all(concat!(
+                r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"read_file"}}"#,
+                "\n",
+                r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"delete_file"}}"#,
+                "\n"
+            ).as_bytes())
+            .await
+            .unwrap();
+        let mut lines = [
+            client_out.next_line().await.unwrap().unwrap(),
+            client_out.next_line().await.unwrap().unwrap(),
+        ];
+        lines.sort_by_key(|l| !l.contains("\"id\":2"));
+        assert!(lines[0].contains("\"content\":[]"));
+        assert!(lines[1].contains("Tool call blocked: Test block"));
+
+        drop(client_out);
+        drop(client_in);
+        drop(client);
+        relay.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn keeps_partial_lines_while_relaying_server_messages() {
+        let server = MockServer::start().await;
+        for id in [1, 2] {
+            Mock::given(method("POST"))
+                .and(body_partial_json(serde_json::json!({ "id": id })))
+                .respond_with(ResponseTemplate::new(200).set_body_raw(
+                    format!(r#"{{"jsonrpc":"2.0","id":{},"result":{{}}}}"#, id),
+                    "application/json",
+                ))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        let interceptor = ProxyInterceptor::new(String::new(), vec![]);
+        let proxy = SseProxyInterceptor::new(&server.uri(), &HashMap::new(), interceptor).unwrap();
+
+        let (client, proxy_end) = tokio::io::duplex(4096);
+        let (proxy_in, proxy_out) = tokio::io::split(proxy_end);
+        let relay =
+            tokio::spawn(async move { proxy.relay(BufReader::new(proxy_in), proxy_out).await });
+        let (client_out, mut client_in) = tokio::io::split(client);
+        let mut client_out = BufReader::new(client_out).lines();
+
+        // The answer to the first request arrives while the second is half written
+        client_in
+            .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"tools/list\"}\n{\"jsonrpc\":")
+            .await
+            .unwrap();
+        let line = client_out.next_line().await.unwrap().unwrap();
+        assert!(line.contains("\"id\":1"));
+
+        client_in
+            .write_all(b"\"2.0\",\"id\":2,\"method\":\"tools/list\"}\n")
+            .await
+            .unwrap();
+        let line = client_out.next_line().await.unwrap().unwrap();
+        assert!(line.contains("\"id\":2"));
+
+        drop(client_out);
+        drop(client_in);
+        relay.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_oversized_requests_without_sending_them() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(202))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let interceptor = ProxyInterceptor::new(String::new(), vec![]).with_max_request_size(100);
+        let proxy = SseProxyInterceptor::new(&server.uri(), &HashMap::new(), interceptor).unwrap();
+
+        let (client, proxy_end) = tokio::io::duplex(4096);
+        let (proxy_in, proxy_out) = tokio::io::split(proxy_end);
+        let relay =
+            tokio::spawn(async move { proxy.relay(BufReader::new(proxy_in), proxy_out).await });
+        let (client_out, mut client_in) = tokio::io::split(client);
+        let mut client_out = BufReader::new(client_out).lines();
+
+        let big = "a".repeat(200);
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "tools/call",
+            "params": {"name": "write_file", "arguments": {"content": big}}
+        });
+        // Notifications aren't limited, so this one is still sent
+        let notification = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/message",
+            "params": {"data": big}
+        });
+        client_in
+            .write_all(format!("{}\n{}\n", request, notification).as_bytes())
+            .await
+            .unwrap();
+        let line = client_out.next_line().await.unwrap().unwrap();
+        assert!(line.contains(&REQUEST_TOO_LARGE.to_string()));
+        assert!(line.contains("\"id\":4"));
+
+        drop(client_out);
+        drop(client_in);
+        relay.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn answers_request_when_server_is_unreachable() {
+        let interceptor = ProxyInterceptor::new(String::new(), vec![]);
+        let proxy =
+            SseProxyInterceptor::new("http://127.0.0.1:1/mcp", &HashMap::new(), interceptor)
+                .unwrap();
+
+        let input = b"{\"jsonrpc\":\"2.0\",\"id\":7,\"method\":\"tools/list\"}\n".to_vec();
+        let (mut client, proxy_end) = tokio::io::duplex(4096);
+        let (proxy_in, proxy_out) = tokio::io::split(proxy_end);
+        let relay =
+            tokio::spawn(async move { proxy.relay(BufReader::new(proxy_in), proxy_out).await });
+
+        client.write_all(&input).await.unwrap();
+        let mut lines = BufReader::new(&mut client).lines();
+        let line = lines.next_line().await.unwrap().unwrap();
+        assert!(line.contains(&UPSTREAM_ERROR.to_string()));
+        assert!(line.contains("\"id\":7"));
+
+        drop(lines);
+        drop(client);
+        relay.await.unwrap().unwrap();
+    }
+}
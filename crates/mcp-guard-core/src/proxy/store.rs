@@ -0,0 +1,181 @@
+//! Proxy rules stored in the `proxy_rules` table, as managed by the API and
+//! the `rules` command.
+
+use crate::db::DbPool;
+use crate::error::Result;
+use crate::proxy::rules::{ProxyRule, RuleAction};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredRule {
+    #[serde(flatten)]
+    pub rule: ProxyRule,
+    pub enabled: bool,
+}
+
+/// `updated_at` value for writes. Millisecond precision, so that
+/// [`RuleStore::version`] changes even for edits within the same second.
+pub const UPDATED_NOW: &str = "strftime('%Y-%m-%d %H:%M:%f', 'now')";
+
+#[derive(Clone)]
+pub struct RuleStore {
+    pool: DbPool,
+}
+
+impl RuleStore {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// All rules, highest priority first.
+    pub fn list(&self) -> Result<Vec<StoredRule>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, tool_pattern, action, priority, enabled, expires_at FROM proxy_rules ORDER BY priority DESC, created_at",
+        )?;
+
+        let rules = stmt
+            .query_map([], |row| {
+                let action_json: String = row.get(2)?;
+                Ok(StoredRule {
+                    rule: ProxyRule {
+                        id: row.get(0)?,
+                        tool_pattern: row.get(1)?,
+                        action: serde_json::from_str(&action_json).unwrap_or(RuleAction::Log),
+                        priority: row.get(3)?,
+                        expires_at: row
+                            .get::<_, Option<String>>(5)?
+                            .and_then(|t| DateTime::parse_from_rfc3339(&t).ok())
+                            .map(|t| t.with_timezone(&Utc)),
+                    },
+                    enabled: row.get::<_, i32>(4)? != 0,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(rules)
+    }
+
+    pub fn insert(&self, rule: &ProxyRule) -> Result<()> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "INSERT INTO proxy_rules (id, tool_pattern, action, priority, expires_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                rule.id,
+                rule.tool_pattern,
+                serde_json::to_string(&rule.action)?,
+                rule.priority,
+                rule.expires_at.map(|t| t.to_rfc3339())
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Returns whether a rule with this ID existed.
+    pub fn delete(&self, id: &str) -> Result<bool> {
+        let conn = self.pool.get()?;
+        let rows = conn.execute("DELETE FROM proxy_rules WHERE id = ?1", [id])?;
+        Ok(rows > 0)
+    }
+
+    /// Returns whether a rule with this ID existed.
+    pub fn set_enabled(&self, id: &str, enabled: bool) -> Result<bool> {
+        let conn = self.pool.get()?;
+        let rows = conn.execute(
+            &format!(
+                "UPDATE proxy_rules SET enabled = ?1, updated_at = {} WHERE id = ?2",
+                UPDATED_NOW
+            ),
+            rusqlite::params![enabled, id],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Changes whenever a rule is added, edited, toggled, or deleted.
+    pub fn version(&self) -> Result<String> {
+        let conn = self.pool.get()?;
+        let version = conn.query_row(
+            "SELECT COUNT(*) || '@' || COALESCE(MAX(updated_at), '') FROM proxy_rules",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(version)
+    }
+
+    /// Mark every rule as updated, so running proxies reload them on their
+    /// next poll. Returns the number of rules.
+    pub fn touch(&self) -> Result<usize> {
+        let conn = self.pool.get()?;
+        let rows = conn.execute(
+            &format!("UPDATE proxy_rules SET updated_at = {}", UPDATED_NOW),
+            [],
+        )?;
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::create_pool;
+    use tempfile::tempdir;
+
+    fn rule(id: &str, priority: i32) -> ProxyRule {
+        ProxyRule {
+            id: id.to_string(),
+            tool_pattern: "delete_*".to_string(),
+            action: RuleAction::Block {
+                reason: "No deletes".to_string(),
+            },
+            priority,
+            expires_at: None,
+        }
+    }
+
+    #[test]
+    fn insert_list_toggle_and_delete() {
+        let dir = tempdir().unwrap();
+        let store = RuleStore::new(create_pool(&dir.path().join("test.db")).unwrap());
+
+        store.insert(&rule("low", 1)).unwrap();
+        store.insert(&rule("high", 10)).unwrap();
+
+        let rules = store.list().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].rule.id, "high");
+        assert!(rules.iter().all(|r| r.enabled));
+        assert!(matches!(rules[0].rule.action, RuleAction::Block { .. }));
+
+        assert!(store.set_enabled("low", false).unwrap());
+        assert!(!store.list().unwrap()[1].enabled);
+        assert!(!store.set_enabled("missing", false).unwrap());
+
+        assert!(store.delete("high").unwrap());
+        assert!(!store.delete("high").unwrap());
+        assert_eq!(store.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn version_changes_on_every_write() {
+        let dir = tempdir().unwrap();
+        let store = RuleStore::new(create_pool(&dir.path().join("test.db")).unwrap());
+
+        let mut versions = vec![store.version().unwrap()];
+        store.insert(&rule("a", 1)).unwrap();
+        versions.push(store.version().unwrap());
+        store.set_enabled("a", false).unwrap();
+        versions.push(store.version().unwrap());
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        assert_eq!(store.touch().unwrap(), 1);
+        versions.push(store.version().unwrap());
+        store.delete("a").unwrap();
+        versions.push(store.version().unwrap());
+
+        for pair in versions.windows(2) {
+            assert_ne!(pair[0], pair[1]);
+        }
+        assert_eq!(store.version().unwrap(), versions[4]);
+    }
+}
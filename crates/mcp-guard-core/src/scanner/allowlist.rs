@@ -0,0 +1,148 @@
+//! Allowlist of known false positives to leave out of scan reports.
+//!
+//! ```toml
+//! [[ignore]]
+//! id = "DESC-INJ-001"
+//! server = "my-server"
+//! reason = "Known false positive"
+//! ```
+//!
+//! `scan --remediation-file` writes its suggested suppressions in this
+//! format, so a reviewed remediation file can be used as is.
+
+use crate::error::{Error, Result};
+use crate::scanner::report::{ScanResult, Threat};
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Allowlist {
+    #[serde(default)]
+    pub ignore: Vec<AllowlistEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AllowlistEntry {
+    /// Threat ID to ignore, e.g. `NO-AUTH-LOCAL`
+    pub id: String,
+    /// Server the threat was found on
+    pub server: String,
+    /// Why the threat is acceptable, for whoever reviews the allowlist
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl Allowlist {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| Error::ConfigRead {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        toml::from_str(&content).map_err(|e| Error::config_parse(path, e.to_string()))
+    }
+
+    /// Whether `threat` found on `server` is allowlisted.
+    pub fn allows(&self, server: &str, threat: &Threat) -> bool {
+        self.ignore
+            .iter()
+            .any(|entry| entry.id == threat.id && entry.server == server)
+    }
+
+    /// Mark allowlisted threats as suppressed, keeping them in the results.
+    /// Returns how many threats are marked.
+    pub fn mark(&self, results: &mut [ScanResult]) -> usize {
+        let mut marked = 0;
+        for result in results {
+            for threat in &mut result.threats {
+                if threat.suppressed || self.allows(&result.server.name, threat) {
+                    threat.suppressed = true;
+                    marked += 1;
+                }
+            }
+        }
+        marked
+    }
+
+    /// Remove allowlisted threats, including ones already marked by
+    /// [`Allowlist::mark`]. Returns how many were removed.
+    pub fn filter(&self, results: &mut [ScanResult]) -> usize {
+        let suppressed = self.mark(results);
+        for result in results.iter_mut() {
+            result.threats.retain(|t| !t.suppressed);
+        }
+        suppressed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::ServerConfig;
+    use crate::scanner::report::{Severity, ThreatCategory};
+    use chrono::Utc;
+    use std::time::Duration;
+
+    fn result(server: &str, ids: &[&str]) -> ScanResult {
+        ScanResult {
+            server: ServerConfig::new(server, "npx"),
+            tools: vec![],
+            resources: vec![],
+            prompts: vec![],
+            threats: ids
+                .iter()
+                .map(|id| Threat::new(*id, Severity::High, ThreatCategory::NoAuth, "t"))
+                .collect(),
+            snapshot_diff: None,
+            scan_duration: Duration::from_millis(1),
+            scanned_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn filter_matches_id_and_server() {
+        let allowlist: Allowlist = toml::from_str(
+            r#"
+            [[ignore]]
+            id = "NO-AUTH-LOCAL"
+            server = "alpha"
+            reason = "Local only"
+            "#,
+        )
+        .unwrap();
+
+        let mut results = vec![
+            result("alpha", &["NO-AUTH-LOCAL", "DESC-INJ-001"]),
+            result("beta", &["NO-AUTH-LOCAL"]),
+        ];
+        assert_eq!(allowlist.mark(&mut results), 1);
+        assert!(results[0].threats[0].suppressed);
+        assert_eq!(results[0].threats.len(), 2);
+
+        assert_eq!(allowlist.filter(&mut results), 1);
+        assert_eq!(results[0].threats.len(), 1);
+        assert_eq!(results[0].threats[0].id, "DESC-INJ-001");
+        assert_eq!(results[1].threats.len(), 1);
+    }
+
+    #[test]
+    fn reads_remediation_file_suppressions() {
+        let mut results = vec![result("alpha", &["NO-AUTH-LOCAL"])];
+        results[0].threats[0].severity = Severity::Info;
+        let remediation = crate::scanner::remediation::remediation_toml(&results);
+
+        let allowlist: Allowlist = toml::from_str(&remediation).unwrap();
+        assert_eq!(allowlist.ignore.len(), 1);
+        assert_eq!(allowlist.filter(&mut results), 1);
+    }
+
+    #[test]
+    fn load_reports_bad_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("allowlist.toml");
+        std::fs::write(&path, "[[ignore]]\nid = 1\n").unwrap();
+        assert!(matches!(
+            Allowlist::load(&path),
+            Err(Error::ConfigParse { .. })
+        ));
+    }
+}
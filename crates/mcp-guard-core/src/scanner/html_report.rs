@@ -0,0 +1,401 @@
+//! Self-contained HTML security report built from stored scan history.
+
+use crate::db::StoredScanResult;
+use crate::scanner::report::{Severity, Threat};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+const CHART_WIDTH: f64 = 760.0;
+const CHART_HEIGHT: f64 = 220.0;
+const CHART_PADDING: f64 = 36.0;
+
+/// Line colors for the trend chart, reused in order when there are more
+/// servers than colors.
+const SERIES_COLORS: &[&str] = &[
+    "#2563eb", "#dc2626", "#16a34a", "#d97706", "#7c3aed", "#0891b2", "#db2777", "#4b5563",
+];
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; margin: 2rem auto; max-width: 960px; color: #1f2937; padding: 0 1rem; }
+h1 { margin-bottom: 0.25rem; }
+.meta { color: #6b7280; margin-top: 0; }
+table { border-collapse: collapse; width: 100%; margin: 1rem 0 2rem; }
+th, td { text-align: left; padding: 0.5rem; border-bottom: 1px solid #e5e7eb; vertical-align: top; }
+th { background: #f9fafb; }
+.badge { display: inline-block; padding: 0.1rem 0.6rem; border-radius: 999px; font-weight: 600; font-size: 0.85rem; color: #fff; }
+.pass { background: #16a34a; }
+.fail { background: #dc2626; }
+.sev { font-weight: 600; text-transform: uppercase; font-size: 0.8rem; }
+.sev-critical { color: #991b1b; }
+.sev-high { color: #dc2626; }
+.sev-medium { color: #d97706; }
+.sev-low { color: #2563eb; }
+.sev-info { color: #6b7280; }
+.threat { border: 1px solid #e5e7eb; border-radius: 6px; padding: 0.75rem 1rem; margin: 0.75rem 0; }
+.threat h4 { margin: 0 0 0.25rem; }
+pre { background: #f3f4f6; padding: 0.5rem; white-space: pre-wrap; word-break: break-word; }
+.legend span { cursor: pointer; margin-right: 1rem; user-select: none; }
+.legend span.off { opacity: 0.35; }
+svg text { font-size: 11px; fill: #6b7280; }
+"#;
+
+/// Clicking a legend entry hides or shows that server's line.
+const SCRIPT: &str = r#"
+document.querySelectorAll('.legend span').forEach(function (item) {
+  item.addEventListener('click', function () {
+    var series = document.getElementById(item.dataset.series);
+    var hidden = item.classList.toggle('off');
+    series.style.display = hidden ? 'none' : '';
+  });
+});
+"#;
+
+/// Render an HTML report from `history` (oldest scan first, as returned by
+/// [`ScanResultStore::history`](crate::db::ScanResultStore::history)). Each
+/// server's most recent scan decides its summary row, badge, and the
+/// threats listed in detail; every scan contributes to the trend chart.
+pub fn html_report(
+    history: &[(StoredScanResult, Vec<Threat>)],
+    generated_at: DateTime<Utc>,
+) -> String {
+    let mut servers: BTreeMap<&str, Vec<&(StoredScanResult, Vec<Threat>)>> = BTreeMap::new();
+    for entry in history {
+        servers
+            .entry(entry.0.server_name.as_str())
+            .or_default()
+            .push(entry);
+    }
+
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>mcp-scanner security report</title>\n<style>{}</style>\n</head>\n<body>\n\
+         <h1>MCP security report</h1>\n\
+         <p class=\"meta\">Generated by mcp-scanner {} on {} from {} stored scan(s)</p>\n",
+        STYLE,
+        env!("CARGO_PKG_VERSION"),
+        generated_at.format("%Y-%m-%d %H:%M UTC"),
+        history.len()
+    );
+
+    if servers.is_empty() {
+        out.push_str("<p>No scans recorded. Run <code>mcp-scanner scan</code> first.</p>\n");
+        out.push_str("</body>\n</html>\n");
+        return out;
+    }
+
+    write_summary(&mut out, &servers);
+    write_trend_chart(&mut out, &servers);
+    write_details(&mut out, &servers);
+
+    let _ = write!(out, "<script>{}</script>\n</body>\n</html>\n", SCRIPT);
+    out
+}
+
+/// A server fails when its latest scan found a critical or high threat,
+/// matching the scan command's exit code.
+fn passes(threats: &[Threat]) -> bool {
+    !threats.iter().any(|t| t.severity <= Severity::High)
+}
+
+fn highest_severity(threats: &[Threat]) -> Option<Severity> {
+    threats.iter().map(|t| t.severity).min()
+}
+
+fn write_summary(
+    out: &mut String,
+    servers: &BTreeMap<&str, Vec<&(StoredScanResult, Vec<Threat>)>>,
+) {
+    out.push_str(
+        "<h2>Summary</h2>\n<table>\n<thead><tr><th>Server</th><th>Source</th><th>Last scanned</th>\
+         <th>Threats</th><th>Highest severity</th><th>Status</th></tr></thead>\n<tbody>\n",
+    );
+
+    for (name, scans) in servers {
+        let (latest, threats) = scans[scans.len() - 1];
+        let severity = match highest_severity(threats) {
+            Some(severity) => format!("<span class=\"sev sev-{0}\">{0}</span>", severity.as_str()),
+            None => "&mdash;".to_string(),
+        };
+        let badge = if passes(threats) {
+            "<span class=\"badge pass\">PASS</span>"
+        } else {
+            "<span class=\"badge fail\">FAIL</span>"
+        };
+
+        let _ = writeln!(
+            out,
+            "<tr class=\"server-row\"><td><a href=\"#{}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            anchor(name),
+            escape_html(name),
+            escape_html(&latest.server_source),
+            latest.scanned_at.format("%Y-%m-%d %H:%M"),
+            threats.len(),
+            severity,
+            badge
+        );
+    }
+
+    out.push_str("</tbody>\n</table>\n");
+}
+
+fn write_trend_chart(
+    out: &mut String,
+    servers: &BTreeMap<&str, Vec<&(StoredScanResult, Vec<Threat>)>>,
+) {
+    let scans = servers.values().flatten();
+    let times = scans.clone().map(|(r, _)| r.scanned_at.timestamp());
+    let (Some(first), Some(last)) = (times.clone().min(), times.max()) else {
+        return;
+    };
+    let max_count = scans.map(|(r, _)| r.threat_count).max().unwrap_or(0).max(1);
+
+    let plot_width = CHART_WIDTH - 2.0 * CHART_PADDING;
+    let plot_height = CHART_HEIGHT - 2.0 * CHART_PADDING;
+    let x = |t: i64| {
+        if last == first {
+            CHART_PADDING + plot_width / 2.0
+        } else {
+            CHART_PADDING + (t - first) as f64 / (last - first) as f64 * plot_width
+        }
+    };
+    let y =
+        |count: i64| CHART_PADDING + plot_height - count as f64 / max_count as f64 * plot_height;
+
+    let _ = write!(
+        out,
+        "<h2>Threat trend</h2>\n<svg viewBox=\"0 0 {w} {h}\" width=\"100%\" role=\"img\" aria-label=\"Threat count over time per server\">\n\
+         <line x1=\"{p}\" y1=\"{b}\" x2=\"{r}\" y2=\"{b}\" stroke=\"#d1d5db\"/>\n\
+         <line x1=\"{p}\" y1=\"{p}\" x2=\"{p}\" y2=\"{b}\" stroke=\"#d1d5db\"/>\n\
+         <text x=\"{lx}\" y=\"{ty}\" text-anchor=\"end\">{max}</text>\n\
+         <text x=\"{lx}\" y=\"{by}\" text-anchor=\"end\">0</text>\n\
+         <text x=\"{p}\" y=\"{dy}\">{start}</text>\n\
+         <text x=\"{r}\" y=\"{dy}\" text-anchor=\"end\">{end}</text>\n",
+        w = CHART_WIDTH,
+        h = CHART_HEIGHT,
+        p = CHART_PADDING,
+        r = CHART_WIDTH - CHART_PADDING,
+        b = CHART_HEIGHT - CHART_PADDING,
+        lx = CHART_PADDING - 6.0,
+        ty = CHART_PADDING + 4.0,
+        by = CHART_HEIGHT - CHART_PADDING + 4.0,
+        dy = CHART_HEIGHT - CHART_PADDING + 18.0,
+        max = max_count,
+        start = DateTime::from_timestamp(first, 0).unwrap_or_default().format("%Y-%m-%d"),
+        end = DateTime::from_timestamp(last, 0).unwrap_or_default().format("%Y-%m-%d"),
+    );
+
+    let mut legend = String::from("<p class=\"legend\">");
+    for (index, (name, scans)) in servers.iter().enumerate() {
+        let color = SERIES_COLORS[index % SERIES_COLORS.len()];
+        let points: Vec<(f64, f64)> = scans
+            .iter()
+            .map(|(r, _)| (x(r.scanned_at.timestamp()), y(r.threat_count)))
+            .collect();
+
+        let _ = write!(out, "<g id=\"series-{}\">", index);
+        if points.len() > 1 {
+            let _ = write!(
+                out,
+                "<polyline fill=\"none\" stroke=\"{}\" stroke-width=\"2\" points=\"{}\"/>",
+                color,
+                points
+                    .iter()
+                    .map(|(px, py)| format!("{:.1},{:.1}", px, py))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            );
+        }
+        for ((px, py), (result, _)) in points.iter().zip(scans) {
+            let _ = write!(
+                out,
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"{}\"><title>{}: {} threat(s) on {}</title></circle>",
+                px,
+                py,
+                color,
+                escape_html(name),
+                result.threat_count,
+                result.scanned_at.format("%Y-%m-%d %H:%M")
+            );
+        }
+        out.push_str("</g>\n");
+
+        let _ = write!(
+            legend,
+            "<span data-series=\"series-{}\" style=\"color: {}\">&#9632; {}</span>",
+            index,
+            color,
+            escape_html(name)
+        );
+    }
+    legend.push_str("</p>\n");
+
+    out.push_str("</svg>\n");
+    out.push_str(&legend);
+}
+
+fn write_details(
+    out: &mut String,
+    servers: &BTreeMap<&str, Vec<&(StoredScanResult, Vec<Threat>)>>,
+) {
+    out.push_str("<h2>Threats</h2>\n");
+
+    for (name, scans) in servers {
+        let (latest, threats) = scans[scans.len() - 1];
+        let _ = writeln!(
+            out,
+            "<h3 id=\"{}\">{}</h3>\n<p class=\"meta\">Latest scan {}: {} tool(s), {} resource(s)</p>",
+            anchor(name),
+            escape_html(name),
+            latest.scanned_at.format("%Y-%m-%d %H:%M"),
+            latest.tool_count,
+            latest.resource_count
+        );
+
+        if threats.is_empty() {
+            out.push_str("<p>No threats found.</p>\n");
+            continue;
+        }
+
+        let mut threats: Vec<&Threat> = threats.iter().collect();
+        threats.sort_by_key(|t| t.severity);
+        for threat in threats {
+            let _ = write!(
+                out,
+                "<div class=\"threat\">\n<h4><span class=\"sev sev-{sev}\">{sev}</span> {title}</h4>\n\
+                 <p><code>{id}</code>{tool}</p>\n<p>{message}</p>\n",
+                sev = threat.severity.as_str(),
+                title = escape_html(&threat.title),
+                id = escape_html(&threat.id),
+                tool = threat
+                    .tool_name
+                    .as_deref()
+                    .map(|t| format!(" &middot; tool <code>{}</code>", escape_html(t)))
+                    .unwrap_or_default(),
+                message = escape_html(&threat.message),
+            );
+            if !threat.evidence.is_empty() {
+                let _ = write!(
+                    out,
+                    "<p><strong>Evidence</strong></p>\n<pre>{}</pre>\n",
+                    escape_html(&threat.evidence)
+                );
+            }
+            if !threat.remediation.is_empty() {
+                let _ = writeln!(
+                    out,
+                    "<p><strong>Remediation:</strong> {}</p>",
+                    escape_html(&threat.remediation)
+                );
+            }
+            out.push_str("</div>\n");
+        }
+    }
+}
+
+/// An `id` attribute for a server's details section.
+fn anchor(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("server-{}", slug)
+}
+
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::SeverityCounts;
+    use crate::scanner::report::ThreatCategory;
+
+    fn scan(
+        id: i64,
+        server: &str,
+        days_ago: i64,
+        threats: Vec<Threat>,
+    ) -> (StoredScanResult, Vec<Threat>) {
+        (
+            StoredScanResult {
+                id,
+                server_name: server.to_string(),
+                server_source: "Claude Desktop".to_string(),
+                tool_count: 3,
+                resource_count: 0,
+                threat_count: threats.len() as i64,
+                threats_by_severity: SeverityCounts::from_threats(&threats),
+                scan_duration_ms: 100,
+                scanned_at: Utc::now() - chrono::Duration::days(days_ago),
+            },
+            threats,
+        )
+    }
+
+    fn threat(id: &str, severity: Severity) -> Threat {
+        Threat::new(
+            id,
+            severity,
+            ThreatCategory::DescriptionInjection,
+            "Injected <b>instructions</b>",
+        )
+        .with_evidence("<IMPORTANT>ignore previous</IMPORTANT>")
+        .with_remediation("Remove the server")
+    }
+
+    #[test]
+    fn renders_row_and_details_per_server() {
+        let history = vec![
+            scan(1, "filesystem", 3, vec![]),
+            scan(2, "evil", 2, vec![threat("INJ-1", Severity::Critical)]),
+            scan(3, "filesystem", 1, vec![threat("LOW-1", Severity::Low)]),
+        ];
+
+        let html = html_report(&history, Utc::now());
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<html"));
+        assert!(html.contains("</html>"));
+        assert_eq!(html.matches("<tr class=\"server-row\">").count(), 2);
+        assert!(html.contains("<h3 id=\"server-evil\">evil</h3>"));
+        assert!(html.contains("<h3 id=\"server-filesystem\">filesystem</h3>"));
+        assert_eq!(html.matches("badge fail").count(), 1);
+        assert_eq!(html.matches("badge pass").count(), 1);
+        assert!(html.contains("<polyline"));
+        assert!(html.contains("INJ-1"));
+        assert!(html.contains("Remove the server"));
+    }
+
+    #[test]
+    fn escapes_threat_text() {
+        let history = vec![scan(1, "evil", 0, vec![threat("INJ-1", Severity::High)])];
+        let html = html_report(&history, Utc::now());
+
+        assert!(html.contains("&lt;IMPORTANT&gt;ignore previous&lt;/IMPORTANT&gt;"));
+        assert!(!html.contains("<IMPORTANT>"));
+        assert!(html.contains("Injected &lt;b&gt;instructions&lt;/b&gt;"));
+    }
+
+    #[test]
+    fn empty_history_still_renders_document() {
+        let html = html_report(&[], Utc::now());
+        assert!(html.contains("<html"));
+        assert!(html.contains("No scans recorded"));
+        assert!(!html.contains("<table>"));
+    }
+}
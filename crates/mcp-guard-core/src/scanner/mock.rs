@@ -0,0 +1,94 @@
+//! Fixture files standing in for a server in [`Scanner::scan_mock`].
+//!
+//! ```json
+//! {
+//!   "tools": [{ "name": "read_file", "description": "...", "inputSchema": { "type": "object" } }],
+//!   "resources": [{ "uri": "file:///etc/hosts", "name": "hosts" }]
+//! }
+//! ```
+//!
+//! [`Scanner::scan_mock`]: crate::scanner::Scanner::scan_mock
+
+use crate::error::{Error, Result};
+use crate::protocol::mcp::{Resource, Tool};
+use crate::scanner::report::{ResourceInfo, ToolInfo};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Tools and resources in the shapes `tools/list` and `resources/list`
+/// return them.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MockServer {
+    #[serde(default)]
+    pub tools: Vec<Tool>,
+    #[serde(default)]
+    pub resources: Vec<Resource>,
+}
+
+impl MockServer {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| Error::ConfigRead {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        serde_json::from_str(&content).map_err(|e| Error::config_parse(path, e.to_string()))
+    }
+
+    pub fn into_parts(self) -> (Vec<ToolInfo>, Vec<ResourceInfo>) {
+        (
+            self.tools.into_iter().map(ToolInfo::from).collect(),
+            self.resources.into_iter().map(ResourceInfo::from).collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::Scanner;
+
+    #[test]
+    fn scan_mock_runs_detectors_on_fixture() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "tools": [{
+                    "name": "run",
+                    "description": "Execute a shell command",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": { "command": { "type": "string" } },
+                        "required": ["command"]
+                    }
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let (tools, resources) = MockServer::load(&path).unwrap().into_parts();
+        assert!(resources.is_empty());
+
+        let result = Scanner::new()
+            .without_snapshots()
+            .scan_mock(tools, resources)
+            .unwrap();
+        assert_eq!(result.tools.len(), 1);
+        assert!(result
+            .threats
+            .iter()
+            .any(|t| t.tool_name.as_deref() == Some("run")));
+    }
+
+    #[test]
+    fn load_rejects_tools_without_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server.json");
+        std::fs::write(&path, r#"{ "tools": [{ "name": "run" }] }"#).unwrap();
+        assert!(matches!(
+            MockServer::load(&path),
+            Err(Error::ConfigParse { .. })
+        ));
+    }
+}
@@ -0,0 +1,309 @@
+//! Threat detectors loaded from dynamic libraries, for rules that can't be
+//! published or built into the scanner.
+//!
+//! # ABI
+//!
+//! A plugin is a `cdylib` exporting one C function that returns its
+//! function table by value:
+//!
+//! ```c
+//! typedef struct {
+//!     uint32_t abi_version;                     /* MCP_GUARD_PLUGIN_ABI_VERSION (1) */
+//!     const char *(*name_fn)(void);             /* detector name, e.g. "AcmePolicy" */
+//!     const char *(*version_fn)(void);          /* plugin version, e.g. "1.2.0" */
+//!     const char *(*category_fn)(void);         /* threat category, e.g. "permission_scope" */
+//!     char *(*detect_fn)(const char *input);    /* threats found, as JSON */
+//!     void (*free_fn)(char *result);            /* frees a detect_fn result */
+//! } ThreatDetectorPlugin;
+//!
+//! ThreatDetectorPlugin create_detector(void);
+//! ```
+//!
+//! All strings are NUL-terminated UTF-8. Those returned by `name_fn`,
+//! `version_fn`, and `category_fn` must stay valid while the library is
+//! loaded; the scanner never unloads it.
+//!
+//! `detect_fn` receives one server's details as JSON and returns a JSON
+//! array of threats, or NULL if it fails. The scanner hands every non-NULL
+//! result back to `free_fn`. Servers may be scanned concurrently, so
+//! `detect_fn` must be thread-safe.
+//!
+//! ```json
+//! {
+//!   "server": { "name": "filesystem", "command": "npx", "args": [], "env": {}, ... },
+//!   "tools": [{ "name": "read_file", "description": "...", "input_schema": { ... } }],
+//!   "resources": [{ "uri": "file:///etc/hosts", "name": "hosts", ... }]
+//! }
+//! ```
+//!
+//! ```json
+//! [{
+//!   "id": "ACME-001",
+//!   "severity": "high",
+//!   "category": "permission_scope",
+//!   "title": "Tool outside the approved list",
+//!   "message": "...", "evidence": "...", "remediation": "...", "tool_name": "read_file"
+//! }]
+//! ```
+//!
+//! `message`, `evidence`, `remediation`, and `tool_name` may be omitted.
+
+use crate::discovery::ServerConfig;
+use crate::error::{Error, Result};
+use crate::scanner::report::{ResourceInfo, Severity, Threat, ThreatCategory, ToolInfo};
+use crate::scanner::threats::ThreatDetector;
+use libloading::Library;
+use serde::{Deserialize, Serialize};
+use std::ffi::{c_char, CStr, CString};
+use std::path::Path;
+use std::sync::Arc;
+
+/// ABI version a plugin's `create_detector` must report.
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Function table returned by a plugin's `create_detector`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct ThreatDetectorPlugin {
+    pub abi_version: u32,
+    pub name_fn: extern "C" fn() -> *const c_char,
+    pub version_fn: extern "C" fn() -> *const c_char,
+    pub category_fn: extern "C" fn() -> *const c_char,
+    pub detect_fn: extern "C" fn(input: *const c_char) -> *mut c_char,
+    pub free_fn: extern "C" fn(result: *mut c_char),
+}
+
+type CreateDetector = unsafe extern "C" fn() -> ThreatDetectorPlugin;
+
+#[derive(Serialize)]
+struct DetectInput<'a> {
+    server: &'a ServerConfig,
+    tools: &'a [ToolInfo],
+    resources: &'a [ResourceInfo],
+}
+
+#[derive(Deserialize)]
+struct PluginThreat {
+    id: String,
+    severity: Severity,
+    category: ThreatCategory,
+    title: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    evidence: String,
+    #[serde(default)]
+    remediation: String,
+    #[serde(default)]
+    tool_name: Option<String>,
+}
+
+/// A detector implemented by a plugin library.
+pub struct PluginDetector {
+    plugin: ThreatDetectorPlugin,
+    name: &'static str,
+    description: &'static str,
+    category: ThreatCategory,
+    /// Keeps the plugin's functions loaded.
+    _library: Arc<Library>,
+}
+
+impl PluginDetector {
+    /// Load the plugin library at `path` and call its `create_detector`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let error = |message: String| {
+            Error::Other(format!(
+                "Failed to load plugin {}: {}",
+                path.display(),
+                message
+            ))
+        };
+
+        // SAFETY: loading runs the library's initializers; plugins are
+        // trusted code chosen by the user, like the scanner itself
+        let library = unsafe { Library::new(path) }.map_err(|e| error(e.to_string()))?;
+        // SAFETY: the symbol is declared by the ABI documented above
+        let plugin = unsafe {
+            let create = library
+                .get::<CreateDetector>(b"create_detector")
+                .map_err(|e| error(e.to_string()))?;
+            create()
+        };
+        if plugin.abi_version != PLUGIN_ABI_VERSION {
+            return Err(error(format!(
+                "ABI version {} is not supported (expected {})",
+                plugin.abi_version, PLUGIN_ABI_VERSION
+            )));
+        }
+
+        let name = plugin_str(plugin.name_fn).ok_or_else(|| error("no name".to_string()))?;
+        let version = plugin_str(plugin.version_fn).unwrap_or_default();
+        let category = plugin_str(plugin.category_fn)
+            .and_then(|c| serde_json::from_value(serde_json::Value::String(c)).ok())
+            .ok_or_else(|| error("unknown threat category".to_string()))?;
+
+        // Detector names and descriptions are `'static`; plugins stay loaded
+        // for the life of the process anyway
+        let description = format!("Plugin {} {} from {}", name, version, path.display());
+        Ok(Self {
+            plugin,
+            name: Box::leak(name.into_boxed_str()),
+            description: Box::leak(description.into_boxed_str()),
+            category,
+            _library: Arc::new(library),
+        })
+    }
+
+    fn run(&self, input: &str) -> Option<String> {
+        let input = CString::new(input).ok()?;
+        let result = (self.plugin.detect_fn)(input.as_ptr());
+        if result.is_null() {
+            return None;
+        }
+        // SAFETY: detect_fn returns a NUL-terminated string, valid until
+        // it's passed to free_fn
+        let output = unsafe { CStr::from_ptr(result) }
+            .to_string_lossy()
+            .into_owned();
+        (self.plugin.free_fn)(result);
+        Some(output)
+    }
+}
+
+/// A string returned by one of the plugin's metadata functions.
+fn plugin_str(f: extern "C" fn() -> *const c_char) -> Option<String> {
+    let ptr = f();
+    if ptr.is_null() {
+        return None;
+    }
+    // SAFETY: the ABI requires a NUL-terminated string that outlives the call
+    let s = unsafe { CStr::from_ptr(ptr) };
+    Some(s.to_string_lossy().into_owned())
+}
+
+impl ThreatDetector for PluginDetector {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn category(&self) -> ThreatCategory {
+        self.category
+    }
+
+    fn detect(
+        &self,
+        server: &ServerConfig,
+        tools: &[ToolInfo],
+        resources: &[ResourceInfo],
+    ) -> Vec<Threat> {
+        let input = DetectInput {
+            server,
+            tools,
+            resources,
+        };
+        let Ok(input) = serde_json::to_string(&input) else {
+            return Vec::new();
+        };
+        let Some(output) = self.run(&input) else {
+            tracing::warn!(plugin = self.name, server = %server.name, "Plugin detector failed");
+            return Vec::new();
+        };
+
+        match serde_json::from_str::<Vec<PluginThreat>>(&output) {
+            Ok(threats) => threats
+                .into_iter()
+                .map(|t| {
+                    let threat = Threat::new(t.id, t.severity, t.category, t.title)
+                        .with_message(t.message)
+                        .with_evidence(t.evidence)
+                        .with_remediation(t.remediation);
+                    match t.tool_name {
+                        Some(tool) => threat.with_tool(tool),
+                        None => threat,
+                    }
+                })
+                .collect(),
+            Err(e) => {
+                tracing::warn!(plugin = self.name, error = %e, "Plugin returned invalid threats");
+                Vec::new()
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    /// Compile the fixture plugin into `dir` with the toolchain running the
+    /// tests.
+    fn build_plugin(dir: &Path) -> std::path::PathBuf {
+        let source = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/plugin.rs");
+        let output = dir.join("libtest_plugin.so");
+        let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+        let status = Command::new(rustc)
+            .args(["--edition", "2021", "--crate-type", "cdylib", "-o"])
+            .arg(&output)
+            .arg(source)
+            .status()
+            .unwrap();
+        assert!(status.success(), "failed to build the test plugin");
+        output
+    }
+
+    fn tool(name: &str) -> ToolInfo {
+        ToolInfo {
+            name: name.to_string(),
+            description: Some("Test tool".to_string()),
+            input_schema: serde_json::json!({ "type": "object" }),
+        }
+    }
+
+    #[test]
+    fn loads_and_runs_plugin() {
+        let dir = tempfile::tempdir().unwrap();
+        let detector = PluginDetector::load(&build_plugin(dir.path())).unwrap();
+        assert_eq!(detector.name(), "TestPlugin");
+        assert_eq!(detector.category(), ThreatCategory::PermissionScope);
+        assert!(detector.description().contains("0.1.0"));
+
+        let server = ServerConfig::new("test", "cmd");
+        let threats = detector.detect(&server, &[tool("secret_token"), tool("echo")], &[]);
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].id, "PLUGIN-TEST-001");
+        assert_eq!(threats[0].severity, Severity::High);
+        assert_eq!(threats[0].tool_name.as_deref(), Some("secret_token"));
+        assert!(detector.detect(&server, &[tool("echo")], &[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn scanner_runs_plugin_detectors() {
+        let dir = tempfile::tempdir().unwrap();
+        let scanner = crate::scanner::Scanner::new()
+            .without_snapshots()
+            .with_plugins(vec![build_plugin(dir.path())])
+            .unwrap();
+        assert!(scanner.detector_names().contains(&"TestPlugin"));
+
+        let result = scanner
+            .scan_mock(vec![tool("secret_token")], vec![])
+            .unwrap();
+        let threat = result
+            .threats
+            .iter()
+            .find(|t| t.id == "PLUGIN-TEST-001")
+            .unwrap();
+        assert_eq!(threat.detector.as_deref(), Some("TestPlugin"));
+    }
+
+    #[test]
+    fn load_reports_missing_library() {
+        let result = PluginDetector::load(Path::new("/nonexistent/libplugin.so"));
+        assert!(matches!(result, Err(Error::Other(m)) if m.contains("Failed to load plugin")));
+    }
+}
@@ -0,0 +1,211 @@
+//! Suggested remediation output generated from scan results.
+
+use crate::scanner::report::{ScanResult, Severity};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// Render a TOML document with suggested suppressions for Info-level
+/// findings, as `[[ignore]]` entries read by `scan --allowlist`, and block
+/// rules for Critical/High threats, as `[[rules]]` read by `proxy --config`
+/// and scoped to the server the threat was found on. `init
+/// --from-remediation` adds both to the user's allowlist and rules files.
+pub fn remediation_toml(results: &[ScanResult]) -> String {
+    let mut out = format!(
+        "# Suggested remediations generated by mcp-scanner {} on {}\n\
+         # Review each entry before applying it.\n",
+        env!("CARGO_PKG_VERSION"),
+        chrono::Utc::now().to_rfc3339()
+    );
+
+    for result in results {
+        for threat in result
+            .threats
+            .iter()
+            .filter(|t| t.severity == Severity::Info)
+        {
+            let _ = write!(
+                out,
+                "\n[[ignore]]\nid = {}\nserver = {}\nreason = {}\n",
+                toml_string(&threat.id),
+                toml_string(&result.server.name),
+                toml_string(&threat.title),
+            );
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for result in results {
+        for threat in result
+            .threats
+            .iter()
+            .filter(|t| t.severity <= Severity::High)
+        {
+            // Only tool-level threats can be expressed as a proxy rule
+            let Some(tool_name) = threat.tool_name.as_deref() else {
+                continue;
+            };
+
+            if !seen.insert((result.server.name.as_str(), tool_name)) {
+                continue;
+            }
+
+            let _ = write!(
+                out,
+                "\n# {} [{}]\n[[rules]]\ntool_pattern = {}\nserver = {}\naction = \"block\"\nreason = {}\npriority = 100\n",
+                comment_text(&threat.id),
+                threat.severity,
+                toml_string(tool_name),
+                toml_string(&result.server.name),
+                toml_string(&threat.title),
+            );
+        }
+    }
+
+    out
+}
+
+fn toml_string(s: &str) -> String {
+    toml::Value::String(s.to_string()).to_string()
+}
+
+/// `s` on one line, so it can't end a comment early.
+fn comment_text(s: &str) -> String {
+    s.replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::ServerConfig;
+    use crate::scanner::report::{Threat, ThreatCategory};
+    use chrono::Utc;
+    use std::time::Duration;
+
+    fn make_result(threats: Vec<Threat>) -> ScanResult {
+        ScanResult {
+            server: ServerConfig::new("test-server", "npx"),
+            tools: vec![],
+            resources: vec![],
+            prompts: vec![],
+            threats,
+            snapshot_diff: None,
+            scan_duration: Duration::from_millis(10),
+            scanned_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn generates_suppressions_and_block_rules() {
+        let results = vec![make_result(vec![
+            Threat::new(
+                "NO-AUTH-LOCAL",
+                Severity::Info,
+                ThreatCategory::NoAuth,
+                "No authentication configured",
+            ),
+            Threat::new(
+                "PERM-EXEC-run",
+                Severity::Critical,
+                ThreatCategory::PermissionScope,
+                "Code execution capability",
+            )
+            .with_tool("run"),
+            Threat::new(
+                "PERM-ROOT",
+                Severity::High,
+                ThreatCategory::PermissionScope,
+                "Server has root filesystem access",
+            ),
+        ])];
+
+        let output = remediation_toml(&results);
+        let parsed: toml::Value = toml::from_str(&output).unwrap();
+
+        let suppressions = parsed["ignore"].as_array().unwrap();
+        assert_eq!(suppressions.len(), 1);
+        assert_eq!(suppressions[0]["id"].as_str(), Some("NO-AUTH-LOCAL"));
+
+        let rules = parsed["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0]["tool_pattern"].as_str(), Some("run"));
+        assert_eq!(rules[0]["server"].as_str(), Some("test-server"));
+        assert_eq!(rules[0]["action"].as_str(), Some("block"));
+    }
+
+    #[cfg(feature = "proxy")]
+    #[test]
+    fn output_loads_as_allowlist_and_proxy_config() {
+        use crate::proxy::config::ProxyConfig;
+        use crate::proxy::rules::RuleResult;
+        use crate::scanner::Allowlist;
+
+        let results = vec![make_result(vec![
+            Threat::new(
+                "NO-AUTH-LOCAL",
+                Severity::Info,
+                ThreatCategory::NoAuth,
+                "No authentication configured",
+            ),
+            Threat::new(
+                "PERM-EXEC-run",
+                Severity::Critical,
+                ThreatCategory::PermissionScope,
+                "Code execution capability",
+            )
+            .with_tool("run"),
+        ])];
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("remediation.toml");
+        std::fs::write(&path, remediation_toml(&results)).unwrap();
+
+        let allowlist = Allowlist::load(&path).unwrap();
+        assert!(allowlist.allows("test-server", &results[0].threats[0]));
+        assert!(!allowlist.allows("test-server", &results[0].threats[1]));
+
+        let config = ProxyConfig::load(&path).unwrap();
+        let blocks = |server: &str| {
+            config
+                .rule_engine_for(server)
+                .unwrap()
+                .evaluate("run")
+                .iter()
+                .any(|r| matches!(r, RuleResult::Block { .. }))
+        };
+        assert!(blocks("test-server"));
+        assert!(!blocks("other-server"));
+    }
+
+    #[test]
+    fn newlines_in_names_stay_inside_values() {
+        let mut result = make_result(vec![Threat::new(
+            "PERM-EXEC-run\n[[rules]]",
+            Severity::Critical,
+            ThreatCategory::PermissionScope,
+            "Code execution capability",
+        )
+        .with_tool("run")]);
+        result.server.name = "evil\n[[rules]]\ntool_pattern = \"*\"".to_string();
+
+        let parsed: toml::Value = toml::from_str(&remediation_toml(&[result])).unwrap();
+        let rules = parsed["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0]["tool_pattern"].as_str(), Some("run"));
+        assert!(rules[0]["server"].as_str().unwrap().starts_with("evil\n"));
+    }
+
+    #[test]
+    fn escapes_quotes_in_values() {
+        let results = vec![make_result(vec![Threat::new(
+            "X",
+            Severity::Info,
+            ThreatCategory::NoAuth,
+            "Title with \"quotes\"",
+        )])];
+
+        let parsed: toml::Value = toml::from_str(&remediation_toml(&results)).unwrap();
+        assert_eq!(
+            parsed["ignore"][0]["reason"].as_str(),
+            Some("Title with \"quotes\"")
+        );
+    }
+}
@@ -0,0 +1,733 @@
+//! Scan result and threat types.
+
+pub mod junit;
+pub mod markdown;
+pub mod sarif;
+
+use crate::discovery::ServerConfig;
+use crate::error::Error;
+use crate::protocol::mcp::PromptArgument;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// Result of [`Scanner::check`](crate::scanner::Scanner::check): what a
+/// server reported about itself, without any threat detection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerCheck {
+    /// Name from the server's config
+    pub server: String,
+    /// Name the server reported in `initialize`
+    pub server_name: String,
+    pub server_version: String,
+    pub protocol_version: String,
+    /// Whether the server advertises the tools capability
+    pub tools: bool,
+    pub resources: bool,
+    pub prompts: bool,
+    pub tool_count: usize,
+    pub duration_ms: u64,
+}
+
+/// Result of [`Scanner::inspect`](crate::scanner::Scanner::inspect):
+/// everything a server advertises, as reported.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerInspection {
+    /// Name from the server's config
+    pub server: String,
+    /// Name the server reported in `initialize`
+    pub server_name: String,
+    pub server_version: String,
+    pub protocol_version: String,
+    /// The `capabilities` object from `initialize`
+    pub capabilities: serde_json::Value,
+    pub instructions: Option<String>,
+    pub tools: Vec<ToolInfo>,
+    pub resources: Vec<ResourceInfo>,
+    pub prompts: Vec<PromptInfo>,
+}
+
+/// Result of [`Scanner::bench`](crate::scanner::Scanner::bench): round-trip
+/// latency of `tools/list`, in milliseconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchResult {
+    pub server: String,
+    /// Requests measured, excluding warmup
+    pub requests: usize,
+    /// Time to spawn or connect and complete `initialize`
+    pub init_ms: f64,
+    pub min_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanResult {
+    pub server: ServerConfig,
+    pub tools: Vec<ToolInfo>,
+    pub resources: Vec<ResourceInfo>,
+    #[serde(default)]
+    pub prompts: Vec<PromptInfo>,
+    pub threats: Vec<Threat>,
+    /// Diff against the last stored snapshot for this server.
+    /// `None` on the first scan when no prior snapshot exists.
+    pub snapshot_diff: Option<SnapshotDiff>,
+    #[serde(with = "duration_millis")]
+    pub scan_duration: Duration,
+    pub scanned_at: DateTime<Utc>,
+}
+
+/// Outcome of [`Scanner::scan_all`](crate::scanner::Scanner::scan_all): the
+/// servers that scanned, and those that failed with their errors. Both are in
+/// the order the servers were given.
+#[derive(Debug, Default)]
+pub struct ScanAllResult {
+    pub results: Vec<ScanResult>,
+    pub errors: Vec<(ServerConfig, Error)>,
+}
+
+impl ScanAllResult {
+    pub fn total_threats(&self) -> usize {
+        self.results.iter().map(|r| r.threats.len()).sum()
+    }
+
+    /// The most severe threat found on any server.
+    pub fn highest_severity(&self) -> Option<Severity> {
+        self.results
+            .iter()
+            .flat_map(|r| &r.threats)
+            .map(|t| t.severity)
+            .min()
+    }
+}
+
+impl ScanResult {
+    /// Drop threats less severe than `min`.
+    pub fn retain_min_severity(&mut self, min: Severity) {
+        self.threats.retain(|t| t.severity <= min);
+    }
+
+    /// Drop threats whose ID was already found in `previous`.
+    pub fn retain_new(&mut self, previous: &[Threat]) {
+        let known: HashSet<&str> = previous.iter().map(|t| t.id.as_str()).collect();
+        self.threats.retain(|t| !known.contains(t.id.as_str()));
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub input_schema: serde_json::Value,
+}
+
+impl ToolInfo {
+    /// Returns the description collapsed onto one line and cut to at most
+    /// `max_chars` characters, with an ellipsis when truncated.
+    pub fn short_description(&self, max_chars: usize) -> String {
+        let collapsed = self
+            .description
+            .as_deref()
+            .unwrap_or_default()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if collapsed.chars().count() <= max_chars {
+            return collapsed;
+        }
+
+        let mut truncated: String = collapsed.chars().take(max_chars).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+impl From<crate::protocol::mcp::Tool> for ToolInfo {
+    fn from(tool: crate::protocol::mcp::Tool) -> Self {
+        Self {
+            name: tool.name,
+            description: tool.description,
+            input_schema: tool.input_schema,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceInfo {
+    pub uri: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+}
+
+impl From<crate::protocol::mcp::Resource> for ResourceInfo {
+    fn from(resource: crate::protocol::mcp::Resource) -> Self {
+        Self {
+            uri: resource.uri,
+            name: resource.name,
+            description: resource.description,
+            mime_type: resource.mime_type,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub arguments: Vec<PromptArgument>,
+}
+
+impl From<crate::protocol::mcp::Prompt> for PromptInfo {
+    fn from(prompt: crate::protocol::mcp::Prompt) -> Self {
+        Self {
+            name: prompt.name,
+            description: prompt.description,
+            arguments: prompt.arguments,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Threat {
+    pub id: String,
+    pub severity: Severity,
+    pub category: ThreatCategory,
+    pub title: String,
+    pub message: String,
+    pub evidence: String,
+    pub remediation: String,
+    pub tool_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_name: Option<String>,
+    /// Name of the detector that reported the threat, when it came from one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detector: Option<String>,
+    /// Matched an allowlist entry: kept in scan history but not reported.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub suppressed: bool,
+}
+
+impl Threat {
+    pub fn new(
+        id: impl Into<String>,
+        severity: Severity,
+        category: ThreatCategory,
+        title: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            severity,
+            category,
+            title: title.into(),
+            message: String::new(),
+            evidence: String::new(),
+            remediation: String::new(),
+            tool_name: None,
+            prompt_name: None,
+            detector: None,
+            suppressed: false,
+        }
+    }
+
+    pub fn with_detector(mut self, detector: impl Into<String>) -> Self {
+        self.detector = Some(detector.into());
+        self
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    pub fn with_evidence(mut self, evidence: impl Into<String>) -> Self {
+        self.evidence = evidence.into();
+        self
+    }
+
+    pub fn with_remediation(mut self, remediation: impl Into<String>) -> Self {
+        self.remediation = remediation.into();
+        self
+    }
+
+    pub fn with_tool(mut self, tool_name: impl Into<String>) -> Self {
+        self.tool_name = Some(tool_name.into());
+        self
+    }
+
+    pub fn with_prompt(mut self, prompt_name: impl Into<String>) -> Self {
+        self.prompt_name = Some(prompt_name.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Critical,
+    High,
+    Medium,
+    Low,
+    Info,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Critical => "critical",
+            Severity::High => "high",
+            Severity::Medium => "medium",
+            Severity::Low => "low",
+            Severity::Info => "info",
+        }
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThreatCategory {
+    DescriptionInjection,
+    PermissionScope,
+    NoAuth,
+    ToolShadowing,
+    DescriptionDrift,
+    DataExfiltration,
+    CommandInjection,
+    PathTraversal,
+    SupplyChain,
+    SchemaValidation,
+    ResourceUri,
+    Protocol,
+}
+
+impl ThreatCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThreatCategory::DescriptionInjection => "description_injection",
+            ThreatCategory::PermissionScope => "permission_scope",
+            ThreatCategory::NoAuth => "no_auth",
+            ThreatCategory::ToolShadowing => "tool_shadowing",
+            ThreatCategory::DescriptionDrift => "description_drift",
+            ThreatCategory::DataExfiltration => "data_exfiltration",
+            ThreatCategory::CommandInjection => "command_injection",
+            ThreatCategory::PathTraversal => "path_traversal",
+            ThreatCategory::SupplyChain => "supply_chain",
+            ThreatCategory::SchemaValidation => "schema_validation",
+            ThreatCategory::ResourceUri => "resource_uri",
+            ThreatCategory::Protocol => "protocol",
+        }
+    }
+}
+
+impl std::fmt::Display for ThreatCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    pub added_tools: Vec<String>,
+    pub removed_tools: Vec<String>,
+    pub changed_descriptions: Vec<DescriptionChange>,
+    /// Whether the server started or stopped advertising tools, resources,
+    /// or prompts.
+    #[serde(default)]
+    pub capabilities_changed: bool,
+    /// Which capabilities were added or dropped, e.g. `+resources, -prompts`.
+    #[serde(default)]
+    pub capability_details: String,
+    /// The previous and current protocol version, if it changed.
+    #[serde(default)]
+    pub protocol_version_change: Option<(String, String)>,
+}
+
+#[cfg(test)]
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_tools.is_empty()
+            && self.removed_tools.is_empty()
+            && self.changed_descriptions.is_empty()
+            && !self.capabilities_changed
+            && self.protocol_version_change.is_none()
+    }
+}
+
+/// Changes in findings between the previous stored scan of a server and the
+/// current one. Threats are matched on their ID together with the tool or
+/// prompt they were reported for.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanDiff {
+    /// The scan the diff was computed from.
+    #[serde(skip)]
+    pub result: ScanResult,
+    /// When the previous scan ran. `None` if there was no previous scan, in
+    /// which case every threat counts as new.
+    pub previous_scanned_at: Option<DateTime<Utc>>,
+    pub new_threats: Vec<Threat>,
+    pub resolved_threats: Vec<Threat>,
+    pub unchanged_threats: Vec<Threat>,
+}
+
+impl ScanDiff {
+    pub fn new(
+        result: ScanResult,
+        previous_scanned_at: Option<DateTime<Utc>>,
+        previous: &[Threat],
+    ) -> Self {
+        let previous_keys: HashSet<_> = previous.iter().map(threat_key).collect();
+        let current_keys: HashSet<_> = result.threats.iter().map(threat_key).collect();
+
+        let (unchanged_threats, new_threats) = result
+            .threats
+            .iter()
+            .cloned()
+            .partition(|t| previous_keys.contains(&threat_key(t)));
+        let resolved_threats = previous
+            .iter()
+            .filter(|t| !current_keys.contains(&threat_key(t)))
+            .cloned()
+            .collect();
+
+        Self {
+            result,
+            previous_scanned_at,
+            new_threats,
+            resolved_threats,
+            unchanged_threats,
+        }
+    }
+
+    pub fn has_changes(&self) -> bool {
+        !self.new_threats.is_empty() || !self.resolved_threats.is_empty()
+    }
+
+    /// Drop new, resolved, and unchanged threats less severe than `min`.
+    pub fn retain_min_severity(&mut self, min: Severity) {
+        self.result.retain_min_severity(min);
+        for threats in [
+            &mut self.new_threats,
+            &mut self.resolved_threats,
+            &mut self.unchanged_threats,
+        ] {
+            threats.retain(|t| t.severity <= min);
+        }
+    }
+}
+
+/// Changes between two stored scans, as shown by the `diff` command. Threats
+/// are matched the same way as in [`ScanDiff`].
+#[derive(Debug, Clone, Serialize)]
+pub struct RunDiff {
+    pub server: String,
+    pub from_scanned_at: DateTime<Utc>,
+    pub to_scanned_at: DateTime<Utc>,
+    /// Threats in the later scan but not the earlier one
+    pub new_threats: Vec<Threat>,
+    /// Threats in the earlier scan but not the later one
+    pub resolved_threats: Vec<Threat>,
+    pub added_tools: Vec<String>,
+    pub removed_tools: Vec<String>,
+}
+
+impl RunDiff {
+    pub fn new(from: &ScanResult, to: &ScanResult) -> Self {
+        let only_in = |a: &ScanResult, b: &ScanResult| -> Vec<Threat> {
+            let keys: HashSet<_> = b.threats.iter().map(threat_key).collect();
+            a.threats
+                .iter()
+                .filter(|t| !keys.contains(&threat_key(t)))
+                .cloned()
+                .collect()
+        };
+        let tools_only_in = |a: &ScanResult, b: &ScanResult| -> Vec<String> {
+            let names: HashSet<_> = b.tools.iter().map(|t| t.name.as_str()).collect();
+            a.tools
+                .iter()
+                .filter(|t| !names.contains(t.name.as_str()))
+                .map(|t| t.name.clone())
+                .collect()
+        };
+
+        Self {
+            server: to.server.name.clone(),
+            from_scanned_at: from.scanned_at,
+            to_scanned_at: to.scanned_at,
+            new_threats: only_in(to, from),
+            resolved_threats: only_in(from, to),
+            added_tools: tools_only_in(to, from),
+            removed_tools: tools_only_in(from, to),
+        }
+    }
+
+    pub fn has_changes(&self) -> bool {
+        !self.new_threats.is_empty()
+            || !self.resolved_threats.is_empty()
+            || !self.added_tools.is_empty()
+            || !self.removed_tools.is_empty()
+    }
+}
+
+fn threat_key(t: &Threat) -> (String, Option<String>, Option<String>) {
+    (t.id.clone(), t.tool_name.clone(), t.prompt_name.clone())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescriptionChange {
+    pub tool_name: String,
+    pub old_description: String,
+    pub new_description: String,
+    pub old_hash: String,
+    pub new_hash: String,
+}
+
+mod duration_millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u64(duration.as_millis() as u64)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let millis = u64::deserialize(deserializer)?;
+        Ok(Duration::from_millis(millis))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_description_truncates_and_collapses() {
+        let tool = ToolInfo {
+            name: "read".to_string(),
+            description: Some("Reads a file\n  from disk.".to_string()),
+            input_schema: serde_json::json!({}),
+        };
+
+        assert_eq!(tool.short_description(80), "Reads a file from disk.");
+        assert_eq!(tool.short_description(5), "Reads…");
+    }
+
+    #[test]
+    fn severity_ordering() {
+        assert!(Severity::Critical < Severity::High);
+        assert!(Severity::High < Severity::Medium);
+        assert!(Severity::Medium < Severity::Low);
+        assert!(Severity::Low < Severity::Info);
+    }
+
+    #[test]
+    fn threat_builder() {
+        let threat = Threat::new(
+            "TEST-001",
+            Severity::High,
+            ThreatCategory::DescriptionInjection,
+            "Test Threat",
+        )
+        .with_message("This is a test")
+        .with_evidence("suspicious content")
+        .with_tool("test_tool");
+
+        assert_eq!(threat.id, "TEST-001");
+        assert_eq!(threat.severity, Severity::High);
+        assert_eq!(threat.tool_name, Some("test_tool".to_string()));
+    }
+
+    #[test]
+    fn scan_all_result_summarizes_threats() {
+        let result = |threats: Vec<Threat>| ScanResult {
+            server: ServerConfig::new("test", "cmd"),
+            tools: vec![],
+            resources: vec![],
+            prompts: vec![],
+            threats,
+            snapshot_diff: None,
+            scan_duration: Duration::from_millis(1),
+            scanned_at: Utc::now(),
+        };
+        let threat =
+            |severity| Threat::new("T", severity, ThreatCategory::PermissionScope, "Threat");
+
+        let empty = ScanAllResult::default();
+        assert_eq!(empty.total_threats(), 0);
+        assert_eq!(empty.highest_severity(), None);
+
+        let outcome = ScanAllResult {
+            results: vec![
+                result(vec![threat(Severity::Medium), threat(Severity::Low)]),
+                result(vec![threat(Severity::High)]),
+                result(vec![]),
+            ],
+            errors: vec![(
+                ServerConfig::new("broken", "cmd"),
+                Error::Other("failed".to_string()),
+            )],
+        };
+        assert_eq!(outcome.total_threats(), 3);
+        assert_eq!(outcome.highest_severity(), Some(Severity::High));
+    }
+
+    #[test]
+    fn snapshot_diff_is_empty() {
+        let empty = SnapshotDiff::default();
+        assert!(empty.is_empty());
+
+        let not_empty = SnapshotDiff {
+            added_tools: vec!["new_tool".to_string()],
+            ..SnapshotDiff::default()
+        };
+        assert!(!not_empty.is_empty());
+    }
+
+    #[test]
+    fn scan_diff_splits_new_resolved_and_unchanged() {
+        let threat = |id: &str, tool: &str| {
+            Threat::new(id, Severity::High, ThreatCategory::PermissionScope, id).with_tool(tool)
+        };
+        let previous = vec![threat("A", "one"), threat("B", "one"), threat("A", "two")];
+        let result = ScanResult {
+            server: ServerConfig::new("test", "cmd"),
+            tools: vec![],
+            resources: vec![],
+            prompts: vec![],
+            threats: vec![threat("A", "one"), threat("C", "one")],
+            snapshot_diff: None,
+            scan_duration: Duration::from_millis(1),
+            scanned_at: Utc::now(),
+        };
+
+        let diff = ScanDiff::new(result, Some(Utc::now()), &previous);
+        let ids = |threats: &[Threat]| -> Vec<(String, Option<String>)> {
+            threats
+                .iter()
+                .map(|t| (t.id.clone(), t.tool_name.clone()))
+                .collect()
+        };
+
+        assert_eq!(
+            ids(&diff.unchanged_threats),
+            vec![("A".into(), Some("one".into()))]
+        );
+        assert_eq!(
+            ids(&diff.new_threats),
+            vec![("C".into(), Some("one".into()))]
+        );
+        assert_eq!(
+            ids(&diff.resolved_threats),
+            vec![
+                ("B".into(), Some("one".into())),
+                ("A".into(), Some("two".into()))
+            ]
+        );
+        assert!(diff.has_changes());
+    }
+
+    #[test]
+    fn run_diff_reports_threat_and_tool_changes() {
+        let tool = |name: &str| ToolInfo {
+            name: name.to_string(),
+            description: None,
+            input_schema: serde_json::json!({}),
+        };
+        let threat = |id: &str| {
+            Threat::new(id, Severity::High, ThreatCategory::PermissionScope, id).with_tool("one")
+        };
+        let run = |tools: Vec<ToolInfo>, threats: Vec<Threat>| ScanResult {
+            server: ServerConfig::new("test", "cmd"),
+            tools,
+            resources: vec![],
+            prompts: vec![],
+            threats,
+            snapshot_diff: None,
+            scan_duration: Duration::from_millis(1),
+            scanned_at: Utc::now(),
+        };
+
+        let before = run(
+            vec![tool("one"), tool("two")],
+            vec![threat("A"), threat("B")],
+        );
+        let after = run(
+            vec![tool("one"), tool("three")],
+            vec![threat("A"), threat("C")],
+        );
+
+        let diff = RunDiff::new(&before, &after);
+        assert_eq!(diff.server, "test");
+        assert_eq!(diff.new_threats.len(), 1);
+        assert_eq!(diff.new_threats[0].id, "C");
+        assert_eq!(diff.resolved_threats.len(), 1);
+        assert_eq!(diff.resolved_threats[0].id, "B");
+        assert_eq!(diff.added_tools, vec!["three"]);
+        assert_eq!(diff.removed_tools, vec!["two"]);
+        assert!(diff.has_changes());
+        assert!(!RunDiff::new(&after, &after).has_changes());
+    }
+
+    #[test]
+    fn retain_min_severity_hides_lower_severities() {
+        let mut result = ScanResult {
+            server: ServerConfig::new("test", "cmd"),
+            tools: vec![],
+            resources: vec![],
+            prompts: vec![],
+            threats: [
+                Severity::Critical,
+                Severity::High,
+                Severity::Medium,
+                Severity::Low,
+                Severity::Info,
+            ]
+            .into_iter()
+            .map(|s| Threat::new(s.as_str(), s, ThreatCategory::NoAuth, "t"))
+            .collect(),
+            snapshot_diff: None,
+            scan_duration: Duration::from_millis(1),
+            scanned_at: Utc::now(),
+        };
+
+        result.retain_min_severity(Severity::High);
+
+        let severities: Vec<Severity> = result.threats.iter().map(|t| t.severity).collect();
+        assert_eq!(severities, vec![Severity::Critical, Severity::High]);
+    }
+
+    #[test]
+    fn retain_new_drops_previously_found_threats() {
+        let threat = |id: &str| Threat::new(id, Severity::High, ThreatCategory::NoAuth, "t");
+        let mut result = ScanResult {
+            server: ServerConfig::new("test", "cmd"),
+            tools: vec![],
+            resources: vec![],
+            prompts: vec![],
+            threats: vec![threat("A"), threat("B")],
+            snapshot_diff: None,
+            scan_duration: Duration::from_millis(1),
+            scanned_at: Utc::now(),
+        };
+
+        result.retain_new(&[threat("A"), threat("C")]);
+
+        assert_eq!(result.threats.len(), 1);
+        assert_eq!(result.threats[0].id, "B");
+    }
+}
@@ -0,0 +1,211 @@
+//! JUnit XML output, for CI systems that show test reports (Jenkins, GitLab).
+//!
+//! Each server is a `<testsuite>` and each threat a `<testcase>`. Critical
+//! and high threats are failures, medium threats errors, and low and info
+//! threats pass with the finding attached as output.
+
+use crate::scanner::report::{ScanResult, Severity, Threat};
+use std::fmt::Write;
+
+pub struct JunitReport;
+
+impl JunitReport {
+    pub fn from_results(results: &[ScanResult]) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let (tests, failures, errors) = results
+            .iter()
+            .map(counts)
+            .fold((0, 0, 0), |a, b| (a.0 + b.0, a.1 + b.1, a.2 + b.2));
+        let time: f64 = results.iter().map(|r| r.scan_duration.as_secs_f64()).sum();
+
+        let _ = writeln!(
+            xml,
+            r#"<testsuites name="mcp-scanner" tests="{}" failures="{}" errors="{}" time="{:.3}">"#,
+            tests, failures, errors, time
+        );
+        for result in results {
+            write_suite(&mut xml, result);
+        }
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+}
+
+/// Tests, failures, and errors in one server's suite.
+fn counts(result: &ScanResult) -> (usize, usize, usize) {
+    let failures = result
+        .threats
+        .iter()
+        .filter(|t| t.severity <= Severity::High)
+        .count();
+    let errors = result
+        .threats
+        .iter()
+        .filter(|t| t.severity == Severity::Medium)
+        .count();
+    (result.threats.len(), failures, errors)
+}
+
+fn write_suite(xml: &mut String, result: &ScanResult) {
+    let (tests, failures, errors) = counts(result);
+    let _ = writeln!(
+        xml,
+        r#"  <testsuite name="{}" tests="{}" failures="{}" errors="{}" time="{:.3}" timestamp="{}">"#,
+        escape(&result.server.name),
+        tests,
+        failures,
+        errors,
+        result.scan_duration.as_secs_f64(),
+        result.scanned_at.format("%Y-%m-%dT%H:%M:%S"),
+    );
+
+    for threat in &result.threats {
+        let _ = writeln!(
+            xml,
+            r#"    <testcase name="{}" classname="{}">"#,
+            escape(&threat.title),
+            threat.category.as_str()
+        );
+        let element = match threat.severity {
+            Severity::Critical | Severity::High => Some("failure"),
+            Severity::Medium => Some("error"),
+            Severity::Low | Severity::Info => None,
+        };
+        match element {
+            Some(element) => {
+                let _ = writeln!(
+                    xml,
+                    r#"      <{} message="{}" type="{}">{}</{}>"#,
+                    element,
+                    escape(&threat.message),
+                    threat.severity,
+                    escape(&details(threat)),
+                    element
+                );
+            }
+            None => {
+                let _ = writeln!(
+                    xml,
+                    "      <system-out>{}</system-out>",
+                    escape(&details(threat))
+                );
+            }
+        }
+        xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n");
+}
+
+fn details(threat: &Threat) -> String {
+    let mut lines = vec![format!(
+        "[{}] {}: {}",
+        threat.severity, threat.id, threat.message
+    )];
+    if let Some(tool) = &threat.tool_name {
+        lines.push(format!("Tool: {}", tool));
+    }
+    if let Some(prompt) = &threat.prompt_name {
+        lines.push(format!("Prompt: {}", prompt));
+    }
+    if !threat.evidence.is_empty() {
+        lines.push(format!("Evidence: {}", threat.evidence));
+    }
+    if !threat.remediation.is_empty() {
+        lines.push(format!("Remediation: {}", threat.remediation));
+    }
+    lines.join("\n")
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Not allowed in XML 1.0, even escaped
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::ServerConfig;
+    use crate::scanner::report::ThreatCategory;
+    use chrono::Utc;
+    use std::time::Duration;
+
+    fn scan_result(server: &str, threats: Vec<Threat>) -> ScanResult {
+        ScanResult {
+            server: ServerConfig::new(server, "npx"),
+            tools: vec![],
+            resources: vec![],
+            prompts: vec![],
+            threats,
+            snapshot_diff: None,
+            scan_duration: Duration::from_millis(1500),
+            scanned_at: Utc::now(),
+        }
+    }
+
+    fn threat(id: &str, severity: Severity) -> Threat {
+        Threat::new(
+            id,
+            severity,
+            ThreatCategory::DescriptionInjection,
+            "Injection <in> \"description\"",
+        )
+        .with_message("Tool & description")
+        .with_tool("read_file")
+    }
+
+    #[test]
+    fn maps_severities_to_failures_errors_and_passes() {
+        let results = vec![
+            scan_result(
+                "fs",
+                vec![
+                    threat("A", Severity::Critical),
+                    threat("B", Severity::High),
+                    threat("C", Severity::Medium),
+                    threat("D", Severity::Low),
+                    threat("E", Severity::Info),
+                ],
+            ),
+            scan_result("clean", vec![]),
+        ];
+
+        let xml = JunitReport::from_results(&results);
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains(
+            r#"<testsuites name="mcp-scanner" tests="5" failures="2" errors="1" time="3.000">"#
+        ));
+        assert!(
+            xml.contains(r#"<testsuite name="fs" tests="5" failures="2" errors="1" time="1.500""#)
+        );
+        assert!(xml
+            .contains(r#"<testsuite name="clean" tests="0" failures="0" errors="0" time="1.500""#));
+        assert_eq!(xml.matches("<testcase ").count(), 5);
+        assert_eq!(xml.matches("<failure ").count(), 2);
+        assert_eq!(xml.matches("<error ").count(), 1);
+        assert_eq!(xml.matches("<system-out>").count(), 2);
+        assert!(xml.contains(
+            r#"<testcase name="Injection &lt;in&gt; &quot;description&quot;" classname="description_injection">"#
+        ));
+        assert!(xml.contains(r#"<failure message="Tool &amp; description" type="critical">"#));
+        assert!(xml.contains("Tool: read_file"));
+        assert!(xml.trim_end().ends_with("</testsuites>"));
+    }
+
+    #[test]
+    fn escape_drops_invalid_characters() {
+        assert_eq!(escape("a\u{0}b\tc'"), "ab\tc&apos;");
+    }
+}
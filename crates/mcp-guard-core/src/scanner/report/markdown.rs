@@ -0,0 +1,191 @@
+//! GitHub-flavored Markdown output, for posting as a PR comment.
+//!
+//! A summary table with one row per server is followed by a section per
+//! server, with each threat in a collapsed `<details>` block.
+
+use crate::scanner::report::{ScanResult, Severity, Threat};
+use std::fmt::Write;
+
+pub struct MarkdownReport;
+
+impl MarkdownReport {
+    pub fn from_results(results: &[ScanResult]) -> String {
+        let mut md = String::from("## MCP Scanner Results\n\n");
+        md.push_str("| Server | Tools | Critical | High | Medium | Low |\n");
+        md.push_str("| --- | ---: | ---: | ---: | ---: | ---: |\n");
+        for result in results {
+            let count = |severity| {
+                result
+                    .threats
+                    .iter()
+                    .filter(|t| t.severity == severity)
+                    .count()
+            };
+            let _ = writeln!(
+                md,
+                "| {} | {} | {} | {} | {} | {} |",
+                escape(&result.server.name),
+                result.tools.len(),
+                count(Severity::Critical),
+                count(Severity::High),
+                count(Severity::Medium),
+                count(Severity::Low),
+            );
+        }
+
+        for result in results {
+            let _ = write!(md, "\n### {}\n\n", escape(&result.server.name));
+            if result.threats.is_empty() {
+                md.push_str("No threats found.\n");
+            }
+            for threat in &result.threats {
+                write_threat(&mut md, threat);
+            }
+        }
+        md
+    }
+}
+
+fn write_threat(md: &mut String, threat: &Threat) {
+    let mut summary = format!(
+        "{} <b>{}</b> {}: {}",
+        badge(threat.severity),
+        severity_label(threat.severity),
+        escape(&threat.id),
+        escape(&threat.title)
+    );
+    if let Some(tool) = &threat.tool_name {
+        let _ = write!(summary, " (tool <code>{}</code>)", escape(tool));
+    } else if let Some(prompt) = &threat.prompt_name {
+        let _ = write!(summary, " (prompt <code>{}</code>)", escape(prompt));
+    }
+
+    let _ = writeln!(md, "<details>\n<summary>{}</summary>\n", summary);
+    if !threat.message.is_empty() {
+        let _ = writeln!(md, "{}\n", escape(&threat.message));
+    }
+    if !threat.evidence.is_empty() {
+        let _ = writeln!(md, "**Evidence:** {}\n", escape(&threat.evidence));
+    }
+    if !threat.remediation.is_empty() {
+        let _ = writeln!(md, "**Remediation:** {}\n", escape(&threat.remediation));
+    }
+    md.push_str("</details>\n\n");
+}
+
+fn badge(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "🔴",
+        Severity::High => "🟠",
+        Severity::Medium => "🟡",
+        Severity::Low => "🔵",
+        Severity::Info => "⚪",
+    }
+}
+
+fn severity_label(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "Critical",
+        Severity::High => "High",
+        Severity::Medium => "Medium",
+        Severity::Low => "Low",
+        Severity::Info => "Info",
+    }
+}
+
+/// Keep server-controlled text from opening tags, breaking table cells, or
+/// ending a paragraph early.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '|' => escaped.push_str("&#124;"),
+            '\n' | '\r' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::ServerConfig;
+    use crate::scanner::report::{ThreatCategory, ToolInfo};
+    use chrono::Utc;
+    use std::time::Duration;
+
+    fn scan_result(server: &str, threats: Vec<Threat>) -> ScanResult {
+        ScanResult {
+            server: ServerConfig::new(server, "npx"),
+            tools: vec![ToolInfo {
+                name: "read_file".to_string(),
+                description: None,
+                input_schema: serde_json::json!({}),
+            }],
+            resources: vec![],
+            prompts: vec![],
+            threats,
+            snapshot_diff: None,
+            scan_duration: Duration::from_millis(1),
+            scanned_at: Utc::now(),
+        }
+    }
+
+    fn threat(id: &str, severity: Severity) -> Threat {
+        Threat::new(
+            id,
+            severity,
+            ThreatCategory::DescriptionInjection,
+            "Injection in description",
+        )
+        .with_message("Says <details> | ignore previous")
+        .with_evidence("line one\nline two")
+        .with_remediation("Remove the instructions")
+        .with_tool("read_file")
+    }
+
+    #[test]
+    fn summarizes_servers_and_collapses_threats() {
+        let results = vec![
+            scan_result(
+                "fs",
+                vec![
+                    threat("A", Severity::Critical),
+                    threat("B", Severity::High),
+                    threat("C", Severity::High),
+                    threat("D", Severity::Medium),
+                ],
+            ),
+            scan_result("clean", vec![]),
+        ];
+
+        let md = MarkdownReport::from_results(&results);
+        assert!(md.contains("| Server | Tools | Critical | High | Medium | Low |"));
+        assert!(md.contains("| fs | 1 | 1 | 2 | 1 | 0 |"));
+        assert!(md.contains("| clean | 1 | 0 | 0 | 0 | 0 |"));
+        assert!(md.contains("### clean\n\nNo threats found."));
+        assert!(md.contains(
+            "<summary>🔴 <b>Critical</b> A: Injection in description (tool <code>read_file</code>)</summary>"
+        ));
+        assert!(md.contains("🟠 <b>High</b> B"));
+        assert!(md.contains("🟡 <b>Medium</b> D"));
+        assert!(md.contains("**Evidence:** line one line two"));
+        assert!(md.contains("**Remediation:** Remove the instructions"));
+
+        // Every tag opened is closed, including ones in threat text
+        for tag in ["details", "summary", "b", "code"] {
+            assert_eq!(
+                md.matches(&format!("<{}>", tag)).count(),
+                md.matches(&format!("</{}>", tag)).count(),
+                "unbalanced <{}>",
+                tag
+            );
+        }
+        assert_eq!(md.matches("<details>").count(), 4);
+        assert!(md.contains("Says &lt;details&gt; &#124; ignore previous"));
+    }
+}
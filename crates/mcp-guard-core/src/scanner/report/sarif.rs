@@ -0,0 +1,217 @@
+//! SARIF 2.1.0 output, for GitHub code scanning and other SARIF consumers.
+
+use crate::scanner::report::{ScanResult, Severity, Threat};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+const SCHEMA: &str =
+    "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/master/Schemata/sarif-schema-2.1.0.json";
+const INFORMATION_URI: &str = "https://github.com/oabraham1/mcp-scanner";
+const THREATS_DOC: &str = "https://github.com/oabraham1/mcp-scanner/blob/main/docs/src/threats.md";
+
+pub struct SarifReport;
+
+impl SarifReport {
+    /// One run with a rule per distinct threat ID, in order of first
+    /// appearance, and a result per threat.
+    pub fn from_results(results: &[ScanResult]) -> Value {
+        let mut rules = Vec::new();
+        let mut rule_index: HashMap<&str, usize> = HashMap::new();
+        let mut sarif_results = Vec::new();
+
+        for result in results {
+            for threat in &result.threats {
+                let index = *rule_index.entry(threat.id.as_str()).or_insert_with(|| {
+                    rules.push(rule(threat));
+                    rules.len() - 1
+                });
+                sarif_results.push(sarif_result(result, threat, index));
+            }
+        }
+
+        json!({
+            "$schema": SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "mcp-scanner",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": INFORMATION_URI,
+                        "rules": rules
+                    }
+                },
+                "results": sarif_results
+            }]
+        })
+    }
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low | Severity::Info => "note",
+    }
+}
+
+fn rule(threat: &Threat) -> Value {
+    json!({
+        "id": threat.id,
+        "name": threat.title,
+        "shortDescription": { "text": threat.title },
+        // Threat categories map to headings in the threats doc
+        "helpUri": format!("{}#{}", THREATS_DOC, threat.category.as_str().replace('_', "-")),
+        "defaultConfiguration": { "level": level(threat.severity) },
+        "properties": { "category": threat.category.as_str() }
+    })
+}
+
+fn sarif_result(result: &ScanResult, threat: &Threat, rule_index: usize) -> Value {
+    let mut sarif = json!({
+        "ruleId": threat.id,
+        "ruleIndex": rule_index,
+        "level": level(threat.severity),
+        "message": { "text": threat.message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": {
+                    "uri": artifact_uri(result)
+                }
+            }
+        }]
+    });
+
+    // Point at the tool or prompt the finding came from, then the server
+    let mut logical = Vec::new();
+    match (&threat.tool_name, &threat.prompt_name) {
+        (Some(tool), _) => logical.push(json!({ "name": tool, "kind": "tool" })),
+        (None, Some(prompt)) => logical.push(json!({ "name": prompt, "kind": "prompt" })),
+        (None, None) => {}
+    }
+    logical.push(json!({ "name": result.server.name, "kind": "module" }));
+    sarif["locations"][0]["logicalLocations"] = Value::Array(logical);
+    sarif
+}
+
+/// The client config file the server was found in, or just its name for
+/// servers given on the command line.
+fn artifact_uri(result: &ScanResult) -> String {
+    match result.server.config_path() {
+        Some(path) => reqwest::Url::from_file_path(&path)
+            .map(String::from)
+            .unwrap_or_else(|_| path.display().to_string()),
+        None => result.server.name.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{ConfigSource, ServerConfig};
+    use crate::scanner::report::ThreatCategory;
+    use chrono::Utc;
+    use std::time::Duration;
+
+    fn scan_result(server: &str, threats: Vec<Threat>) -> ScanResult {
+        ScanResult {
+            server: ServerConfig::new(server, "npx"),
+            tools: vec![],
+            resources: vec![],
+            prompts: vec![],
+            threats,
+            snapshot_diff: None,
+            scan_duration: Duration::from_millis(1),
+            scanned_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn rules_are_unique_and_indexed() {
+        let injection = Threat::new(
+            "DESC-INJ-001",
+            Severity::Critical,
+            ThreatCategory::DescriptionInjection,
+            "Prompt injection in description",
+        )
+        .with_tool("read_file");
+        let no_auth = Threat::new(
+            "NO-AUTH-LOCAL",
+            Severity::Info,
+            ThreatCategory::NoAuth,
+            "No authentication",
+        );
+        let results = vec![
+            scan_result("a", vec![injection.clone(), no_auth]),
+            scan_result("b", vec![injection]),
+        ];
+
+        let sarif = SarifReport::from_results(&results);
+        let run = &sarif["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0]["id"], "DESC-INJ-001");
+        assert_eq!(rules[0]["name"], "Prompt injection in description");
+        assert_eq!(rules[0]["defaultConfiguration"]["level"], "error");
+        assert!(rules[0]["helpUri"]
+            .as_str()
+            .unwrap()
+            .ends_with("threats.md#description-injection"));
+        assert_eq!(rules[1]["defaultConfiguration"]["level"], "note");
+
+        let sarif_results = run["results"].as_array().unwrap();
+        assert_eq!(sarif_results.len(), 3);
+        for result in sarif_results {
+            let index = result["ruleIndex"].as_u64().unwrap() as usize;
+            assert_eq!(rules[index]["id"], result["ruleId"]);
+        }
+        assert_eq!(
+            sarif_results[2]["locations"][0]["logicalLocations"][0]["name"],
+            "read_file"
+        );
+        assert_eq!(
+            sarif_results[2]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "b"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn config_path_is_artifact_location() {
+        let mut result = scan_result(
+            "fs",
+            vec![Threat::new(
+                "NO-AUTH-LOCAL",
+                Severity::Info,
+                ThreatCategory::NoAuth,
+                "No authentication",
+            )],
+        );
+        result.server.source = ConfigSource::Client {
+            name: "Claude Desktop".to_string(),
+            config_path: "/home/me/claude_desktop_config.json".into(),
+        };
+
+        let sarif = SarifReport::from_results(&[result]);
+        let location = &sarif["runs"][0]["results"][0]["locations"][0];
+        assert_eq!(
+            location["physicalLocation"]["artifactLocation"]["uri"],
+            "file:///home/me/claude_desktop_config.json"
+        );
+        assert_eq!(
+            location["logicalLocations"],
+            json!([{ "name": "fs", "kind": "module" }])
+        );
+    }
+
+    #[test]
+    fn empty_results_have_no_rules() {
+        let sarif = SarifReport::from_results(&[]);
+        assert_eq!(sarif["version"], "2.1.0");
+        assert!(sarif["runs"][0]["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+        assert!(sarif["runs"][0]["results"].as_array().unwrap().is_empty());
+    }
+}
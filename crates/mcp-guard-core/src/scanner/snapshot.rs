@@ -0,0 +1,515 @@
+//! Snapshot storage and comparison for detecting description and capability
+//! drift.
+
+#[cfg(feature = "db")]
+use crate::db::{DbPool, SnapshotDb};
+use crate::error::{Error, Result};
+use crate::protocol::mcp::ServerCapabilities;
+use crate::scanner::report::{DescriptionChange, SnapshotDiff, ToolInfo};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(Clone)]
+pub struct SnapshotStore {
+    data_dir: PathBuf,
+}
+
+impl SnapshotStore {
+    pub fn new() -> Result<Self> {
+        Self::new_in(Self::default_data_dir()?)
+    }
+
+    /// Store snapshots in `data_dir` instead of `~/.mcp-guard/snapshots/`.
+    pub fn new_in(data_dir: PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(&data_dir)?;
+        Ok(Self { data_dir })
+    }
+
+    fn default_data_dir() -> Result<PathBuf> {
+        let home = dirs::home_dir()
+            .ok_or_else(|| Error::Other("Could not find home directory".to_string()))?;
+        Ok(home.join(".mcp-guard").join("snapshots"))
+    }
+
+    fn snapshot_path(&self, server_name: &str) -> PathBuf {
+        let safe_name = server_name.replace(['/', '\\', ':'], "_");
+        self.data_dir.join(format!("{}.json", safe_name))
+    }
+
+    pub fn load(&self, server_name: &str) -> Result<Option<Snapshot>> {
+        let path = self.snapshot_path(server_name);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(&path)?;
+        let snapshot: Snapshot = serde_json::from_str(&content)?;
+        Ok(Some(snapshot))
+    }
+
+    pub fn save(&self, server_name: &str, snapshot: &Snapshot) -> Result<()> {
+        let content = serde_json::to_string_pretty(snapshot)?;
+        std::fs::write(self.snapshot_path(server_name), content)?;
+        Ok(())
+    }
+
+    /// Write `snapshot` as the stored snapshot for `server_name`.
+    pub fn import(&self, server_name: &str, snapshot: &Snapshot) -> Result<()> {
+        self.save(server_name, snapshot)
+    }
+
+    /// Remove the snapshot file for `server_name`. Returns whether one existed.
+    pub fn delete(&self, server_name: &str) -> Result<bool> {
+        let path = self.snapshot_path(server_name);
+        if !path.exists() {
+            return Ok(false);
+        }
+        std::fs::remove_file(path)?;
+        Ok(true)
+    }
+
+    /// Every stored snapshot, sorted by server name. Names come from file
+    /// names, so `/`, `\`, and `:` show up as `_`.
+    pub fn list(&self) -> Result<Vec<(String, Snapshot)>> {
+        let mut snapshots = Vec::new();
+        for entry in std::fs::read_dir(&self.data_dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if let Some(snapshot) = self.load(name)? {
+                snapshots.push((name.to_string(), snapshot));
+            }
+        }
+        snapshots.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(snapshots)
+    }
+
+    pub fn compare(&self, server_name: &str, current: &Snapshot) -> Result<Option<SnapshotDiff>> {
+        Ok(self
+            .load(server_name)?
+            .map(|previous| previous.diff(current)))
+    }
+}
+
+impl Default for SnapshotStore {
+    fn default() -> Self {
+        Self::new().expect("Failed to create snapshot store")
+    }
+}
+
+/// Snapshot store backed by the `snapshots` table of the mcp-guard database,
+/// so snapshots don't accumulate as files.
+#[cfg(feature = "db")]
+#[derive(Clone)]
+pub struct DbSnapshotStore {
+    db: SnapshotDb,
+}
+
+#[cfg(feature = "db")]
+impl DbSnapshotStore {
+    pub fn new(pool: DbPool) -> Self {
+        Self {
+            db: SnapshotDb::new(pool),
+        }
+    }
+
+    pub fn load(&self, server_name: &str) -> Result<Option<Snapshot>> {
+        self.db.load(server_name)
+    }
+
+    pub fn save(&self, server_name: &str, snapshot: &Snapshot) -> Result<()> {
+        self.db.import(server_name, snapshot)
+    }
+
+    pub fn import(&self, server_name: &str, snapshot: &Snapshot) -> Result<()> {
+        self.db.import(server_name, snapshot)
+    }
+
+    pub fn delete(&self, server_name: &str) -> Result<bool> {
+        self.db.delete(server_name)
+    }
+
+    pub fn list(&self) -> Result<Vec<(String, Snapshot)>> {
+        self.db.list()
+    }
+
+    pub fn compare(&self, server_name: &str, current: &Snapshot) -> Result<Option<SnapshotDiff>> {
+        Ok(self
+            .load(server_name)?
+            .map(|previous| previous.diff(current)))
+    }
+}
+
+/// Where a [`Scanner`](crate::scanner::Scanner) keeps snapshots between scans.
+pub enum SnapshotBackend {
+    /// One JSON file per server under `~/.mcp-guard/snapshots/`
+    File,
+    /// One JSON file per server in the given directory, e.g. baselines
+    /// committed to a repository
+    Directory(PathBuf),
+    /// The `snapshots` table of the given database
+    #[cfg(feature = "db")]
+    Database(DbPool),
+}
+
+/// An opened [`SnapshotBackend`].
+#[derive(Clone)]
+pub enum SnapshotStorage {
+    File(SnapshotStore),
+    #[cfg(feature = "db")]
+    Database(DbSnapshotStore),
+}
+
+impl SnapshotStorage {
+    pub fn open(backend: SnapshotBackend) -> Result<Self> {
+        match backend {
+            SnapshotBackend::File => Ok(Self::File(SnapshotStore::new()?)),
+            SnapshotBackend::Directory(dir) => Ok(Self::File(SnapshotStore::new_in(dir)?)),
+            #[cfg(feature = "db")]
+            SnapshotBackend::Database(pool) => Ok(Self::Database(DbSnapshotStore::new(pool))),
+        }
+    }
+
+    pub fn load(&self, server_name: &str) -> Result<Option<Snapshot>> {
+        match self {
+            Self::File(store) => store.load(server_name),
+            #[cfg(feature = "db")]
+            Self::Database(store) => store.load(server_name),
+        }
+    }
+
+    pub fn save(&self, server_name: &str, snapshot: &Snapshot) -> Result<()> {
+        match self {
+            Self::File(store) => store.save(server_name, snapshot),
+            #[cfg(feature = "db")]
+            Self::Database(store) => store.save(server_name, snapshot),
+        }
+    }
+
+    pub fn import(&self, server_name: &str, snapshot: &Snapshot) -> Result<()> {
+        match self {
+            Self::File(store) => store.import(server_name, snapshot),
+            #[cfg(feature = "db")]
+            Self::Database(store) => store.import(server_name, snapshot),
+        }
+    }
+
+    pub fn delete(&self, server_name: &str) -> Result<bool> {
+        match self {
+            Self::File(store) => store.delete(server_name),
+            #[cfg(feature = "db")]
+            Self::Database(store) => store.delete(server_name),
+        }
+    }
+
+    pub fn list(&self) -> Result<Vec<(String, Snapshot)>> {
+        match self {
+            Self::File(store) => store.list(),
+            #[cfg(feature = "db")]
+            Self::Database(store) => store.list(),
+        }
+    }
+
+    #[tracing::instrument(skip_all, fields(server = server_name))]
+    pub fn compare(&self, server_name: &str, current: &Snapshot) -> Result<Option<SnapshotDiff>> {
+        match self {
+            Self::File(store) => store.compare(server_name, current),
+            #[cfg(feature = "db")]
+            Self::Database(store) => store.compare(server_name, current),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Snapshot {
+    pub tools: HashMap<String, ToolSnapshot>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// The `capabilities` object from the server's initialize result. `null`
+    /// in snapshots saved before capabilities were recorded.
+    #[serde(default)]
+    pub server_capabilities: serde_json::Value,
+    /// The protocol version from the server's initialize result.
+    #[serde(default)]
+    pub protocol_version: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ToolSnapshot {
+    pub description: Option<String>,
+    pub description_hash: String,
+}
+
+impl Snapshot {
+    pub fn from_tools(tools: &[ToolInfo]) -> Self {
+        let mut tool_map = HashMap::new();
+
+        for tool in tools {
+            let desc = tool.description.as_deref().unwrap_or("");
+            let hash = hash_description(desc);
+
+            tool_map.insert(
+                tool.name.clone(),
+                ToolSnapshot {
+                    description: tool.description.clone(),
+                    description_hash: hash,
+                },
+            );
+        }
+
+        Self {
+            tools: tool_map,
+            created_at: chrono::Utc::now(),
+            server_capabilities: serde_json::Value::Null,
+            protocol_version: None,
+        }
+    }
+
+    /// Record what the server reported at initialization alongside its tools.
+    pub fn with_server_info(
+        mut self,
+        capabilities: &ServerCapabilities,
+        protocol_version: impl Into<String>,
+    ) -> Self {
+        self.server_capabilities =
+            serde_json::to_value(capabilities).unwrap_or(serde_json::Value::Null);
+        self.protocol_version = Some(protocol_version.into());
+        self
+    }
+
+    pub fn diff(&self, current: &Snapshot) -> SnapshotDiff {
+        let mut added_tools = Vec::new();
+        let mut removed_tools = Vec::new();
+        let mut changed_descriptions = Vec::new();
+
+        // Find added and changed tools
+        for (name, current_tool) in &current.tools {
+            match self.tools.get(name) {
+                Some(old_tool) => {
+                    if old_tool.description_hash != current_tool.description_hash {
+                        changed_descriptions.push(DescriptionChange {
+                            tool_name: name.clone(),
+                            old_description: old_tool.description.clone().unwrap_or_default(),
+                            new_description: current_tool.description.clone().unwrap_or_default(),
+                            old_hash: old_tool.description_hash.clone(),
+                            new_hash: current_tool.description_hash.clone(),
+                        });
+                    }
+                }
+                None => {
+                    added_tools.push(name.clone());
+                }
+            }
+        }
+
+        // Find removed tools
+        for name in self.tools.keys() {
+            if !current.tools.contains_key(name) {
+                removed_tools.push(name.clone());
+            }
+        }
+
+        // Snapshots saved before server info was recorded have nothing to
+        // compare against
+        let capability_details = if self.server_capabilities.is_null() {
+            String::new()
+        } else {
+            CAPABILITIES
+                .iter()
+                .filter_map(|name| {
+                    let before = self.server_capabilities.get(name).is_some();
+                    let after = current.server_capabilities.get(name).is_some();
+                    match (before, after) {
+                        (false, true) => Some(format!("+{}", name)),
+                        (true, false) => Some(format!("-{}", name)),
+                        _ => None,
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let protocol_version_change = match (&self.protocol_version, &current.protocol_version) {
+            (Some(old), Some(new)) if old != new => Some((old.clone(), new.clone())),
+            _ => None,
+        };
+
+        SnapshotDiff {
+            added_tools,
+            removed_tools,
+            changed_descriptions,
+            capabilities_changed: !capability_details.is_empty(),
+            capability_details,
+            protocol_version_change,
+        }
+    }
+}
+
+/// Capabilities whose appearance or removal counts as drift.
+const CAPABILITIES: &[&str] = &["tools", "resources", "prompts"];
+
+pub(crate) fn hash_description(description: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(description.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn make_tool(name: &str, description: &str) -> ToolInfo {
+        ToolInfo {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            input_schema: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn snapshot_from_tools() {
+        let tools = vec![
+            make_tool("read_file", "Read a file"),
+            make_tool("write_file", "Write a file"),
+        ];
+
+        let snapshot = Snapshot::from_tools(&tools);
+        assert_eq!(snapshot.tools.len(), 2);
+        assert!(snapshot.tools.contains_key("read_file"));
+    }
+
+    #[test]
+    fn diff_detects_added_tool() {
+        let old_tools = vec![make_tool("tool1", "desc1")];
+        let new_tools = vec![make_tool("tool1", "desc1"), make_tool("tool2", "desc2")];
+
+        let old_snapshot = Snapshot::from_tools(&old_tools);
+        let new_snapshot = Snapshot::from_tools(&new_tools);
+
+        let diff = old_snapshot.diff(&new_snapshot);
+        assert_eq!(diff.added_tools, vec!["tool2"]);
+        assert!(diff.removed_tools.is_empty());
+        assert!(diff.changed_descriptions.is_empty());
+    }
+
+    #[test]
+    fn diff_detects_removed_tool() {
+        let old_tools = vec![make_tool("tool1", "desc1"), make_tool("tool2", "desc2")];
+        let new_tools = vec![make_tool("tool1", "desc1")];
+
+        let old_snapshot = Snapshot::from_tools(&old_tools);
+        let new_snapshot = Snapshot::from_tools(&new_tools);
+
+        let diff = old_snapshot.diff(&new_snapshot);
+        assert!(diff.added_tools.is_empty());
+        assert_eq!(diff.removed_tools, vec!["tool2"]);
+    }
+
+    #[test]
+    fn diff_detects_changed_description() {
+        let old_tools = vec![make_tool("tool1", "old description")];
+        let new_tools = vec![make_tool("tool1", "new description with injection")];
+
+        let old_snapshot = Snapshot::from_tools(&old_tools);
+        let new_snapshot = Snapshot::from_tools(&new_tools);
+
+        let diff = old_snapshot.diff(&new_snapshot);
+        assert!(diff.added_tools.is_empty());
+        assert!(diff.removed_tools.is_empty());
+        assert_eq!(diff.changed_descriptions.len(), 1);
+        assert_eq!(diff.changed_descriptions[0].tool_name, "tool1");
+    }
+
+    #[test]
+    fn diff_detects_capability_and_protocol_changes() {
+        let tools = vec![make_tool("tool1", "desc1")];
+        let capabilities = |json: serde_json::Value| -> ServerCapabilities {
+            serde_json::from_value(json).unwrap()
+        };
+
+        let old = Snapshot::from_tools(&tools).with_server_info(
+            &capabilities(json!({ "tools": {}, "prompts": {} })),
+            "2024-11-05",
+        );
+        let same = Snapshot::from_tools(&tools).with_server_info(
+            &capabilities(json!({ "tools": {}, "prompts": {} })),
+            "2024-11-05",
+        );
+        assert!(old.diff(&same).is_empty());
+
+        let new = Snapshot::from_tools(&tools).with_server_info(
+            &capabilities(json!({ "tools": {}, "resources": {} })),
+            "2025-03-26",
+        );
+        let diff = old.diff(&new);
+        assert!(diff.capabilities_changed);
+        assert_eq!(diff.capability_details, "+resources, -prompts");
+        assert_eq!(
+            diff.protocol_version_change,
+            Some(("2024-11-05".to_string(), "2025-03-26".to_string()))
+        );
+
+        // Older snapshots didn't record server info
+        assert!(Snapshot::from_tools(&tools).diff(&new).is_empty());
+    }
+
+    #[cfg(feature = "db")]
+    #[test]
+    fn db_store_compares_with_saved_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::create_pool(&dir.path().join("test.db")).unwrap();
+        let store = DbSnapshotStore::new(pool);
+
+        let old = Snapshot::from_tools(&[make_tool("tool1", "old description")]);
+        assert!(store.compare("server1", &old).unwrap().is_none());
+        store.save("server1", &old).unwrap();
+
+        let new = Snapshot::from_tools(&[
+            make_tool("tool1", "new description"),
+            make_tool("tool2", "desc2"),
+        ]);
+        let diff = store.compare("server1", &new).unwrap().unwrap();
+        assert_eq!(diff.added_tools, vec!["tool2"]);
+        assert_eq!(diff.changed_descriptions.len(), 1);
+        assert_eq!(diff.changed_descriptions[0].tool_name, "tool1");
+    }
+
+    #[test]
+    fn file_store_import_list_and_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new_in(dir.path().to_path_buf()).unwrap();
+
+        store
+            .save(
+                "server1",
+                &Snapshot::from_tools(&[make_tool("tool1", "desc1")]),
+            )
+            .unwrap();
+        let exported = store.load("server1").unwrap().unwrap();
+        store.import("server2", &exported).unwrap();
+
+        let listed = store.list().unwrap();
+        let names: Vec<&str> = listed.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["server1", "server2"]);
+        assert_eq!(listed[1].1.created_at, exported.created_at);
+
+        assert!(store.delete("server1").unwrap());
+        assert!(!store.delete("server1").unwrap());
+        assert_eq!(store.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn hash_is_deterministic() {
+        let hash1 = hash_description("test description");
+        let hash2 = hash_description("test description");
+        assert_eq!(hash1, hash2);
+
+        let hash3 = hash_description("different description");
+        assert_ne!(hash1, hash3);
+    }
+}
@@ -0,0 +1,258 @@
+//! Checks the results of tools called during an active scan.
+//!
+//! Passive checks only see what a server says about its tools. An active scan
+//! calls the tools the user opted into with the least input their schema
+//! allows, then looks at how each one responded: a tool that runs without
+//! its required arguments isn't validating input, and one that errors or
+//! hangs on minimal input may fail the same way for a model.
+
+use crate::discovery::ServerConfig;
+use crate::error::Error;
+use crate::scanner::report::{ResourceInfo, Severity, Threat, ThreatCategory, ToolInfo};
+use crate::scanner::threats::description_injection::truncate;
+use crate::scanner::threats::{ThreatDetector, ToolOutputInjectionDetector};
+use serde_json::{Map, Value};
+use std::time::Duration;
+use tracing::debug;
+
+/// JSON-RPC "Invalid params": the server rejected the arguments, which is
+/// the expected answer to a call missing required fields.
+const INVALID_PARAMS: i32 = -32602;
+
+/// One `tools/call` made during an active scan.
+#[derive(Debug)]
+pub struct ActiveCall {
+    pub tool: String,
+    pub arguments: Value,
+    pub outcome: crate::error::Result<Value>,
+    pub duration: Duration,
+}
+
+/// Build the smallest arguments object `schema` allows: properties with a
+/// `default` get it, required properties without one get `null`, and the
+/// rest are left out.
+pub fn make_minimal_args(schema: &Value) -> Value {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+
+    let mut args = Map::new();
+    if let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) {
+        for (name, property) in properties {
+            if let Some(default) = property.get("default") {
+                args.insert(name.clone(), default.clone());
+            } else if required.contains(&name.as_str()) {
+                args.insert(name.clone(), Value::Null);
+            }
+        }
+    }
+    // Required fields the schema doesn't describe still have to be sent
+    for name in required {
+        args.entry(name).or_insert(Value::Null);
+    }
+    Value::Object(args)
+}
+
+pub struct ActiveScanDetector;
+
+impl ActiveScanDetector {
+    /// Check how `tool` responded to an active scan call.
+    pub fn check_call(&self, tool: &ToolInfo, call: &ActiveCall) -> Vec<Threat> {
+        debug!(
+            tool = %call.tool,
+            duration_ms = call.duration.as_millis(),
+            ok = call.outcome.is_ok(),
+            "Active tool call"
+        );
+
+        match &call.outcome {
+            Ok(result) => {
+                let mut threats = ToolOutputInjectionDetector.check_output(result, &call.tool);
+                let is_error = result.get("isError").and_then(|v| v.as_bool()) == Some(true);
+                let missing = missing_required(tool, &call.arguments);
+                if !is_error && !missing.is_empty() {
+                    threats.push(
+                        threat(&call.tool, "ACTIVE-NO-VALIDATION", Severity::Medium, ThreatCategory::SchemaValidation, "Required arguments not validated")
+                            .with_message(format!(
+                                "Tool '{}' returned a result when called without its required arguments",
+                                call.tool
+                            ))
+                            .with_evidence(format!("missing: {}", missing.join(", ")))
+                            .with_remediation("Validate tool arguments against the input schema before running the tool."),
+                    );
+                }
+                threats
+            }
+            Err(Error::JsonRpc { code, .. }) if *code == INVALID_PARAMS => Vec::new(),
+            Err(Error::JsonRpc { code, message, .. }) => vec![threat(
+                &call.tool,
+                "ACTIVE-SERVER-ERROR",
+                Severity::Low,
+                ThreatCategory::Protocol,
+                "Tool call caused a server error",
+            )
+            .with_message(format!(
+                "Calling '{}' with minimal arguments returned JSON-RPC error {} instead of a tool result",
+                call.tool, code
+            ))
+            .with_evidence(truncate(message, 200))
+            .with_remediation(
+                "Return invalid input as a tool error (isError) or an Invalid params error.",
+            )],
+            Err(e) => vec![threat(
+                &call.tool,
+                "ACTIVE-CALL-FAILED",
+                Severity::Low,
+                ThreatCategory::Protocol,
+                "Tool call failed",
+            )
+            .with_message(format!(
+                "Calling '{}' with minimal arguments failed after {} ms",
+                call.tool,
+                call.duration.as_millis()
+            ))
+            .with_evidence(truncate(&e.to_string(), 200))
+            .with_remediation("Check that the tool answers promptly when given invalid input.")],
+        }
+    }
+}
+
+/// Tool listings don't show how tools respond, so `detect` finds nothing;
+/// the calls made for the active scan patterns are checked by `detect_call`.
+impl ThreatDetector for ActiveScanDetector {
+    fn category(&self) -> ThreatCategory {
+        ThreatCategory::SchemaValidation
+    }
+
+    fn name(&self) -> &'static str {
+        "ActiveScan"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tools that run without their required arguments, or fail on minimal input"
+    }
+
+    fn detect(
+        &self,
+        _server: &ServerConfig,
+        _tools: &[ToolInfo],
+        _resources: &[ResourceInfo],
+    ) -> Vec<Threat> {
+        Vec::new()
+    }
+
+    fn detect_call(&self, tool: &ToolInfo, call: &ActiveCall) -> Vec<Threat> {
+        self.check_call(tool, call)
+    }
+}
+
+/// Required properties of `tool` that are absent or `null` in `arguments`.
+fn missing_required(tool: &ToolInfo, arguments: &Value) -> Vec<String> {
+    tool.input_schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+        .filter(|name| arguments.get(name).is_none_or(|v| v.is_null()))
+        .map(String::from)
+        .collect()
+}
+
+fn threat(
+    tool: &str,
+    id: &str,
+    severity: Severity,
+    category: ThreatCategory,
+    title: &str,
+) -> Threat {
+    Threat::new(format!("{}-{}", id, tool), severity, category, title).with_tool(tool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tool(schema: Value) -> ToolInfo {
+        ToolInfo {
+            name: "read_file".to_string(),
+            description: None,
+            input_schema: schema,
+        }
+    }
+
+    fn call(outcome: crate::error::Result<Value>) -> ActiveCall {
+        ActiveCall {
+            tool: "read_file".to_string(),
+            arguments: json!({ "path": null }),
+            outcome,
+            duration: Duration::from_millis(12),
+        }
+    }
+
+    #[test]
+    fn minimal_args_use_defaults_and_null_for_required() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" },
+                "encoding": { "type": "string", "default": "utf-8" },
+                "limit": { "type": "integer" }
+            },
+            "required": ["path", "mode"]
+        });
+        assert_eq!(
+            make_minimal_args(&schema),
+            json!({ "path": null, "encoding": "utf-8", "mode": null })
+        );
+        assert_eq!(make_minimal_args(&json!({ "type": "object" })), json!({}));
+    }
+
+    #[test]
+    fn flags_results_for_calls_missing_required_args() {
+        let read_file = tool(json!({ "required": ["path"] }));
+        let ok = call(Ok(
+            json!({ "content": [{ "type": "text", "text": "done" }] }),
+        ));
+        let threats = ActiveScanDetector.check_call(&read_file, &ok);
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].id, "ACTIVE-NO-VALIDATION-read_file");
+        assert_eq!(threats[0].category, ThreatCategory::SchemaValidation);
+
+        let rejected = call(Ok(json!({ "content": [], "isError": true })));
+        assert!(ActiveScanDetector
+            .check_call(&read_file, &rejected)
+            .is_empty());
+
+        let no_required = tool(json!({ "type": "object" }));
+        assert!(ActiveScanDetector.check_call(&no_required, &ok).is_empty());
+    }
+
+    #[test]
+    fn flags_server_errors_but_not_invalid_params() {
+        let tool = tool(json!({ "required": ["path"] }));
+        let rpc_error = |code| {
+            call(Err(Error::JsonRpc {
+                code,
+                message: "boom".to_string(),
+                data: None,
+            }))
+        };
+
+        assert!(ActiveScanDetector
+            .check_call(&tool, &rpc_error(INVALID_PARAMS))
+            .is_empty());
+
+        let threats = ActiveScanDetector.check_call(&tool, &rpc_error(-32603));
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].id, "ACTIVE-SERVER-ERROR-read_file");
+
+        let timeout = call(Err(Error::Timeout { timeout_secs: 5 }));
+        let threats = ActiveScanDetector.check_call(&tool, &timeout);
+        assert_eq!(threats[0].id, "ACTIVE-CALL-FAILED-read_file");
+        assert_eq!(threats[0].severity, Severity::Low);
+    }
+}
@@ -0,0 +1,231 @@
+//! Detects tools whose input schemas accept raw shell commands or code.
+//!
+//! A free-form `command` or `script` string is the classic injection sink:
+//! anything the model is tricked into writing there gets executed.
+
+use crate::discovery::ServerConfig;
+use crate::scanner::report::{ResourceInfo, Severity, Threat, ThreatCategory, ToolInfo};
+use crate::scanner::threats::description_injection::truncate;
+use crate::scanner::threats::{name_words, unconstrained_string_properties, ThreatDetector};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Schema properties that typically carry a command line or code snippet.
+const COMMAND_PROPERTIES: &[&str] = &["command", "shell", "args", "argv", "cmd", "script", "code"];
+
+/// Tool name words that suggest the tool executes its input.
+const EXEC_NAME_WORDS: &[&str] = &[
+    "exec", "run", "shell", "bash", "sh", "python", "node", "eval",
+];
+
+/// Descriptions that admit the tool runs whatever it is given.
+static ARBITRARY_EXEC_PATTERNS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(runs?|executes?|evaluates?)\s+(any|arbitrary|user[\s-]?(provided|supplied))\b",
+    )
+    .unwrap()
+});
+
+const REMEDIATION: &str = "Validate commands against an allowlist, constrain parameters with an enum or pattern in the schema, and run the server in a sandbox.";
+
+pub struct CommandInjectionDetector;
+
+impl CommandInjectionDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn check_tool(&self, tool: &ToolInfo) -> Option<Threat> {
+        let description = tool.description.as_deref().unwrap_or("");
+        let unconstrained = unconstrained_string_properties(&tool.input_schema);
+
+        let command_props: Vec<&str> = unconstrained
+            .iter()
+            .copied()
+            .filter(|name| {
+                COMMAND_PROPERTIES
+                    .iter()
+                    .any(|c| name.eq_ignore_ascii_case(c))
+            })
+            .collect();
+        let exec_name = name_words(&tool.name)
+            .iter()
+            .any(|w| EXEC_NAME_WORDS.contains(&w.as_str()));
+        let arbitrary = ARBITRARY_EXEC_PATTERNS.is_match(description);
+
+        if command_props.is_empty() {
+            // An exec-style name with a free-form string input is still worth
+            // a look, even if the parameter is called something innocuous.
+            if !exec_name || unconstrained.is_empty() {
+                return None;
+            }
+            return Some(
+                Threat::new(
+                    format!("CMD-INJ-NAME-{}", tool.name),
+                    Severity::Medium,
+                    ThreatCategory::CommandInjection,
+                    "Execution tool accepts free-form input",
+                )
+                .with_message(format!(
+                    "Tool '{}' looks like it executes its input and accepts unvalidated strings",
+                    tool.name
+                ))
+                .with_evidence(format!(
+                    "Unconstrained string parameter(s): {}",
+                    unconstrained.join(", ")
+                ))
+                .with_remediation(REMEDIATION)
+                .with_tool(&tool.name),
+            );
+        }
+
+        let (severity, title) = if arbitrary {
+            (Severity::Critical, "Executes arbitrary commands")
+        } else {
+            (Severity::High, "Accepts raw shell arguments")
+        };
+
+        let mut evidence = vec![format!(
+            "Unconstrained command parameter(s): {}",
+            command_props.join(", ")
+        )];
+        if exec_name {
+            evidence.push(format!("Tool name '{}' suggests execution", tool.name));
+        }
+        if arbitrary {
+            evidence.push(format!("Description: {}", truncate(description, 200)));
+        }
+
+        Some(
+            Threat::new(
+                format!("CMD-INJ-SCHEMA-{}", tool.name),
+                severity,
+                ThreatCategory::CommandInjection,
+                title,
+            )
+            .with_message(format!(
+                "Tool '{}' passes unvalidated string input that may reach a shell or interpreter",
+                tool.name
+            ))
+            .with_evidence(evidence.join("; "))
+            .with_remediation(REMEDIATION)
+            .with_tool(&tool.name),
+        )
+    }
+}
+
+impl Default for CommandInjectionDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThreatDetector for CommandInjectionDetector {
+    fn category(&self) -> ThreatCategory {
+        ThreatCategory::CommandInjection
+    }
+
+    fn name(&self) -> &'static str {
+        "CommandInjection"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tool inputs that accept raw shell commands or code"
+    }
+
+    fn detect(
+        &self,
+        _server: &ServerConfig,
+        tools: &[ToolInfo],
+        _resources: &[ResourceInfo],
+    ) -> Vec<Threat> {
+        tools
+            .iter()
+            .filter_map(|tool| self.check_tool(tool))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn make_tool(name: &str, description: &str, properties: serde_json::Value) -> ToolInfo {
+        ToolInfo {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            input_schema: json!({"type": "object", "properties": properties}),
+        }
+    }
+
+    fn detect(tool: ToolInfo) -> Vec<Threat> {
+        CommandInjectionDetector::new().detect(&ServerConfig::new("test", "cmd"), &[tool], &[])
+    }
+
+    #[test]
+    fn detects_raw_command_property() {
+        let threats = detect(make_tool(
+            "terminal",
+            "Open a terminal",
+            json!({"command": {"type": "string"}}),
+        ));
+
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].id, "CMD-INJ-SCHEMA-terminal");
+        assert_eq!(threats[0].severity, Severity::High);
+        assert!(threats[0].remediation.contains("allowlist"));
+    }
+
+    #[test]
+    fn escalates_on_arbitrary_execution_description() {
+        let threats = detect(make_tool(
+            "run_script",
+            "Executes user-provided Python code",
+            json!({"code": {"type": "string"}}),
+        ));
+
+        assert_eq!(threats[0].severity, Severity::Critical);
+        assert!(threats[0].evidence.contains("suggests execution"));
+    }
+
+    #[test]
+    fn constrained_command_passes() {
+        let enum_tool = make_tool(
+            "git",
+            "Run a git subcommand",
+            json!({"cmd": {"type": "string", "enum": ["status", "log"]}}),
+        );
+        let pattern_tool = make_tool(
+            "git",
+            "Run a git subcommand",
+            json!({"args": {"type": "string", "pattern": "^[a-z]+$"}}),
+        );
+
+        assert!(detect(enum_tool).is_empty());
+        assert!(detect(pattern_tool).is_empty());
+    }
+
+    #[test]
+    fn exec_name_with_free_form_input_is_medium() {
+        let threats = detect(make_tool(
+            "bash",
+            "Bash helper",
+            json!({"input": {"type": "string"}}),
+        ));
+
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].severity, Severity::Medium);
+    }
+
+    #[test]
+    fn exec_name_matches_whole_words_only() {
+        let threats = detect(make_tool(
+            "share_notes",
+            "Share notes",
+            json!({"text": {"type": "string"}}),
+        ));
+
+        assert!(threats.is_empty());
+    }
+}
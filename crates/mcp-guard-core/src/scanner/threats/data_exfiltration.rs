@@ -0,0 +1,265 @@
+//! Detects tools that can send data to external destinations.
+//!
+//! A tool is flagged when its name or description advertises outbound
+//! delivery (webhooks, HTTP POST, email, cloud uploads) or when its input
+//! schema lets the caller choose an arbitrary destination URL. Either
+//! combined with a server that has no auth configured is escalated to
+//! critical, since anyone who can reach the server can ship data out.
+
+use crate::discovery::ServerConfig;
+use crate::scanner::report::{ResourceInfo, Severity, Threat, ThreatCategory, ToolInfo};
+use crate::scanner::threats::description_injection::{truncate, InjectionPattern};
+use crate::scanner::threats::no_auth::has_auth_configured;
+use crate::scanner::threats::{unconstrained_string_properties, ThreatDetector};
+use std::sync::LazyLock;
+
+/// Patterns that indicate outbound data transfer, matched against the tool
+/// name and description.
+static EXFIL_PATTERNS: LazyLock<Vec<InjectionPattern>> = LazyLock::new(|| {
+    vec![
+        InjectionPattern::new(r"(?i)webhook", "Sends data to a webhook", Severity::High),
+        InjectionPattern::new(
+            r"(?i)\b(post|put)\b.*\burl\b|\bhttps?[\s_-]*(post|put)\b",
+            "Sends data via HTTP POST",
+            Severity::High,
+        ),
+        InjectionPattern::new(
+            r"(?i)\bsend.*e-?mail",
+            "Sends data by email",
+            Severity::High,
+        ),
+        InjectionPattern::new(
+            r"(?i)\bupload.*\b(s3|gcs|bucket|blob)\b",
+            "Uploads data to cloud storage",
+            Severity::High,
+        ),
+        InjectionPattern::new(r"(?i)exfiltrat", "Mentions exfiltration", Severity::High),
+    ]
+});
+
+/// Schema properties that name where data is sent.
+const DESTINATION_PROPERTIES: &[&str] = &["url", "endpoint", "webhook_url", "destination"];
+
+pub struct DataExfiltrationDetector;
+
+impl DataExfiltrationDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn check_tool(&self, tool: &ToolInfo, has_auth: bool) -> Option<Threat> {
+        let description = tool.description.as_deref().unwrap_or("");
+        let text = format!("{} {}", tool.name.replace(['_', '-'], " "), description);
+
+        let matched: Vec<&InjectionPattern> = EXFIL_PATTERNS
+            .iter()
+            .filter(|p| p.regex.is_match(&text))
+            .collect();
+
+        let destinations: Vec<&str> = unconstrained_string_properties(&tool.input_schema)
+            .into_iter()
+            .filter(|name| {
+                DESTINATION_PROPERTIES
+                    .iter()
+                    .any(|d| name.eq_ignore_ascii_case(d))
+            })
+            .collect();
+
+        if matched.is_empty() && destinations.is_empty() {
+            return None;
+        }
+
+        let (severity, title) = if !has_auth {
+            (Severity::Critical, "Unauthenticated data exfiltration path")
+        } else if let Some(pattern) = matched.first() {
+            (pattern.severity, pattern.title)
+        } else {
+            (Severity::Medium, "Accepts arbitrary destination")
+        };
+
+        let mut evidence: Vec<String> = matched.iter().map(|p| p.title.to_string()).collect();
+        if !destinations.is_empty() {
+            evidence.push(format!(
+                "Arbitrary destination parameter(s): {}",
+                destinations.join(", ")
+            ));
+        }
+        if !description.is_empty() {
+            evidence.push(format!("Description: {}", truncate(description, 200)));
+        }
+
+        let mut message = format!(
+            "Tool '{}' can send data to external destinations",
+            tool.name
+        );
+        if !has_auth {
+            message.push_str(" and the server has no authentication configured");
+        }
+
+        Some(
+            Threat::new(
+                format!("EXFIL-{}", tool.name),
+                severity,
+                ThreatCategory::DataExfiltration,
+                title,
+            )
+            .with_message(message)
+            .with_evidence(evidence.join("; "))
+            .with_remediation(
+                "Restrict destinations to an allowlist (e.g. an enum of known URLs) and require authentication on the server.",
+            )
+            .with_tool(&tool.name),
+        )
+    }
+}
+
+impl Default for DataExfiltrationDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThreatDetector for DataExfiltrationDetector {
+    fn category(&self) -> ThreatCategory {
+        ThreatCategory::DataExfiltration
+    }
+
+    fn name(&self) -> &'static str {
+        "DataExfiltration"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tools that can send data to arbitrary external destinations"
+    }
+
+    fn detect(
+        &self,
+        server: &ServerConfig,
+        tools: &[ToolInfo],
+        _resources: &[ResourceInfo],
+    ) -> Vec<Threat> {
+        let has_auth = has_auth_configured(server);
+        tools
+            .iter()
+            .filter_map(|tool| self.check_tool(tool, has_auth))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn make_tool(name: &str, description: &str, input_schema: serde_json::Value) -> ToolInfo {
+        ToolInfo {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            input_schema,
+        }
+    }
+
+    fn authed_server() -> ServerConfig {
+        let mut server = ServerConfig::new("test", "cmd");
+        server
+            .env
+            .insert("API_TOKEN".to_string(), "secret".to_string());
+        server
+    }
+
+    fn detect_one(server: &ServerConfig, tool: ToolInfo) -> Vec<Threat> {
+        DataExfiltrationDetector::new().detect(server, &[tool], &[])
+    }
+
+    #[test]
+    fn detects_each_pattern_type() {
+        let cases = [
+            ("notify", "Forward results to a Slack webhook", "webhook"),
+            ("submit", "POST the payload to the given URL", "HTTP POST"),
+            ("http_post", "Make a request", "HTTP POST"),
+            ("notify", "Send an email with the report", "email"),
+            ("backup", "Upload files to an S3 bucket", "cloud storage"),
+            ("sync", "Exfiltrate the workspace", "exfiltration"),
+        ];
+
+        for (name, description, expected) in cases {
+            let threats = detect_one(&authed_server(), make_tool(name, description, json!({})));
+            assert_eq!(threats.len(), 1, "no threat for {:?}", description);
+            assert_eq!(threats[0].severity, Severity::High);
+            assert!(
+                threats[0].title.contains(expected),
+                "{:?} -> {:?}",
+                description,
+                threats[0].title
+            );
+        }
+    }
+
+    #[test]
+    fn detects_arbitrary_destination_property() {
+        let tool = make_tool(
+            "fetch",
+            "Fetch a page",
+            json!({
+                "type": "object",
+                "properties": {"URL": {"type": "string"}}
+            }),
+        );
+
+        let threats = detect_one(&authed_server(), tool);
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].severity, Severity::Medium);
+        assert!(threats[0].evidence.contains("URL"));
+    }
+
+    #[test]
+    fn enum_destination_is_not_flagged() {
+        let tool = make_tool(
+            "notify",
+            "Notify the team",
+            json!({
+                "type": "object",
+                "properties": {
+                    "destination": {"type": "string", "enum": ["ops", "dev"]}
+                }
+            }),
+        );
+
+        assert!(detect_one(&authed_server(), tool).is_empty());
+    }
+
+    #[test]
+    fn escalates_to_critical_without_auth() {
+        let unauthenticated = ServerConfig::new("test", "cmd");
+
+        let pattern_tool = make_tool("notify", "Posts results to a webhook", json!({}));
+        let threats = detect_one(&unauthenticated, pattern_tool);
+        assert_eq!(threats[0].severity, Severity::Critical);
+
+        let schema_tool = make_tool(
+            "deliver",
+            "Deliver a message",
+            json!({
+                "type": "object",
+                "properties": {"endpoint": {"type": "string"}}
+            }),
+        );
+        let threats = detect_one(&unauthenticated, schema_tool);
+        assert_eq!(threats[0].severity, Severity::Critical);
+        assert!(threats[0].message.contains("no authentication"));
+    }
+
+    #[test]
+    fn clean_tool_passes() {
+        let tool = make_tool(
+            "read_file",
+            "Read the contents of a file",
+            json!({
+                "type": "object",
+                "properties": {"path": {"type": "string"}}
+            }),
+        );
+
+        assert!(detect_one(&ServerConfig::new("test", "cmd"), tool).is_empty());
+    }
+}
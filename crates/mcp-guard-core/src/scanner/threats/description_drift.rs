@@ -1,4 +1,5 @@
-//! Detects changes in tool descriptions compared to stored snapshots.
+//! Detects changes in tool descriptions and server capabilities compared to
+//! stored snapshots.
 
 use crate::discovery::ServerConfig;
 use crate::scanner::report::{
@@ -71,21 +72,68 @@ impl DescriptionDriftDetector {
             );
         }
 
+        if diff.capabilities_changed {
+            threats.push(
+                Threat::new(
+                    "DRIFT-CAP-CHANGE",
+                    Severity::Medium,
+                    ThreatCategory::DescriptionDrift,
+                    "Server capabilities changed",
+                )
+                .with_message("Server advertises different capabilities than at the last scan")
+                .with_evidence(format!("Capabilities: {}", diff.capability_details))
+                .with_remediation(
+                    "Verify the server update was expected. New capabilities expose new tools, resources, or prompts to the model.",
+                ),
+            );
+        }
+
+        if let Some((old, new)) = &diff.protocol_version_change {
+            threats.push(
+                Threat::new(
+                    "DRIFT-PROTO-VERSION",
+                    Severity::Low,
+                    ThreatCategory::DescriptionDrift,
+                    "Protocol version changed",
+                )
+                .with_message(
+                    "Server negotiated a different MCP protocol version than at the last scan",
+                )
+                .with_evidence(format!("Old: {} → New: {}", old, new))
+                .with_remediation("Verify the server update was expected."),
+            );
+        }
+
         threats
     }
 }
 
 impl ThreatDetector for DescriptionDriftDetector {
+    fn category(&self) -> ThreatCategory {
+        ThreatCategory::DescriptionDrift
+    }
+
+    fn name(&self) -> &'static str {
+        "DescriptionDrift"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tool descriptions that changed since the stored snapshot"
+    }
+
     fn detect(
         &self,
         _server: &ServerConfig,
         _tools: &[ToolInfo],
         _resources: &[ResourceInfo],
     ) -> Vec<Threat> {
-        // This detector is special - it needs historical snapshot data
-        // The actual detection happens in Scanner::scan() using threats_from_diff()
+        // Drift needs the stored snapshot, so it's found by detect_drift
         Vec::new()
     }
+
+    fn detect_drift(&self, diff: &SnapshotDiff) -> Vec<Threat> {
+        Self::threats_from_diff(diff)
+    }
 }
 
 fn truncate(s: &str, max_len: usize) -> String {
@@ -104,8 +152,6 @@ mod tests {
     #[test]
     fn detects_changed_descriptions() {
         let diff = SnapshotDiff {
-            added_tools: vec![],
-            removed_tools: vec![],
             changed_descriptions: vec![DescriptionChange {
                 tool_name: "read_file".to_string(),
                 old_description: "Read a file from disk".to_string(),
@@ -113,6 +159,7 @@ mod tests {
                 old_hash: "abc".to_string(),
                 new_hash: "def".to_string(),
             }],
+            ..SnapshotDiff::default()
         };
 
         let threats = DescriptionDriftDetector::threats_from_diff(&diff);
@@ -124,8 +171,7 @@ mod tests {
     fn detects_added_tools() {
         let diff = SnapshotDiff {
             added_tools: vec!["new_tool".to_string()],
-            removed_tools: vec![],
-            changed_descriptions: vec![],
+            ..SnapshotDiff::default()
         };
 
         let threats = DescriptionDriftDetector::threats_from_diff(&diff);
@@ -136,9 +182,8 @@ mod tests {
     #[test]
     fn detects_removed_tools() {
         let diff = SnapshotDiff {
-            added_tools: vec![],
             removed_tools: vec!["old_tool".to_string()],
-            changed_descriptions: vec![],
+            ..SnapshotDiff::default()
         };
 
         let threats = DescriptionDriftDetector::threats_from_diff(&diff);
@@ -147,13 +192,27 @@ mod tests {
     }
 
     #[test]
-    fn empty_diff_no_threats() {
+    fn detects_capability_and_protocol_changes() {
         let diff = SnapshotDiff {
-            added_tools: vec![],
-            removed_tools: vec![],
-            changed_descriptions: vec![],
+            capabilities_changed: true,
+            capability_details: "+resources".to_string(),
+            protocol_version_change: Some(("2024-11-05".to_string(), "2025-03-26".to_string())),
+            ..SnapshotDiff::default()
         };
 
+        let threats = DescriptionDriftDetector::threats_from_diff(&diff);
+        assert_eq!(threats.len(), 2);
+        assert_eq!(threats[0].id, "DRIFT-CAP-CHANGE");
+        assert_eq!(threats[0].severity, Severity::Medium);
+        assert!(threats[0].evidence.contains("+resources"));
+        assert_eq!(threats[1].id, "DRIFT-PROTO-VERSION");
+        assert_eq!(threats[1].severity, Severity::Low);
+    }
+
+    #[test]
+    fn empty_diff_no_threats() {
+        let diff = SnapshotDiff::default();
+
         let threats = DescriptionDriftDetector::threats_from_diff(&diff);
         assert!(threats.is_empty());
     }
@@ -0,0 +1,250 @@
+//! Detects tools that expose the server's environment variables.
+//!
+//! A local server inherits the env block from its client config, which is
+//! usually where its API tokens live. A tool that lists the environment, or
+//! reads any variable the caller names, hands those tokens to the model and
+//! to anything that manages to inject instructions into it.
+
+use crate::discovery::{ServerConfig, TransportType};
+use crate::scanner::report::{ResourceInfo, Severity, Threat, ThreatCategory, ToolInfo};
+use crate::scanner::threats::description_injection::truncate;
+use crate::scanner::threats::{unconstrained_string_properties, ThreatDetector};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Wording that indicates the tool enumerates environment variables,
+/// matched against the tool name and description.
+static ENV_DUMP_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(list|dump|print|show)\b.{0,30}\b(env|environment[\s_-]*var(iable)?s?)\b|\benv(ironment)?[\s_-]*var(iable)?s\b|\benviron\b",
+    )
+    .unwrap()
+});
+
+/// Schema properties that name the variable to read.
+const VARIABLE_PROPERTIES: &[&str] = &["env_var", "variable_name"];
+
+/// Env var names that look like credentials.
+const CREDENTIAL_MARKERS: &[&str] = &["token", "key", "secret", "auth", "password"];
+
+pub struct EnvLeakageDetector;
+
+impl EnvLeakageDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn check_tool(&self, tool: &ToolInfo, credentials: &[&str]) -> Option<Threat> {
+        let description = tool.description.as_deref().unwrap_or("");
+        let text = format!("{} {}", tool.name.replace(['_', '-'], " "), description);
+
+        let enumerates = ENV_DUMP_PATTERN.is_match(&text);
+        let variables: Vec<&str> = unconstrained_string_properties(&tool.input_schema)
+            .into_iter()
+            .filter(|name| {
+                VARIABLE_PROPERTIES
+                    .iter()
+                    .any(|p| name.eq_ignore_ascii_case(p))
+            })
+            .collect();
+
+        if !enumerates && variables.is_empty() {
+            return None;
+        }
+
+        let mut evidence = Vec::new();
+        if enumerates {
+            evidence.push("Name or description mentions listing environment variables".to_string());
+        }
+        if !variables.is_empty() {
+            evidence.push(format!(
+                "Arbitrary variable name parameter(s): {}",
+                variables.join(", ")
+            ));
+        }
+        if !description.is_empty() {
+            evidence.push(format!("Description: {}", truncate(description, 200)));
+        }
+
+        let threat = if credentials.is_empty() {
+            Threat::new(
+                format!("ENV-LEAK-{}", tool.name),
+                Severity::High,
+                ThreatCategory::PermissionScope,
+                "Exposes environment variables",
+            )
+            .with_message(format!(
+                "Tool '{}' can return the server's environment variables to the model",
+                tool.name
+            ))
+        } else {
+            evidence.push(format!("Credentials in env: {}", credentials.join(", ")));
+            Threat::new(
+                format!("ENV-LEAK-CRED-{}", tool.name),
+                Severity::Critical,
+                ThreatCategory::PermissionScope,
+                "Exposes credentials from the environment",
+            )
+            .with_message(format!(
+                "Tool '{}' can return the server's environment variables, which include credentials",
+                tool.name
+            ))
+        };
+
+        Some(
+            threat
+                .with_evidence(evidence.join("; "))
+                .with_remediation(
+                    "Remove the tool or limit it to an enum of non-sensitive variable names.",
+                )
+                .with_tool(&tool.name),
+        )
+    }
+}
+
+impl Default for EnvLeakageDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Credential-looking env var names passed to a local server's process.
+/// Remote servers only use their env for request headers, so whatever
+/// their tools read is the remote process's environment, not these.
+fn credential_env_vars(server: &ServerConfig) -> Vec<&str> {
+    if !matches!(server.transport, TransportType::Stdio) {
+        return Vec::new();
+    }
+
+    let mut names: Vec<&str> = server
+        .env
+        .keys()
+        .filter(|k| {
+            let k_lower = k.to_lowercase();
+            CREDENTIAL_MARKERS.iter().any(|m| k_lower.contains(m))
+        })
+        .map(String::as_str)
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+impl ThreatDetector for EnvLeakageDetector {
+    fn category(&self) -> ThreatCategory {
+        ThreatCategory::PermissionScope
+    }
+
+    fn name(&self) -> &'static str {
+        "EnvLeakage"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tools that expose the server's environment variables"
+    }
+
+    fn detect(
+        &self,
+        server: &ServerConfig,
+        tools: &[ToolInfo],
+        _resources: &[ResourceInfo],
+    ) -> Vec<Threat> {
+        let credentials = credential_env_vars(server);
+        tools
+            .iter()
+            .filter_map(|tool| self.check_tool(tool, &credentials))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn make_tool(name: &str, description: &str, input_schema: serde_json::Value) -> ToolInfo {
+        ToolInfo {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            input_schema,
+        }
+    }
+
+    fn detect(server: &ServerConfig, tool: ToolInfo) -> Vec<Threat> {
+        EnvLeakageDetector::new().detect(server, &[tool], &[])
+    }
+
+    #[test]
+    fn detects_env_dumping_tools() {
+        let server = ServerConfig::new("test", "cmd");
+        let cases = [
+            ("list_env", "Debugging helper"),
+            ("debug", "Dump environment variables"),
+            ("inspect", "Returns os.environ as JSON"),
+            ("config", "Print all env vars for the process"),
+        ];
+
+        for (name, description) in cases {
+            let threats = detect(&server, make_tool(name, description, json!({})));
+            assert_eq!(threats.len(), 1, "no threat for {:?}", description);
+            assert_eq!(threats[0].id, format!("ENV-LEAK-{}", name));
+            assert_eq!(threats[0].severity, Severity::High);
+        }
+    }
+
+    #[test]
+    fn ignores_unrelated_environment_mentions() {
+        let server = ServerConfig::new("test", "cmd");
+        for description in [
+            "Run code in a sandboxed environment",
+            "List files in the development environment",
+        ] {
+            assert!(
+                detect(&server, make_tool("run", description, json!({}))).is_empty(),
+                "{:?}",
+                description
+            );
+        }
+    }
+
+    #[test]
+    fn detects_arbitrary_variable_parameter() {
+        let server = ServerConfig::new("test", "cmd");
+        let schema = |constraint: serde_json::Value| {
+            let mut property = json!({ "type": "string" });
+            property
+                .as_object_mut()
+                .unwrap()
+                .extend(constraint.as_object().unwrap().clone());
+            json!({ "type": "object", "properties": { "variable_name": property } })
+        };
+
+        let threats = detect(
+            &server,
+            make_tool("get_var", "Read a value", schema(json!({}))),
+        );
+        assert_eq!(threats.len(), 1);
+        assert!(threats[0].evidence.contains("variable_name"));
+
+        let constrained = schema(json!({ "enum": ["HOME", "LANG"] }));
+        assert!(detect(&server, make_tool("get_var", "Read a value", constrained)).is_empty());
+    }
+
+    #[test]
+    fn escalates_when_local_server_holds_credentials() {
+        let tool = make_tool("list_env", "List env vars", json!({}));
+        let mut server = ServerConfig::new("test", "cmd");
+        server
+            .env
+            .insert("GITHUB_TOKEN".to_string(), "ghp_x".to_string());
+
+        let threats = detect(&server, tool.clone());
+        assert_eq!(threats[0].id, "ENV-LEAK-CRED-list_env");
+        assert_eq!(threats[0].severity, Severity::Critical);
+        assert!(threats[0].evidence.contains("GITHUB_TOKEN"));
+
+        server.transport = TransportType::StreamableHttp {
+            url: "https://example.com/mcp".to_string(),
+        };
+        assert_eq!(detect(&server, tool)[0].severity, Severity::High);
+    }
+}
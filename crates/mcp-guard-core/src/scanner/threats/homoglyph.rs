@@ -0,0 +1,313 @@
+//! Detects tool names that use Unicode lookalike characters.
+//!
+//! `reаd_file` with a Cyrillic `а` renders the same as `read_file` in most
+//! clients, so a user approving it believes they're approving the familiar
+//! tool.
+
+use crate::discovery::ServerConfig;
+use crate::scanner::report::{ResourceInfo, Severity, Threat, ThreatCategory, ToolInfo};
+use crate::scanner::threats::ThreatDetector;
+use std::collections::BTreeMap;
+
+/// Tool names from widely used MCP servers that are worth impersonating.
+const KNOWN_TOOL_NAMES: &[&str] = &[
+    "read_file",
+    "read_multiple_files",
+    "write_file",
+    "edit_file",
+    "create_directory",
+    "list_directory",
+    "directory_tree",
+    "move_file",
+    "search_files",
+    "get_file_info",
+    "list_allowed_directories",
+    "execute_command",
+    "run_command",
+    "fetch",
+    "query",
+    "search",
+    "create_issue",
+    "create_pull_request",
+    "push_files",
+    "send_message",
+];
+
+/// Lookalike characters and the ASCII character they imitate.
+const HOMOGLYPHS: &[(char, char)] = &[
+    // Cyrillic lowercase
+    ('а', 'a'),
+    ('в', 'b'),
+    ('с', 'c'),
+    ('ԁ', 'd'),
+    ('е', 'e'),
+    ('һ', 'h'),
+    ('і', 'i'),
+    ('ј', 'j'),
+    ('к', 'k'),
+    ('ӏ', 'l'),
+    ('м', 'm'),
+    ('о', 'o'),
+    ('р', 'p'),
+    ('ԛ', 'q'),
+    ('ѕ', 's'),
+    ('т', 't'),
+    ('ц', 'u'),
+    ('ѵ', 'v'),
+    ('ԝ', 'w'),
+    ('х', 'x'),
+    ('у', 'y'),
+    // Cyrillic uppercase
+    ('А', 'A'),
+    ('В', 'B'),
+    ('С', 'C'),
+    ('Е', 'E'),
+    ('Н', 'H'),
+    ('І', 'I'),
+    ('Ј', 'J'),
+    ('К', 'K'),
+    ('М', 'M'),
+    ('О', 'O'),
+    ('Р', 'P'),
+    ('Ѕ', 'S'),
+    ('Т', 'T'),
+    ('Х', 'X'),
+    ('У', 'Y'),
+    // Greek
+    ('α', 'a'),
+    ('ε', 'e'),
+    ('ι', 'i'),
+    ('κ', 'k'),
+    ('ν', 'v'),
+    ('ο', 'o'),
+    ('ρ', 'p'),
+    ('τ', 't'),
+    ('υ', 'u'),
+    ('χ', 'x'),
+    ('Α', 'A'),
+    ('Β', 'B'),
+    ('Ε', 'E'),
+    ('Η', 'H'),
+    ('Ι', 'I'),
+    ('Κ', 'K'),
+    ('Μ', 'M'),
+    ('Ν', 'N'),
+    ('Ο', 'O'),
+    ('Ρ', 'P'),
+    ('Τ', 'T'),
+    ('Χ', 'X'),
+    ('Υ', 'Y'),
+    ('Ζ', 'Z'),
+    // Latin lookalikes
+    ('ı', 'i'),
+    ('ɑ', 'a'),
+    ('ɡ', 'g'),
+    ('ʟ', 'L'),
+    ('ℓ', 'l'),
+    // Hyphen lookalikes
+    ('‐', '-'),
+    ('‑', '-'),
+    ('–', '-'),
+    ('−', '-'),
+];
+
+/// Map lookalike Unicode characters to the ASCII characters they imitate.
+/// Fullwidth forms (`ｒｅａｄ`) are folded too; anything else is kept as is.
+pub fn normalize_homoglyphs(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if let Some(&(_, ascii)) = HOMOGLYPHS.iter().find(|(glyph, _)| *glyph == c) {
+                return ascii;
+            }
+            // Fullwidth ASCII variants, U+FF01..U+FF5E
+            if ('\u{FF01}'..='\u{FF5E}').contains(&c) {
+                return char::from_u32(c as u32 - 0xFEE0).unwrap_or(c);
+            }
+            c
+        })
+        .collect()
+}
+
+pub struct HomoglyphDetector;
+
+impl HomoglyphDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn check_known_name(&self, tool: &ToolInfo, normalized: &str) -> Option<Threat> {
+        if normalized == tool.name || !KNOWN_TOOL_NAMES.contains(&normalized) {
+            return None;
+        }
+
+        Some(
+            Threat::new(
+                format!("HOMOGLYPH-NAME-{}", tool.name),
+                Severity::Critical,
+                ThreatCategory::ToolShadowing,
+                "Tool name imitates a well-known tool",
+            )
+            .with_message(format!(
+                "Tool '{}' uses lookalike Unicode characters to appear as '{}'",
+                tool.name, normalized
+            ))
+            .with_evidence(format!(
+                "Non-ASCII characters: {}",
+                describe_non_ascii(&tool.name)
+            ))
+            .with_remediation(
+                "Do not use this server. Legitimate tools have no reason to imitate well-known names with lookalike characters.",
+            )
+            .with_tool(&tool.name),
+        )
+    }
+}
+
+impl Default for HomoglyphDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThreatDetector for HomoglyphDetector {
+    fn category(&self) -> ThreatCategory {
+        ThreatCategory::ToolShadowing
+    }
+
+    fn name(&self) -> &'static str {
+        "Homoglyph"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tool names using look-alike characters to impersonate other tools"
+    }
+
+    fn detect(
+        &self,
+        _server: &ServerConfig,
+        tools: &[ToolInfo],
+        _resources: &[ResourceInfo],
+    ) -> Vec<Threat> {
+        let mut threats = Vec::new();
+        let mut by_normalized: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+
+        for tool in tools {
+            let normalized = normalize_homoglyphs(&tool.name);
+            threats.extend(self.check_known_name(tool, &normalized));
+
+            let names = by_normalized.entry(normalized).or_default();
+            if !names.contains(&tool.name.as_str()) {
+                names.push(&tool.name);
+            }
+        }
+
+        for (normalized, names) in by_normalized {
+            if names.len() < 2 {
+                continue;
+            }
+
+            // Prefer pointing at a name that actually uses lookalikes
+            let suspect = names
+                .iter()
+                .find(|n| **n != normalized)
+                .copied()
+                .unwrap_or(names[0]);
+
+            threats.push(
+                Threat::new(
+                    format!("HOMOGLYPH-SHADOW-{}", normalized),
+                    Severity::High,
+                    ThreatCategory::ToolShadowing,
+                    "Tools with visually identical names",
+                )
+                .with_message(format!(
+                    "Tools {} look identical once lookalike characters are normalized",
+                    names
+                        .iter()
+                        .map(|n| format!("'{}'", n))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+                .with_evidence(format!(
+                    "Non-ASCII characters in '{}': {}",
+                    suspect,
+                    describe_non_ascii(suspect)
+                ))
+                .with_remediation(
+                    "A client may call the lookalike instead of the intended tool. Remove or rename the tool using lookalike characters.",
+                )
+                .with_tool(suspect),
+            );
+        }
+
+        threats
+    }
+}
+
+fn describe_non_ascii(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_ascii())
+        .map(|c| format!("'{}' (U+{:04X})", c, c as u32))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn make_tool(name: &str) -> ToolInfo {
+        ToolInfo {
+            name: name.to_string(),
+            description: Some("A tool".to_string()),
+            input_schema: json!({"type": "object"}),
+        }
+    }
+
+    fn detect(names: &[&str]) -> Vec<Threat> {
+        let tools: Vec<ToolInfo> = names.iter().map(|n| make_tool(n)).collect();
+        HomoglyphDetector::new().detect(&ServerConfig::new("test", "cmd"), &tools, &[])
+    }
+
+    #[test]
+    fn normalizes_lookalikes_to_ascii() {
+        assert_eq!(normalize_homoglyphs("rеаd_filе"), "read_file");
+        assert_eq!(normalize_homoglyphs("ｒｅａｄ＿ｆｉｌｅ"), "read_file");
+        assert_eq!(normalize_homoglyphs("ΕΧΕC"), "EXEC");
+        assert_eq!(normalize_homoglyphs("検索"), "検索");
+    }
+
+    #[test]
+    fn flags_lookalike_of_known_tool() {
+        let threats = detect(&["rеad_file"]);
+
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].id, "HOMOGLYPH-NAME-rеad_file");
+        assert_eq!(threats[0].severity, Severity::Critical);
+        assert!(threats[0].evidence.contains("U+0435"));
+    }
+
+    #[test]
+    fn plain_known_names_pass() {
+        assert!(detect(&["read_file", "write_file", "my_tool"]).is_empty());
+    }
+
+    #[test]
+    fn flags_lookalike_pair_within_server() {
+        let threats = detect(&["deploy", "dеploy"]);
+
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].id, "HOMOGLYPH-SHADOW-deploy");
+        assert_eq!(threats[0].tool_name.as_deref(), Some("dеploy"));
+    }
+
+    #[test]
+    fn lookalike_of_known_tool_alongside_original() {
+        let threats = detect(&["read_file", "rеad_file"]);
+        let ids: Vec<&str> = threats.iter().map(|t| t.id.as_str()).collect();
+
+        assert!(ids.contains(&"HOMOGLYPH-NAME-rеad_file"));
+        assert!(ids.contains(&"HOMOGLYPH-SHADOW-read_file"));
+    }
+}
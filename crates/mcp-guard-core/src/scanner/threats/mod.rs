@@ -0,0 +1,162 @@
+//! Threat detection framework and implementations.
+
+mod active_scan;
+mod command_injection;
+mod data_exfiltration;
+mod description_drift;
+mod description_injection;
+mod env_leakage;
+mod homoglyph;
+mod no_auth;
+mod output_injection;
+mod path_traversal;
+mod permission_scope;
+mod private_key;
+mod resource_uri;
+mod schema_validation;
+mod shadowing;
+mod supply_chain;
+mod tool_count;
+
+pub use active_scan::{make_minimal_args, ActiveCall, ActiveScanDetector};
+pub use command_injection::CommandInjectionDetector;
+pub use data_exfiltration::DataExfiltrationDetector;
+pub use description_drift::DescriptionDriftDetector;
+pub use description_injection::{DescriptionInjectionDetector, DEFAULT_MAX_DESCRIPTION_LENGTH};
+pub use env_leakage::EnvLeakageDetector;
+pub use homoglyph::{normalize_homoglyphs, HomoglyphDetector};
+pub use no_auth::NoAuthDetector;
+pub use output_injection::{is_safe_to_call, ToolOutputInjectionDetector};
+pub use path_traversal::PathTraversalDetector;
+pub use permission_scope::PermissionScopeDetector;
+pub use private_key::PrivateKeyDetector;
+pub use resource_uri::ResourceUriDetector;
+pub use schema_validation::SchemaValidationDetector;
+pub use shadowing::{MultiServerShadowingDetector, ShadowingDetector};
+pub use supply_chain::{PackageDenylist, SupplyChainDetector};
+pub use tool_count::{
+    ToolCountAnomalyDetector, DEFAULT_TOOL_COUNT_CRITICAL_THRESHOLD,
+    DEFAULT_TOOL_COUNT_WARN_THRESHOLD,
+};
+
+use crate::discovery::ServerConfig;
+use crate::scanner::report::{
+    PromptInfo, ResourceInfo, SnapshotDiff, Threat, ThreatCategory, ToolInfo,
+};
+use std::sync::Arc;
+
+pub trait ThreatDetector: Send + Sync {
+    /// Short name shown in reports and recorded on each threat, e.g.
+    /// `DescriptionInjection`.
+    fn name(&self) -> &'static str;
+
+    /// One-line summary of what the detector looks for.
+    fn description(&self) -> &'static str;
+
+    /// Category of the threats this detector reports.
+    fn category(&self) -> ThreatCategory;
+
+    fn detect(
+        &self,
+        server: &ServerConfig,
+        tools: &[ToolInfo],
+        resources: &[ResourceInfo],
+    ) -> Vec<Threat>;
+
+    // The checks below see what a scan gathers besides tools and resources.
+    // Most detectors don't need it, so they find nothing by default.
+
+    /// Check the prompts a server lists.
+    fn detect_prompts(&self, _prompts: &[PromptInfo]) -> Vec<Threat> {
+        Vec::new()
+    }
+
+    /// Check the tools listed by a server that advertised the tools
+    /// capability.
+    fn detect_advertised_tools(&self, _server: &ServerConfig, _tools: &[ToolInfo]) -> Vec<Threat> {
+        Vec::new()
+    }
+
+    /// Check the result of a call to `tool_name` made during the scan.
+    fn detect_output(&self, _output: &serde_json::Value, _tool_name: &str) -> Vec<Threat> {
+        Vec::new()
+    }
+
+    /// Check how `tool` responded to an active scan call.
+    fn detect_call(&self, _tool: &ToolInfo, _call: &ActiveCall) -> Vec<Threat> {
+        Vec::new()
+    }
+
+    /// Check how a server changed since its stored snapshot.
+    fn detect_drift(&self, _diff: &SnapshotDiff) -> Vec<Threat> {
+        Vec::new()
+    }
+}
+
+pub fn all_detectors() -> Vec<Arc<dyn ThreatDetector>> {
+    vec![
+        Arc::new(DescriptionInjectionDetector::new()),
+        Arc::new(PermissionScopeDetector::new()),
+        Arc::new(NoAuthDetector),
+        Arc::new(DataExfiltrationDetector::new()),
+        Arc::new(CommandInjectionDetector::new()),
+        Arc::new(PathTraversalDetector::new()),
+        Arc::new(HomoglyphDetector::new()),
+        Arc::new(ShadowingDetector::new()),
+        Arc::new(SupplyChainDetector::new()),
+        Arc::new(SchemaValidationDetector::new()),
+        Arc::new(ResourceUriDetector::new()),
+        Arc::new(EnvLeakageDetector::new()),
+        Arc::new(PrivateKeyDetector::new()),
+        Arc::new(ToolCountAnomalyDetector::default()),
+        Arc::new(ToolOutputInjectionDetector),
+        Arc::new(ActiveScanDetector),
+        Arc::new(DescriptionDriftDetector),
+    ]
+}
+
+/// Split `snake_case`, `kebab-case`, and `camelCase` names into lowercase words.
+pub(super) fn name_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+
+    for c in name.chars() {
+        let boundary = !c.is_ascii_alphanumeric() || (c.is_ascii_uppercase() && prev_lower);
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        if c.is_ascii_alphanumeric() {
+            current.push(c.to_ascii_lowercase());
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Names of `input_schema` properties that accept any string: typed as
+/// `string` with no `enum`, `const`, or `pattern` constraint.
+pub(super) fn unconstrained_string_properties(schema: &serde_json::Value) -> Vec<&str> {
+    let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+        return Vec::new();
+    };
+
+    properties
+        .iter()
+        .filter(|(_, prop)| {
+            let is_string = match prop.get("type") {
+                Some(serde_json::Value::String(t)) => t == "string",
+                Some(serde_json::Value::Array(types)) => types.iter().any(|t| t == "string"),
+                _ => false,
+            };
+            is_string
+                && prop.get("enum").is_none()
+                && prop.get("const").is_none()
+                && prop.get("pattern").is_none()
+        })
+        .map(|(name, _)| name.as_str())
+        .collect()
+}
@@ -0,0 +1,273 @@
+//! Detects tools that accept filesystem paths without validation.
+//!
+//! Unlike the root-access check in `PermissionScopeDetector`, which looks at
+//! how the server was launched, this looks at each tool's input schema: a
+//! free-form `path` string lets an injected prompt reach `../../.ssh`.
+
+use crate::discovery::ServerConfig;
+use crate::scanner::report::{ResourceInfo, Severity, Threat, ThreatCategory, ToolInfo};
+use crate::scanner::threats::description_injection::truncate;
+use crate::scanner::threats::{name_words, unconstrained_string_properties, ThreatDetector};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Property name words that indicate a filesystem path.
+const PATH_WORDS: &[&str] = &[
+    "path",
+    "file",
+    "filename",
+    "directory",
+    "dir",
+    "filepath",
+    "dest",
+];
+
+/// Descriptions that invite paths outside the intended directory. `..`
+/// only counts as a path component, so an ellipsis ("Loading...") doesn't.
+static TRAVERSAL_PATTERNS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)\bany\s+path\b|\brelative\s+path|\bparent\s+director|(^|[\s/\\"'`(])\.\.([/\\]|\s|$)"#,
+    )
+    .unwrap()
+});
+
+/// Default values that point at absolute or parent locations.
+const SUSPICIOUS_DEFAULT_PREFIXES: &[&str] = &["/", "~", "../", "C:\\"];
+
+pub struct PathTraversalDetector;
+
+impl PathTraversalDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn check_schema(&self, tool: &ToolInfo) -> Option<Threat> {
+        let path_props: Vec<&str> = unconstrained_string_properties(&tool.input_schema)
+            .into_iter()
+            .filter(|name| {
+                name_words(name)
+                    .iter()
+                    .any(|w| PATH_WORDS.contains(&w.as_str()))
+            })
+            .collect();
+
+        if path_props.is_empty() {
+            return None;
+        }
+
+        let description = tool.description.as_deref().unwrap_or("");
+        let invites_traversal = TRAVERSAL_PATTERNS.find(description);
+
+        let (severity, title) = if invites_traversal.is_some() {
+            (Severity::High, "Accepts arbitrary filesystem paths")
+        } else {
+            (Severity::Medium, "Unvalidated path parameter")
+        };
+
+        let mut evidence = format!(
+            "Path parameter(s) without pattern: {}",
+            path_props.join(", ")
+        );
+        if invites_traversal.is_some() {
+            evidence.push_str(&format!("; Description: {}", truncate(description, 200)));
+        }
+
+        Some(
+            Threat::new(
+                format!("PATH-TRAV-SCHEMA-{}", tool.name),
+                severity,
+                ThreatCategory::PathTraversal,
+                title,
+            )
+            .with_message(format!(
+                "Tool '{}' accepts file paths with no validation in its schema",
+                tool.name
+            ))
+            .with_evidence(evidence)
+            .with_remediation(
+                "Add a `pattern` to path parameters, resolve paths and reject anything outside the allowed root, and refuse `..` components.",
+            )
+            .with_tool(&tool.name),
+        )
+    }
+
+    fn check_defaults(&self, tool: &ToolInfo) -> Vec<Threat> {
+        let Some(properties) = tool
+            .input_schema
+            .get("properties")
+            .and_then(|p| p.as_object())
+        else {
+            return Vec::new();
+        };
+
+        properties
+            .iter()
+            .filter_map(|(name, prop)| {
+                let default = prop.get("default")?.as_str()?;
+                SUSPICIOUS_DEFAULT_PREFIXES
+                    .iter()
+                    .any(|p| default.starts_with(p))
+                    .then(|| {
+                        Threat::new(
+                            format!("PATH-TRAV-DEFAULT-{}-{}", tool.name, name),
+                            Severity::High,
+                            ThreatCategory::PathTraversal,
+                            "Path default outside the working directory",
+                        )
+                        .with_message(format!(
+                            "Parameter '{}' of tool '{}' defaults to an absolute or parent path",
+                            name, tool.name
+                        ))
+                        .with_evidence(format!("default: {}", truncate(default, 200)))
+                        .with_remediation(
+                            "Default to a path inside the server's allowed directory, or remove the default.",
+                        )
+                        .with_tool(&tool.name)
+                    })
+            })
+            .collect()
+    }
+}
+
+impl Default for PathTraversalDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThreatDetector for PathTraversalDetector {
+    fn category(&self) -> ThreatCategory {
+        ThreatCategory::PathTraversal
+    }
+
+    fn name(&self) -> &'static str {
+        "PathTraversal"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tools that accept filesystem paths without validation"
+    }
+
+    fn detect(
+        &self,
+        _server: &ServerConfig,
+        tools: &[ToolInfo],
+        _resources: &[ResourceInfo],
+    ) -> Vec<Threat> {
+        let mut threats = Vec::new();
+        for tool in tools {
+            threats.extend(self.check_schema(tool));
+            threats.extend(self.check_defaults(tool));
+        }
+        threats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn make_tool(name: &str, description: &str, properties: serde_json::Value) -> ToolInfo {
+        ToolInfo {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            input_schema: json!({"type": "object", "properties": properties}),
+        }
+    }
+
+    fn detect(tool: ToolInfo) -> Vec<Threat> {
+        PathTraversalDetector::new().detect(&ServerConfig::new("test", "cmd"), &[tool], &[])
+    }
+
+    #[test]
+    fn flags_unvalidated_path_property() {
+        let threats = detect(make_tool(
+            "read_file",
+            "Read a file",
+            json!({"file_path": {"type": "string"}}),
+        ));
+
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].id, "PATH-TRAV-SCHEMA-read_file");
+        assert_eq!(threats[0].severity, Severity::Medium);
+    }
+
+    #[test]
+    fn escalates_when_description_invites_traversal() {
+        for description in [
+            "Read a file at any path",
+            "Accepts a relative path",
+            "Can read from the parent directory",
+            "Paths like ../config work",
+            "Resolves C:\\data\\..\\secrets",
+            "Use .. to go up a level",
+        ] {
+            let threats = detect(make_tool(
+                "read_file",
+                description,
+                json!({"path": {"type": "string"}}),
+            ));
+            assert_eq!(threats[0].severity, Severity::High, "{}", description);
+        }
+    }
+
+    #[test]
+    fn ellipses_are_not_traversal() {
+        for description in [
+            "Loading...",
+            "Reads a file... or a folder",
+            "Wait.. then read",
+        ] {
+            let threats = detect(make_tool(
+                "read_file",
+                description,
+                json!({"path": {"type": "string"}}),
+            ));
+            assert_eq!(threats[0].severity, Severity::Medium, "{}", description);
+        }
+    }
+
+    #[test]
+    fn pattern_validated_path_passes() {
+        let threats = detect(make_tool(
+            "read_file",
+            "Read a file",
+            json!({"path": {"type": "string", "pattern": "^[a-z0-9_/]+$"}}),
+        ));
+
+        assert!(threats.is_empty());
+    }
+
+    #[test]
+    fn unrelated_string_properties_pass() {
+        let threats = detect(make_tool(
+            "search",
+            "Search notes",
+            json!({"query": {"type": "string"}, "profile": {"type": "string"}}),
+        ));
+
+        assert!(threats.is_empty());
+    }
+
+    #[test]
+    fn flags_suspicious_defaults() {
+        for default in ["/etc", "~/.ssh", "../secrets", "C:\\Windows"] {
+            let threats = detect(make_tool(
+                "list",
+                "List entries",
+                json!({"root": {"type": "string", "default": default}}),
+            ));
+            assert_eq!(threats.len(), 1, "{}", default);
+            assert_eq!(threats[0].id, "PATH-TRAV-DEFAULT-list-root");
+            assert_eq!(threats[0].severity, Severity::High);
+        }
+
+        let threats = detect(make_tool(
+            "list",
+            "List entries",
+            json!({"root": {"type": "string", "default": "docs"}}),
+        ));
+        assert!(threats.is_empty());
+    }
+}
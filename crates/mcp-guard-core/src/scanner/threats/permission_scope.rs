@@ -4,6 +4,8 @@
 //! - Actual code execution (shell, eval, exec in descriptions)
 //! - Credential exposure (tools that return/handle secrets)
 //! - Root filesystem access (server configured with / or ~)
+//! - Input schemas whose defaults let a tool run with no arguments, or
+//!   point it at sensitive paths and URLs
 
 use crate::discovery::ServerConfig;
 use crate::scanner::report::{ResourceInfo, Severity, Threat, ThreatCategory, ToolInfo};
@@ -143,10 +145,85 @@ impl PermissionScopeDetector {
             );
         }
 
+        threats.extend(self.check_schema_defaults(tool));
+        threats
+    }
+
+    fn check_schema_defaults(&self, tool: &ToolInfo) -> Vec<Threat> {
+        let mut threats = Vec::new();
+        let Some(properties) = tool
+            .input_schema
+            .get("properties")
+            .and_then(|p| p.as_object())
+        else {
+            return threats;
+        };
+        let default_of = |name: &str| properties.get(name).and_then(|p| p.get("default"));
+
+        // Defaults for every required property mean the tool can be called
+        // with no arguments at all
+        let required: Vec<&str> = tool
+            .input_schema
+            .get("required")
+            .and_then(|r| r.as_array())
+            .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+        if !required.is_empty() && required.iter().all(|name| default_of(name).is_some()) {
+            threats.push(
+                Threat::new(
+                    "SCHEMA-DEFAULT-BYPASS",
+                    Severity::Medium,
+                    ThreatCategory::PermissionScope,
+                    "Required arguments all have defaults",
+                )
+                .with_message(format!(
+                    "Every required argument of tool '{}' has a default, so it can be invoked with no arguments and act on values the caller never chose",
+                    tool.name
+                ))
+                .with_evidence(format!("Required with defaults: {}", required.join(", ")))
+                .with_remediation(
+                    "Remove defaults from required arguments so callers must supply them explicitly.",
+                )
+                .with_tool(&tool.name),
+            );
+        }
+
+        let sensitive: Vec<String> = properties
+            .iter()
+            .filter_map(|(name, property)| {
+                let default = property.get("default")?.as_str()?;
+                is_sensitive_default(default).then(|| format!("{} = {:?}", name, default))
+            })
+            .collect();
+        if !sensitive.is_empty() {
+            threats.push(
+                Threat::new(
+                    "SCHEMA-DEFAULT-SENSITIVE",
+                    Severity::Medium,
+                    ThreatCategory::PermissionScope,
+                    "Argument defaults to a sensitive path or URL",
+                )
+                .with_message(format!(
+                    "Tool '{}' defaults arguments to the filesystem root, the home directory, or a URL when the caller leaves them out",
+                    tool.name
+                ))
+                .with_evidence(sensitive.join(", "))
+                .with_remediation(
+                    "Default to a narrow, task-specific path, or require the argument.",
+                )
+                .with_tool(&tool.name),
+            );
+        }
+
         threats
     }
 }
 
+/// The filesystem root, the home directory, or an absolute URL.
+fn is_sensitive_default(value: &str) -> bool {
+    matches!(value, "/" | "~" | "~/") || reqwest::Url::parse(value).is_ok_and(|u| u.has_host())
+}
+
 impl Default for PermissionScopeDetector {
     fn default() -> Self {
         Self::new()
@@ -154,6 +231,18 @@ impl Default for PermissionScopeDetector {
 }
 
 impl ThreatDetector for PermissionScopeDetector {
+    fn category(&self) -> ThreatCategory {
+        ThreatCategory::PermissionScope
+    }
+
+    fn name(&self) -> &'static str {
+        "PermissionScope"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tools with broad or dangerous capabilities"
+    }
+
     fn detect(
         &self,
         server: &ServerConfig,
@@ -249,4 +338,68 @@ mod tests {
         // Should NOT flag /tmp as root access
         assert!(!threats.iter().any(|t| t.id == "PERM-ROOT"));
     }
+
+    fn schema_tool(input_schema: serde_json::Value) -> ToolInfo {
+        ToolInfo {
+            name: "fetch".to_string(),
+            description: Some("Fetches a page".to_string()),
+            input_schema,
+        }
+    }
+
+    fn ids(tool: ToolInfo) -> Vec<String> {
+        PermissionScopeDetector::new()
+            .detect(&ServerConfig::new("test", "cmd"), &[tool], &[])
+            .into_iter()
+            .map(|t| t.id)
+            .collect()
+    }
+
+    #[test]
+    fn detects_required_arguments_that_all_have_defaults() {
+        let tool = schema_tool(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "host": { "type": "string", "default": "internal" },
+                "port": { "type": "integer", "default": 8080 }
+            },
+            "required": ["host", "port"]
+        }));
+        assert_eq!(ids(tool), ["SCHEMA-DEFAULT-BYPASS"]);
+
+        // One required argument without a default is enough
+        let tool = schema_tool(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "host": { "type": "string", "default": "internal" },
+                "port": { "type": "integer" }
+            },
+            "required": ["host", "port"]
+        }));
+        assert!(ids(tool).is_empty());
+
+        // Nothing required isn't a bypass
+        let tool = schema_tool(serde_json::json!({
+            "type": "object",
+            "properties": { "limit": { "type": "integer", "default": 10 } }
+        }));
+        assert!(ids(tool).is_empty());
+    }
+
+    #[test]
+    fn detects_sensitive_default_values() {
+        for default in ["/", "~", "http://169.254.169.254/latest/meta-data"] {
+            let tool = schema_tool(serde_json::json!({
+                "type": "object",
+                "properties": { "target": { "type": "string", "default": default } }
+            }));
+            assert_eq!(ids(tool), ["SCHEMA-DEFAULT-SENSITIVE"], "{}", default);
+        }
+
+        let tool = schema_tool(serde_json::json!({
+            "type": "object",
+            "properties": { "target": { "type": "string", "default": "./data" } }
+        }));
+        assert!(ids(tool).is_empty());
+    }
 }
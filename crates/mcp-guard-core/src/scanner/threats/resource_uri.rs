@@ -0,0 +1,237 @@
+//! Detects resources whose URIs point somewhere a client shouldn't follow.
+//!
+//! Clients read resources on the model's behalf, so a resource exposing
+//! `/etc`, an inline script, or a traversal sequence hands that access to
+//! whatever the model is asked to do with it.
+
+use crate::discovery::ServerConfig;
+use crate::scanner::report::{ResourceInfo, Severity, Threat, ThreatCategory, ToolInfo};
+use crate::scanner::threats::description_injection::truncate;
+use crate::scanner::threats::ThreatDetector;
+
+/// System directories that no MCP resource should need to expose.
+const SYSTEM_PATHS: &[&str] = &["file:///etc/", "file:///proc/", "file:///sys/"];
+
+/// Data URIs that carry markup or script rather than data.
+const ACTIVE_DATA_URIS: &[&str] = &["data:text/html", "data:text/javascript"];
+
+/// `../` with one or both parts percent-encoded, or as is.
+const TRAVERSAL_SEQUENCES: &[&str] = &["../", "%2e%2e%2f", "%2e%2e/", "..%2f"];
+
+pub struct ResourceUriDetector;
+
+impl ResourceUriDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn check_resource(&self, resource: &ResourceInfo) -> Vec<Threat> {
+        // Schemes and percent-encodings are case-insensitive
+        let uri = resource.uri.to_lowercase();
+        let mut threats = Vec::new();
+
+        if uri.starts_with("javascript:") {
+            threats.push(
+                threat(resource, "RES-JS-SCHEME", Severity::Critical, "JavaScript URI")
+                    .with_message(format!(
+                        "Resource '{}' uses the javascript: scheme, which runs script in clients that render it",
+                        resource.name
+                    ))
+                    .with_remediation("Serve the content as data with a text MIME type instead of a javascript: URI."),
+            );
+        }
+        if ACTIVE_DATA_URIS.iter().any(|p| uri.starts_with(p)) {
+            threats.push(
+                threat(resource, "RES-DATA-URI", Severity::High, "Executable data URI")
+                    .with_message(format!(
+                        "Resource '{}' inlines HTML or JavaScript in a data: URI",
+                        resource.name
+                    ))
+                    .with_remediation("Serve HTML and scripts from a reviewed location, or return them as plain text."),
+            );
+        }
+        if SYSTEM_PATHS.iter().any(|p| uri.starts_with(p)) {
+            threats.push(
+                threat(
+                    resource,
+                    "RES-SYSTEM-PATH",
+                    Severity::High,
+                    "System path exposed as resource",
+                )
+                .with_message(format!(
+                    "Resource '{}' points into /etc, /proc, or /sys",
+                    resource.name
+                ))
+                .with_remediation(
+                    "Limit file resources to the directory the server is meant to expose.",
+                ),
+            );
+        }
+        if TRAVERSAL_SEQUENCES.iter().any(|s| uri.contains(s)) {
+            threats.push(
+                threat(
+                    resource,
+                    "RES-TRAVERSAL",
+                    Severity::High,
+                    "Path traversal in resource URI",
+                )
+                .with_message(format!(
+                    "Resource '{}' contains a parent directory reference, possibly percent-encoded",
+                    resource.name
+                ))
+                .with_remediation(
+                    "Normalize resource URIs and reject any that resolve outside the exposed root.",
+                ),
+            );
+        }
+        if uri.starts_with("http://") {
+            threats.push(
+                threat(resource, "RES-HTTP", Severity::Medium, "Unencrypted remote resource")
+                    .with_message(format!(
+                        "Resource '{}' is fetched over plain HTTP, so its content can be modified in transit",
+                        resource.name
+                    ))
+                    .with_remediation("Serve the resource over HTTPS."),
+            );
+        }
+        if uri.starts_with("file://") && resource.mime_type.as_deref() == Some("text/html") {
+            threats.push(
+                threat(
+                    resource,
+                    "RES-HTML-FILE",
+                    Severity::Medium,
+                    "Local HTML file resource",
+                )
+                .with_message(format!(
+                    "Resource '{}' is a local HTML file, which may contain scripts",
+                    resource.name
+                ))
+                .with_remediation("Check the file for scripts, or expose it as text/plain."),
+            );
+        }
+
+        threats
+    }
+}
+
+impl Default for ResourceUriDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThreatDetector for ResourceUriDetector {
+    fn category(&self) -> ThreatCategory {
+        ThreatCategory::ResourceUri
+    }
+
+    fn name(&self) -> &'static str {
+        "ResourceUri"
+    }
+
+    fn description(&self) -> &'static str {
+        "Resource URIs pointing somewhere a client shouldn't follow"
+    }
+
+    fn detect(
+        &self,
+        _server: &ServerConfig,
+        _tools: &[ToolInfo],
+        resources: &[ResourceInfo],
+    ) -> Vec<Threat> {
+        resources
+            .iter()
+            .flat_map(|resource| self.check_resource(resource))
+            .collect()
+    }
+}
+
+fn threat(resource: &ResourceInfo, id: &str, severity: Severity, title: &str) -> Threat {
+    Threat::new(
+        format!("{}-{}", id, resource.name),
+        severity,
+        ThreatCategory::ResourceUri,
+        title,
+    )
+    .with_evidence(format!("uri: {}", truncate(&resource.uri, 200)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(uri: &str, mime_type: Option<&str>) -> ResourceInfo {
+        ResourceInfo {
+            uri: uri.to_string(),
+            name: "res".to_string(),
+            description: None,
+            mime_type: mime_type.map(String::from),
+        }
+    }
+
+    fn detect(resources: &[ResourceInfo]) -> Vec<Threat> {
+        ResourceUriDetector::new().detect(&ServerConfig::new("test", "cmd"), &[], resources)
+    }
+
+    #[test]
+    fn flags_suspicious_uris() {
+        for (uri, id, severity) in [
+            ("file:///etc/passwd", "RES-SYSTEM-PATH-res", Severity::High),
+            (
+                "file:///proc/self/environ",
+                "RES-SYSTEM-PATH-res",
+                Severity::High,
+            ),
+            ("FILE:///sys/kernel", "RES-SYSTEM-PATH-res", Severity::High),
+            (
+                "data:text/html,<script>",
+                "RES-DATA-URI-res",
+                Severity::High,
+            ),
+            (
+                "data:text/javascript,alert(1)",
+                "RES-DATA-URI-res",
+                Severity::High,
+            ),
+            (
+                "javascript:alert(1)",
+                "RES-JS-SCHEME-res",
+                Severity::Critical,
+            ),
+            ("http://example.com/doc", "RES-HTTP-res", Severity::Medium),
+            (
+                "file:///srv/%2E%2E%2Fetc",
+                "RES-TRAVERSAL-res",
+                Severity::High,
+            ),
+            ("file:///srv/../secret", "RES-TRAVERSAL-res", Severity::High),
+        ] {
+            let threats = detect(&[resource(uri, None)]);
+            assert_eq!(threats.len(), 1, "{}", uri);
+            assert_eq!(threats[0].id, id, "{}", uri);
+            assert_eq!(threats[0].severity, severity, "{}", uri);
+            assert_eq!(threats[0].category, ThreatCategory::ResourceUri);
+        }
+    }
+
+    #[test]
+    fn flags_local_html_files() {
+        let threats = detect(&[resource("file:///srv/docs/index.html", Some("text/html"))]);
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].id, "RES-HTML-FILE-res");
+        assert_eq!(threats[0].severity, Severity::Medium);
+
+        assert!(detect(&[resource("https://example.com/", Some("text/html"))]).is_empty());
+    }
+
+    #[test]
+    fn ignores_ordinary_resources() {
+        let resources = [
+            resource("file:///home/user/project/README.md", Some("text/markdown")),
+            resource("https://example.com/api/data.json", None),
+            resource("postgres://localhost/db/users", None),
+            resource("data:text/plain,hello", None),
+        ];
+        assert!(detect(&resources).is_empty());
+    }
+}
@@ -0,0 +1,182 @@
+//! Checks tool input schemas against the JSON Schema meta-schema.
+//!
+//! Clients build arguments from `inputSchema`, so a schema that isn't valid
+//! JSON Schema may be read differently by every client, and one where every
+//! property is optional lets a model call the tool with nothing at all.
+
+use crate::discovery::ServerConfig;
+use crate::scanner::report::{ResourceInfo, Severity, Threat, ThreatCategory, ToolInfo};
+use crate::scanner::threats::description_injection::truncate;
+use crate::scanner::threats::ThreatDetector;
+
+pub struct SchemaValidationDetector;
+
+impl SchemaValidationDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn check_meta_schema(&self, tool: &ToolInfo) -> Option<Threat> {
+        // Schemas without `$schema` are checked as draft 2020-12
+        let problem = match jsonschema::meta::try_validate(&tool.input_schema) {
+            Ok(Ok(())) => return None,
+            Ok(Err(e)) => format!("{} at '{}'", e, e.instance_path),
+            Err(e) => format!("Unrecognized $schema: {}", e),
+        };
+
+        Some(
+            Threat::new(
+                format!("SCHEMA-INVALID-{}", tool.name),
+                Severity::Low,
+                ThreatCategory::SchemaValidation,
+                "Invalid input schema",
+            )
+            .with_message(format!(
+                "Tool '{}' has an input schema that is not valid JSON Schema",
+                tool.name
+            ))
+            .with_evidence(truncate(&problem, 200))
+            .with_remediation(
+                "Fix the schema so clients validate arguments consistently. Use --skip-schema-validation if the server intentionally ships a non-standard schema.",
+            )
+            .with_tool(&tool.name),
+        )
+    }
+
+    fn check_required(&self, tool: &ToolInfo) -> Option<Threat> {
+        let properties = tool.input_schema.get("properties")?.as_object()?;
+        if properties.is_empty() || tool.input_schema.get("required").is_some() {
+            return None;
+        }
+
+        let mut names: Vec<&str> = properties.keys().map(String::as_str).collect();
+        names.sort_unstable();
+
+        Some(
+            Threat::new(
+                format!("SCHEMA-MISSING-REQUIRED-{}", tool.name),
+                Severity::Medium,
+                ThreatCategory::SchemaValidation,
+                "Input schema has no required arguments",
+            )
+            .with_message(format!(
+                "Every argument of tool '{}' is optional, so it can be called without any of them",
+                tool.name
+            ))
+            .with_evidence(format!("Properties: {}", names.join(", ")))
+            .with_remediation(
+                "List the arguments the tool needs in a `required` array, or use an empty array if all are genuinely optional.",
+            )
+            .with_tool(&tool.name),
+        )
+    }
+}
+
+impl Default for SchemaValidationDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThreatDetector for SchemaValidationDetector {
+    fn category(&self) -> ThreatCategory {
+        ThreatCategory::SchemaValidation
+    }
+
+    fn name(&self) -> &'static str {
+        "SchemaValidation"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tool input schemas that aren't valid JSON Schema or require nothing"
+    }
+
+    fn detect(
+        &self,
+        _server: &ServerConfig,
+        tools: &[ToolInfo],
+        _resources: &[ResourceInfo],
+    ) -> Vec<Threat> {
+        tools
+            .iter()
+            .flat_map(|tool| [self.check_meta_schema(tool), self.check_required(tool)])
+            .flatten()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn detect(input_schema: serde_json::Value) -> Vec<Threat> {
+        let tool = ToolInfo {
+            name: "read_file".to_string(),
+            description: Some("Read a file".to_string()),
+            input_schema,
+        };
+        SchemaValidationDetector::new().detect(&ServerConfig::new("test", "cmd"), &[tool], &[])
+    }
+
+    #[test]
+    fn valid_schema_with_required_passes() {
+        let threats = detect(json!({
+            "type": "object",
+            "properties": {"path": {"type": "string"}},
+            "required": ["path"]
+        }));
+        assert!(threats.is_empty());
+    }
+
+    #[test]
+    fn flags_invalid_schema() {
+        let threats = detect(json!({
+            "type": "object",
+            "properties": {"path": {"type": "text"}},
+            "required": ["path"]
+        }));
+
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].id, "SCHEMA-INVALID-read_file");
+        assert_eq!(threats[0].severity, Severity::Low);
+    }
+
+    #[test]
+    fn validates_against_declared_draft() {
+        // `exclusiveMinimum` is a boolean in draft-04 but a number in draft-07
+        let schema = |schema_uri: &str| {
+            json!({
+                "$schema": schema_uri,
+                "type": "object",
+                "properties": {"count": {"type": "integer", "minimum": 0, "exclusiveMinimum": true}},
+                "required": ["count"]
+            })
+        };
+
+        assert!(detect(schema("http://json-schema.org/draft-04/schema#")).is_empty());
+        assert_eq!(
+            detect(schema("http://json-schema.org/draft-07/schema#"))[0].id,
+            "SCHEMA-INVALID-read_file"
+        );
+    }
+
+    #[test]
+    fn flags_properties_without_required() {
+        let threats = detect(json!({
+            "type": "object",
+            "properties": {"path": {"type": "string"}, "encoding": {"type": "string"}}
+        }));
+
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].id, "SCHEMA-MISSING-REQUIRED-read_file");
+        assert_eq!(threats[0].severity, Severity::Medium);
+        assert_eq!(threats[0].evidence, "Properties: encoding, path");
+    }
+
+    #[test]
+    fn schema_without_properties_needs_no_required() {
+        assert!(detect(json!({"type": "object"})).is_empty());
+        assert!(detect(json!({"type": "object", "properties": {}})).is_empty());
+    }
+}
@@ -0,0 +1,408 @@
+//! Detects tool name collisions and similar names, within one server and
+//! across all scanned servers.
+
+use crate::discovery::ServerConfig;
+use crate::scanner::report::{
+    ResourceInfo, ScanResult, Severity, Threat, ThreatCategory, ToolInfo,
+};
+use crate::scanner::threats::ThreatDetector;
+use std::collections::BTreeMap;
+use unicode_normalization::UnicodeNormalization;
+
+/// Names within this edit distance of each other are reported as similar.
+const SIMILARITY_THRESHOLD: usize = 2;
+
+fn nfc(name: &str) -> String {
+    name.nfc().collect()
+}
+
+/// Whether `a` and `b` are close enough to be mistaken for each other.
+/// Singular/plural pairs like `get_issue`/`get_issues` are common in
+/// legitimate servers and not counted.
+fn similar_names(a: &str, b: &str) -> Option<usize> {
+    let distance = strsim::levenshtein(a, b);
+    if distance == 0 || distance > SIMILARITY_THRESHOLD {
+        return None;
+    }
+
+    let (short, long) = if a.len() < b.len() { (a, b) } else { (b, a) };
+    let plural = long
+        .strip_prefix(short)
+        .is_some_and(|suffix| suffix == "s" || suffix == "es");
+    (!plural).then_some(distance)
+}
+
+/// Flags tools on a single server whose names are identical after Unicode
+/// NFC normalization (`SHADOW-NORM`) or within a small edit distance
+/// (`SHADOW-SIM`).
+pub struct ShadowingDetector;
+
+impl ShadowingDetector {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ShadowingDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThreatDetector for ShadowingDetector {
+    fn category(&self) -> ThreatCategory {
+        ThreatCategory::ToolShadowing
+    }
+
+    fn name(&self) -> &'static str {
+        "Shadowing"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tool names that collide with or closely resemble other tools"
+    }
+
+    fn detect(
+        &self,
+        _server: &ServerConfig,
+        tools: &[ToolInfo],
+        _resources: &[ResourceInfo],
+    ) -> Vec<Threat> {
+        let mut threats = Vec::new();
+        let mut by_normalized: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+
+        for tool in tools {
+            let names = by_normalized.entry(nfc(&tool.name)).or_default();
+            if !names.contains(&tool.name.as_str()) {
+                names.push(&tool.name);
+            }
+        }
+
+        for (normalized, names) in &by_normalized {
+            if names.len() < 2 {
+                continue;
+            }
+
+            threats.push(
+                Threat::new(
+                    format!("SHADOW-NORM-{}", normalized),
+                    Severity::High,
+                    ThreatCategory::ToolShadowing,
+                    "Tool names identical after Unicode normalization",
+                )
+                .with_message(format!(
+                    "Tools {} are the same name in different Unicode encodings",
+                    names
+                        .iter()
+                        .map(|n| format!("'{}'", n))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+                .with_evidence(format!(
+                    "Code points: {}",
+                    names
+                        .iter()
+                        .map(|n| describe_code_points(n))
+                        .collect::<Vec<_>>()
+                        .join(" vs ")
+                ))
+                .with_remediation(
+                    "A client may call either tool when the user picks one. Remove the duplicate.",
+                )
+                .with_tool(names[1]),
+            );
+        }
+
+        let normalized: Vec<&String> = by_normalized.keys().collect();
+        for (i, name1) in normalized.iter().enumerate() {
+            for name2 in normalized.iter().skip(i + 1) {
+                let Some(distance) = similar_names(name1, name2) else {
+                    continue;
+                };
+
+                threats.push(
+                    Threat::new(
+                        format!("SHADOW-SIM-{}-{}", name1, name2),
+                        Severity::Medium,
+                        ThreatCategory::ToolShadowing,
+                        "Similar tool names detected",
+                    )
+                    .with_message(format!(
+                        "Tools '{}' and '{}' have similar names (distance: {})",
+                        name1, name2, distance
+                    ))
+                    .with_evidence(format!("'{}' and '{}'", name1, name2))
+                    .with_remediation(
+                        "Verify these are intentionally different tools. A model may confuse them and call the wrong one.",
+                    )
+                    .with_tool(name2.as_str()),
+                );
+            }
+        }
+
+        threats
+    }
+}
+
+/// Flags tool name collisions and near-collisions between servers. Runs
+/// over a whole scan's results, since no single server's tool list shows
+/// the conflict.
+pub struct MultiServerShadowingDetector {
+    similarity_threshold: usize,
+}
+
+impl MultiServerShadowingDetector {
+    pub fn new() -> Self {
+        Self {
+            similarity_threshold: SIMILARITY_THRESHOLD,
+        }
+    }
+
+    /// Report names on different servers within `threshold` edits of each
+    /// other. Defaults to 2, as within a server.
+    pub fn with_similarity_threshold(mut self, threshold: usize) -> Self {
+        self.similarity_threshold = threshold;
+        self
+    }
+
+    /// Threats paired with the index in `results` of each server involved;
+    /// every server sharing a name gets its own copy.
+    pub fn detect(&self, results: &[ScanResult]) -> Vec<(usize, Threat)> {
+        let mut threats = Vec::new();
+        let mut tool_registry: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+
+        for (index, result) in results.iter().enumerate() {
+            for tool in &result.tools {
+                let servers = tool_registry.entry(nfc(&tool.name)).or_default();
+                if !servers.contains(&index) {
+                    servers.push(index);
+                }
+            }
+        }
+
+        let server_names = |indexes: &[usize]| {
+            indexes
+                .iter()
+                .map(|&i| results[i].server.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        for (tool_name, servers) in &tool_registry {
+            if servers.len() < 2 {
+                continue;
+            }
+
+            let threat = Threat::new(
+                format!("SHADOW-{}", tool_name),
+                Severity::High,
+                ThreatCategory::ToolShadowing,
+                "Tool name collision",
+            )
+            .with_message(format!(
+                "Tool '{}' is registered by multiple servers: {}",
+                tool_name,
+                server_names(servers)
+            ))
+            .with_evidence(format!("Servers: {}", server_names(servers)))
+            .with_remediation(
+                "Rename one of the tools to avoid conflicts. The tool loaded last may shadow earlier ones.",
+            )
+            .with_tool(tool_name);
+
+            threats.extend(servers.iter().map(|&i| (i, threat.clone())));
+        }
+
+        let tool_names: Vec<&String> = tool_registry.keys().collect();
+        for (i, name1) in tool_names.iter().enumerate() {
+            for name2 in tool_names.iter().skip(i + 1) {
+                let distance = strsim::levenshtein(name1, name2);
+                if distance == 0 || distance > self.similarity_threshold {
+                    continue;
+                }
+
+                let servers1 = &tool_registry[*name1];
+                let servers2 = &tool_registry[*name2];
+                // Similar names on the same server are ShadowingDetector's job
+                if servers1 == servers2 {
+                    continue;
+                }
+
+                let threat = Threat::new(
+                    format!("SHADOW-SIM-{}-{}", name1, name2),
+                    Severity::Medium,
+                    ThreatCategory::ToolShadowing,
+                    "Similar tool names detected",
+                )
+                .with_message(format!(
+                    "Tools '{}' and '{}' have similar names (distance: {})",
+                    name1, name2, distance
+                ))
+                .with_evidence(format!(
+                    "'{}' from {}, '{}' from {}",
+                    name1,
+                    server_names(servers1),
+                    name2,
+                    server_names(servers2)
+                ))
+                .with_remediation(
+                    "Verify these are intentionally different tools. Similar names could indicate typosquatting.",
+                );
+
+                threats.push((servers1[0], threat.clone().with_tool(name1.as_str())));
+                threats.push((servers2[0], threat.with_tool(name2.as_str())));
+            }
+        }
+
+        threats
+    }
+}
+
+impl Default for MultiServerShadowingDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn describe_code_points(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_ascii())
+        .map(|c| format!("U+{:04X}", c as u32))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::time::Duration;
+
+    fn make_tools(tool_names: &[&str]) -> Vec<ToolInfo> {
+        tool_names
+            .iter()
+            .map(|&n| ToolInfo {
+                name: n.to_string(),
+                description: None,
+                input_schema: serde_json::json!({}),
+            })
+            .collect()
+    }
+
+    fn make_result(name: &str, tool_names: &[&str]) -> ScanResult {
+        ScanResult {
+            server: ServerConfig::new(name, "cmd"),
+            tools: make_tools(tool_names),
+            resources: Vec::new(),
+            prompts: Vec::new(),
+            threats: Vec::new(),
+            snapshot_diff: None,
+            scan_duration: Duration::ZERO,
+            scanned_at: Utc::now(),
+        }
+    }
+
+    fn detect_single(tool_names: &[&str]) -> Vec<Threat> {
+        ShadowingDetector::new().detect(
+            &ServerConfig::new("test", "cmd"),
+            &make_tools(tool_names),
+            &[],
+        )
+    }
+
+    #[test]
+    fn detects_nfc_equivalent_names_on_one_server() {
+        // Precomposed é vs e + combining acute accent
+        let threats = detect_single(&["caf\u{e9}_lookup", "cafe\u{301}_lookup"]);
+
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].id, "SHADOW-NORM-caf\u{e9}_lookup");
+        assert_eq!(threats[0].severity, Severity::High);
+    }
+
+    #[test]
+    fn detects_similar_names_on_one_server() {
+        let threats = detect_single(&["read_file", "read_fi1e", "write_database"]);
+
+        assert_eq!(threats.len(), 1);
+        assert!(threats[0].id.starts_with("SHADOW-SIM-"));
+        assert_eq!(threats[0].severity, Severity::Medium);
+    }
+
+    #[test]
+    fn plural_pairs_on_one_server_not_flagged() {
+        assert!(detect_single(&["get_issue", "get_issues", "list_branches"]).is_empty());
+    }
+
+    #[test]
+    fn detects_exact_collision() {
+        let detector = MultiServerShadowingDetector::new();
+        let results = vec![
+            make_result("server1", &["read_file", "write_file"]),
+            make_result("server2", &["read_file", "delete_file"]),
+        ];
+
+        let threats = detector.detect(&results);
+        let collisions: Vec<usize> = threats
+            .iter()
+            .filter(|(_, t)| t.title == "Tool name collision")
+            .map(|(i, _)| *i)
+            .collect();
+        assert_eq!(collisions, vec![0, 1]);
+    }
+
+    #[test]
+    fn detects_similar_names() {
+        let detector = MultiServerShadowingDetector::new();
+        let results = vec![
+            make_result("trusted", &["read_file"]),
+            make_result("suspicious", &["read_fi1e"]),
+        ];
+
+        let threats = detector.detect(&results);
+        assert!(threats
+            .iter()
+            .any(|(_, t)| t.title == "Similar tool names detected"));
+    }
+
+    #[test]
+    fn similarity_threshold_defaults_to_two() {
+        // Three edits apart
+        let results = vec![
+            make_result("trusted", &["read_file"]),
+            make_result("suspicious", &["rad_fl"]),
+        ];
+        let similar = |detector: MultiServerShadowingDetector| {
+            detector
+                .detect(&results)
+                .iter()
+                .any(|(_, t)| t.title == "Similar tool names detected")
+        };
+
+        assert!(!similar(MultiServerShadowingDetector::new()));
+        assert!(similar(
+            MultiServerShadowingDetector::new().with_similarity_threshold(3)
+        ));
+    }
+
+    #[test]
+    fn no_false_positive_for_different_names() {
+        let detector = MultiServerShadowingDetector::new();
+        let results = vec![
+            make_result("server1", &["read_file"]),
+            make_result("server2", &["write_database"]),
+        ];
+
+        let threats = detector.detect(&results);
+        assert!(threats.is_empty());
+    }
+
+    #[test]
+    fn same_server_similar_names_not_flagged() {
+        let detector = MultiServerShadowingDetector::new();
+        let results = vec![make_result("server1", &["read_file", "read_files"])];
+
+        let threats = detector.detect(&results);
+        assert!(threats.is_empty());
+    }
+}
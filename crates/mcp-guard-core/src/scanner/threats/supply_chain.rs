@@ -0,0 +1,390 @@
+//! Detects servers launched from known malicious or risky packages.
+//!
+//! `npx`, `uvx`, and `pipx` download and run whatever package the config
+//! names, so a typosquatted or hijacked package runs with the user's
+//! credentials the first time the client starts.
+
+use crate::discovery::ServerConfig;
+use crate::error::{Error, Result};
+use crate::scanner::report::{ResourceInfo, Severity, Threat, ThreatCategory, ToolInfo};
+use crate::scanner::threats::ThreatDetector;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const BUNDLED_DENYLIST: &str = include_str!("package_denylist.yaml");
+
+/// Package registry a launcher installs from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ecosystem {
+    Npm,
+    Pypi,
+}
+
+impl Ecosystem {
+    fn as_str(self) -> &'static str {
+        match self {
+            Ecosystem::Npm => "npm",
+            Ecosystem::Pypi => "PyPI",
+        }
+    }
+}
+
+/// Known malicious packages and packages that should always be pinned,
+/// in the format of the bundled `package_denylist.yaml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PackageDenylist {
+    /// Malicious npm package names and why they're listed
+    #[serde(default)]
+    npm: BTreeMap<String, String>,
+    /// Malicious PyPI package names and why they're listed
+    #[serde(default)]
+    pypi: BTreeMap<String, String>,
+    #[serde(default)]
+    pin: PinList,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct PinList {
+    #[serde(default)]
+    npm: Vec<String>,
+    #[serde(default)]
+    pypi: Vec<String>,
+}
+
+impl PackageDenylist {
+    /// The list shipped with mcp-guard.
+    pub fn bundled() -> Self {
+        serde_yaml::from_str(BUNDLED_DENYLIST).expect("bundled package denylist is valid YAML")
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| Error::ConfigRead {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        serde_yaml::from_str(&content).map_err(|e| Error::config_parse(path, e.to_string()))
+    }
+
+    /// Add `other`'s entries to this list.
+    pub fn extend(&mut self, other: PackageDenylist) {
+        self.npm.extend(other.npm);
+        self.pypi.extend(other.pypi);
+        self.pin.npm.extend(other.pin.npm);
+        self.pin.pypi.extend(other.pin.pypi);
+    }
+
+    fn malicious(&self, ecosystem: Ecosystem, name: &str) -> Option<&str> {
+        let list = match ecosystem {
+            Ecosystem::Npm => &self.npm,
+            Ecosystem::Pypi => &self.pypi,
+        };
+        list.iter()
+            .find(|(listed, _)| normalize_name(ecosystem, listed) == name)
+            .map(|(_, reason)| reason.as_str())
+    }
+
+    fn should_pin(&self, ecosystem: Ecosystem, name: &str) -> bool {
+        let list = match ecosystem {
+            Ecosystem::Npm => &self.pin.npm,
+            Ecosystem::Pypi => &self.pin.pypi,
+        };
+        list.iter()
+            .any(|listed| normalize_name(ecosystem, listed) == name)
+    }
+}
+
+/// A package named on a launcher's command line.
+#[derive(Debug, PartialEq, Eq)]
+struct PackageSpec {
+    ecosystem: Ecosystem,
+    name: String,
+    version: Option<String>,
+}
+
+impl PackageSpec {
+    fn parse(ecosystem: Ecosystem, spec: &str) -> Option<Self> {
+        // Local paths and URLs aren't registry packages
+        if spec.is_empty() || spec.contains("://") || spec.starts_with(['.', '/', '~']) {
+            return None;
+        }
+
+        let (name, version) = match ecosystem {
+            Ecosystem::Npm => {
+                // The version separator is the first `@` after a scope's leading `@`
+                match spec.char_indices().skip(1).find(|&(_, c)| c == '@') {
+                    Some((i, _)) => (&spec[..i], Some(&spec[i + 1..])),
+                    None => (spec, None),
+                }
+            }
+            Ecosystem::Pypi => {
+                let end = spec
+                    .find(['=', '<', '>', '!', '~', '[', ';', '@', ' '])
+                    .unwrap_or(spec.len());
+                let rest = &spec[end..];
+                let rest = rest.find(['=', '@']).map_or("", |i| &rest[i..]);
+                let version = rest.strip_prefix("==").or_else(|| rest.strip_prefix('@'));
+                (&spec[..end], version)
+            }
+        };
+
+        Some(Self {
+            ecosystem,
+            name: normalize_name(ecosystem, name),
+            version: version.map(str::to_string),
+        })
+    }
+
+    fn is_pinned(&self) -> bool {
+        self.version
+            .as_deref()
+            .is_some_and(|v| !v.is_empty() && v != "latest")
+    }
+}
+
+/// Registry names are case-insensitive, and PyPI also treats `-`, `_`,
+/// and `.` as the same character.
+fn normalize_name(ecosystem: Ecosystem, name: &str) -> String {
+    let lower = name.to_lowercase();
+    match ecosystem {
+        Ecosystem::Npm => lower,
+        Ecosystem::Pypi => lower.replace(['_', '.'], "-"),
+    }
+}
+
+/// The package `server` is launched from, if its command is a package
+/// runner.
+fn launched_package(server: &ServerConfig) -> Option<PackageSpec> {
+    let program = Path::new(&server.command).file_stem()?.to_str()?;
+
+    let (ecosystem, package_flags, value_flags): (_, &[&str], &[&str]) = match program {
+        "npx" => (
+            Ecosystem::Npm,
+            &["-p", "--package"],
+            &["--registry", "--cache"],
+        ),
+        "uvx" => (
+            Ecosystem::Pypi,
+            &["--from"],
+            &[
+                "--with",
+                "--python",
+                "-p",
+                "--index-url",
+                "--extra-index-url",
+            ],
+        ),
+        "pipx" => (
+            Ecosystem::Pypi,
+            &["--spec"],
+            &["--python", "--pip-args", "--index-url"],
+        ),
+        _ => return None,
+    };
+
+    let mut args = server.args.iter().map(String::as_str);
+    while let Some(arg) = args.next() {
+        if package_flags.contains(&arg) {
+            return PackageSpec::parse(ecosystem, args.next()?);
+        }
+        if let Some(value) = package_flags
+            .iter()
+            .find_map(|flag| arg.strip_prefix(flag)?.strip_prefix('='))
+        {
+            return PackageSpec::parse(ecosystem, value);
+        }
+        if value_flags.contains(&arg) {
+            args.next();
+            continue;
+        }
+        if arg.starts_with('-') || (program == "pipx" && arg == "run") {
+            continue;
+        }
+        return PackageSpec::parse(ecosystem, arg);
+    }
+
+    None
+}
+
+pub struct SupplyChainDetector {
+    denylist: PackageDenylist,
+}
+
+impl SupplyChainDetector {
+    pub fn new() -> Self {
+        Self::with_denylist(PackageDenylist::bundled())
+    }
+
+    pub fn with_denylist(denylist: PackageDenylist) -> Self {
+        Self { denylist }
+    }
+
+    /// The threat for `server` if it launches a package on the deny-list.
+    /// Needs only the config, so it can be checked before the server, and
+    /// with it the package, is started.
+    pub fn malicious_package(&self, server: &ServerConfig) -> Option<Threat> {
+        let package = launched_package(server)?;
+        let reason = self.denylist.malicious(package.ecosystem, &package.name)?;
+
+        Some(
+            Threat::new(
+                format!("SUPPLY-CHAIN-PKG-{}", package.name),
+                Severity::Critical,
+                ThreatCategory::SupplyChain,
+                "Known malicious package",
+            )
+            .with_message(format!(
+                "Server '{}' runs {} package '{}': {}",
+                server.name,
+                package.ecosystem.as_str(),
+                package.name,
+                reason
+            ))
+            .with_evidence(format!("{} {}", server.command, server.args.join(" ")))
+            .with_remediation(
+                "Remove this server, rotate any credentials it had access to, and check the machine for persistence.",
+            ),
+        )
+    }
+}
+
+impl Default for SupplyChainDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThreatDetector for SupplyChainDetector {
+    fn category(&self) -> ThreatCategory {
+        ThreatCategory::SupplyChain
+    }
+
+    fn name(&self) -> &'static str {
+        "SupplyChain"
+    }
+
+    fn description(&self) -> &'static str {
+        "Servers launched from known malicious or risky packages"
+    }
+
+    fn detect(
+        &self,
+        server: &ServerConfig,
+        _tools: &[ToolInfo],
+        _resources: &[ResourceInfo],
+    ) -> Vec<Threat> {
+        if let Some(threat) = self.malicious_package(server) {
+            return vec![threat];
+        }
+
+        let Some(package) = launched_package(server) else {
+            return Vec::new();
+        };
+        let evidence = format!("{} {}", server.command, server.args.join(" "));
+
+        if self.denylist.should_pin(package.ecosystem, &package.name) && !package.is_pinned() {
+            return vec![Threat::new(
+                format!("SUPPLY-CHAIN-UNPIN-{}", package.name),
+                Severity::Medium,
+                ThreatCategory::SupplyChain,
+                "Previously hijacked package is not pinned",
+            )
+            .with_message(format!(
+                "Server '{}' runs the latest release of '{}', a package that has shipped hijacked releases before",
+                server.name, package.name
+            ))
+            .with_evidence(evidence)
+            .with_remediation(
+                "Pin the package to a reviewed version, e.g. name@1.2.3 for npx or name==1.2.3 for uvx and pipx.",
+            )];
+        }
+
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(command: &str, args: &[&str]) -> ServerConfig {
+        ServerConfig::new("test", command).with_args(args.iter().map(|a| a.to_string()).collect())
+    }
+
+    fn detect(command: &str, args: &[&str]) -> Vec<Threat> {
+        SupplyChainDetector::new().detect(&server(command, args), &[], &[])
+    }
+
+    #[test]
+    fn bundled_denylist_parses() {
+        let denylist = PackageDenylist::bundled();
+        assert!(denylist.malicious(Ecosystem::Npm, "postmark-mcp").is_some());
+        assert!(denylist.should_pin(Ecosystem::Npm, "event-stream"));
+    }
+
+    #[test]
+    fn parses_package_specs() {
+        assert_eq!(
+            PackageSpec::parse(Ecosystem::Npm, "@scope/pkg@1.2.3"),
+            Some(PackageSpec {
+                ecosystem: Ecosystem::Npm,
+                name: "@scope/pkg".to_string(),
+                version: Some("1.2.3".to_string()),
+            })
+        );
+        assert_eq!(
+            PackageSpec::parse(Ecosystem::Pypi, "Python_DateUtil==2.8.0")
+                .unwrap()
+                .name,
+            "python-dateutil"
+        );
+        assert!(PackageSpec::parse(Ecosystem::Npm, "./local-server").is_none());
+    }
+
+    #[test]
+    fn flags_malicious_npx_package() {
+        let threats = detect("npx", &["-y", "postmark-mcp@1.0.16"]);
+
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].id, "SUPPLY-CHAIN-PKG-postmark-mcp");
+        assert_eq!(threats[0].severity, Severity::Critical);
+    }
+
+    #[test]
+    fn flags_malicious_uvx_and_pipx_packages() {
+        assert_eq!(detect("uvx", &["--from", "colourama", "run"]).len(), 1);
+        assert_eq!(detect("pipx", &["run", "python3-dateutil"]).len(), 1);
+        assert_eq!(detect("/usr/local/bin/uvx", &["jeilyfish"]).len(), 1);
+    }
+
+    #[test]
+    fn flags_unpinned_previously_hijacked_package() {
+        let threats = detect("npx", &["-y", "ua-parser-js@latest"]);
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].id, "SUPPLY-CHAIN-UNPIN-ua-parser-js");
+        assert_eq!(threats[0].severity, Severity::Medium);
+
+        assert!(detect("npx", &["-y", "ua-parser-js@1.0.2"]).is_empty());
+    }
+
+    #[test]
+    fn ignores_other_packages_and_commands() {
+        assert!(detect(
+            "npx",
+            &["-y", "@modelcontextprotocol/server-filesystem", "/"]
+        )
+        .is_empty());
+        assert!(detect("node", &["postmark-mcp"]).is_empty());
+    }
+
+    #[test]
+    fn custom_denylist_extends_bundled() {
+        let mut denylist = PackageDenylist::bundled();
+        denylist.extend(serde_yaml::from_str("npm:\n  evil-mcp: internal report\n").unwrap());
+        let detector = SupplyChainDetector::with_denylist(denylist);
+
+        let threats = detector.detect(&server("npx", &["evil-mcp"]), &[], &[]);
+        assert_eq!(threats.len(), 1);
+        assert!(threats[0].message.contains("internal report"));
+    }
+}
@@ -0,0 +1,193 @@
+//! Detects servers advertising an unusual number of tools: none at all
+//! despite the tools capability, or enough to stand out.
+
+use crate::discovery::ServerConfig;
+use crate::scanner::report::{ResourceInfo, Severity, Threat, ThreatCategory, ToolInfo};
+use crate::scanner::threats::ThreatDetector;
+
+/// Tool count above which a server is reported as having many tools.
+pub const DEFAULT_TOOL_COUNT_WARN_THRESHOLD: usize = 50;
+
+/// Tool count above which a server's tool list is reported as exploding.
+pub const DEFAULT_TOOL_COUNT_CRITICAL_THRESHOLD: usize = 200;
+
+pub struct ToolCountAnomalyDetector {
+    warn_threshold: usize,
+    critical_threshold: usize,
+}
+
+impl ToolCountAnomalyDetector {
+    /// Report servers with more than `warn_threshold` tools, and separately
+    /// those with more than `critical_threshold`.
+    pub fn new(warn_threshold: usize, critical_threshold: usize) -> Self {
+        Self {
+            warn_threshold,
+            critical_threshold,
+        }
+    }
+
+    /// Report a server that advertised the tools capability but listed no
+    /// tools. Only called for servers that advertised it.
+    pub fn check_advertised_tools(&self, server: &ServerConfig, tools: &[ToolInfo]) -> Vec<Threat> {
+        if !tools.is_empty() {
+            return Vec::new();
+        }
+
+        vec![Threat::new(
+            "TOOL-COUNT-ZERO",
+            Severity::Medium,
+            ThreatCategory::PermissionScope,
+            "Server advertises tools but lists none",
+        )
+        .with_message(format!(
+            "Server '{}' declares the tools capability but returned an empty tool list; it may be broken, or withholding tools until later",
+            server.name
+        ))
+        .with_evidence("capabilities.tools is set, tools/list returned 0 tools")
+        .with_remediation(
+            "Check that the server starts correctly, and watch for tools appearing after the scan with description drift detection.",
+        )]
+    }
+}
+
+impl Default for ToolCountAnomalyDetector {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_TOOL_COUNT_WARN_THRESHOLD,
+            DEFAULT_TOOL_COUNT_CRITICAL_THRESHOLD,
+        )
+    }
+}
+
+impl ThreatDetector for ToolCountAnomalyDetector {
+    fn name(&self) -> &'static str {
+        "ToolCountAnomaly"
+    }
+
+    fn description(&self) -> &'static str {
+        "Servers with no tools despite the tools capability, or unusually many"
+    }
+
+    fn category(&self) -> ThreatCategory {
+        ThreatCategory::PermissionScope
+    }
+
+    fn detect(
+        &self,
+        server: &ServerConfig,
+        tools: &[ToolInfo],
+        _resources: &[ResourceInfo],
+    ) -> Vec<Threat> {
+        let mut threats = Vec::new();
+        let count = tools.len();
+
+        if count > self.warn_threshold {
+            threats.push(
+                Threat::new(
+                    "TOOL-COUNT-HIGH",
+                    Severity::Low,
+                    ThreatCategory::PermissionScope,
+                    "Server exposes many tools",
+                )
+                .with_message(format!(
+                    "Server '{}' exposes {} tools. Every tool adds to the attack surface, and all their descriptions take up the model's context",
+                    server.name, count
+                ))
+                .with_evidence(format!("{} tools (threshold: {})", count, self.warn_threshold))
+                .with_remediation(
+                    "Disable the tools you don't use, or split the server so clients load only what they need.",
+                ),
+            );
+        }
+
+        if count > self.critical_threshold {
+            threats.push(
+                Threat::new(
+                    "TOOL-COUNT-EXPLOSION",
+                    Severity::Info,
+                    ThreatCategory::PermissionScope,
+                    "Server exposes an extreme number of tools",
+                )
+                .with_message(format!(
+                    "Server '{}' exposes {} tools, far more than servers normally do; it may be generating tool names dynamically to crowd out or shadow other tools",
+                    server.name, count
+                ))
+                .with_evidence(format!(
+                    "{} tools (threshold: {})",
+                    count, self.critical_threshold
+                ))
+                .with_remediation(
+                    "Check whether the tool list changes between runs and where the tool names come from.",
+                ),
+            );
+        }
+
+        threats
+    }
+
+    fn detect_advertised_tools(&self, server: &ServerConfig, tools: &[ToolInfo]) -> Vec<Threat> {
+        self.check_advertised_tools(server, tools)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tools(count: usize) -> Vec<ToolInfo> {
+        (0..count)
+            .map(|i| ToolInfo {
+                name: format!("tool_{}", i),
+                description: None,
+                input_schema: serde_json::json!({ "type": "object" }),
+            })
+            .collect()
+    }
+
+    fn ids(threats: &[Threat]) -> Vec<&str> {
+        threats.iter().map(|t| t.id.as_str()).collect()
+    }
+
+    #[test]
+    fn flags_high_and_exploding_tool_counts() {
+        let server = ServerConfig::new("big", "cmd");
+        let detector = ToolCountAnomalyDetector::default();
+
+        assert!(detector.detect(&server, &tools(50), &[]).is_empty());
+        assert_eq!(
+            ids(&detector.detect(&server, &tools(51), &[])),
+            ["TOOL-COUNT-HIGH"]
+        );
+        let threats = detector.detect(&server, &tools(201), &[]);
+        assert_eq!(ids(&threats), ["TOOL-COUNT-HIGH", "TOOL-COUNT-EXPLOSION"]);
+        assert_eq!(threats[1].severity, Severity::Info);
+    }
+
+    #[test]
+    fn thresholds_are_configurable() {
+        let server = ServerConfig::new("small", "cmd");
+        let detector = ToolCountAnomalyDetector::new(2, 4);
+
+        assert!(detector.detect(&server, &tools(2), &[]).is_empty());
+        assert_eq!(
+            ids(&detector.detect(&server, &tools(5), &[])),
+            ["TOOL-COUNT-HIGH", "TOOL-COUNT-EXPLOSION"]
+        );
+    }
+
+    #[test]
+    fn flags_empty_tool_list() {
+        let server = ServerConfig::new("empty", "cmd");
+        let detector = ToolCountAnomalyDetector::default();
+
+        let threats = detector.check_advertised_tools(&server, &[]);
+        assert_eq!(ids(&threats), ["TOOL-COUNT-ZERO"]);
+        assert_eq!(threats[0].severity, Severity::Medium);
+        assert!(detector
+            .check_advertised_tools(&server, &tools(1))
+            .is_empty());
+        // Without the capability an empty list is normal, so detect
+        // alone reports nothing
+        assert!(detector.detect(&server, &[], &[]).is_empty());
+    }
+}
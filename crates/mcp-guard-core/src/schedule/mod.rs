@@ -0,0 +1,327 @@
+//! Scans run on cron schedules while `serve` is running.
+//!
+//! Schedules are kept in the database, so they survive restarts. Each run
+//! scans one server, stores the result in the scan history, and sends its
+//! threats to the configured webhooks. A run that's due while the previous
+//! run of the same schedule is still going is skipped.
+
+pub mod store;
+
+pub use store::{ScanSchedule, ScheduleStore};
+
+use crate::db::{DbPool, ScanResultStore};
+use crate::discovery::{discover_all, ServerConfig};
+use crate::error::{Error, Result};
+use crate::notifications::WebhookNotifier;
+use crate::scanner::{Allowlist, Scanner, Severity, SnapshotBackend};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_cron_scheduler::{Job, JobScheduler, JobSchedulerError};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Timeout for each scheduled scan unless overridden with
+/// [`ScanScheduler::with_timeout`].
+pub const DEFAULT_SCHEDULED_SCAN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What a scheduled job needs to run a scan. Each job gets its own copy,
+/// taken when it's scheduled.
+#[derive(Clone)]
+struct ScanContext {
+    pool: DbPool,
+    store: ScheduleStore,
+    notifier: Option<Arc<WebhookNotifier>>,
+    /// Servers to pick from; `None` discovers them again on each run, so
+    /// config changes are picked up.
+    servers: Option<Vec<ServerConfig>>,
+    timeout: Duration,
+    /// Threats to store as suppressed and leave out of notifications
+    allowlist: Option<Allowlist>,
+    /// Threats below this severity are stored but not notified
+    min_severity: Severity,
+}
+
+impl ScanContext {
+    async fn scan(&self, server_name: &str) -> Result<()> {
+        let servers = match &self.servers {
+            Some(servers) => servers.clone(),
+            None => discover_all()?,
+        };
+        let server = servers
+            .into_iter()
+            .find(|s| s.name == server_name)
+            .ok_or_else(|| Error::Other(format!("Server '{}' not found", server_name)))?;
+
+        let scanner = Scanner::new()
+            .with_timeout(self.timeout)
+            .with_snapshot_backend(SnapshotBackend::Database(self.pool.clone()));
+        let mut results = vec![scanner.scan(&server).await?];
+        if let Some(allowlist) = &self.allowlist {
+            allowlist.mark(&mut results);
+        }
+        let result = &results[0];
+        ScanResultStore::new(self.pool.clone()).insert(result)?;
+        info!(server = %server.name, threats = result.threats.len(), "Scheduled scan finished");
+
+        if let Some(notifier) = self.notifier.as_ref().filter(|n| n.is_enabled()) {
+            let threats: Vec<_> = result
+                .threats
+                .iter()
+                .filter(|t| !t.suppressed && t.severity <= self.min_severity)
+                .cloned()
+                .collect();
+            if let Err(e) = notifier.notify_threats(&server.name, &threats).await {
+                warn!(server = %server.name, error = %e, "Failed to send webhook");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs stored [`ScanSchedule`]s and keeps the running jobs in step with
+/// schedules added or deleted through it.
+pub struct ScanScheduler {
+    scheduler: JobScheduler,
+    context: ScanContext,
+    /// Running job for each schedule ID
+    jobs: Mutex<HashMap<String, Uuid>>,
+}
+
+impl ScanScheduler {
+    pub async fn new(pool: DbPool) -> Result<Self> {
+        Ok(Self {
+            scheduler: JobScheduler::new().await.map_err(scheduler_error)?,
+            context: ScanContext {
+                store: ScheduleStore::new(pool.clone()),
+                pool,
+                notifier: None,
+                servers: None,
+                timeout: DEFAULT_SCHEDULED_SCAN_TIMEOUT,
+                allowlist: None,
+                min_severity: Severity::Info,
+            },
+            jobs: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Send the threats found by each scheduled scan to these webhooks.
+    pub fn with_notifier(mut self, notifier: WebhookNotifier) -> Self {
+        self.context.notifier = Some(Arc::new(notifier));
+        self
+    }
+
+    /// Look scheduled servers up in `servers` instead of discovering them
+    /// from client configs on each run.
+    pub fn with_servers(mut self, servers: Vec<ServerConfig>) -> Self {
+        self.context.servers = Some(servers);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.context.timeout = timeout;
+        self
+    }
+
+    /// Store threats allowed by `allowlist` as suppressed, and don't notify
+    /// about them.
+    pub fn with_allowlist(mut self, allowlist: Allowlist) -> Self {
+        self.context.allowlist = Some(allowlist);
+        self
+    }
+
+    /// Only notify about threats at or above `severity`. Results are stored
+    /// with every threat.
+    pub fn with_min_severity(mut self, severity: Severity) -> Self {
+        self.context.min_severity = severity;
+        self
+    }
+
+    /// Schedule every enabled stored schedule and start running them.
+    pub async fn start(&self) -> Result<()> {
+        for schedule in self.context.store.list()? {
+            if schedule.enabled {
+                self.schedule(&schedule).await?;
+            }
+        }
+        self.scheduler.start().await.map_err(scheduler_error)
+    }
+
+    pub fn list(&self) -> Result<Vec<ScanSchedule>> {
+        self.context.store.list()
+    }
+
+    /// Store a new schedule for `server_name` and start running it.
+    /// `cron_expression` has six fields, starting with seconds.
+    pub async fn add(&self, server_name: &str, cron_expression: &str) -> Result<ScanSchedule> {
+        let mut schedule = ScanSchedule {
+            id: Uuid::new_v4().to_string(),
+            server_name: server_name.to_string(),
+            cron_expression: cron_expression.to_string(),
+            enabled: true,
+            last_run: None,
+            next_run: None,
+        };
+        // Scheduled first, so an invalid expression is never stored
+        let job = self.schedule(&schedule).await?;
+        if let Err(e) = self.context.store.insert(&schedule) {
+            self.unschedule(&schedule.id).await?;
+            return Err(e);
+        }
+
+        schedule.next_run = self.next_run(job).await;
+        self.context
+            .store
+            .set_next_run(&schedule.id, schedule.next_run)?;
+        Ok(schedule)
+    }
+
+    /// Stop running the schedule and delete it. Returns whether it existed.
+    pub async fn remove(&self, id: &str) -> Result<bool> {
+        self.unschedule(id).await?;
+        self.context.store.delete(id)
+    }
+
+    async fn schedule(&self, schedule: &ScanSchedule) -> Result<Uuid> {
+        let context = Arc::new(self.context.clone());
+        let id = schedule.id.clone();
+        let server_name = schedule.server_name.clone();
+        let running = Arc::new(AtomicBool::new(false));
+
+        let job = Job::new_async(
+            schedule.cron_expression.as_str(),
+            move |job_id, mut scheduler| {
+                let context = context.clone();
+                let id = id.clone();
+                let server_name = server_name.clone();
+                let running = running.clone();
+                Box::pin(async move {
+                    if running.swap(true, Ordering::AcqRel) {
+                        info!(schedule = %id, "Previous scheduled scan still running, skipping");
+                        return;
+                    }
+                    let started = Utc::now();
+                    {
+                        let _running = RunningGuard(running);
+                        if let Err(e) = context.scan(&server_name).await {
+                            warn!(server = %server_name, error = %e, "Scheduled scan failed");
+                        }
+                    }
+                    let next_run = scheduler.next_tick_for_job(job_id).await.ok().flatten();
+                    if let Err(e) = context.store.record_run(&id, started, next_run) {
+                        warn!(schedule = %id, error = %e, "Failed to record scheduled scan");
+                    }
+                })
+            },
+        )
+        .map_err(|e| Error::InvalidCron {
+            expression: schedule.cron_expression.clone(),
+            message: e.to_string(),
+        })?;
+
+        let job_id = self.scheduler.add(job).await.map_err(scheduler_error)?;
+        self.jobs
+            .lock()
+            .unwrap()
+            .insert(schedule.id.clone(), job_id);
+        Ok(job_id)
+    }
+
+    async fn unschedule(&self, id: &str) -> Result<()> {
+        let job = self.jobs.lock().unwrap().remove(id);
+        if let Some(job) = job {
+            self.scheduler.remove(&job).await.map_err(scheduler_error)?;
+        }
+        Ok(())
+    }
+
+    async fn next_run(&self, job: Uuid) -> Option<chrono::DateTime<Utc>> {
+        self.scheduler
+            .clone()
+            .next_tick_for_job(job)
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// Stop running schedules. They stay stored for the next [`start`].
+    ///
+    /// [`start`]: ScanScheduler::start
+    pub async fn shutdown(&self) -> Result<()> {
+        self.scheduler
+            .clone()
+            .shutdown()
+            .await
+            .map_err(scheduler_error)
+    }
+}
+
+/// Clears a schedule's running flag when its scan ends, including by
+/// panic, so later runs aren't skipped forever.
+struct RunningGuard(Arc<AtomicBool>);
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+fn scheduler_error(e: JobSchedulerError) -> Error {
+    Error::Other(format!("Scan scheduler error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn rejects_invalid_cron_expression() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::create_pool(&dir.path().join("test.db")).unwrap();
+        let scheduler = ScanScheduler::new(pool).await.unwrap();
+
+        let result = scheduler.add("mock", "every minute").await;
+        assert!(matches!(result, Err(Error::InvalidCron { .. })));
+        assert!(scheduler.list().unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn scheduled_scan_stores_result() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::create_pool(&dir.path().join("test.db")).unwrap();
+        let script = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/mock_server.sh");
+        let server = ServerConfig::new("mock", "sh").with_args(vec![script.to_string()]);
+        let allowlist: Allowlist =
+            toml::from_str("[[ignore]]\nid = \"NO-AUTH-LOCAL\"\nserver = \"mock\"\n").unwrap();
+
+        let scheduler = ScanScheduler::new(pool.clone())
+            .await
+            .unwrap()
+            .with_servers(vec![server])
+            .with_allowlist(allowlist);
+        scheduler.start().await.unwrap();
+        let schedule = scheduler.add("mock", "* * * * * *").await.unwrap();
+
+        let store = ScanResultStore::new(pool);
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+        let threats = loop {
+            if let Some((_, threats)) = store.latest_threats("mock").unwrap() {
+                break threats;
+            }
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "no scheduled scan was stored"
+            );
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        };
+        let no_auth = threats.iter().find(|t| t.id == "NO-AUTH-LOCAL");
+        assert!(no_auth.unwrap().suppressed);
+
+        assert!(scheduler.remove(&schedule.id).await.unwrap());
+        assert!(scheduler.list().unwrap().is_empty());
+        scheduler.shutdown().await.unwrap();
+    }
+}
@@ -0,0 +1,139 @@
+//! Scan schedules stored in the `scan_schedules` table, as managed by the
+//! API.
+
+use crate::db::{parse_datetime, DbPool};
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanSchedule {
+    pub id: String,
+    pub server_name: String,
+    /// Six fields, starting with seconds: `0 0 * * * *` runs hourly
+    pub cron_expression: String,
+    pub enabled: bool,
+    pub last_run: Option<DateTime<Utc>>,
+    pub next_run: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone)]
+pub struct ScheduleStore {
+    pool: DbPool,
+}
+
+impl ScheduleStore {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// All schedules, oldest first.
+    pub fn list(&self) -> Result<Vec<ScanSchedule>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, server_name, cron_expression, enabled, last_run, next_run FROM scan_schedules ORDER BY created_at, id",
+        )?;
+
+        let schedules = stmt
+            .query_map([], |row| {
+                Ok(ScanSchedule {
+                    id: row.get(0)?,
+                    server_name: row.get(1)?,
+                    cron_expression: row.get(2)?,
+                    enabled: row.get::<_, i32>(3)? != 0,
+                    last_run: row.get::<_, Option<String>>(4)?.map(parse_datetime),
+                    next_run: row.get::<_, Option<String>>(5)?.map(parse_datetime),
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(schedules)
+    }
+
+    pub fn insert(&self, schedule: &ScanSchedule) -> Result<()> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "INSERT INTO scan_schedules (id, server_name, cron_expression, enabled, last_run, next_run) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                schedule.id,
+                schedule.server_name,
+                schedule.cron_expression,
+                schedule.enabled,
+                schedule.last_run.map(|t| t.to_rfc3339()),
+                schedule.next_run.map(|t| t.to_rfc3339()),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Returns whether a schedule with this ID existed.
+    pub fn delete(&self, id: &str) -> Result<bool> {
+        let conn = self.pool.get()?;
+        let rows = conn.execute("DELETE FROM scan_schedules WHERE id = ?1", [id])?;
+        Ok(rows > 0)
+    }
+
+    /// Record that the schedule ran at `last_run` and is next due at
+    /// `next_run`.
+    pub fn record_run(
+        &self,
+        id: &str,
+        last_run: DateTime<Utc>,
+        next_run: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "UPDATE scan_schedules SET last_run = ?1, next_run = ?2 WHERE id = ?3",
+            rusqlite::params![last_run.to_rfc3339(), next_run.map(|t| t.to_rfc3339()), id],
+        )?;
+        Ok(())
+    }
+
+    /// Set when the schedule is next due, leaving `last_run` as it is.
+    pub fn set_next_run(&self, id: &str, next_run: Option<DateTime<Utc>>) -> Result<()> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "UPDATE scan_schedules SET next_run = ?1 WHERE id = ?2",
+            rusqlite::params![next_run.map(|t| t.to_rfc3339()), id],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_list_record_and_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let store =
+            ScheduleStore::new(crate::db::create_pool(&dir.path().join("test.db")).unwrap());
+
+        store
+            .insert(&ScanSchedule {
+                id: "a".to_string(),
+                server_name: "filesystem".to_string(),
+                cron_expression: "0 0 * * * *".to_string(),
+                enabled: true,
+                last_run: None,
+                next_run: None,
+            })
+            .unwrap();
+
+        let ran_at = Utc::now();
+        store.record_run("a", ran_at, None).unwrap();
+        let schedules = store.list().unwrap();
+        assert_eq!(schedules.len(), 1);
+        assert_eq!(schedules[0].server_name, "filesystem");
+        assert_eq!(
+            schedules[0].last_run.map(|t| t.timestamp()),
+            Some(ran_at.timestamp())
+        );
+
+        assert!(store.delete("a").unwrap());
+        assert!(!store.delete("a").unwrap());
+        assert!(store.list().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,208 @@
+//! Re-scan scheduling for watch mode.
+//!
+//! Editors often save a file as a burst of events (write to a temp file,
+//! rename, touch), so change events are debounced until the burst settles.
+//! An optional interval triggers periodic re-scans on top of that; an interval
+//! that fires while a change is pending counts as a single re-scan.
+
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+
+/// Default quiet period before a burst of change events triggers a re-scan.
+pub const DEFAULT_DEBOUNCE_MS: u64 = 500;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchConfig {
+    /// Re-scan periodically even without config changes.
+    pub interval: Option<Duration>,
+    /// How long change events must stop arriving before a re-scan.
+    pub debounce_ms: u64,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            interval: None,
+            debounce_ms: DEFAULT_DEBOUNCE_MS,
+        }
+    }
+}
+
+impl WatchConfig {
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    pub fn with_debounce_ms(mut self, debounce_ms: u64) -> Self {
+        self.debounce_ms = debounce_ms;
+        self
+    }
+}
+
+/// Why a re-scan was triggered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rescan {
+    ConfigChanged,
+    Interval,
+}
+
+/// Turns a stream of config change events into debounced re-scan triggers.
+pub struct RescanSchedule {
+    events: mpsc::UnboundedReceiver<()>,
+    debounce: Duration,
+    interval: Option<Interval>,
+}
+
+impl RescanSchedule {
+    /// Creates a schedule and the sender that file watchers report changes to.
+    pub fn new(config: &WatchConfig) -> (mpsc::UnboundedSender<()>, Self) {
+        let (tx, events) = mpsc::unbounded_channel();
+        let interval = config.interval.map(|period| {
+            let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            interval
+        });
+
+        let schedule = Self {
+            events,
+            debounce: Duration::from_millis(config.debounce_ms),
+            interval,
+        };
+        (tx, schedule)
+    }
+
+    /// Waits for the next re-scan. Returns `None` once every sender is dropped.
+    pub async fn next(&mut self) -> Option<Rescan> {
+        let trigger = tokio::select! {
+            event = self.events.recv() => {
+                event?;
+                self.settle().await;
+                Rescan::ConfigChanged
+            }
+            _ = tick(&mut self.interval) => Rescan::Interval,
+        };
+
+        // Changes that arrived alongside the tick are covered by this re-scan,
+        // and the next periodic re-scan is a full interval away
+        while self.events.try_recv().is_ok() {}
+        if let Some(interval) = self.interval.as_mut() {
+            interval.reset();
+        }
+
+        Some(trigger)
+    }
+
+    async fn settle(&mut self) {
+        loop {
+            tokio::select! {
+                event = self.events.recv() => {
+                    if event.is_none() {
+                        return;
+                    }
+                }
+                _ = tokio::time::sleep(self.debounce) => return,
+            }
+        }
+    }
+}
+
+async fn tick(interval: &mut Option<Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(debounce_ms: u64) -> WatchConfig {
+        WatchConfig::default().with_debounce_ms(debounce_ms)
+    }
+
+    async fn next_within(schedule: &mut RescanSchedule, ms: u64) -> Option<Rescan> {
+        tokio::time::timeout(Duration::from_millis(ms), schedule.next())
+            .await
+            .ok()
+            .flatten()
+    }
+
+    #[tokio::test]
+    async fn rapid_events_trigger_one_rescan() {
+        let (tx, mut schedule) = RescanSchedule::new(&config(50));
+
+        for _ in 0..5 {
+            tx.send(()).unwrap();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(
+            next_within(&mut schedule, 500).await,
+            Some(Rescan::ConfigChanged)
+        );
+        assert_eq!(next_within(&mut schedule, 150).await, None);
+    }
+
+    #[tokio::test]
+    async fn waits_for_burst_to_settle() {
+        let (tx, mut schedule) = RescanSchedule::new(&config(100));
+
+        let start = Instant::now();
+        tokio::spawn(async move {
+            for _ in 0..3 {
+                tx.send(()).unwrap();
+                tokio::time::sleep(Duration::from_millis(40)).await;
+            }
+            // Keep the sender alive past the debounce window
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        });
+
+        assert_eq!(schedule.next().await, Some(Rescan::ConfigChanged));
+        assert!(start.elapsed() >= Duration::from_millis(180));
+    }
+
+    #[tokio::test]
+    async fn interval_triggers_without_changes() {
+        let (_tx, mut schedule) =
+            RescanSchedule::new(&config(50).with_interval(Duration::from_millis(50)));
+
+        assert_eq!(
+            next_within(&mut schedule, 500).await,
+            Some(Rescan::Interval)
+        );
+        assert_eq!(
+            next_within(&mut schedule, 500).await,
+            Some(Rescan::Interval)
+        );
+    }
+
+    #[tokio::test]
+    async fn interval_and_pending_change_count_once() {
+        let (tx, mut schedule) =
+            RescanSchedule::new(&config(200).with_interval(Duration::from_millis(50)));
+
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        tx.send(()).unwrap();
+
+        assert!(next_within(&mut schedule, 500).await.is_some());
+        // The change was folded into that re-scan; only the interval remains
+        let start = Instant::now();
+        assert_eq!(
+            next_within(&mut schedule, 500).await,
+            Some(Rescan::Interval)
+        );
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[tokio::test]
+    async fn ends_when_senders_dropped() {
+        let (tx, mut schedule) = RescanSchedule::new(&config(50));
+        drop(tx);
+        assert_eq!(schedule.next().await, None);
+    }
+}
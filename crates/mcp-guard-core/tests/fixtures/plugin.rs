@@ -0,0 +1,52 @@
+//! Minimal detector plugin for the scanner's plugin tests, built with
+//! `rustc --crate-type cdylib`. Flags any tool named `secret_token`.
+
+use std::ffi::{c_char, CStr, CString};
+
+#[repr(C)]
+pub struct ThreatDetectorPlugin {
+    abi_version: u32,
+    name_fn: extern "C" fn() -> *const c_char,
+    version_fn: extern "C" fn() -> *const c_char,
+    category_fn: extern "C" fn() -> *const c_char,
+    detect_fn: extern "C" fn(*const c_char) -> *mut c_char,
+    free_fn: extern "C" fn(*mut c_char),
+}
+
+extern "C" fn name() -> *const c_char {
+    c"TestPlugin".as_ptr()
+}
+
+extern "C" fn version() -> *const c_char {
+    c"0.1.0".as_ptr()
+}
+
+extern "C" fn category() -> *const c_char {
+    c"permission_scope".as_ptr()
+}
+
+extern "C" fn detect(input: *const c_char) -> *mut c_char {
+    let input = unsafe { CStr::from_ptr(input) }.to_string_lossy();
+    let threats = if input.contains(r#""name":"secret_token""#) {
+        r#"[{"id":"PLUGIN-TEST-001","severity":"high","category":"permission_scope","title":"Secret tool","tool_name":"secret_token"}]"#
+    } else {
+        "[]"
+    };
+    CString::new(threats).unwrap().into_raw()
+}
+
+extern "C" fn free(result: *mut c_char) {
+    drop(unsafe { CString::from_raw(result) });
+}
+
+#[no_mangle]
+pub extern "C" fn create_detector() -> ThreatDetectorPlugin {
+    ThreatDetectorPlugin {
+        abi_version: 1,
+        name_fn: name,
+        version_fn: version,
+        category_fn: category,
+        detect_fn: detect,
+        free_fn: free,
+    }
+}
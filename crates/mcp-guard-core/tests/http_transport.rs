@@ -0,0 +1,212 @@
+//! Integration tests for the remote (SSE and streamable HTTP) transports
+//! against local mock MCP servers.
+
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::sse::{Event, Sse};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures::stream::{self, Stream, StreamExt};
+use mcp_guard_core::protocol::mcp::{methods, InitializeParams, ToolsListResult};
+use mcp_guard_core::protocol::transport::{auth_headers, SseTransport, StreamableHttpTransport};
+use mcp_guard_core::protocol::{McpTransport, Notification, Request};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use wiremock::matchers::{header, method};
+use wiremock::{Mock, MockServer, Respond, ResponseTemplate};
+
+/// Canned reply for a JSON-RPC request, or `None` for notifications.
+fn mock_reply(request: &Value) -> Option<Value> {
+    let id = request.get("id")?.clone();
+    let result = match request["method"].as_str()? {
+        "initialize" => json!({
+            "protocolVersion": "2025-11-25",
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "mock-remote", "version": "1.0.0" }
+        }),
+        "tools/list" => json!({
+            "tools": [{
+                "name": "fetch",
+                "description": "Fetch a URL",
+                "inputSchema": { "type": "object" }
+            }]
+        }),
+        _ => {
+            return Some(
+                json!({"jsonrpc": "2.0", "id": id, "error": {"code": -32601, "message": "Method not found"}}),
+            )
+        }
+    };
+    Some(json!({"jsonrpc": "2.0", "id": id, "result": result}))
+}
+
+fn credentials() -> HashMap<String, String> {
+    auth_headers(&HashMap::from([(
+        "API_TOKEN".to_string(),
+        "secret".to_string(),
+    )]))
+}
+
+async fn initialize_and_list_tools(transport: &mut dyn McpTransport) -> ToolsListResult {
+    let init = Request::new(
+        methods::INITIALIZE,
+        Some(serde_json::to_value(InitializeParams::default()).unwrap()),
+    );
+    let init_response = transport.send(init).await.unwrap();
+    assert_eq!(init_response.result["serverInfo"]["name"], "mock-remote");
+
+    transport
+        .send_notification(Notification::new("notifications/initialized", None))
+        .await
+        .unwrap();
+
+    let tools = transport
+        .send(Request::new(methods::TOOLS_LIST, Some(json!({}))))
+        .await
+        .unwrap();
+    serde_json::from_value(tools.result).unwrap()
+}
+
+struct EchoResponder {
+    event_stream: bool,
+}
+
+impl Respond for EchoResponder {
+    fn respond(&self, request: &wiremock::Request) -> ResponseTemplate {
+        let body: Value = serde_json::from_slice(&request.body).unwrap();
+        let template = ResponseTemplate::new(200).insert_header("Mcp-Session-Id", "session-1");
+        match mock_reply(&body) {
+            None => ResponseTemplate::new(202),
+            Some(reply) if self.event_stream => template.set_body_raw(
+                format!("event: message\ndata: {}\n\n", reply),
+                "text/event-stream",
+            ),
+            Some(reply) => template.set_body_json(reply),
+        }
+    }
+}
+
+#[tokio::test]
+async fn streamable_http_json_responses() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(header("Authorization", "Bearer secret"))
+        .respond_with(EchoResponder {
+            event_stream: false,
+        })
+        .mount(&server)
+        .await;
+
+    let mut transport =
+        StreamableHttpTransport::connect(&format!("{}/mcp", server.uri()), &credentials()).unwrap();
+    let tools = initialize_and_list_tools(&mut transport).await;
+    assert_eq!(tools.tools[0].name, "fetch");
+
+    // Requests after initialize carry the session ID the server issued
+    let requests = server.received_requests().await.unwrap();
+    assert!(requests[0].headers.get("Mcp-Session-Id").is_none());
+    assert_eq!(
+        requests.last().unwrap().headers["Mcp-Session-Id"],
+        "session-1"
+    );
+}
+
+#[tokio::test]
+async fn streamable_http_event_stream_responses() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(EchoResponder { event_stream: true })
+        .mount(&server)
+        .await;
+
+    let mut transport =
+        StreamableHttpTransport::connect(&format!("{}/mcp", server.uri()), &HashMap::new())
+            .unwrap();
+    let tools = initialize_and_list_tools(&mut transport).await;
+    assert_eq!(tools.tools.len(), 1);
+}
+
+#[tokio::test]
+async fn streamable_http_surfaces_http_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let mut transport = StreamableHttpTransport::connect(&server.uri(), &HashMap::new()).unwrap();
+    let result = transport
+        .send(Request::new(methods::INITIALIZE, Some(json!({}))))
+        .await;
+    assert!(result.is_err());
+}
+
+#[derive(Clone, Default)]
+struct SseState {
+    sender: Arc<Mutex<Option<mpsc::UnboundedSender<Value>>>>,
+    authorization: Arc<Mutex<Option<String>>>,
+}
+
+async fn sse_stream(
+    State(state): State<SseState>,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    *state.authorization.lock().unwrap() = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+
+    let (tx, rx) = mpsc::unbounded_channel::<Value>();
+    *state.sender.lock().unwrap() = Some(tx);
+
+    let endpoint = stream::once(async {
+        Ok(Event::default()
+            .event("endpoint")
+            .data("/messages?session=abc"))
+    });
+    let messages = stream::unfold(rx, |mut rx| async move {
+        let reply = rx.recv().await?;
+        Some((
+            Ok(Event::default().event("message").data(reply.to_string())),
+            rx,
+        ))
+    });
+    Sse::new(endpoint.chain(messages))
+}
+
+async fn sse_post(State(state): State<SseState>, Json(body): Json<Value>) -> StatusCode {
+    if let Some(reply) = mock_reply(&body) {
+        if let Some(tx) = state.sender.lock().unwrap().as_ref() {
+            tx.send(reply).ok();
+        }
+    }
+    StatusCode::ACCEPTED
+}
+
+#[tokio::test]
+async fn sse_transport_round_trip() {
+    let state = SseState::default();
+    let app = Router::new()
+        .route("/sse", get(sse_stream))
+        .route("/messages", post(sse_post))
+        .with_state(state.clone());
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let mut transport = SseTransport::connect(&format!("http://{}/sse", addr), &credentials())
+        .await
+        .unwrap();
+    let tools = initialize_and_list_tools(&mut transport).await;
+    transport.close().await.unwrap();
+
+    assert_eq!(tools.tools[0].name, "fetch");
+    assert_eq!(
+        state.authorization.lock().unwrap().as_deref(),
+        Some("Bearer secret")
+    );
+}
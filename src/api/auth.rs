@@ -0,0 +1,91 @@
+//! API key authentication for `/api/` routes.
+
+use axum::{
+    extract::{Query, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use mcp_guard_core::error::{Error, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use subtle::ConstantTimeEq;
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Bcrypt hash of the key clients must present.
+#[derive(Clone)]
+pub struct ApiKey {
+    hash: Arc<String>,
+    /// Last key that verified, so repeat requests skip the bcrypt round
+    verified: Arc<Mutex<Option<String>>>,
+}
+
+impl ApiKey {
+    pub fn from_hash(hash: impl Into<String>) -> Self {
+        Self {
+            hash: Arc::new(hash.into()),
+            verified: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    async fn verify(&self, key: &str) -> bool {
+        // Constant time, so response timing doesn't reveal the cached key
+        let cached = self
+            .verified
+            .lock()
+            .unwrap()
+            .as_deref()
+            .is_some_and(|verified| bool::from(verified.as_bytes().ct_eq(key.as_bytes())));
+        if cached {
+            return true;
+        }
+
+        let hash = self.hash.clone();
+        let candidate = key.to_string();
+        let valid =
+            tokio::task::spawn_blocking(move || bcrypt::verify(candidate, &hash).unwrap_or(false))
+                .await
+                .unwrap_or(false);
+
+        if valid {
+            *self.verified.lock().unwrap() = Some(key.to_string());
+        }
+        valid
+    }
+}
+
+/// Generate a new random API key.
+pub fn generate_api_key() -> String {
+    format!(
+        "mcps_{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+/// Salted bcrypt hash of `key`, for storing in the config file.
+pub fn hash_api_key(key: &str) -> Result<String> {
+    bcrypt::hash(key, bcrypt::DEFAULT_COST)
+        .map_err(|e| Error::Other(format!("Failed to hash API key: {}", e)))
+}
+
+/// Reject requests without a valid key in the `X-API-Key` header or the
+/// `api_key` query parameter.
+pub async fn require_api_key(State(api_key): State<ApiKey>, req: Request, next: Next) -> Response {
+    let presented = req
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .or_else(|| {
+            Query::<HashMap<String, String>>::try_from_uri(req.uri())
+                .ok()
+                .and_then(|Query(mut params)| params.remove("api_key"))
+        });
+
+    match presented {
+        Some(key) if api_key.verify(&key).await => next.run(req).await,
+        _ => (StatusCode::UNAUTHORIZED, "Missing or invalid API key").into_response(),
+    }
+}
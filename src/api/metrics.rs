@@ -0,0 +1,159 @@
+//! Prometheus metrics for `GET /metrics`.
+//!
+//! Scan metrics are recorded by the scan endpoints as they run. Proxies run
+//! as separate processes, so tool call counts are read from the audit log
+//! they write to: each scrape adds the entries logged since the last one.
+
+use mcp_guard_core::db::{AuditLog, DbPool};
+use mcp_guard_core::error::{Error, Result};
+use mcp_guard_core::scanner::ScanResult;
+use prometheus::core::Collector;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder,
+};
+use std::sync::Mutex;
+
+pub struct Metrics {
+    registry: Registry,
+    scans_total: IntCounterVec,
+    threats_total: IntCounterVec,
+    tool_calls_total: IntCounterVec,
+    scan_duration_seconds: HistogramVec,
+    /// ID of the last audit log entry counted in `tool_calls_total`
+    last_audit_id: Mutex<i64>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let scans_total = IntCounterVec::new(
+            Opts::new("mcp_guard_scans_total", "Server scans run by the API"),
+            &["server", "status"],
+        )
+        .expect("valid metric");
+        let threats_total = IntCounterVec::new(
+            Opts::new("mcp_guard_threats_total", "Threats found by API scans"),
+            &["server", "severity", "category"],
+        )
+        .expect("valid metric");
+        let tool_calls_total = IntCounterVec::new(
+            Opts::new(
+                "mcp_guard_tool_calls_total",
+                "Tool calls recorded in the proxy audit log",
+            ),
+            &["server", "tool", "blocked"],
+        )
+        .expect("valid metric");
+        let scan_duration_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "mcp_guard_scan_duration_seconds",
+                "Duration of successful server scans",
+            )
+            .buckets(vec![0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0]),
+            &["server"],
+        )
+        .expect("valid metric");
+
+        for collector in [
+            Box::new(scans_total.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(threats_total.clone()),
+            Box::new(tool_calls_total.clone()),
+            Box::new(scan_duration_seconds.clone()),
+        ] {
+            registry.register(collector).expect("unique metric names");
+        }
+
+        Self {
+            registry,
+            scans_total,
+            threats_total,
+            tool_calls_total,
+            scan_duration_seconds,
+            last_audit_id: Mutex::new(0),
+        }
+    }
+
+    pub fn record_scan(&self, result: &ScanResult) {
+        let server = result.server.name.as_str();
+        self.scans_total
+            .with_label_values(&[server, "success"])
+            .inc();
+        self.scan_duration_seconds
+            .with_label_values(&[server])
+            .observe(result.scan_duration.as_secs_f64());
+
+        for threat in &result.threats {
+            self.threats_total
+                .with_label_values(&[
+                    server,
+                    &threat.severity.to_string(),
+                    &threat.category.to_string(),
+                ])
+                .inc();
+        }
+    }
+
+    pub fn record_scan_error(&self, server: &str) {
+        self.scans_total.with_label_values(&[server, "error"]).inc();
+    }
+
+    /// Encode all metrics in the Prometheus text format, after catching the
+    /// tool call counters up with the audit log.
+    pub fn render(&self, db: &DbPool) -> Result<String> {
+        self.count_tool_calls(db)?;
+
+        let families = self.registry.gather();
+        let mut buf = Vec::new();
+        TextEncoder::new()
+            .encode(&families, &mut buf)
+            .map_err(|e| Error::Other(format!("Failed to encode metrics: {}", e)))?;
+        let mut text = String::from_utf8(buf).map_err(|e| Error::Other(e.to_string()))?;
+
+        // A metric has no samples until something is recorded with it, and
+        // the encoder skips it until then; list it anyway, so it's there
+        // at zero from the first scrape
+        let collectors: [(&dyn Collector, &str); 4] = [
+            (&self.scans_total, "counter"),
+            (&self.threats_total, "counter"),
+            (&self.tool_calls_total, "counter"),
+            (&self.scan_duration_seconds, "histogram"),
+        ];
+        for (collector, kind) in collectors {
+            for desc in collector.desc() {
+                if !families.iter().any(|f| f.get_name() == desc.fq_name) {
+                    text.push_str(&format!(
+                        "# HELP {} {}\n# TYPE {} {}\n",
+                        desc.fq_name, desc.help, desc.fq_name, kind
+                    ));
+                }
+            }
+        }
+
+        Ok(text)
+    }
+
+    /// Add the calls logged since the last scrape to `tool_calls_total`.
+    /// Held across the query, so concurrent scrapes don't count a call twice.
+    fn count_tool_calls(&self, db: &DbPool) -> Result<()> {
+        let mut last_audit_id = self.last_audit_id.lock().unwrap();
+        let (counts, last_id) = AuditLog::new(db.clone()).call_counts_since(*last_audit_id)?;
+        for count in counts {
+            self.tool_calls_total
+                .with_label_values(&[
+                    &count.server_name,
+                    &count.tool_name,
+                    if count.blocked { "true" } else { "false" },
+                ])
+                .inc_by(count.count);
+        }
+        *last_audit_id = last_id;
+        Ok(())
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
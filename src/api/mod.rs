@@ -1,21 +1,31 @@
 //! HTTP API for mcp-guard.
 
+pub mod auth;
+pub mod metrics;
+pub mod openapi;
 pub mod routes;
 pub mod state;
+pub mod tls;
 
-use crate::db::DbPool;
 use crate::ui;
+use auth::ApiKey;
 use axum::{
+    middleware,
     routing::{delete, get, post, put},
     Router,
 };
+use mcp_guard_core::db::DbPool;
+use mcp_guard_core::schedule::ScanScheduler;
 use state::AppState;
+use std::sync::Arc;
+use tls::TlsOptions;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
+use utoipa_swagger_ui::SwaggerUi;
 
-pub fn create_router(db: DbPool) -> Router {
-    let state = AppState::new(db);
-
+/// Build the app. With an `api_key`, every `/api/` route except
+/// `/api/health` and the API docs requires it, as does `/metrics` when `with_metrics` is set.
+pub fn create_router(state: AppState, api_key: Option<ApiKey>, with_metrics: bool) -> Router {
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
@@ -25,39 +35,374 @@ pub fn create_router(db: DbPool) -> Router {
     let api_routes = Router::new()
         // Audit
         .route("/api/audit", get(routes::audit::list_audit))
+        .route("/api/audit/tools", get(routes::audit::list_audit_tools))
+        .route("/api/audit/stats", get(routes::audit::audit_stats))
+        .route("/api/audit/export", get(routes::audit::export_audit))
         // Rules
         .route("/api/rules", get(routes::rules::list_rules))
         .route("/api/rules", post(routes::rules::create_rule))
+        .route("/api/rules/reload", post(routes::rules::reload_rules))
         .route("/api/rules/:id", put(routes::rules::update_rule))
         .route("/api/rules/:id", delete(routes::rules::delete_rule))
-        .with_state(state);
+        // Scan
+        .route("/api/detectors", get(routes::detectors::list_detectors))
+        .route("/api/scan", post(routes::scan::run_scan))
+        .route("/api/scan/history", get(routes::scan::scan_history))
+        .route("/api/scan/schedule", get(routes::schedule::list_schedules))
+        .route(
+            "/api/scan/schedule",
+            post(routes::schedule::create_schedule),
+        )
+        .route(
+            "/api/scan/schedule/:id",
+            delete(routes::schedule::delete_schedule),
+        )
+        .route(
+            "/api/scan/history/:server_name",
+            get(routes::scan::server_scan_history),
+        )
+        .route("/api/ws/scan", get(routes::ws::scan_ws));
+
+    let api_routes = if with_metrics {
+        api_routes.route("/metrics", get(routes::metrics::metrics))
+    } else {
+        api_routes
+    };
+
+    let api_routes = api_routes
+        .with_state(state)
+        // Servers
+        .route("/api/servers", get(routes::servers::list_servers));
+
+    let api_routes = match api_key {
+        Some(key) => {
+            api_routes.route_layer(middleware::from_fn_with_state(key, auth::require_api_key))
+        }
+        None => api_routes,
+    };
 
-    // Stateless routes
-    let stateless_routes = Router::new()
+    // Unauthenticated routes
+    let public_routes = Router::new()
         // UI routes
         .merge(ui::ui_routes())
         // Health
         .route("/api/health", get(routes::health::health))
-        // Servers
-        .route("/api/servers", get(routes::servers::list_servers))
-        // Scan
-        .route("/api/scan", post(routes::scan::run_scan));
+        // API docs
+        .route("/api/openapi.json", get(openapi::openapi_json))
+        .route("/api/openapi.yaml", get(openapi::openapi_yaml))
+        .merge(
+            SwaggerUi::new("/api/docs")
+                .config(utoipa_swagger_ui::Config::from("/api/openapi.json")),
+        );
 
-    stateless_routes
+    public_routes
         .merge(api_routes)
         .layer(cors)
         .layer(TraceLayer::new_for_http())
 }
 
-pub async fn serve(db: DbPool, bind: &str, port: u16) -> crate::error::Result<()> {
-    let app = create_router(db);
+/// Only `/metrics`, without authentication, for serving on its own port.
+pub fn metrics_router(state: AppState) -> Router {
+    Router::new()
+        .route("/metrics", get(routes::metrics::metrics))
+        .with_state(state)
+}
+
+/// Serve the app. With a `metrics_port`, `/metrics` moves from the main
+/// port to that one. The schedule endpoints manage `scheduler`'s schedules.
+/// With `tls`, the app is served over HTTPS, and plain HTTP on its redirect
+/// port is redirected to it.
+pub async fn serve(
+    db: DbPool,
+    scheduler: Option<Arc<ScanScheduler>>,
+    bind: &str,
+    port: u16,
+    api_key: Option<ApiKey>,
+    metrics_port: Option<u16>,
+    tls: Option<TlsOptions>,
+) -> mcp_guard_core::error::Result<()> {
+    let mut state = AppState::new(db);
+    if let Some(scheduler) = scheduler {
+        state = state.with_scheduler(scheduler);
+    }
+    let app = create_router(state.clone(), api_key, metrics_port.is_none());
     let listener = tokio::net::TcpListener::bind(format!("{}:{}", bind, port)).await?;
 
     tracing::info!("API server listening on {}:{}", bind, port);
 
-    axum::serve(listener, app)
-        .await
-        .map_err(|e| crate::error::Error::Other(e.to_string()))?;
+    if let Some(metrics_port) = metrics_port {
+        let metrics_listener =
+            tokio::net::TcpListener::bind(format!("{}:{}", bind, metrics_port)).await?;
+        tracing::info!("Metrics listening on {}:{}", bind, metrics_port);
+
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(metrics_listener, metrics_router(state)).await {
+                tracing::error!(error = %e, "Metrics server failed");
+            }
+        });
+    }
+
+    let Some(tls) = tls else {
+        axum::serve(listener, app)
+            .await
+            .map_err(|e| mcp_guard_core::error::Error::Other(e.to_string()))?;
+        return Ok(());
+    };
+
+    let config = tls.rustls_config(bind).await?;
+
+    if let Some(redirect_port) = tls.redirect_port {
+        let redirect_listener =
+            tokio::net::TcpListener::bind(format!("{}:{}", bind, redirect_port)).await?;
+        tracing::info!("Redirecting HTTP on {}:{} to HTTPS", bind, redirect_port);
+
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(redirect_listener, tls::redirect_router(port)).await {
+                tracing::error!(error = %e, "HTTPS redirect server failed");
+            }
+        });
+    }
+
+    axum_server::from_tcp_rustls(listener.into_std()?, config)
+        .serve(app.into_make_service())
+        .await?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    fn test_state(dir: &tempfile::TempDir) -> AppState {
+        let pool = mcp_guard_core::db::create_pool(&dir.path().join("test.db")).unwrap();
+        AppState::new(pool)
+    }
+
+    async fn get(app: Router, uri: &str) -> (StatusCode, String) {
+        let response = app
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    fn log_call(state: &AppState, tool: &str) {
+        use mcp_guard_core::db::{AuditEntry, AuditLog};
+
+        AuditLog::new(state.db.as_ref().clone())
+            .insert(&AuditEntry {
+                id: 0,
+                timestamp: chrono::Utc::now(),
+                server_name: "fs".to_string(),
+                tool_name: tool.to_string(),
+                tool_args: None,
+                result: None,
+                blocked: false,
+                block_reason: None,
+                duration_ms: 1,
+                redacted: false,
+                original_args: None,
+                oversized: false,
+            })
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn metrics_lists_every_metric_from_the_start() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = test_state(&dir);
+
+        let (status, body) = get(create_router(state.clone(), None, true), "/metrics").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(
+            body.contains("# TYPE mcp_guard_scans_total counter"),
+            "{}",
+            body
+        );
+        assert!(body.contains("# TYPE mcp_guard_threats_total counter"));
+        assert!(body.contains("# TYPE mcp_guard_tool_calls_total counter"));
+        assert!(body.contains("# TYPE mcp_guard_scan_duration_seconds histogram"));
+
+        state.metrics.record_scan_error("fs");
+        let (_, body) = get(create_router(state, None, true), "/metrics").await;
+        assert!(body.contains(r#"mcp_guard_scans_total{server="fs",status="error"} 1"#));
+        assert_eq!(body.matches("# TYPE mcp_guard_scans_total").count(), 1);
+    }
+
+    #[tokio::test]
+    async fn metrics_route_is_only_on_the_main_port_when_enabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = test_state(&dir);
+
+        let (status, _) = get(create_router(state.clone(), None, false), "/metrics").await;
+        assert_ne!(status, StatusCode::OK);
+        let (status, body) = get(metrics_router(state), "/metrics").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("mcp_guard_scans_total"));
+    }
+
+    async fn status_with_key(app: &Router, uri: &str, key: Option<&str>) -> StatusCode {
+        let mut request = Request::get(uri);
+        if let Some(key) = key {
+            request = request.header(auth::API_KEY_HEADER, key);
+        }
+        app.clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn api_key_is_required_outside_public_routes() {
+        let dir = tempfile::tempdir().unwrap();
+        // The lowest cost keeps the test fast; verification works the same
+        let hash = bcrypt::hash("right-key", 4).unwrap();
+        let app = create_router(test_state(&dir), Some(ApiKey::from_hash(hash)), true);
+
+        assert_eq!(
+            status_with_key(&app, "/api/rules", None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status_with_key(&app, "/api/rules", Some("wrong-key")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status_with_key(&app, "/metrics", None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status_with_key(&app, "/api/health", None).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status_with_key(&app, "/api/openapi.json", None).await,
+            StatusCode::OK
+        );
+
+        assert_eq!(
+            status_with_key(&app, "/api/rules", Some("right-key")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status_with_key(&app, "/api/rules?api_key=right-key", None).await,
+            StatusCode::OK
+        );
+
+        // The verified key is cached; other keys still go through bcrypt
+        assert_eq!(
+            status_with_key(&app, "/api/rules", Some("wrong-key")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status_with_key(&app, "/api/rules?api_key=right-kex", None).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn openapi_spec_documents_every_route() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = test_state(&dir);
+
+        let (status, body) = get(
+            create_router(state.clone(), None, true),
+            "/api/openapi.json",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let spec: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+
+        // Every route mounted by create_router, in the spec's path syntax
+        for (method, path) in [
+            ("get", "/api/health"),
+            ("get", "/api/servers"),
+            ("get", "/api/detectors"),
+            ("post", "/api/scan"),
+            ("get", "/api/scan/history"),
+            ("get", "/api/scan/history/{server_name}"),
+            ("get", "/api/scan/schedule"),
+            ("post", "/api/scan/schedule"),
+            ("delete", "/api/scan/schedule/{id}"),
+            ("get", "/api/ws/scan"),
+            ("get", "/api/audit"),
+            ("get", "/api/audit/tools"),
+            ("get", "/api/audit/stats"),
+            ("get", "/api/audit/export"),
+            ("get", "/api/rules"),
+            ("post", "/api/rules"),
+            ("post", "/api/rules/reload"),
+            ("put", "/api/rules/{id}"),
+            ("delete", "/api/rules/{id}"),
+            ("get", "/metrics"),
+        ] {
+            assert!(
+                spec["paths"][path][method].is_object(),
+                "{} {} missing from the spec",
+                method,
+                path
+            );
+        }
+
+        let response = create_router(state, None, true)
+            .oneshot(
+                Request::get("/api/openapi.yaml")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "application/yaml");
+    }
+
+    #[tokio::test]
+    async fn tool_call_counts_keep_growing_after_a_purge() {
+        use mcp_guard_core::db::AuditLog;
+
+        let dir = tempfile::tempdir().unwrap();
+        let state = test_state(&dir);
+        let sample = r#"mcp_guard_tool_calls_total{blocked="false",server="fs",tool="read"}"#;
+        for _ in 0..3 {
+            log_call(&state, "read");
+        }
+        let (_, body) = get(create_router(state.clone(), None, true), "/metrics").await;
+        assert!(body.contains(&format!("{} 3", sample)), "{}", body);
+
+        let log = AuditLog::new(state.db.as_ref().clone());
+        let cutoff = chrono::Utc::now() + chrono::Duration::seconds(1);
+        assert_eq!(log.purge_before(cutoff).unwrap(), 3);
+        log_call(&state, "read");
+        let (_, body) = get(create_router(state.clone(), None, true), "/metrics").await;
+        assert!(body.contains(&format!("{} 4", sample)), "{}", body);
+
+        // Nothing new is logged, so nothing is added
+        let (_, body) = get(create_router(state, None, true), "/metrics").await;
+        assert!(body.contains(&format!("{} 4", sample)), "{}", body);
+    }
+
+    #[tokio::test]
+    async fn audit_export_streams_every_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = test_state(&dir);
+        for i in 0..3 {
+            log_call(&state, &format!("=tool_{}", i));
+        }
+
+        let (status, body) = get(create_router(state, None, false), "/api/audit/export").await;
+        assert_eq!(status, StatusCode::OK);
+        let lines: Vec<&str> = body.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].contains(",fs,'=tool_0,"), "{}", body);
+    }
+}
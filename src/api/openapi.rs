@@ -0,0 +1,86 @@
+//! OpenAPI description of the HTTP API, served at `/api/openapi.json` and
+//! `/api/openapi.yaml` and browsable at `/api/docs`.
+
+use crate::api::routes;
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "mcp-guard API"),
+    paths(
+        routes::health::health,
+        routes::servers::list_servers,
+        routes::detectors::list_detectors,
+        routes::scan::run_scan,
+        routes::scan::scan_history,
+        routes::scan::server_scan_history,
+        routes::schedule::list_schedules,
+        routes::schedule::create_schedule,
+        routes::schedule::delete_schedule,
+        routes::ws::scan_ws,
+        routes::audit::list_audit,
+        routes::audit::list_audit_tools,
+        routes::audit::audit_stats,
+        routes::audit::export_audit,
+        routes::rules::list_rules,
+        routes::rules::create_rule,
+        routes::rules::update_rule,
+        routes::rules::delete_rule,
+        routes::rules::reload_rules,
+        routes::metrics::metrics,
+    ),
+    components(schemas(
+        routes::health::HealthResponse,
+        routes::servers::ServersResponse,
+        routes::servers::ServerInfo,
+        routes::detectors::DetectorInfo,
+        routes::scan::ScanResponse,
+        routes::scan::ScanResultSummary,
+        routes::scan::ThreatInfo,
+        routes::scan::HistoryResponse,
+        routes::scan::ServerHistoryResponse,
+        routes::scan::TrendPoint,
+        routes::schedule::CreateScheduleRequest,
+        routes::schedule::SchedulesResponse,
+        routes::ws::ScanRequest,
+        routes::audit::AuditResponse,
+        routes::audit::AuditEntryInfo,
+        routes::rules::ProxyRule,
+        routes::rules::CreateRuleRequest,
+        routes::rules::RulesResponse,
+        routes::rules::ReloadResponse,
+    )),
+    modifiers(&ApiKeyAuth),
+    security(("api_key" = [])),
+)]
+pub struct ApiDoc;
+
+/// Documents the `X-API-Key` header `serve --api-key` requires.
+struct ApiKeyAuth;
+
+impl Modify for ApiKeyAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-API-Key"))),
+        );
+    }
+}
+
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+pub async fn openapi_yaml() -> Response {
+    match ApiDoc::openapi().to_yaml() {
+        Ok(yaml) => ([(header::CONTENT_TYPE, "application/yaml")], yaml).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
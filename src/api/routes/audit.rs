@@ -1,41 +1,78 @@
 //! Audit log endpoints.
 
 use crate::api::state::AppState;
-use crate::db::AuditLog;
 use axum::{
+    body::Body,
     extract::{Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
+    response::IntoResponse,
     Json,
 };
+use mcp_guard_core::db::{AuditLog, AuditStats, ToolStats};
 use serde::{Deserialize, Serialize};
+use std::io;
+use tokio::sync::mpsc;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct AuditQuery {
     pub server: Option<String>,
     pub tool: Option<String>,
     pub blocked: Option<bool>,
     pub limit: Option<usize>,
+    pub cursor: Option<i64>,
+    /// Deprecated in favour of `cursor`
     pub offset: Option<usize>,
 }
 
-#[derive(Serialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportQuery {
+    pub server: Option<String>,
+    pub blocked: Option<bool>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatsQuery {
+    /// Only count calls at or after this time: RFC 3339, YYYY-MM-DD, or a
+    /// duration ago like `24h`
+    pub since: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
 pub struct AuditResponse {
     pub entries: Vec<AuditEntryInfo>,
     pub total: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<i64>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct AuditEntryInfo {
     pub id: i64,
     pub timestamp: String,
     pub server_name: String,
     pub tool_name: String,
     pub tool_args: Option<serde_json::Value>,
+    /// Arguments as the client sent them, when rules rewrote them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_args: Option<serde_json::Value>,
     pub blocked: bool,
     pub block_reason: Option<String>,
     pub duration_ms: u64,
+    /// Rejected for exceeding the proxy's request size limit
+    pub oversized: bool,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/audit",
+    tag = "audit",
+    params(AuditQuery),
+    responses((status = 200, body = AuditResponse), (status = 500, description = "Database error", body = String))
+)]
 pub async fn list_audit(
     State(state): State<AppState>,
     Query(query): Query<AuditQuery>,
@@ -44,22 +81,31 @@ pub async fn list_audit(
     let audit_log = AuditLog::new(pool.clone());
 
     let limit = query.limit.unwrap_or(50).min(1000);
-    let offset = query.offset.unwrap_or(0);
+    let mut next_cursor = None;
 
     let entries =
         if query.server.is_some() || query.tool.is_some() || query.blocked.unwrap_or(false) {
-            audit_log
-                .search(
+            let (entries, next) = audit_log
+                .search_after(
+                    query.cursor,
                     query.server.as_deref(),
                     query.tool.as_deref(),
                     query.blocked.unwrap_or(false),
                     limit,
                 )
-                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
-        } else {
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            next_cursor = next;
+            entries
+        } else if let (Some(offset), None) = (query.offset, query.cursor) {
             audit_log
                 .list(limit, offset)
                 .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        } else {
+            let (entries, next) = audit_log
+                .list_after(query.cursor, limit)
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            next_cursor = next;
+            entries
         };
 
     let total = audit_log
@@ -74,14 +120,128 @@ pub async fn list_audit(
             server_name: e.server_name,
             tool_name: e.tool_name,
             tool_args: e.tool_args,
+            original_args: e.original_args,
             blocked: e.blocked,
             block_reason: e.block_reason,
             duration_ms: e.duration_ms,
+            oversized: e.oversized,
         })
         .collect();
 
     Ok(Json(AuditResponse {
         entries: entry_infos,
         total,
+        next_cursor,
     }))
 }
+
+#[utoipa::path(
+    get,
+    path = "/api/audit/tools",
+    tag = "audit",
+    responses(
+        (status = 200, description = "Call counts for the 50 most used tools", body = Vec<Object>),
+        (status = 500, description = "Database error", body = String)
+    )
+)]
+pub async fn list_audit_tools(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ToolStats>>, (StatusCode, String)> {
+    let audit_log = AuditLog::new(state.db.as_ref().clone());
+
+    let stats = audit_log
+        .tool_stats(50)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(stats))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/audit/stats",
+    tag = "audit",
+    params(StatsQuery),
+    responses(
+        (status = 200, description = "Totals, the 10 most called tools, blocked calls per tool, and call durations", body = Object),
+        (status = 400, description = "Invalid since", body = String),
+        (status = 500, description = "Database error", body = String)
+    )
+)]
+pub async fn audit_stats(
+    State(state): State<AppState>,
+    Query(query): Query<StatsQuery>,
+) -> Result<Json<AuditStats>, (StatusCode, String)> {
+    let since = query
+        .since
+        .as_deref()
+        .map(crate::cli::parse_datetime)
+        .transpose()
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid since: {}", e)))?;
+
+    let stats = AuditLog::new(state.db.as_ref().clone())
+        .stats(since)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(stats))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/audit/export",
+    tag = "audit",
+    params(ExportQuery),
+    responses((status = 200, body = String, content_type = "text/csv"))
+)]
+pub async fn export_audit(
+    State(state): State<AppState>,
+    Query(query): Query<ExportQuery>,
+) -> impl IntoResponse {
+    let audit_log = AuditLog::new(state.db.as_ref().clone());
+
+    // Rows are sent as they're read, so a large log isn't held in memory.
+    // An error part way through ends the response early.
+    let (tx, rx) = mpsc::channel(16);
+    tokio::task::spawn_blocking(move || {
+        let errors = tx.clone();
+        if let Err(e) = audit_log.export_csv(
+            BodyWriter { tx },
+            query.server.as_deref(),
+            query.blocked.unwrap_or(false),
+        ) {
+            tracing::warn!(error = %e, "Audit export failed");
+            let _ = errors.blocking_send(Err(io::Error::other(e.to_string())));
+        }
+    });
+    let body = Body::from_stream(futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk, rx))
+    }));
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"audit_log.csv\"",
+            ),
+        ],
+        body,
+    )
+}
+
+/// Sends what the export writes to the response body.
+struct BodyWriter {
+    tx: mpsc::Sender<io::Result<Vec<u8>>>,
+}
+
+impl io::Write for BodyWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.tx
+            .blocking_send(Ok(buf.to_vec()))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "Client disconnected"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+//! Detector listing endpoint.
+
+use crate::api::state::AppState;
+use axum::{extract::State, http::StatusCode, Json};
+use mcp_guard_core::db::ScanResultStore;
+use mcp_guard_core::scanner::all_detectors;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct DetectorInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub category: String,
+    /// Threats this detector has reported across the scan history
+    pub threat_count: usize,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/detectors",
+    tag = "scan",
+    responses((status = 200, body = Vec<DetectorInfo>), (status = 500, description = "Database error", body = String))
+)]
+pub async fn list_detectors(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<DetectorInfo>>, (StatusCode, String)> {
+    let counts = ScanResultStore::new(state.db.as_ref().clone())
+        .threat_counts_by_detector()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let detectors = all_detectors()
+        .iter()
+        .map(|d| DetectorInfo {
+            name: d.name(),
+            description: d.description(),
+            category: d.category().to_string(),
+            threat_count: counts.get(d.name()).copied().unwrap_or(0),
+        })
+        .collect();
+
+    Ok(Json(detectors))
+}
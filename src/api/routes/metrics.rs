@@ -0,0 +1,21 @@
+//! Prometheus metrics endpoint.
+
+use crate::api::state::AppState;
+use axum::{extract::State, http::header, http::StatusCode, response::IntoResponse};
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "metrics",
+    responses((status = 200, description = "Prometheus text format", body = String, content_type = "text/plain"))
+)]
+pub async fn metrics(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let body = state
+        .metrics
+        .render(&state.db)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body))
+}
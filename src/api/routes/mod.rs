@@ -1,7 +1,11 @@
 //! API route handlers.
 
 pub mod audit;
+pub mod detectors;
 pub mod health;
+pub mod metrics;
 pub mod rules;
 pub mod scan;
+pub mod schedule;
 pub mod servers;
+pub mod ws;
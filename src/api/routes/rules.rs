@@ -6,39 +6,55 @@ use axum::{
     http::StatusCode,
     Json,
 };
+use chrono::{DateTime, Utc};
+use mcp_guard_core::proxy::rules::{RuleAction, RULE_POLL_INTERVAL};
+use mcp_guard_core::proxy::store::UPDATED_NOW;
+use mcp_guard_core::proxy::RuleStore;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ProxyRule {
     pub id: String,
     pub tool_pattern: String,
+    #[schema(value_type = Object)]
     pub action: RuleAction,
     pub priority: i32,
     pub enabled: bool,
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "snake_case")]
-pub enum RuleAction {
-    Allow,
-    Block { reason: String },
-    RateLimit { max_calls: u32, window_secs: u64 },
-    Log,
-}
-
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreateRuleRequest {
     pub tool_pattern: String,
+    #[schema(value_type = Object)]
     pub action: RuleAction,
     pub priority: Option<i32>,
+    /// The rule stops applying after this time
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct RulesResponse {
     pub rules: Vec<ProxyRule>,
 }
 
+#[derive(Serialize, ToSchema)]
+pub struct ReloadResponse {
+    /// Number of stored rules marked for reload
+    pub rules: usize,
+    /// Running proxies pick up the rules within this many seconds
+    pub poll_interval_secs: u64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/rules",
+    tag = "rules",
+    responses((status = 200, body = RulesResponse), (status = 500, description = "Database error", body = String))
+)]
 pub async fn list_rules(
     State(state): State<AppState>,
 ) -> Result<Json<RulesResponse>, (StatusCode, String)> {
@@ -49,7 +65,7 @@ pub async fn list_rules(
 
     let mut stmt = conn
         .prepare(
-            "SELECT id, tool_pattern, action, priority, enabled FROM proxy_rules ORDER BY priority DESC",
+            "SELECT id, tool_pattern, action, priority, enabled, expires_at FROM proxy_rules ORDER BY priority DESC",
         )
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
@@ -64,6 +80,10 @@ pub async fn list_rules(
                 action,
                 priority: row.get(3)?,
                 enabled: row.get::<_, i32>(4)? != 0,
+                expires_at: row
+                    .get::<_, Option<String>>(5)?
+                    .and_then(|t| DateTime::parse_from_rfc3339(&t).ok())
+                    .map(|t| t.with_timezone(&Utc)),
             })
         })
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -73,6 +93,13 @@ pub async fn list_rules(
     Ok(Json(RulesResponse { rules }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/rules",
+    tag = "rules",
+    request_body = CreateRuleRequest,
+    responses((status = 200, body = ProxyRule), (status = 500, description = "Database error", body = String))
+)]
 pub async fn create_rule(
     State(state): State<AppState>,
     Json(req): Json<CreateRuleRequest>,
@@ -87,8 +114,14 @@ pub async fn create_rule(
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     conn.execute(
-        "INSERT INTO proxy_rules (id, tool_pattern, action, priority) VALUES (?1, ?2, ?3, ?4)",
-        rusqlite::params![id, req.tool_pattern, action_json, req.priority.unwrap_or(0)],
+        "INSERT INTO proxy_rules (id, tool_pattern, action, priority, expires_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![
+            id,
+            req.tool_pattern,
+            action_json,
+            req.priority.unwrap_or(0),
+            req.expires_at.map(|t| t.to_rfc3339())
+        ],
     )
     .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
@@ -98,9 +131,21 @@ pub async fn create_rule(
         action: req.action,
         priority: req.priority.unwrap_or(0),
         enabled: true,
+        expires_at: req.expires_at,
     }))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/rules/{id}",
+    tag = "rules",
+    params(("id" = String, Path, description = "Rule ID")),
+    responses(
+        (status = 204, description = "Rule deleted"),
+        (status = 404, description = "Rule not found", body = String),
+        (status = 500, description = "Database error", body = String)
+    )
+)]
 pub async fn delete_rule(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -121,6 +166,18 @@ pub async fn delete_rule(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/rules/{id}",
+    tag = "rules",
+    params(("id" = String, Path, description = "Rule ID")),
+    request_body = CreateRuleRequest,
+    responses(
+        (status = 200, body = ProxyRule),
+        (status = 404, description = "Rule not found", body = String),
+        (status = 500, description = "Database error", body = String)
+    )
+)]
 pub async fn update_rule(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -136,8 +193,17 @@ pub async fn update_rule(
 
     let rows = conn
         .execute(
-            "UPDATE proxy_rules SET tool_pattern = ?1, action = ?2, priority = ?3, updated_at = datetime('now') WHERE id = ?4",
-            rusqlite::params![req.tool_pattern, action_json, req.priority.unwrap_or(0), id],
+            &format!(
+                "UPDATE proxy_rules SET tool_pattern = ?1, action = ?2, priority = ?3, expires_at = ?4, updated_at = {} WHERE id = ?5",
+                UPDATED_NOW
+            ),
+            rusqlite::params![
+                req.tool_pattern,
+                action_json,
+                req.priority.unwrap_or(0),
+                req.expires_at.map(|t| t.to_rfc3339()),
+                id
+            ],
         )
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
@@ -151,5 +217,27 @@ pub async fn update_rule(
         action: req.action,
         priority: req.priority.unwrap_or(0),
         enabled: true,
+        expires_at: req.expires_at,
+    }))
+}
+
+/// Proxies run in their own processes, so this marks every rule as updated
+/// and each proxy reloads them on its next poll.
+#[utoipa::path(
+    post,
+    path = "/api/rules/reload",
+    tag = "rules",
+    responses((status = 200, body = ReloadResponse), (status = 500, description = "Database error", body = String))
+)]
+pub async fn reload_rules(
+    State(state): State<AppState>,
+) -> Result<Json<ReloadResponse>, (StatusCode, String)> {
+    let rules = RuleStore::new(state.db.as_ref().clone())
+        .touch()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(ReloadResponse {
+        rules,
+        poll_interval_secs: RULE_POLL_INTERVAL.as_secs(),
     }))
 }
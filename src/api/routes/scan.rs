@@ -1,18 +1,31 @@
 //! Scan endpoints.
 
-use crate::discovery::{discover_all, ServerConfig};
-use crate::scanner::{ScanResult, Scanner};
-use axum::{extract::Query, http::StatusCode, Json};
+use crate::api::state::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use mcp_guard_core::db::{ScanResultStore, StoredScanResult};
+use mcp_guard_core::discovery::{discover_all, ServerConfig};
+use mcp_guard_core::scanner::{ScanResult, Severity};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ScanQuery {
+    /// Only scan the server with this name
     pub server: Option<String>,
+    /// Per-server timeout in seconds (default 30)
     pub timeout: Option<u64>,
+    /// Only return threats at or above this severity; scan history keeps them all
+    #[param(value_type = Option<String>, example = "medium")]
+    pub min_severity: Option<Severity>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ScanResponse {
     pub results: Vec<ScanResultSummary>,
     pub total_threats: usize,
@@ -20,17 +33,18 @@ pub struct ScanResponse {
     pub servers_failed: usize,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ScanResultSummary {
     pub server_name: String,
     pub tool_count: usize,
     pub resource_count: usize,
+    pub prompt_count: usize,
     pub threat_count: usize,
     pub threats: Vec<ThreatInfo>,
     pub scan_duration_ms: u64,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ThreatInfo {
     pub id: String,
     pub severity: String,
@@ -38,6 +52,7 @@ pub struct ThreatInfo {
     pub title: String,
     pub message: String,
     pub tool_name: Option<String>,
+    pub detector: Option<String>,
 }
 
 impl From<&ScanResult> for ScanResultSummary {
@@ -46,6 +61,7 @@ impl From<&ScanResult> for ScanResultSummary {
             server_name: r.server.name.clone(),
             tool_count: r.tools.len(),
             resource_count: r.resources.len(),
+            prompt_count: r.prompts.len(),
             threat_count: r.threats.len(),
             threats: r
                 .threats
@@ -57,6 +73,7 @@ impl From<&ScanResult> for ScanResultSummary {
                     title: t.title.clone(),
                     message: t.message.clone(),
                     tool_name: t.tool_name.clone(),
+                    detector: t.detector.clone(),
                 })
                 .collect(),
             scan_duration_ms: r.scan_duration.as_millis() as u64,
@@ -64,7 +81,47 @@ impl From<&ScanResult> for ScanResultSummary {
     }
 }
 
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HistoryQuery {
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct HistoryResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub entries: Vec<StoredScanResult>,
+    pub total: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ServerHistoryResponse {
+    pub server_name: String,
+    #[schema(value_type = Vec<Object>)]
+    pub entries: Vec<StoredScanResult>,
+    pub total: i64,
+    /// Threat counts for the returned scans, oldest first
+    pub trend: Vec<TrendPoint>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TrendPoint {
+    pub scanned_at: String,
+    pub threat_count: i64,
+    pub critical: usize,
+    pub high: usize,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/scan",
+    tag = "scan",
+    params(ScanQuery),
+    responses((status = 200, body = ScanResponse), (status = 500, description = "Discovery failed", body = String))
+)]
 pub async fn run_scan(
+    State(state): State<AppState>,
     Query(query): Query<ScanQuery>,
 ) -> Result<Json<ScanResponse>, (StatusCode, String)> {
     let servers: Vec<ServerConfig> = if let Some(server_name) = query.server {
@@ -89,26 +146,115 @@ pub async fn run_scan(
         }));
     }
 
-    let timeout = Duration::from_secs(query.timeout.unwrap_or(30));
-    let scanner = Scanner::new().with_timeout(timeout);
+    let scanner = state.scanner(query.timeout.map(Duration::from_secs));
+    let store = ScanResultStore::new(state.db.as_ref().clone());
 
-    let mut results = Vec::new();
-    let mut servers_failed = 0;
-
-    for server in &servers {
-        match scanner.scan(server).await {
-            Ok(result) => results.push(result),
-            Err(_) => servers_failed += 1,
+    let mut outcome = scanner.scan_all(&servers).await;
+    for result in &outcome.results {
+        state.metrics.record_scan(result);
+        if let Err(e) = store.insert(result) {
+            tracing::error!(server = %result.server.name, error = %e, "Failed to save scan result");
+        }
+    }
+    for (server, _) in &outcome.errors {
+        state.metrics.record_scan_error(&server.name);
+    }
+    if let Some(min_severity) = query.min_severity {
+        for result in &mut outcome.results {
+            result.retain_min_severity(min_severity);
         }
     }
 
-    let total_threats: usize = results.iter().map(|r| r.threats.len()).sum();
-    let summaries: Vec<ScanResultSummary> = results.iter().map(ScanResultSummary::from).collect();
+    let summaries: Vec<ScanResultSummary> = outcome
+        .results
+        .iter()
+        .map(ScanResultSummary::from)
+        .collect();
 
     Ok(Json(ScanResponse {
         results: summaries,
-        total_threats,
-        servers_scanned: results.len(),
-        servers_failed,
+        total_threats: outcome.total_threats(),
+        servers_scanned: outcome.results.len(),
+        servers_failed: outcome.errors.len(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/scan/history",
+    tag = "scan",
+    params(HistoryQuery),
+    responses((status = 200, body = HistoryResponse), (status = 500, description = "Database error", body = String))
+)]
+pub async fn scan_history(
+    State(state): State<AppState>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<HistoryResponse>, (StatusCode, String)> {
+    let store = ScanResultStore::new(state.db.as_ref().clone());
+
+    let limit = query.limit.unwrap_or(50).min(1000);
+    let offset = query.offset.unwrap_or(0);
+
+    let entries = store
+        .list(None, limit, offset)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let total = store
+        .count(None)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(HistoryResponse { entries, total }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/scan/history/{server_name}",
+    tag = "scan",
+    params(("server_name" = String, Path, description = "Server name"), HistoryQuery),
+    responses(
+        (status = 200, body = ServerHistoryResponse),
+        (status = 404, description = "Server was never scanned", body = String),
+        (status = 500, description = "Database error", body = String)
+    )
+)]
+pub async fn server_scan_history(
+    State(state): State<AppState>,
+    Path(server_name): Path<String>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<ServerHistoryResponse>, (StatusCode, String)> {
+    let store = ScanResultStore::new(state.db.as_ref().clone());
+
+    let limit = query.limit.unwrap_or(50).min(1000);
+    let offset = query.offset.unwrap_or(0);
+
+    let entries = store
+        .list(Some(&server_name), limit, offset)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let total = store
+        .count(Some(&server_name))
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if total == 0 {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!("No scan history for server '{}'", server_name),
+        ));
+    }
+
+    let trend = entries
+        .iter()
+        .rev()
+        .map(|e| TrendPoint {
+            scanned_at: e.scanned_at.to_rfc3339(),
+            threat_count: e.threat_count,
+            critical: e.threats_by_severity.critical,
+            high: e.threats_by_severity.high,
+        })
+        .collect();
+
+    Ok(Json(ServerHistoryResponse {
+        server_name,
+        entries,
+        total,
+        trend,
     }))
 }
@@ -0,0 +1,108 @@
+//! Scan schedule endpoints.
+
+use crate::api::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use mcp_guard_core::error::Error;
+use mcp_guard_core::schedule::{ScanSchedule, ScanScheduler};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateScheduleRequest {
+    pub server_name: String,
+    /// Six fields, starting with seconds
+    pub cron_expression: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SchedulesResponse {
+    #[schema(value_type = Vec<Object>)]
+    pub schedules: Vec<ScanSchedule>,
+}
+
+fn scheduler(state: &AppState) -> Result<&Arc<ScanScheduler>, (StatusCode, String)> {
+    state.scheduler.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Scheduled scans are not running".to_string(),
+        )
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/scan/schedule",
+    tag = "scan",
+    responses(
+        (status = 200, body = SchedulesResponse),
+        (status = 503, description = "Scheduled scans are not running", body = String),
+        (status = 500, description = "Database error", body = String)
+    )
+)]
+pub async fn list_schedules(
+    State(state): State<AppState>,
+) -> Result<Json<SchedulesResponse>, (StatusCode, String)> {
+    let schedules = scheduler(&state)?
+        .list()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(SchedulesResponse { schedules }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/scan/schedule",
+    tag = "scan",
+    request_body = CreateScheduleRequest,
+    responses(
+        (status = 200, description = "The created schedule", body = Object),
+        (status = 400, description = "Invalid cron expression", body = String),
+        (status = 503, description = "Scheduled scans are not running", body = String),
+        (status = 500, description = "Database error", body = String)
+    )
+)]
+pub async fn create_schedule(
+    State(state): State<AppState>,
+    Json(req): Json<CreateScheduleRequest>,
+) -> Result<Json<ScanSchedule>, (StatusCode, String)> {
+    let schedule = scheduler(&state)?
+        .add(&req.server_name, &req.cron_expression)
+        .await
+        .map_err(|e| match e {
+            Error::InvalidCron { .. } => (StatusCode::BAD_REQUEST, e.to_string()),
+            e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        })?;
+    Ok(Json(schedule))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/scan/schedule/{id}",
+    tag = "scan",
+    params(("id" = String, Path, description = "Schedule ID")),
+    responses(
+        (status = 204, description = "Schedule deleted"),
+        (status = 404, description = "Schedule not found", body = String),
+        (status = 503, description = "Scheduled scans are not running", body = String),
+        (status = 500, description = "Database error", body = String)
+    )
+)]
+pub async fn delete_schedule(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let existed = scheduler(&state)?
+        .remove(&id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if !existed {
+        return Err((StatusCode::NOT_FOUND, "Schedule not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
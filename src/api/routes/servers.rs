@@ -1,20 +1,23 @@
 //! Server discovery endpoints.
 
-use crate::discovery::{discover_all, discover_from_client, ServerConfig};
 use axum::{extract::Query, http::StatusCode, Json};
+use mcp_guard_core::discovery::{discover_all, discover_from_client, ServerConfig};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ServersQuery {
+    /// Only servers configured in this client
     pub client: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ServersResponse {
     pub servers: Vec<ServerInfo>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ServerInfo {
     pub name: String,
     pub command: String,
@@ -26,9 +29,11 @@ pub struct ServerInfo {
 impl From<ServerConfig> for ServerInfo {
     fn from(s: ServerConfig) -> Self {
         let transport = match &s.transport {
-            crate::discovery::TransportType::Stdio => "stdio".to_string(),
-            crate::discovery::TransportType::Sse { url } => format!("sse:{}", url),
-            crate::discovery::TransportType::StreamableHttp { url } => format!("http:{}", url),
+            mcp_guard_core::discovery::TransportType::Stdio => "stdio".to_string(),
+            mcp_guard_core::discovery::TransportType::Sse { url } => format!("sse:{}", url),
+            mcp_guard_core::discovery::TransportType::StreamableHttp { url } => {
+                format!("http:{}", url)
+            }
         };
 
         let source = s.display_source();
@@ -43,6 +48,16 @@ impl From<ServerConfig> for ServerInfo {
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/servers",
+    tag = "servers",
+    params(ServersQuery),
+    responses(
+        (status = 200, body = ServersResponse),
+        (status = 400, description = "Unknown client", body = String)
+    )
+)]
 pub async fn list_servers(
     Query(query): Query<ServersQuery>,
 ) -> Result<Json<ServersResponse>, (StatusCode, String)> {
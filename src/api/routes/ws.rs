@@ -0,0 +1,152 @@
+//! WebSocket endpoint streaming live scan progress.
+
+use crate::api::state::AppState;
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::Response,
+};
+use mcp_guard_core::db::ScanResultStore;
+use mcp_guard_core::discovery::discover_all;
+use mcp_guard_core::scanner::Threat;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use utoipa::ToSchema;
+
+/// First message a client sends after connecting.
+#[derive(Deserialize, ToSchema)]
+pub struct ScanRequest {
+    pub servers: Vec<String>,
+    pub timeout: Option<u64>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScanEvent {
+    ScanStart { server: String },
+    ThreatFound { server: String, threat: Threat },
+    ScanComplete { server: String, threat_count: usize },
+    ScanError { server: String, error: String },
+    AllComplete,
+    Error { message: String },
+}
+
+/// Send a [`ScanRequest`] as the first message; each [`ScanEvent`] comes
+/// back as a JSON text message.
+#[utoipa::path(
+    get,
+    path = "/api/ws/scan",
+    tag = "scan",
+    responses((status = 101, description = "WebSocket upgrade"))
+)]
+pub async fn scan_ws(ws: WebSocketUpgrade, State(state): State<AppState>) -> Response {
+    ws.on_upgrade(move |socket| handle_socket(socket, state))
+}
+
+async fn handle_socket(mut socket: WebSocket, state: AppState) {
+    let request = loop {
+        match socket.recv().await {
+            Some(Ok(Message::Text(text))) => match serde_json::from_str::<ScanRequest>(&text) {
+                Ok(request) => break request,
+                Err(e) => {
+                    let _ = send_event(
+                        &mut socket,
+                        &ScanEvent::Error {
+                            message: format!("Invalid scan request: {}", e),
+                        },
+                    )
+                    .await;
+                    return;
+                }
+            },
+            // Pings are answered by axum; ignore anything else until a request arrives
+            Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+            Some(Ok(_)) => continue,
+        }
+    };
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let scan_task = tokio::spawn(run_scans(request, state, tx));
+
+    while let Some(event) = rx.recv().await {
+        if send_event(&mut socket, &event).await.is_err() {
+            // Client went away; stop scanning
+            scan_task.abort();
+            return;
+        }
+    }
+
+    let _ = socket.close().await;
+}
+
+async fn run_scans(request: ScanRequest, state: AppState, tx: mpsc::UnboundedSender<ScanEvent>) {
+    let discovered = match discover_all() {
+        Ok(servers) => servers,
+        Err(e) => {
+            let _ = tx.send(ScanEvent::Error {
+                message: e.to_string(),
+            });
+            return;
+        }
+    };
+
+    let scanner = state.scanner(request.timeout.map(Duration::from_secs));
+    let store = ScanResultStore::new(state.db.as_ref().clone());
+
+    for name in &request.servers {
+        let Some(server) = discovered.iter().find(|s| &s.name == name) else {
+            let _ = tx.send(ScanEvent::ScanError {
+                server: name.clone(),
+                error: "Server not found".to_string(),
+            });
+            continue;
+        };
+
+        if tx
+            .send(ScanEvent::ScanStart {
+                server: name.clone(),
+            })
+            .is_err()
+        {
+            return;
+        }
+
+        match scanner.scan(server).await {
+            Ok(result) => {
+                state.metrics.record_scan(&result);
+                if let Err(e) = store.insert(&result) {
+                    tracing::error!(server = %name, error = %e, "Failed to save scan result");
+                }
+
+                let threat_count = result.threats.len();
+                for threat in result.threats {
+                    let _ = tx.send(ScanEvent::ThreatFound {
+                        server: name.clone(),
+                        threat,
+                    });
+                }
+                let _ = tx.send(ScanEvent::ScanComplete {
+                    server: name.clone(),
+                    threat_count,
+                });
+            }
+            Err(e) => {
+                state.metrics.record_scan_error(name);
+                let _ = tx.send(ScanEvent::ScanError {
+                    server: name.clone(),
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+
+    let _ = tx.send(ScanEvent::AllComplete);
+}
+
+async fn send_event(socket: &mut WebSocket, event: &ScanEvent) -> Result<(), axum::Error> {
+    let json = serde_json::to_string(event).unwrap_or_default();
+    socket.send(Message::Text(json)).await
+}
@@ -1,15 +1,44 @@
 //! Shared application state for the API.
 
-use crate::db::DbPool;
+use crate::api::metrics::Metrics;
+use mcp_guard_core::db::DbPool;
+use mcp_guard_core::scanner::{Scanner, SnapshotBackend};
+use mcp_guard_core::schedule::ScanScheduler;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: Arc<DbPool>,
+    pub metrics: Arc<Metrics>,
+    /// Shared by every scan the server runs, keeping snapshots in `db`
+    pub scanner: Arc<Scanner>,
+    /// Runs scheduled scans; the schedule endpoints need it
+    pub scheduler: Option<Arc<ScanScheduler>>,
 }
 
 impl AppState {
     pub fn new(db: DbPool) -> Self {
-        Self { db: Arc::new(db) }
+        let scanner = Scanner::new().with_snapshot_backend(SnapshotBackend::Database(db.clone()));
+        Self {
+            scanner: Arc::new(scanner),
+            db: Arc::new(db),
+            metrics: Arc::new(Metrics::new()),
+            scheduler: None,
+        }
+    }
+
+    /// The shared scanner, or a copy of it with a different per-phase
+    /// `timeout`. Copies share its detectors.
+    pub fn scanner(&self, timeout: Option<Duration>) -> Arc<Scanner> {
+        match timeout {
+            Some(timeout) => Arc::new(self.scanner.as_ref().clone().with_timeout(timeout)),
+            None => self.scanner.clone(),
+        }
+    }
+
+    pub fn with_scheduler(mut self, scheduler: Arc<ScanScheduler>) -> Self {
+        self.scheduler = Some(scheduler);
+        self
     }
 }
@@ -0,0 +1,93 @@
+//! HTTPS for `serve`.
+
+use axum::extract::Host;
+use axum::http::Uri;
+use axum::response::Redirect;
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+use mcp_guard_core::error::{Error, Result};
+use std::path::PathBuf;
+
+/// Where the server's certificate comes from.
+#[derive(Debug, Clone)]
+pub enum TlsSource {
+    /// PEM files for the certificate chain and its private key
+    Pem { cert: PathBuf, key: PathBuf },
+    /// A certificate generated at startup, valid for `localhost` and the
+    /// bind address
+    SelfSigned,
+}
+
+#[derive(Debug, Clone)]
+pub struct TlsOptions {
+    pub source: TlsSource,
+    /// Port on which plain HTTP requests are redirected to HTTPS
+    pub redirect_port: Option<u16>,
+}
+
+impl TlsOptions {
+    pub fn new(source: TlsSource) -> Self {
+        Self {
+            source,
+            redirect_port: None,
+        }
+    }
+
+    pub fn with_redirect_port(mut self, port: Option<u16>) -> Self {
+        self.redirect_port = port;
+        self
+    }
+
+    /// Load or generate the certificate for a server bound to `bind`.
+    pub async fn rustls_config(&self, bind: &str) -> Result<RustlsConfig> {
+        // Other dependencies may enable a second rustls crypto provider, in
+        // which case rustls can't pick one on its own
+        let _ = rustls::crypto::ring::default_provider().install_default();
+
+        match &self.source {
+            TlsSource::Pem { cert, key } => {
+                RustlsConfig::from_pem_file(cert, key).await.map_err(|e| {
+                    Error::Other(format!(
+                        "Failed to load TLS certificate {} and key {}: {}",
+                        cert.display(),
+                        key.display(),
+                        e
+                    ))
+                })
+            }
+            TlsSource::SelfSigned => {
+                let (cert, key) = self_signed_pem(bind)?;
+                RustlsConfig::from_pem(cert.into_bytes(), key.into_bytes())
+                    .await
+                    .map_err(|e| {
+                        Error::Other(format!("Failed to use self-signed certificate: {}", e))
+                    })
+            }
+        }
+    }
+}
+
+/// Generate a self-signed certificate and key, as PEM, for `localhost` and
+/// `bind` unless it's an unspecified address.
+fn self_signed_pem(bind: &str) -> Result<(String, String)> {
+    let mut names = vec!["localhost".to_string()];
+    if !matches!(bind, "0.0.0.0" | "::" | "localhost") {
+        names.push(bind.to_string());
+    }
+
+    let certified = rcgen::generate_simple_self_signed(names)
+        .map_err(|e| Error::Other(format!("Failed to generate self-signed certificate: {}", e)))?;
+    Ok((certified.cert.pem(), certified.key_pair.serialize_pem()))
+}
+
+/// Redirects every request to the same host and path on `https_port`.
+pub fn redirect_router(https_port: u16) -> Router {
+    Router::new().fallback(move |Host(host): Host, uri: Uri| async move {
+        let host = host
+            .rsplit_once(':')
+            .filter(|(_, port)| port.chars().all(|c| c.is_ascii_digit()))
+            .map_or(host.as_str(), |(host, _)| host);
+        let path = uri.path_and_query().map_or("/", |p| p.as_str());
+        Redirect::permanent(&format!("https://{}:{}{}", host, https_port, path))
+    })
+}
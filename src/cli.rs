@@ -1,6 +1,9 @@
 //! Command-line interface definitions using clap.
 
-use clap::{Parser, Subcommand, ValueEnum};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use mcp_guard_core::scanner::{Severity, ThreatCategory};
+use serde::Deserialize;
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -11,52 +14,108 @@ pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
 
-    /// Enable verbose logging
+    /// Enable verbose logging and list each server's tools in scan output
     #[arg(short, long, global = true)]
     pub verbose: bool,
 
-    /// Output format
-    #[arg(short, long, global = true, default_value = "table")]
-    pub output: OutputFormat,
+    /// Output format [default: table, or `[output] format` in the config file for `scan`]
+    #[arg(short, long, global = true)]
+    pub output: Option<OutputFormat>,
+
+    /// Print without colors, as when `NO_COLOR` is set or `TERM=dumb`
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Export tracing spans over OTLP, as when `OTEL_EXPORTER_OTLP_ENDPOINT` is set
+    #[arg(long, global = true)]
+    pub trace: bool,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Scan MCP servers for security vulnerabilities
-    Scan {
-        /// Scan only servers from a specific client
-        #[arg(long)]
-        client: Option<String>,
-
-        /// Scan a specific server command directly
-        #[arg(long)]
-        server: Option<String>,
-
-        /// Scan servers from a config file
-        #[arg(long)]
-        config: Option<PathBuf>,
-
-        /// Timeout in seconds for each server
-        #[arg(long, default_value = "30")]
-        timeout: u64,
-    },
+    Scan(ScanArgs),
 
     /// Watch for config changes and re-scan automatically
     Watch {
         /// Clients to watch (comma-separated, or all if not specified)
         #[arg(long)]
         clients: Option<String>,
+
+        /// Also re-scan every N seconds, even without config changes
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        interval: Option<u64>,
     },
 
     /// Start a proxy between client and MCP server
     Proxy {
-        /// Server command to proxy
-        #[arg(long, required_unless_present = "config")]
+        /// Server command to proxy, overriding `[server]` in the config file
+        #[arg(long, required_unless_present_any = ["config", "server_url"])]
         server: Option<String>,
 
-        /// Proxy rules config file
+        /// URL of a remote server's HTTP endpoint to proxy instead of a
+        /// command, bridging the local STDIO client to it
+        #[arg(long, value_name = "URL", conflicts_with = "server")]
+        server_url: Option<String>,
+
+        /// Proxy config file with the server, limits, and rules
         #[arg(long)]
         config: Option<PathBuf>,
+
+        /// Display name for the server in audit logs (defaults to the binary name)
+        #[arg(long)]
+        server_name: Option<String>,
+
+        /// Client ID for per-client rate limits (defaults to the name the
+        /// client sends in `initialize`)
+        #[arg(long, env = "MCP_GUARD_CLIENT_ID")]
+        client_id: Option<String>,
+
+        /// Forward every call without applying rules, still recording each
+        /// one in the audit log
+        #[arg(long)]
+        transparent: bool,
+
+        /// Apply rules but forward calls they would block, reporting each
+        /// one and recording it in the audit log as blocked
+        #[arg(long, conflicts_with = "transparent")]
+        dry_run: bool,
+
+        /// Reject client messages longer than this many bytes [default:
+        /// 1048576, or `[limits] max_request_bytes` in the config file]
+        #[arg(long)]
+        max_request_size: Option<usize>,
+
+        /// Answer a request with an error if the server doesn't respond
+        /// within this many seconds (defaults to `[limits]
+        /// upstream_timeout_secs` in the config file)
+        #[arg(long, value_name = "SECS")]
+        upstream_timeout: Option<u64>,
+
+        /// Refuse calls to a tool after this many consecutive errors
+        #[arg(long)]
+        circuit_threshold: Option<u32>,
+
+        /// Window in seconds in which the errors must occur
+        #[arg(long, default_value = "60", requires = "circuit_threshold")]
+        circuit_window: u64,
+
+        /// Seconds before an open circuit lets a trial call through
+        #[arg(long, default_value = "30", requires = "circuit_threshold")]
+        circuit_recovery: u64,
+
+        /// Also append audit entries to this file as JSON lines
+        #[arg(long, value_name = "PATH")]
+        log_file: Option<PathBuf>,
+
+        /// Rotate the log file once it reaches this size, keeping 3 old files
+        #[arg(long, default_value = "104857600", requires = "log_file")]
+        log_max_bytes: u64,
+
+        /// Also record each tool call's response in the audit log,
+        /// truncated to 4096 bytes
+        #[arg(long)]
+        audit_responses: bool,
     },
 
     /// Start the web UI and API server
@@ -72,6 +131,40 @@ pub enum Commands {
         /// Don't open browser automatically
         #[arg(long)]
         headless: bool,
+
+        /// Scan all discovered servers before accepting requests
+        #[arg(long)]
+        scan_on_startup: bool,
+
+        /// Require this key on /api/ routes [default: the key stored by `init --api-key`]
+        #[arg(long)]
+        api_key: Option<String>,
+
+        /// Generate a new API key, store its hash in the config file, and print it
+        #[arg(long, conflicts_with = "api_key")]
+        generate_api_key: bool,
+
+        /// Serve Prometheus `/metrics` on this port, without authentication,
+        /// instead of on the main port
+        #[arg(long)]
+        metrics_port: Option<u16>,
+
+        /// Serve HTTPS with this PEM certificate chain
+        #[arg(long, value_name = "PEM", requires = "tls_key")]
+        tls_cert: Option<PathBuf>,
+
+        /// Private key for --tls-cert, in PEM
+        #[arg(long, value_name = "PEM", requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+
+        /// Serve HTTPS with a self-signed certificate generated at startup
+        #[arg(long, conflicts_with = "tls_cert")]
+        tls_self_signed: bool,
+
+        /// Redirect plain HTTP on this port to HTTPS (with --tls-cert or
+        /// --tls-self-signed)
+        #[arg(long, value_name = "PORT")]
+        tls_redirect_port: Option<u16>,
     },
 
     /// Initialize mcp-guard configuration
@@ -79,6 +172,26 @@ pub enum Commands {
         /// Force overwrite existing config
         #[arg(long)]
         force: bool,
+
+        /// API key for `serve`; only its bcrypt hash is written to the config
+        #[arg(long)]
+        api_key: Option<String>,
+
+        /// Also write a copy of this client's MCP config with every server
+        /// wrapped in `proxy` (e.g. claude-desktop)
+        #[arg(long)]
+        client: Option<String>,
+
+        /// Create a project config (.mcp-guard.toml) in the current
+        /// directory instead of the user config
+        #[arg(long, conflicts_with_all = ["api_key", "from_remediation"])]
+        project: bool,
+
+        /// Add the suppressions and rules of a reviewed `scan
+        /// --remediation-file` to the allowlist and proxy rules files named
+        /// in the config, creating them if unset
+        #[arg(long, value_name = "PATH")]
+        from_remediation: Option<PathBuf>,
     },
 
     /// Generate shell completions
@@ -93,14 +206,435 @@ pub enum Commands {
         /// Only list servers from a specific client
         #[arg(long)]
         client: Option<String>,
+
+        /// Print every client's servers as one `mcpServers` JSON document,
+        /// renaming differently configured servers that share a name
+        #[arg(long, conflicts_with = "client")]
+        merge: bool,
+    },
+
+    /// Query tool calls recorded by the proxy
+    Audit {
+        /// Only show calls to this server
+        #[arg(long)]
+        server: Option<String>,
+
+        /// Only show calls to this tool
+        #[arg(long)]
+        tool: Option<String>,
+
+        /// Only show blocked calls
+        #[arg(long)]
+        blocked: bool,
+
+        /// Maximum number of entries to show
+        #[arg(long, default_value = "50")]
+        limit: usize,
+
+        /// Only show calls at or after this time (RFC 3339, YYYY-MM-DD, or a duration ago like 24h)
+        #[arg(long, value_parser = parse_datetime)]
+        since: Option<DateTime<Utc>>,
+
+        /// Only show calls at or before this time (RFC 3339, YYYY-MM-DD, or a duration ago like 24h)
+        #[arg(long, value_parser = parse_datetime)]
+        until: Option<DateTime<Utc>>,
+
+        /// Write every matching entry to FILE as CSV instead of printing them
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["tool", "limit", "since", "until"])]
+        export: Option<PathBuf>,
+
+        /// Print totals, the most called and most blocked tools, and call
+        /// durations instead of entries. Takes `--since`
+        #[arg(long, conflicts_with_all = ["server", "tool", "blocked", "limit", "until", "export"])]
+        stats: bool,
+
+        /// Delete every entry logged before this time (RFC 3339, YYYY-MM-DD,
+        /// or a duration ago like 90d) instead of printing entries
+        #[arg(
+            long,
+            value_name = "DATE",
+            value_parser = parse_datetime,
+            conflicts_with_all = ["server", "tool", "blocked", "limit", "since", "until", "export", "stats"]
+        )]
+        purge_before: Option<DateTime<Utc>>,
+    },
+
+    /// Manage the tool snapshots used for description drift detection
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+
+    /// Manage the proxy rules stored in the database
+    Rules {
+        #[command(subcommand)]
+        action: RulesAction,
+    },
+
+    /// Re-send tool calls from the audit log and compare the responses
+    Replay {
+        /// Audit entry ID to replay
+        #[arg(long, conflicts_with = "last")]
+        id: Option<i64>,
+
+        /// Only replay calls made to this server
+        #[arg(long)]
+        server: Option<String>,
+
+        /// Replay the N most recent calls [default: 1]
+        #[arg(long)]
+        last: Option<usize>,
+
+        /// Server command to replay against (defaults to the discovered server with the logged name)
+        #[arg(long)]
+        command: Option<String>,
+    },
+
+    /// Check that a server starts and speaks MCP, without scanning for threats
+    Check {
+        /// Server command to check, e.g. "npx -y @modelcontextprotocol/server-filesystem /"
+        #[arg(long)]
+        server: String,
+
+        /// Timeout in seconds
+        #[arg(long, default_value = "30")]
+        timeout: u64,
+    },
+
+    /// Show what changed between two stored scans of a server
+    Diff {
+        /// Server name
+        #[arg(long)]
+        server: String,
+
+        /// Earlier scan result ID (default: the scan before --run2)
+        #[arg(long)]
+        run1: Option<i64>,
+
+        /// Later scan result ID (default: the most recent scan)
+        #[arg(long)]
+        run2: Option<i64>,
+    },
+
+    /// Measure tools/list latency of a server
+    Bench {
+        /// Discovered server name, or a server command to run
+        #[arg(long)]
+        server: String,
+
+        /// Number of timed requests
+        #[arg(long, default_value = "100")]
+        requests: usize,
+
+        /// Requests sent before timing starts
+        #[arg(long, default_value = "5")]
+        warmup: usize,
+
+        /// Timeout in seconds for each request
+        #[arg(long, default_value = "30")]
+        timeout: u64,
+    },
+
+    /// Show everything a server advertises: info, capabilities, tools with their input schemas, resources, and prompts
+    ServerInfo {
+        /// Discovered server name, or a server command to run
+        #[arg(long)]
+        server: String,
+
+        /// Timeout in seconds
+        #[arg(long, default_value = "30")]
+        timeout: u64,
+    },
+
+    /// Write an HTML security report from the scan history
+    Report {
+        /// Only include this server
+        #[arg(long)]
+        server: Option<String>,
+
+        /// HTML file to write
+        #[arg(long, default_value = "mcp-scanner-report.html")]
+        file: PathBuf,
+
+        /// Only include scans at or after this time (RFC 3339, YYYY-MM-DD, or a duration ago like 24h)
+        #[arg(long, value_parser = parse_datetime)]
+        since: Option<DateTime<Utc>>,
     },
 }
 
-#[derive(Clone, Copy, ValueEnum)]
+/// Arguments for the `scan` command.
+#[derive(Args)]
+pub struct ScanArgs {
+    /// Scan only servers from a specific client
+    #[arg(long)]
+    pub client: Option<String>,
+
+    /// Scan a specific server command directly
+    #[arg(long)]
+    pub server: Option<String>,
+
+    /// Scan servers from a config file
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
+    /// Run the detectors on tools and resources from a JSON fixture (`{"tools": [...], "resources": [...]}`) instead of a real server
+    #[arg(long, value_name = "JSON_FILE", conflicts_with_all = ["client", "server", "config", "diff", "only_new", "scan_outputs", "active", "validate"])]
+    pub mock: Option<PathBuf>,
+
+    /// Dot-separated key path to the server map in --config (e.g. `ai.mcpServers`)
+    #[arg(long, requires = "config")]
+    pub json_path: Option<String>,
+
+    /// Timeout in seconds for each server [default: 30, or `[scan] timeout` in the config file]
+    #[arg(long)]
+    pub timeout: Option<u64>,
+
+    /// Seconds to wait for a server to start or accept the connection [default: --timeout]
+    #[arg(long, value_name = "SECS")]
+    pub timeout_connect: Option<u64>,
+
+    /// Seconds to wait for the `initialize` response [default: --timeout]
+    #[arg(long, value_name = "SECS")]
+    pub timeout_init: Option<u64>,
+
+    /// Seconds to wait for each tools, resources, or prompts listing [default: --timeout]
+    #[arg(long, value_name = "SECS")]
+    pub timeout_list: Option<u64>,
+
+    /// Maximum number of servers to scan in parallel [default: 4, or `[scan] concurrency` in the config file]
+    #[arg(long)]
+    pub concurrency: Option<usize>,
+
+    /// Call tools that need no arguments and have no obvious side effects, and check their output for prompt injection
+    #[arg(long)]
+    pub scan_outputs: bool,
+
+    /// Write suggested suppressions and proxy rules to a TOML file. Once
+    /// reviewed, apply it with `init --from-remediation`, or use it as is
+    /// with --allowlist and proxy --config
+    #[arg(long)]
+    pub remediation_file: Option<PathBuf>,
+
+    /// Truncate tool descriptions to this many characters in verbose table output
+    #[arg(long, default_value = "80")]
+    pub max_tool_desc_len: usize,
+
+    /// Only report threats that are new or resolved since each server's previous scan
+    #[arg(long, conflicts_with = "scan_outputs")]
+    pub diff: bool,
+
+    /// Only report threats that weren't found in each server's previous scan, and only fail on those
+    #[arg(long, conflicts_with = "diff")]
+    pub only_new: bool,
+
+    /// TOML file of `[[ignore]]` entries (threat `id` and `server`) to leave out of the report
+    #[arg(long, value_name = "FILE")]
+    pub allowlist: Option<PathBuf>,
+
+    /// Only report threats at or above this severity (the exit code still considers all threats) [default: info, or `[scan] min_severity` in the config file]
+    #[arg(long)]
+    pub severity: Option<SeverityLevel>,
+
+    /// Leave info threats out of the report [default: `[output] show_info` in the config file, or show them]
+    #[arg(long, conflicts_with_all = ["include_info", "info_as_warning"])]
+    pub no_info: bool,
+
+    /// Report info threats even when `[output] show_info = false`
+    #[arg(long)]
+    pub include_info: bool,
+
+    /// Highlight info threats like medium ones in table output (they still don't affect the exit code)
+    #[arg(long)]
+    pub info_as_warning: bool,
+
+    /// Only run detectors for this threat category (repeatable)
+    #[arg(long = "category")]
+    pub categories: Vec<CategoryArg>,
+
+    /// YAML file of extra malicious or must-pin packages for the supply chain check
+    #[arg(long)]
+    pub package_denylist: Option<PathBuf>,
+
+    /// Dynamic library with a custom threat detector to run as well (repeatable)
+    #[arg(long = "plugin", value_name = "PATH")]
+    pub plugins: Vec<PathBuf>,
+
+    /// Don't check tool input schemas against the JSON Schema meta-schema
+    #[arg(long)]
+    pub skip_schema_validation: bool,
+
+    /// Don't compare with or update tool snapshots, so description drift isn't checked and stored baselines stay untouched
+    #[arg(long, conflicts_with = "snapshot_dir")]
+    pub no_snapshot: bool,
+
+    /// Keep tool snapshots as JSON files in this directory instead of the history database, e.g. baselines committed to the repository
+    #[arg(long, value_name = "PATH")]
+    pub snapshot_dir: Option<PathBuf>,
+
+    /// Call tools whose names match this glob pattern with minimal arguments and check how they respond (repeatable; the tools really run)
+    #[arg(long = "active", value_name = "PATTERN")]
+    pub active: Vec<String>,
+
+    /// Check server configs (commands on PATH, URLs, env) without connecting; exits 2 on warnings, 1 on errors
+    #[arg(long, conflicts_with_all = ["diff", "only_new", "scan_outputs", "active"])]
+    pub validate: bool,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
     Table,
     Json,
     Sarif,
+    /// GitHub Actions workflow commands that annotate the run
+    #[value(name = "github")]
+    #[serde(rename = "github")]
+    GithubActions,
+    /// JUnit XML, for CI test report views
+    Junit,
+    /// GitHub-flavored Markdown, for PR comments
+    Markdown,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SeverityLevel {
+    Critical,
+    High,
+    Medium,
+    Low,
+    Info,
+}
+
+impl SeverityLevel {
+    pub fn to_severity(self) -> Severity {
+        match self {
+            SeverityLevel::Critical => Severity::Critical,
+            SeverityLevel::High => Severity::High,
+            SeverityLevel::Medium => Severity::Medium,
+            SeverityLevel::Low => Severity::Low,
+            SeverityLevel::Info => Severity::Info,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum CategoryArg {
+    DescriptionInjection,
+    PermissionScope,
+    NoAuth,
+    ToolShadowing,
+    DescriptionDrift,
+    DataExfiltration,
+    CommandInjection,
+    PathTraversal,
+    SupplyChain,
+    SchemaValidation,
+    ResourceUri,
+    Protocol,
+}
+
+impl CategoryArg {
+    pub fn to_category(self) -> ThreatCategory {
+        match self {
+            CategoryArg::DescriptionInjection => ThreatCategory::DescriptionInjection,
+            CategoryArg::PermissionScope => ThreatCategory::PermissionScope,
+            CategoryArg::NoAuth => ThreatCategory::NoAuth,
+            CategoryArg::ToolShadowing => ThreatCategory::ToolShadowing,
+            CategoryArg::DescriptionDrift => ThreatCategory::DescriptionDrift,
+            CategoryArg::DataExfiltration => ThreatCategory::DataExfiltration,
+            CategoryArg::CommandInjection => ThreatCategory::CommandInjection,
+            CategoryArg::PathTraversal => ThreatCategory::PathTraversal,
+            CategoryArg::SupplyChain => ThreatCategory::SupplyChain,
+            CategoryArg::SchemaValidation => ThreatCategory::SchemaValidation,
+            CategoryArg::ResourceUri => ThreatCategory::ResourceUri,
+            CategoryArg::Protocol => ThreatCategory::Protocol,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum SnapshotAction {
+    /// List stored snapshots with their tool counts and creation times
+    List,
+
+    /// Show a server's snapshotted tools and description hashes
+    Show {
+        /// Server name
+        server: String,
+    },
+
+    /// Delete a server's snapshot so its next scan starts a fresh baseline
+    Delete {
+        /// Server name
+        server: String,
+    },
+
+    /// Write a server's snapshot to a JSON file
+    Export {
+        /// Server name
+        server: String,
+
+        /// File to write
+        file: PathBuf,
+    },
+
+    /// Replace a server's snapshot with one read from a JSON file
+    Import {
+        /// File written by `snapshot export`
+        file: PathBuf,
+
+        /// Server to import for [default: the file name without extension]
+        #[arg(long)]
+        server: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RulesAction {
+    /// List rules, highest priority first
+    List,
+
+    /// Add a rule
+    Add {
+        /// Tool name glob, e.g. "delete_*"
+        pattern: String,
+
+        /// allow, log, block[:<reason>], rate_limit:<max_calls>/<window_secs>, or size_limit:<max_bytes>
+        action: String,
+
+        /// Reason shown to the client when a block rule rejects a call
+        #[arg(long)]
+        reason: Option<String>,
+
+        /// Higher priority rules are checked first [default: 0]
+        #[arg(long)]
+        priority: Option<i32>,
+    },
+
+    /// Delete a rule
+    Remove {
+        /// Rule ID
+        id: String,
+    },
+
+    /// Re-enable a disabled rule
+    Enable {
+        /// Rule ID
+        id: String,
+    },
+
+    /// Keep a rule without applying it
+    Disable {
+        /// Rule ID
+        id: String,
+    },
+
+    /// Show which rules match a tool name and what the proxy would do
+    Test {
+        /// Tool name
+        tool: String,
+    },
 }
 
 #[derive(Clone, Copy, ValueEnum)]
@@ -122,3 +656,34 @@ impl Shell {
         }
     }
 }
+
+/// Parse an RFC 3339 timestamp, a bare date as midnight UTC, or a time
+/// that long ago, like `30m`, `24h`, `7d`, or `2w`.
+pub(crate) fn parse_datetime(s: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    if let Some(ago) = parse_ago(s) {
+        return Ok(Utc::now() - ago);
+    }
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+        .map_err(|_| {
+            format!(
+                "expected RFC 3339 (2024-01-31T12:00:00Z), YYYY-MM-DD, or a duration like 24h, got '{}'",
+                s
+            )
+        })
+}
+
+fn parse_ago(s: &str) -> Option<chrono::Duration> {
+    let unit = s.chars().last()?;
+    let count: i64 = s[..s.len() - unit.len_utf8()].parse().ok()?;
+    match unit {
+        'm' => chrono::Duration::try_minutes(count),
+        'h' => chrono::Duration::try_hours(count),
+        'd' => chrono::Duration::try_days(count),
+        'w' => chrono::Duration::try_weeks(count),
+        _ => None,
+    }
+}
@@ -0,0 +1,334 @@
+//! User configuration loaded from `~/.mcp-scanner/config.toml`, and the
+//! project configuration in `.mcp-guard.toml` in the current directory.
+//!
+//! Every setting is optional; command-line flags take precedence over the
+//! files, the project file over the user file, and built-in defaults apply
+//! when none of them set a value. The project file can only set the keys in
+//! [`PROJECT_KEYS`].
+
+use crate::cli::OutputFormat;
+use colored::Colorize;
+use mcp_guard_core::error::{Error, Result};
+use mcp_guard_core::notifications::NotificationConfig;
+use mcp_guard_core::scanner::Severity;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Project config file, looked up in the current directory.
+pub const PROJECT_CONFIG_FILE: &str = ".mcp-guard.toml";
+
+/// Settings the project file may set, by section. A checked-out repository
+/// shouldn't be able to change the API key, where notifications go, which
+/// proxy rules apply, which threats are reported, or how long records are
+/// kept, so everything else only comes from the user config.
+pub const PROJECT_KEYS: &[(&str, &[&str])] = &[
+    (
+        "scan",
+        &["timeout", "concurrency", "max_description_length"],
+    ),
+    ("output", &["format", "show_info"]),
+];
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub scan: ScanConfig,
+    pub output: OutputConfig,
+    pub proxy: ProxyConfig,
+    pub serve: ServeConfig,
+    pub notifications: NotificationConfig,
+    pub audit: AuditConfig,
+    pub scan_results: ScanResultsConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ScanConfig {
+    /// Timeout in seconds for each server
+    pub timeout: Option<u64>,
+    /// Maximum number of servers scanned at once
+    pub concurrency: Option<usize>,
+    /// Descriptions longer than this many bytes are flagged
+    pub max_description_length: Option<usize>,
+    /// Only report threats at or above this severity
+    pub min_severity: Option<Severity>,
+    /// Allowlist used when `--allowlist` isn't given, and by scheduled scans
+    pub allowlist: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct OutputConfig {
+    /// Output format of `scan` when `--output` isn't given
+    pub format: Option<OutputFormat>,
+    /// Whether `scan` reports info threats
+    pub show_info: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ProxyConfig {
+    /// Rules file used by `proxy` when `--config` isn't given
+    pub rules_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ServeConfig {
+    /// Bcrypt hash of the API key required by `serve`
+    pub api_key_hash: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    /// `serve` deletes audit entries older than this many days, once a day
+    pub retention_days: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ScanResultsConfig {
+    /// `serve` deletes stored scan results older than this many days, once
+    /// a day
+    pub retention_days: Option<u32>,
+}
+
+impl Config {
+    /// Load only the config file at `path`, or the defaults if there's none.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        parse(path, read_table(path)?)
+    }
+
+    /// Load the user config with the project config in the current
+    /// directory on top: project values replace user values. Project
+    /// settings outside [`PROJECT_KEYS`] are ignored with a warning. Missing
+    /// files are skipped.
+    pub fn load_merged() -> Result<Self> {
+        let mut merged = toml::Table::new();
+        let mut config = Self::default();
+        if let Some(path) = config_path().filter(|p| p.exists()) {
+            merged = read_table(&path)?;
+            config = parse(&path, merged.clone())?;
+        }
+
+        let project_path = PathBuf::from(PROJECT_CONFIG_FILE);
+        if project_path.exists() {
+            let project = project_table(&project_path, read_table(&project_path)?);
+            // Checked on its own so errors name the file at fault
+            parse(&project_path, project.clone())?;
+            merge(&mut merged, project);
+            config = parse(&project_path, merged)?;
+        }
+        Ok(config)
+    }
+}
+
+fn parse(path: &Path, table: toml::Table) -> Result<Config> {
+    toml::Value::Table(table)
+        .try_into()
+        .map_err(|e| Error::config_parse(path, e.to_string()))
+}
+
+/// The settings in `table` that [`PROJECT_KEYS`] allows.
+fn project_table(path: &Path, table: toml::Table) -> toml::Table {
+    let mut allowed = toml::Table::new();
+    for (section, value) in table {
+        let keys = PROJECT_KEYS
+            .iter()
+            .find(|(name, _)| *name == section)
+            .map(|(_, keys)| *keys);
+        let (Some(keys), toml::Value::Table(entries)) = (keys, value) else {
+            ignore_project_setting(path, &section);
+            continue;
+        };
+
+        let mut kept = toml::Table::new();
+        for (key, value) in entries {
+            if keys.contains(&key.as_str()) {
+                kept.insert(key, value);
+            } else {
+                ignore_project_setting(path, &format!("{}.{}", section, key));
+            }
+        }
+        allowed.insert(section, toml::Value::Table(kept));
+    }
+    allowed
+}
+
+fn ignore_project_setting(path: &Path, setting: &str) {
+    eprintln!(
+        "{}",
+        format!(
+            "Warning: ignoring `{}` in {}; only ~/.mcp-scanner/config.toml can set it",
+            setting,
+            path.display()
+        )
+        .yellow()
+    );
+}
+
+fn read_table(path: &Path) -> Result<toml::Table> {
+    let content = std::fs::read_to_string(path).map_err(|e| Error::ConfigRead {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    content
+        .parse()
+        .map_err(|e: toml::de::Error| Error::config_parse(path, e.to_string()))
+}
+
+/// Merge `overlay` into `base`: tables merge key by key, arrays are
+/// concatenated, and anything else in `overlay` replaces the value in `base`.
+fn merge(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => merge(base, overlay),
+            (Some(toml::Value::Array(base)), toml::Value::Array(overlay)) => base.extend(overlay),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Location of the user config file written by `init`.
+pub fn config_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".mcp-scanner").join("config.toml"))
+}
+
+/// Store `hash` as `[serve] api_key_hash` in the config file at `path`,
+/// creating the file if needed.
+pub fn store_api_key_hash(path: &Path, hash: &str) -> Result<()> {
+    store_setting(path, "serve", "api_key_hash", hash)
+}
+
+/// Set `key` in `[section]` of the config file at `path` to the string
+/// `value`, creating the file if needed. Edits the text in place so comments
+/// and other settings are kept.
+pub fn store_setting(path: &Path, section: &str, key: &str, value: &str) -> Result<()> {
+    let content = if path.exists() {
+        std::fs::read_to_string(path).map_err(|e| Error::ConfigRead {
+            path: path.to_path_buf(),
+            source: e,
+        })?
+    } else {
+        String::new()
+    };
+
+    let entry = format!("{} = {}", key, toml::Value::String(value.to_string()));
+    let header = format!("[{}]", section);
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+
+    if let Some(start) = lines.iter().position(|l| l.trim() == header) {
+        // The section runs until the next table header
+        let end = lines[start + 1..]
+            .iter()
+            .position(|l| l.trim_start().starts_with('['))
+            .map_or(lines.len(), |i| start + 1 + i);
+        let existing = lines[start + 1..end]
+            .iter()
+            .position(|l| l.split('=').next().map(str::trim) == Some(key));
+        match existing {
+            Some(i) => lines[start + 1 + i] = entry,
+            None => lines.insert(start + 1, entry),
+        }
+    } else {
+        if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.push(header);
+        lines.push(entry);
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, lines.join("\n") + "\n")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored_hash(path: &Path) -> Option<String> {
+        Config::load(path).unwrap().serve.api_key_hash
+    }
+
+    #[test]
+    fn api_key_hash_replaces_an_existing_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "# keep me\n[serve]\napi_key_hash = \"old\"\n# rotated yearly\n",
+        )
+        .unwrap();
+
+        store_api_key_hash(&path, "new").unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content,
+            "# keep me\n[serve]\napi_key_hash = \"new\"\n# rotated yearly\n"
+        );
+        assert_eq!(stored_hash(&path).as_deref(), Some("new"));
+    }
+
+    #[test]
+    fn api_key_hash_goes_under_an_existing_serve_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[serve]\n# rotated yearly\n\n[scan]\ntimeout = 5\n").unwrap();
+
+        store_api_key_hash(&path, "hash").unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            content.starts_with("[serve]\napi_key_hash = \"hash\"\n# rotated yearly\n"),
+            "{}",
+            content
+        );
+        assert_eq!(stored_hash(&path).as_deref(), Some("hash"));
+    }
+
+    #[test]
+    fn settings_are_only_replaced_in_their_own_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "[proxy]\nrules_path = \"a.toml\"\n\n[scan]\ntimeout = 5\n",
+        )
+        .unwrap();
+
+        store_setting(&path, "scan", "allowlist", "allow.toml").unwrap();
+        store_setting(&path, "proxy", "rules_path", "b.toml").unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.scan.allowlist, Some(PathBuf::from("allow.toml")));
+        assert_eq!(config.scan.timeout, Some(5));
+        assert_eq!(config.proxy.rules_path, Some(PathBuf::from("b.toml")));
+    }
+
+    #[test]
+    fn api_key_hash_adds_a_serve_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[scan]\ntimeout = 5\n").unwrap();
+
+        store_api_key_hash(&path, "hash").unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content,
+            "[scan]\ntimeout = 5\n\n[serve]\napi_key_hash = \"hash\"\n"
+        );
+        assert_eq!(stored_hash(&path).as_deref(), Some("hash"));
+
+        // A missing file is created, along with its directory
+        let path = dir.path().join("new").join("config.toml");
+        store_api_key_hash(&path, "hash").unwrap();
+        assert_eq!(stored_hash(&path).as_deref(), Some("hash"));
+    }
+}
@@ -2,27 +2,37 @@
 
 mod api;
 mod cli;
-mod db;
-mod discovery;
-mod error;
-mod protocol;
-mod proxy;
-mod scanner;
+mod config;
+mod telemetry;
 mod ui;
 
+use chrono::{DateTime, Utc};
 use clap::Parser;
-use cli::{Cli, Commands, OutputFormat};
+use cli::{Cli, Commands, OutputFormat, RulesAction, ScanArgs, SeverityLevel, SnapshotAction};
 use colored::Colorize;
-use discovery::{discover_all, discover_from_client, ServerConfig};
+use discovery::{discover_all, discover_from_client, ServerConfig, ValidationLevel};
 use error::Result;
-use scanner::{ScanResult, Scanner, Severity};
+use mcp_guard_core::notifications::WebhookNotifier;
+use mcp_guard_core::{db, discovery, error, proxy, scanner, watch};
+use scanner::{ScanDiff, ScanResult, Scanner, Severity, SnapshotBackend};
+use std::collections::HashMap;
 use std::time::Duration;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::EnvFilter;
+use watch::{Rescan, RescanSchedule, WatchConfig};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    let no_color = cli.no_color
+        || std::env::var_os("NO_COLOR").is_some()
+        || std::env::var("TERM").as_deref() == Ok("dumb");
+    if no_color {
+        colored::control::set_override(false);
+    }
+
     // Initialize logging
     let filter = if cli.verbose {
         EnvFilter::new("mcp_guard=debug")
@@ -30,55 +40,252 @@ async fn main() -> Result<()> {
         EnvFilter::new("mcp_guard=info")
     };
 
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_target(false)
+    // Spans also go to an OpenTelemetry collector when asked for
+    let otel = if telemetry::enabled(cli.trace) {
+        Some(tracing_opentelemetry::layer().with_tracer(telemetry::init_tracer()?))
+    } else {
+        None
+    };
+    let _telemetry = telemetry::ShutdownOnDrop;
+
+    // Logs go to stderr: stdout carries scan reports and, in proxy mode,
+    // the MCP messages themselves
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_target(false)
+                .with_ansi(!no_color)
+                .with_writer(std::io::stderr),
+        )
+        .with(otel)
         .init();
 
+    // Only `scan` reads a default format from the config file
+    let output = cli.output.unwrap_or(OutputFormat::Table);
+
     match cli.command {
-        Commands::Scan {
-            client,
+        Commands::Scan(args) => {
+            cmd_scan(args, cli.output, cli.verbose).await?;
+        }
+        Commands::Watch { clients, interval } => {
+            let mut config = WatchConfig::default();
+            if let Some(secs) = interval {
+                config = config.with_interval(Duration::from_secs(secs));
+            }
+            cmd_watch(clients, config).await?;
+        }
+        Commands::Proxy {
             server,
+            server_url,
             config,
-            timeout,
+            server_name,
+            client_id,
+            transparent,
+            dry_run,
+            max_request_size,
+            upstream_timeout,
+            circuit_threshold,
+            circuit_window,
+            circuit_recovery,
+            log_file,
+            log_max_bytes,
+            audit_responses,
         } => {
-            cmd_scan(client, server, config, timeout, cli.output).await?;
-        }
-        Commands::Watch { clients } => {
-            cmd_watch(clients).await?;
-        }
-        Commands::Proxy { server, config } => {
-            cmd_proxy(server, config).await?;
+            let circuit_breaker =
+                circuit_threshold.map(|threshold| (threshold, circuit_window, circuit_recovery));
+            cmd_proxy(
+                server,
+                server_url,
+                config,
+                server_name,
+                client_id,
+                transparent,
+                dry_run,
+                max_request_size,
+                upstream_timeout.map(Duration::from_secs),
+                circuit_breaker,
+                log_file.map(|path| (path, log_max_bytes)),
+                audit_responses,
+            )
+            .await?;
         }
         Commands::Serve {
             port,
             bind,
             headless,
+            scan_on_startup,
+            api_key,
+            generate_api_key,
+            metrics_port,
+            tls_cert,
+            tls_key,
+            tls_self_signed,
+            tls_redirect_port,
         } => {
-            cmd_serve(port, bind, headless).await?;
+            let tls_source = match (tls_cert, tls_key) {
+                (Some(cert), Some(key)) => Some(api::tls::TlsSource::Pem { cert, key }),
+                _ if tls_self_signed => Some(api::tls::TlsSource::SelfSigned),
+                _ => None,
+            };
+            let tls = match tls_source {
+                Some(source) => {
+                    Some(api::tls::TlsOptions::new(source).with_redirect_port(tls_redirect_port))
+                }
+                None if tls_redirect_port.is_some() => {
+                    return Err(error::Error::Other(
+                        "--tls-redirect-port requires --tls-cert or --tls-self-signed".to_string(),
+                    ));
+                }
+                None => None,
+            };
+            cmd_serve(
+                port,
+                bind,
+                headless,
+                scan_on_startup,
+                api_key,
+                generate_api_key,
+                metrics_port,
+                tls,
+            )
+            .await?;
         }
-        Commands::Init { force } => {
-            cmd_init(force)?;
+        Commands::Init {
+            force,
+            api_key,
+            client,
+            project,
+            from_remediation,
+        } => {
+            cmd_init(force, api_key, client, project, from_remediation)?;
         }
         Commands::Completions { shell } => {
             cmd_completions(shell);
         }
-        Commands::List { client } => {
-            cmd_list(client)?;
+        Commands::List { client, merge } => {
+            cmd_list(client, merge, output)?;
+        }
+        Commands::Snapshot { action } => {
+            cmd_snapshot(action)?;
+        }
+        Commands::Rules { action } => {
+            cmd_rules(action, output)?;
+        }
+        Commands::Audit {
+            server,
+            tool,
+            blocked,
+            limit,
+            since,
+            until,
+            export,
+            stats,
+            purge_before,
+        } => match (export, purge_before) {
+            (_, Some(cutoff)) => cmd_audit_purge(cutoff)?,
+            _ if stats => cmd_audit_stats(since, output)?,
+            (Some(path), None) => cmd_audit_export(&path, server, blocked)?,
+            (None, None) => cmd_audit(server, tool, blocked, limit, since, until, output)?,
+        },
+        Commands::Replay {
+            id,
+            server,
+            last,
+            command,
+        } => {
+            cmd_replay(id, server, last, command).await?;
+        }
+        Commands::Check { server, timeout } => {
+            cmd_check(server, timeout, output).await?;
+        }
+        Commands::Diff { server, run1, run2 } => {
+            cmd_diff(server, run1, run2, output)?;
+        }
+        Commands::Bench {
+            server,
+            requests,
+            warmup,
+            timeout,
+        } => {
+            cmd_bench(server, requests, warmup, timeout, output).await?;
+        }
+        Commands::ServerInfo { server, timeout } => {
+            cmd_server_info(server, timeout, output).await?;
+        }
+        Commands::Report {
+            server,
+            file,
+            since,
+        } => {
+            cmd_report(server, file, since)?;
         }
     }
 
     Ok(())
 }
 
-async fn cmd_scan(
-    client: Option<String>,
-    server: Option<String>,
-    config: Option<std::path::PathBuf>,
-    timeout: u64,
-    output: OutputFormat,
-) -> Result<()> {
-    let servers = if let Some(server_cmd) = server {
+async fn cmd_scan(args: ScanArgs, output: Option<OutputFormat>, verbose: bool) -> Result<()> {
+    let ScanArgs {
+        client,
+        server,
+        config,
+        mock,
+        json_path,
+        timeout,
+        timeout_connect,
+        timeout_init,
+        timeout_list,
+        concurrency,
+        scan_outputs,
+        remediation_file,
+        max_tool_desc_len,
+        diff,
+        only_new,
+        allowlist,
+        severity,
+        no_info,
+        include_info,
+        info_as_warning,
+        categories,
+        package_denylist,
+        plugins,
+        skip_schema_validation,
+        no_snapshot,
+        snapshot_dir,
+        active,
+        validate,
+    } = args;
+    let user_config = config::Config::load_merged()?;
+    let output = output
+        .or(user_config.output.format)
+        .unwrap_or(OutputFormat::Table);
+    let timeout = timeout.or(user_config.scan.timeout).unwrap_or(30);
+    let allowlist = allowlist
+        .or(user_config.scan.allowlist)
+        .map(|path| scanner::Allowlist::load(&path))
+        .transpose()?;
+    let min_severity = severity
+        .map(SeverityLevel::to_severity)
+        .or(user_config.scan.min_severity)
+        .unwrap_or(Severity::Info);
+    let display = DisplayConfig {
+        show_info: !no_info && (include_info || user_config.output.show_info.unwrap_or(true)),
+        info_as_warning,
+    };
+    let min_severity = if display.show_info {
+        min_severity
+    } else {
+        min_severity.min(Severity::Low)
+    };
+
+    let mock = mock
+        .map(|path| scanner::MockServer::load(&path))
+        .transpose()?;
+
+    let servers = if mock.is_some() {
+        vec![ServerConfig::new("mock", "")]
+    } else if let Some(server_cmd) = server {
         // Parse server command: "npx -y @modelcontextprotocol/server-filesystem /"
         let parts: Vec<String> = shell_words::split(&server_cmd)
             .map_err(|e| error::Error::Other(format!("Invalid server command: {}", e)))?;
@@ -89,7 +296,11 @@ async fn cmd_scan(
 
         vec![ServerConfig::new("manual", &parts[0]).with_args(parts[1..].to_vec())]
     } else if let Some(config_path) = config {
-        discovery::clients::GenericDiscovery::new(config_path).parse_file()?
+        let mut generic = discovery::clients::GenericDiscovery::new(config_path);
+        if let Some(json_path) = json_path {
+            generic = generic.with_json_path(json_path);
+        }
+        generic.parse_file()?
     } else if let Some(client_name) = client {
         discover_from_client(&client_name)?
     } else {
@@ -101,49 +312,313 @@ async fn cmd_scan(
         return Ok(());
     }
 
-    println!(
+    if validate {
+        validate_servers(&servers);
+        return Ok(());
+    }
+
+    // On stderr with the progress lines, so stdout is only the report
+    eprintln!(
         "{}",
         format!("Found {} server(s), scanning...\n", servers.len()).cyan()
     );
 
-    let scanner = Scanner::new().with_timeout(Duration::from_secs(timeout));
-    let mut all_results = Vec::new();
+    let concurrency = concurrency
+        .or(user_config.scan.concurrency)
+        .unwrap_or(scanner::DEFAULT_CONCURRENCY);
+    // Record scan history; a broken database shouldn't fail the scan itself
+    let history_pool = match db::default_db_path().and_then(|path| db::create_pool(&path)) {
+        Ok(pool) => Some(pool),
+        Err(e) if diff || only_new => return Err(e),
+        Err(e) => {
+            tracing::warn!(error = %e, "Could not open database, scan history will not be saved");
+            None
+        }
+    };
+    // Fixtures aren't real servers, so they stay out of the history
+    let history = history_pool
+        .clone()
+        .filter(|_| mock.is_none())
+        .map(db::ScanResultStore::new);
+
+    let mut scanner = Scanner::new()
+        .with_timeout(Duration::from_secs(timeout))
+        .with_concurrency(concurrency);
+    if let Some(secs) = timeout_connect {
+        scanner = scanner.with_connect_timeout(Duration::from_secs(secs));
+    }
+    if let Some(secs) = timeout_init {
+        scanner = scanner.with_init_timeout(Duration::from_secs(secs));
+    }
+    if let Some(secs) = timeout_list {
+        scanner = scanner.with_list_timeout(Duration::from_secs(secs));
+    }
+    if !categories.is_empty() {
+        scanner = scanner.with_categories(categories.iter().map(|c| c.to_category()).collect());
+    }
+    if let Some(path) = package_denylist {
+        scanner = scanner.with_package_denylist(path)?;
+    }
+    if !plugins.is_empty() {
+        scanner = scanner.with_plugins(plugins)?;
+    }
+    if let Some(length) = user_config.scan.max_description_length {
+        scanner = scanner.with_max_description_length(length);
+    }
+    if !active.is_empty() {
+        scanner = scanner.with_active_scan(active)?;
+    }
+    if skip_schema_validation {
+        scanner = scanner.without_schema_validation();
+    }
+    if no_snapshot {
+        scanner = scanner.without_snapshots();
+    } else if let Some(dir) = snapshot_dir {
+        scanner = scanner.with_snapshot_backend(SnapshotBackend::Directory(dir));
+    } else if let Some(pool) = &history_pool {
+        scanner = scanner.with_snapshot_backend(SnapshotBackend::Database(pool.clone()));
+    }
+    if let (true, Some(store)) = (diff, &history) {
+        scanner = scanner.with_result_store(store.clone());
+    }
+    if scan_outputs {
+        scanner = scanner.with_tool_calls();
+    }
 
-    for server in &servers {
-        match scanner.scan(server).await {
-            Ok(result) => all_results.push(result),
-            Err(e) => {
-                eprintln!(
-                    "{} {}: {}",
-                    "✗".red(),
-                    server.name.bold(),
-                    e.to_string().red()
-                );
+    let mut finished = 0;
+    let mut report_progress = |server: &ServerConfig, result: &Result<ScanResult>| {
+        finished += 1;
+        let progress = format!("[{}/{}]", finished, servers.len()).dimmed();
+        match result {
+            Ok(_) => eprintln!("{} {} {}", progress, "✓".green(), server.name),
+            Err(e) => eprintln!(
+                "{} {} {}: {}",
+                progress,
+                "✗".red(),
+                server.name.bold(),
+                e.to_string().red()
+            ),
+        }
+    };
+    let mut all_results = if let Some(mock) = mock {
+        let (tools, resources) = mock.into_parts();
+        let result = scanner.scan_mock(tools, resources);
+        report_progress(&servers[0], &result);
+        result.into_iter().collect()
+    } else {
+        scanner
+            .scan_all_with_progress(&servers, report_progress)
+            .await
+            .results
+    };
+
+    // Allowlisted threats are kept in the history, marked as suppressed
+    if let Some(allowlist) = &allowlist {
+        allowlist.mark(&mut all_results);
+    }
+
+    // Diffed and stored only now, so they include cross-server collisions
+    let mut diffs = Vec::new();
+    if diff {
+        diffs = all_results
+            .drain(..)
+            .map(|result| scanner.record_diff(result))
+            .collect::<Result<Vec<ScanDiff>>>()?;
+        all_results = diffs.iter().map(|d| d.result.clone()).collect();
+    } else {
+        // Read the baselines before this scan is stored over them
+        let baselines = match (&history, only_new) {
+            (Some(store), true) => all_results
+                .iter()
+                .map(|r| store.latest_threats(&r.server.name))
+                .collect::<Result<Vec<_>>>()?,
+            _ => Vec::new(),
+        };
+
+        // record_diff stores results in diff mode
+        if let Some(store) = &history {
+            for result in &all_results {
+                if let Err(e) = store.insert(result) {
+                    tracing::warn!(server = %result.server.name, error = %e, "Failed to save scan result");
+                }
+            }
+        }
+
+        // A server's first scan has no baseline, so everything is new
+        for (result, baseline) in all_results.iter_mut().zip(baselines) {
+            if let Some((_, previous)) = baseline {
+                result.retain_new(&previous);
             }
         }
     }
 
-    match output {
-        OutputFormat::Table => print_table_output(&all_results),
-        OutputFormat::Json => print_json_output(&all_results)?,
-        OutputFormat::Sarif => print_sarif_output(&all_results)?,
+    let suppressed = allowlist.as_ref().map(|allowlist| {
+        let mut suppressed = allowlist.filter(&mut all_results);
+        for diff in &mut diffs {
+            suppressed += allowlist.filter(std::slice::from_mut(&mut diff.result));
+            let server = &diff.result.server.name;
+            for threats in [
+                &mut diff.new_threats,
+                &mut diff.resolved_threats,
+                &mut diff.unchanged_threats,
+            ] {
+                threats.retain(|t| !allowlist.allows(server, t));
+            }
+        }
+        suppressed
+    });
+
+    // Notify about everything found, or only what's new in diff mode
+    let notifier = WebhookNotifier::new(user_config.notifications);
+    if notifier.is_enabled() {
+        let found: Vec<(&str, &[scanner::Threat])> = if diff {
+            diffs
+                .iter()
+                .map(|d| (d.result.server.name.as_str(), d.new_threats.as_slice()))
+                .collect()
+        } else {
+            all_results
+                .iter()
+                .map(|r| (r.server.name.as_str(), r.threats.as_slice()))
+                .collect()
+        };
+        for (server, threats) in found {
+            if let Err(e) = notifier.notify_threats(server, threats).await {
+                eprintln!("{}", format!("Warning: {}", e).yellow());
+            }
+        }
     }
 
-    // Exit with error code if any critical/high threats found
-    let has_critical = all_results
-        .iter()
-        .any(|r| r.threats.iter().any(|t| t.severity <= Severity::High));
+    // Exit with error code if any critical/high threats found, before
+    // --severity filtering so it can't hide them. In diff mode only new
+    // ones count, so known findings don't keep failing CI; --only-new has
+    // already dropped them.
+    let has_critical = if diff {
+        diffs
+            .iter()
+            .any(|d| d.new_threats.iter().any(|t| t.severity <= Severity::High))
+    } else {
+        all_results
+            .iter()
+            .any(|r| r.threats.iter().any(|t| t.severity <= Severity::High))
+    };
+
+    // Remediation suggestions cover every finding, not just reported ones
+    let remediation =
+        remediation_file.map(|path| (scanner::remediation::remediation_toml(&all_results), path));
+
+    for result in &mut all_results {
+        result.retain_min_severity(min_severity);
+    }
+    for diff in &mut diffs {
+        diff.retain_min_severity(min_severity);
+    }
+
+    if diff {
+        match output {
+            OutputFormat::Table => print_diff_table_output(&diffs),
+            OutputFormat::Json => print_diff_json_output(&diffs)?,
+            // SARIF and annotations have no notion of resolved findings;
+            // report what's new
+            OutputFormat::Sarif => print_sarif_output(&new_threat_results(&diffs))?,
+            OutputFormat::GithubActions => print_github_output(&new_threat_results(&diffs)),
+            OutputFormat::Junit => print_junit_output(&new_threat_results(&diffs))?,
+            OutputFormat::Markdown => print_markdown_output(&new_threat_results(&diffs)),
+        }
+    } else {
+        match output {
+            OutputFormat::Table => print_table_output(
+                &all_results,
+                verbose.then_some(max_tool_desc_len),
+                &scanner.detector_names(),
+                display,
+            ),
+            OutputFormat::Json => print_json_output(&all_results)?,
+            OutputFormat::Sarif => print_sarif_output(&all_results)?,
+            OutputFormat::GithubActions => print_github_output(&all_results),
+            OutputFormat::Junit => print_junit_output(&all_results)?,
+            OutputFormat::Markdown => print_markdown_output(&all_results),
+        }
+    }
+
+    if let Some(suppressed) = suppressed {
+        eprintln!(
+            "{}",
+            format!("{} threats suppressed by allowlist", suppressed).dimmed()
+        );
+    }
+
+    if let Some((toml, path)) = remediation {
+        std::fs::write(&path, toml)?;
+        eprintln!(
+            "{}",
+            format!("Wrote suggested remediations to {}", path.display()).green()
+        );
+    }
 
     if has_critical {
-        std::process::exit(1);
+        telemetry::exit(1);
     }
 
     Ok(())
 }
 
-fn print_table_output(results: &[ScanResult]) {
+/// Print an OK/WARN/ERROR row per server, then exit 1 if any config has
+/// errors or 2 if there are only warnings.
+fn validate_servers(servers: &[ServerConfig]) {
+    println!(
+        "{}",
+        format!("Validating {} server(s)...\n", servers.len()).cyan()
+    );
+
+    let mut worst = None;
+    for server in servers {
+        let warnings = server.validate();
+        let level = warnings.iter().map(|w| w.level).max();
+        worst = worst.max(level);
+
+        let status = match level {
+            None => "OK   ".green(),
+            Some(ValidationLevel::Warning) => "WARN ".yellow(),
+            Some(ValidationLevel::Error) => "ERROR".red().bold(),
+        };
+        println!(
+            "  {}  {} {}",
+            status,
+            server.name.bold(),
+            server.display_source().dimmed()
+        );
+        for warning in &warnings {
+            println!("         {}", warning.message);
+        }
+    }
+
+    match worst {
+        Some(ValidationLevel::Error) => telemetry::exit(1),
+        Some(ValidationLevel::Warning) => telemetry::exit(2),
+        None => println!("\n{}", "All server configs are valid.".green()),
+    }
+}
+
+/// How `scan` presents info threats.
+#[derive(Clone, Copy)]
+struct DisplayConfig {
+    /// Report info threats at all
+    show_info: bool,
+    /// Color info threats like medium ones instead of dimming them
+    info_as_warning: bool,
+}
+
+/// Prints one block per server. When `tool_desc_len` is set (verbose mode),
+/// each server's tools are listed with descriptions truncated to that length.
+fn print_table_output(
+    results: &[ScanResult],
+    tool_desc_len: Option<usize>,
+    detectors: &[&str],
+    display: DisplayConfig,
+) {
     for result in results {
-        let threat_summary = summarize_threats(&result.threats);
+        let threat_summary = summarize_threats(&result.threats, display);
         let status = if result.threats.is_empty() {
             "✓".green()
         } else if result
@@ -159,10 +634,11 @@ fn print_table_output(results: &[ScanResult]) {
         };
 
         println!(
-            "{} {} ({} tools, {})",
+            "{} {} ({} tools, {} prompts, {})",
             status,
             result.server.name.bold(),
             result.tools.len(),
+            result.prompts.len(),
             threat_summary
         );
 
@@ -172,6 +648,9 @@ fn print_table_output(results: &[ScanResult]) {
                 Severity::High => format!("[{}]", threat.severity).red(),
                 Severity::Medium => format!("[{}]", threat.severity).yellow(),
                 Severity::Low => format!("[{}]", threat.severity).blue(),
+                Severity::Info if display.info_as_warning => {
+                    format!("[{}]", threat.severity).yellow()
+                }
                 Severity::Info => format!("[{}]", threat.severity).dimmed(),
             };
 
@@ -186,7 +665,17 @@ fn print_table_output(results: &[ScanResult]) {
             }
         }
 
-        if !result.threats.is_empty() {
+        if let Some(max_len) = tool_desc_len {
+            for tool in &result.tools {
+                println!(
+                    "    {} {}",
+                    tool.name.cyan(),
+                    tool.short_description(max_len).dimmed()
+                );
+            }
+        }
+
+        if !result.threats.is_empty() || (tool_desc_len.is_some() && !result.tools.is_empty()) {
             println!();
         }
     }
@@ -218,9 +707,66 @@ fn print_table_output(results: &[ScanResult]) {
             total_high.to_string().red()
         );
     }
+    println!(
+        "{}",
+        format!("Detectors run: {}", detectors.join(", ")).dimmed()
+    );
+}
+
+/// Prints only what changed since each server's previous scan: new threats in
+/// red, resolved threats struck through.
+fn print_diff_table_output(diffs: &[ScanDiff]) {
+    for d in diffs {
+        let summary = format!(
+            "{} new, {} resolved, {} unchanged",
+            d.new_threats.len(),
+            d.resolved_threats.len(),
+            d.unchanged_threats.len()
+        );
+        let status = if d.new_threats.is_empty() {
+            "✓".green()
+        } else {
+            "✗".red()
+        };
+        println!("{} {} ({})", status, d.result.server.name.bold(), summary);
+
+        if d.previous_scanned_at.is_none() {
+            println!("    {}", "No previous scan; all threats are new".dimmed());
+        }
+
+        for threat in &d.new_threats {
+            println!(
+                "  {} {}",
+                format!("+ [{}]", threat.severity).red().bold(),
+                threat.title.red()
+            );
+        }
+        for threat in &d.resolved_threats {
+            println!(
+                "  {} {}",
+                format!("- [{}]", threat.severity).green(),
+                threat.title.strikethrough()
+            );
+        }
+
+        if d.has_changes() {
+            println!();
+        }
+    }
+
+    let total_new: usize = diffs.iter().map(|d| d.new_threats.len()).sum();
+    let total_resolved: usize = diffs.iter().map(|d| d.resolved_threats.len()).sum();
+
+    println!("\n{}", "─".repeat(50));
+    println!(
+        "Scanned {} servers: {} new, {} resolved threats",
+        diffs.len().to_string().bold(),
+        total_new.to_string().red().bold(),
+        total_resolved.to_string().green().bold()
+    );
 }
 
-fn summarize_threats(threats: &[scanner::Threat]) -> String {
+fn summarize_threats(threats: &[scanner::Threat], display: DisplayConfig) -> String {
     if threats.is_empty() {
         return "no threats".green().to_string();
     }
@@ -247,7 +793,13 @@ fn summarize_threats(threats: &[scanner::Threat]) -> String {
         parts.push(format!("{} low", n).blue().to_string());
     }
     if let Some(&n) = by_severity.get(&Severity::Info) {
-        parts.push(format!("{} info", n).dimmed().to_string());
+        let info = format!("{} info", n);
+        let info = if display.info_as_warning {
+            info.yellow()
+        } else {
+            info.dimmed()
+        };
+        parts.push(info.to_string());
     }
 
     parts.join(", ")
@@ -259,51 +811,94 @@ fn print_json_output(results: &[ScanResult]) -> Result<()> {
     Ok(())
 }
 
+/// Scan results as in [`print_json_output`], each with a `diff` key holding
+/// the new, resolved, and unchanged threats.
+fn print_diff_json_output(diffs: &[ScanDiff]) -> Result<()> {
+    let entries = diffs
+        .iter()
+        .map(|d| {
+            let mut entry = serde_json::to_value(&d.result)?;
+            entry["diff"] = serde_json::to_value(d)?;
+            Ok(entry)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    println!("{}", serde_json::to_string_pretty(&entries)?);
+    Ok(())
+}
+
+/// Each diff's result with only its new threats.
+fn new_threat_results(diffs: &[ScanDiff]) -> Vec<ScanResult> {
+    diffs
+        .iter()
+        .map(|d| ScanResult {
+            threats: d.new_threats.clone(),
+            ..d.result.clone()
+        })
+        .collect()
+}
+
+/// Print one GitHub Actions workflow command per threat so findings show up
+/// as annotations on the run and pull request.
+fn print_github_output(results: &[ScanResult]) {
+    for result in results {
+        for threat in &result.threats {
+            let command = match threat.severity {
+                Severity::Critical | Severity::High => "error",
+                Severity::Medium => "warning",
+                Severity::Low | Severity::Info => "notice",
+            };
+            let message = if threat.message.is_empty() {
+                threat.title.clone()
+            } else {
+                format!("{}: {}", threat.title, threat.message)
+            };
+
+            println!(
+                "::{} title={},file={}::{}",
+                command,
+                escape_github_property(&threat.id),
+                escape_github_property(&result.server.name),
+                escape_github_data(&message)
+            );
+        }
+    }
+}
+
+fn escape_github_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_github_property(s: &str) -> String {
+    escape_github_data(s)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
 fn print_sarif_output(results: &[ScanResult]) -> Result<()> {
-    // Basic SARIF 2.1.0 output
-    let sarif = serde_json::json!({
-        "$schema": "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/master/Schemata/sarif-schema-2.1.0.json",
-        "version": "2.1.0",
-        "runs": [{
-            "tool": {
-                "driver": {
-                    "name": "mcp-scanner",
-                    "version": env!("CARGO_PKG_VERSION"),
-                    "informationUri": "https://github.com/oabraham1/mcp-scanner"
-                }
-            },
-            "results": results.iter().flat_map(|r| {
-                r.threats.iter().map(|t| {
-                    serde_json::json!({
-                        "ruleId": t.id,
-                        "level": match t.severity {
-                            Severity::Critical | Severity::High => "error",
-                            Severity::Medium => "warning",
-                            _ => "note"
-                        },
-                        "message": { "text": t.message },
-                        "locations": [{
-                            "physicalLocation": {
-                                "artifactLocation": {
-                                    "uri": r.server.name.clone()
-                                }
-                            }
-                        }]
-                    })
-                })
-            }).collect::<Vec<_>>()
-        }]
-    });
+    let sarif = scanner::report::sarif::SarifReport::from_results(results);
+    println!("{}", serde_json::to_string_pretty(&sarif)?);
+    Ok(())
+}
 
-    let json = serde_json::to_string_pretty(&sarif)?;
-    println!("{}", json);
+fn print_junit_output(results: &[ScanResult]) -> Result<()> {
+    let junit = scanner::report::junit::JunitReport::from_results(results);
+    print!("{}", junit);
     Ok(())
 }
 
-async fn cmd_watch(clients: Option<String>) -> Result<()> {
+fn print_markdown_output(results: &[ScanResult]) {
+    print!(
+        "{}",
+        scanner::report::markdown::MarkdownReport::from_results(results)
+    );
+}
+
+async fn cmd_watch(clients: Option<String>, config: WatchConfig) -> Result<()> {
     use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
     use std::collections::HashSet;
-    use std::sync::mpsc::channel;
 
     println!("{}", "Starting watch mode...".cyan());
     println!("{}", "Press Ctrl+C to stop.\n".dimmed());
@@ -334,13 +929,29 @@ async fn cmd_watch(clients: Option<String>) -> Result<()> {
     println!();
 
     let scanner = Scanner::new();
+    let notifier = WebhookNotifier::new(config::Config::load_merged()?.notifications);
 
     println!("{}", "Running initial scan...".cyan());
-    run_watch_scan(&scanner, &servers).await;
-
-    let (tx, rx) = channel();
-    let mut watcher = RecommendedWatcher::new(tx, Config::default())
-        .map_err(|e| error::Error::Other(format!("Failed to create watcher: {}", e)))?;
+    // Threats from the previous scan of each server; only new ones are notified
+    let mut known_threats: HashMap<String, Vec<scanner::Threat>> =
+        run_watch_scan(&scanner, &servers)
+            .await
+            .into_iter()
+            .map(|r| (r.server.name, r.threats))
+            .collect();
+
+    let (tx, mut schedule) = RescanSchedule::new(&config);
+    let mut watcher = RecommendedWatcher::new(
+        move |event: notify::Result<notify::Event>| match event {
+            Ok(event) if event.kind.is_modify() || event.kind.is_create() => {
+                let _ = tx.send(());
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("{}", format!("Watch error: {}", e).red()),
+        },
+        Config::default(),
+    )
+    .map_err(|e| error::Error::Other(format!("Failed to create watcher: {}", e)))?;
 
     for path in &config_paths {
         watcher
@@ -350,35 +961,38 @@ async fn cmd_watch(clients: Option<String>) -> Result<()> {
             })?;
     }
 
-    loop {
-        match rx.recv() {
-            Ok(Ok(event)) => {
-                if event.kind.is_modify() || event.kind.is_create() {
-                    println!("\n{}", "Config changed, re-scanning...".cyan());
-
-                    let new_servers = if let Some(ref client_name) = clients {
-                        discover_from_client(client_name).unwrap_or_default()
-                    } else {
-                        discover_all().unwrap_or_default()
-                    };
+    while let Some(trigger) = schedule.next().await {
+        match trigger {
+            Rescan::ConfigChanged => println!("\n{}", "Config changed, re-scanning...".cyan()),
+            Rescan::Interval => println!("\n{}", "Interval elapsed, re-scanning...".cyan()),
+        }
+
+        let new_servers = if let Some(ref client_name) = clients {
+            discover_from_client(client_name).unwrap_or_default()
+        } else {
+            discover_all().unwrap_or_default()
+        };
 
-                    run_watch_scan(&scanner, &new_servers).await;
+        for result in run_watch_scan(&scanner, &new_servers).await {
+            let previous = known_threats.remove(&result.server.name);
+            if notifier.is_enabled() {
+                let diff = ScanDiff::new(result.clone(), None, previous.as_deref().unwrap_or(&[]));
+                if let Err(e) = notifier
+                    .notify_threats(&result.server.name, &diff.new_threats)
+                    .await
+                {
+                    eprintln!("{}", format!("Warning: {}", e).yellow());
                 }
             }
-            Ok(Err(e)) => {
-                eprintln!("{}", format!("Watch error: {}", e).red());
-            }
-            Err(e) => {
-                eprintln!("{}", format!("Channel error: {}", e).red());
-                break;
-            }
+            known_threats.insert(result.server.name, result.threats);
         }
     }
 
     Ok(())
 }
 
-async fn run_watch_scan(scanner: &Scanner, servers: &[ServerConfig]) {
+async fn run_watch_scan(scanner: &Scanner, servers: &[ServerConfig]) -> Vec<ScanResult> {
+    let mut results = Vec::new();
     for server in servers {
         match scanner.scan(server).await {
             Ok(result) => {
@@ -411,6 +1025,7 @@ async fn run_watch_scan(scanner: &Scanner, servers: &[ServerConfig]) {
                     };
                     println!("  {} {}", severity_str, threat.title);
                 }
+                results.push(result);
             }
             Err(e) => {
                 eprintln!(
@@ -422,64 +1037,418 @@ async fn run_watch_scan(scanner: &Scanner, servers: &[ServerConfig]) {
             }
         }
     }
+    results
 }
 
-async fn cmd_proxy(server: Option<String>, _config: Option<std::path::PathBuf>) -> Result<()> {
-    let server_cmd = server.ok_or_else(|| {
-        error::Error::Other("--server argument required for proxy mode".to_string())
-    })?;
-
-    let parts: Vec<String> = shell_words::split(&server_cmd)
-        .map_err(|e| error::Error::Other(format!("Invalid server command: {}", e)))?;
+#[allow(clippy::too_many_arguments)] // One per proxy option
+async fn cmd_proxy(
+    server: Option<String>,
+    server_url: Option<String>,
+    config: Option<std::path::PathBuf>,
+    server_name: Option<String>,
+    client_id: Option<String>,
+    transparent: bool,
+    dry_run: bool,
+    max_request_size: Option<usize>,
+    upstream_timeout: Option<Duration>,
+    circuit_breaker: Option<(u32, u64, u64)>,
+    log_file: Option<(std::path::PathBuf, u64)>,
+    audit_responses: bool,
+) -> Result<()> {
+    let rules_path = match config {
+        Some(path) => Some(path),
+        None => config::Config::load_merged()?.proxy.rules_path,
+    };
+    let mut proxy_config = match &rules_path {
+        Some(path) => proxy::ProxyConfig::load(path)?,
+        None => proxy::ProxyConfig::default(),
+    };
 
-    if parts.is_empty() {
-        return Err(error::Error::Other("Empty server command".to_string()));
-    }
+    // A remote server has no command to run. --server overrides the config
+    // file's [server], and so does its name.
+    let (command, args, config_name) = match (&server_url, server, proxy_config.server.take()) {
+        (Some(_), _, _) => (String::new(), Vec::new(), None),
+        (None, Some(server_cmd), _) => {
+            let parts: Vec<String> = shell_words::split(&server_cmd)
+                .map_err(|e| error::Error::Other(format!("Invalid server command: {}", e)))?;
 
-    let command = parts[0].clone();
-    let args = parts[1..].to_vec();
+            if parts.is_empty() {
+                return Err(error::Error::Other("Empty server command".to_string()));
+            }
+            (parts[0].clone(), parts[1..].to_vec(), None)
+        }
+        (None, None, Some(server)) if !server.command.is_empty() => {
+            (server.command, server.args, server.name)
+        }
+        (None, None, Some(_)) => {
+            return Err(error::Error::Other(
+                "Empty server command in [server]".to_string(),
+            ))
+        }
+        (None, None, None) => {
+            return Err(error::Error::Other(
+                "--server, --server-url, or a [server] section in the --config file is required for proxy mode"
+                    .to_string(),
+            ))
+        }
+    };
+    let server_name = server_name
+        .or(config_name)
+        .unwrap_or_else(|| match &server_url {
+            Some(url) => proxy::sse_proxy::default_server_name(url),
+            None => proxy::interceptor::default_server_name(&command),
+        });
+    let max_request_size = max_request_size
+        .or(proxy_config.limits.max_request_bytes)
+        .unwrap_or(proxy::interceptor::DEFAULT_MAX_REQUEST_SIZE);
+    let upstream_timeout = upstream_timeout.or(proxy_config
+        .limits
+        .upstream_timeout_secs
+        .map(Duration::from_secs));
 
     // Set up database for audit logging
     let db_path = db::default_db_path()?;
     let pool = db::create_pool(&db_path)?;
 
+    let target = match &server_url {
+        Some(url) => url.clone(),
+        None => format!("{} {}", command, args.join(" ")),
+    };
     eprintln!(
         "{}",
-        format!("Proxying server: {} {}", command, args.join(" ")).cyan()
+        format!("Proxying server '{}': {}", server_name, target).cyan()
     );
 
-    let interceptor = proxy::ProxyInterceptor::new(command, args).with_db(pool);
+    let rules = match rules_path {
+        Some(path) => {
+            let applied = proxy_config
+                .rules
+                .iter()
+                .filter(|r| r.applies_to(&server_name))
+                .count();
+            eprintln!(
+                "{}",
+                format!("Loaded {} rule(s) from {}", applied, path.display()).dimmed()
+            );
+            proxy_config.rule_engine_for(&server_name)?
+        }
+        None => proxy::rules::RuleEngine::new(),
+    };
 
-    interceptor.run().await
-}
+    // Rules from the database are added on top, and reloaded when they change
+    let rules = proxy::rules::HotReloadableRuleEngine::with_store(
+        rules,
+        proxy::RuleStore::new(pool.clone()),
+    )?;
+    let _poller = rules.spawn_polling(proxy::rules::RULE_POLL_INTERVAL);
 
-async fn cmd_serve(port: u16, bind: String, headless: bool) -> Result<()> {
-    let db_path = db::default_db_path()?;
-    let pool = db::create_pool(&db_path)?;
+    let mut audit = proxy::ProxyAudit::new(pool);
+    if let Some((path, max_bytes)) = log_file {
+        audit = audit.with_log_file(path)?.with_log_max_bytes(max_bytes);
+    }
 
-    let url = format!("http://{}:{}", bind, port);
-    println!(
-        "{}",
-        format!("Starting mcp-scanner server at {}", url).cyan()
-    );
+    let mut interceptor = proxy::ProxyInterceptor::new(command, args)
+        .with_server_name(server_name)
+        .with_rules(rules)
+        .with_audit(audit)
+        .with_transparent_mode(transparent)
+        .with_dry_run(dry_run)
+        .with_audit_responses(audit_responses)
+        .with_max_request_size(max_request_size);
+    if transparent {
+        eprintln!(
+            "{}",
+            "Transparent mode: rules are not applied, calls are only audited".yellow()
+        );
+    }
+    if dry_run {
+        eprintln!(
+            "{}",
+            "Dry run: calls that rules would block are forwarded and audited as blocked".yellow()
+        );
+    }
+    if let Some(client_id) = client_id {
+        interceptor = interceptor.with_client_id(client_id);
+    }
+    if let Some(timeout) = upstream_timeout {
+        interceptor = interceptor.with_upstream_timeout(timeout);
+    }
+    if let Some((threshold, window_secs, recovery_secs)) = circuit_breaker {
+        interceptor = interceptor.with_circuit_breaker(threshold, window_secs, recovery_secs);
+    }
 
-    if !headless {
-        // Try to open browser
-        if let Err(e) = open::that(&url) {
-            eprintln!("{}", format!("Failed to open browser: {}", e).yellow());
+    match server_url {
+        Some(url) => {
+            proxy::SseProxyInterceptor::new(&url, &HashMap::new(), interceptor)?
+                .run()
+                .await
         }
+        None => interceptor.run().await,
     }
-
-    api::serve(pool, &bind, port).await
 }
 
-fn cmd_init(force: bool) -> Result<()> {
-    let home =
-        dirs::home_dir().ok_or_else(|| error::Error::Other("No home directory".to_string()))?;
-    let config_dir = home.join(".mcp-scanner");
-    let config_file = config_dir.join("config.toml");
-
-    if config_file.exists() && !force {
+#[allow(clippy::too_many_arguments)] // One per serve option
+async fn cmd_serve(
+    port: u16,
+    bind: String,
+    headless: bool,
+    scan_on_startup: bool,
+    api_key: Option<String>,
+    generate_api_key: bool,
+    metrics_port: Option<u16>,
+    tls: Option<api::tls::TlsOptions>,
+) -> Result<()> {
+    let api_key_hash = if generate_api_key {
+        let config_file = config::config_path()
+            .ok_or_else(|| error::Error::Other("No home directory".to_string()))?;
+        let key = api::auth::generate_api_key();
+        let hash = api::auth::hash_api_key(&key)?;
+        config::store_api_key_hash(&config_file, &hash)?;
+
+        println!("{}", format!("Generated API key: {}", key).green().bold());
+        println!(
+            "{}",
+            format!(
+                "Its hash is stored in {}; the key itself is not shown again.",
+                config_file.display()
+            )
+            .dimmed()
+        );
+        Some(hash)
+    } else if let Some(key) = api_key {
+        Some(api::auth::hash_api_key(&key)?)
+    } else {
+        config::Config::load_merged()?.serve.api_key_hash
+    };
+
+    let db_path = db::default_db_path()?;
+    let pool = db::create_pool(&db_path)?;
+
+    if scan_on_startup {
+        run_startup_scan(&pool).await;
+    }
+
+    tokio::spawn(warn_on_rule_expiry(pool.clone()));
+
+    let config = config::Config::load_merged()?;
+    let retention = (
+        config.audit.retention_days,
+        config.scan_results.retention_days,
+    );
+    if retention != (None, None) {
+        tokio::spawn(purge_expired_records(
+            pool.clone(),
+            retention.0,
+            retention.1,
+        ));
+    }
+
+    // Runs on the scheduler's own background tasks until the server exits
+    let mut scheduler = mcp_guard_core::schedule::ScanScheduler::new(pool.clone())
+        .await?
+        .with_notifier(WebhookNotifier::new(config.notifications));
+    if let Some(secs) = config.scan.timeout {
+        scheduler = scheduler.with_timeout(Duration::from_secs(secs));
+    }
+    if let Some(severity) = config.scan.min_severity {
+        scheduler = scheduler.with_min_severity(severity);
+    }
+    if let Some(path) = &config.scan.allowlist {
+        scheduler = scheduler.with_allowlist(scanner::Allowlist::load(path)?);
+    }
+    let scheduler = match scheduler.start().await {
+        Ok(()) => Some(std::sync::Arc::new(scheduler)),
+        Err(e) => {
+            eprintln!(
+                "{}",
+                format!("Scheduled scans are disabled: {}", e).yellow()
+            );
+            None
+        }
+    };
+
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    let url = format!("{}://{}:{}", scheme, bind, port);
+    println!(
+        "{}",
+        format!("Starting mcp-scanner server at {}", url).cyan()
+    );
+
+    if !headless {
+        // Try to open browser
+        if let Err(e) = open::that(&url) {
+            eprintln!("{}", format!("Failed to open browser: {}", e).yellow());
+        }
+    }
+
+    if api_key_hash.is_some() {
+        println!(
+            "{}",
+            "API key required: send it in the X-API-Key header or as ?api_key=".dimmed()
+        );
+    }
+
+    if matches!(
+        tls.as_ref().map(|t| &t.source),
+        Some(api::tls::TlsSource::SelfSigned)
+    ) {
+        println!(
+            "{}",
+            "Using a self-signed certificate; browsers will warn until it's trusted".dimmed()
+        );
+    }
+
+    api::serve(
+        pool,
+        scheduler,
+        &bind,
+        port,
+        api_key_hash.map(api::auth::ApiKey::from_hash),
+        metrics_port,
+        tls,
+    )
+    .await
+}
+
+/// Delete audit entries older than `audit_days` and scan results older than
+/// `scan_days`, at startup and then once a day while the server runs.
+async fn purge_expired_records(pool: db::DbPool, audit_days: Option<u32>, scan_days: Option<u32>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(24 * 60 * 60));
+
+    loop {
+        interval.tick().await;
+
+        if let Some(days) = audit_days {
+            let cutoff = Utc::now() - chrono::Duration::days(days.into());
+            match db::AuditLog::new(pool.clone()).purge_before(cutoff) {
+                Ok(deleted) => tracing::info!(deleted, days, "Purged old audit entries"),
+                Err(e) => eprintln!(
+                    "{}",
+                    format!("Failed to purge old audit entries: {}", e).red()
+                ),
+            }
+        }
+        if let Some(days) = scan_days {
+            let cutoff = Utc::now() - chrono::Duration::days(days.into());
+            match db::ScanResultStore::new(pool.clone()).purge_before(cutoff) {
+                Ok(deleted) => tracing::info!(deleted, days, "Purged old scan results"),
+                Err(e) => eprintln!(
+                    "{}",
+                    format!("Failed to purge old scan results: {}", e).red()
+                ),
+            }
+        }
+    }
+}
+
+/// Log a warning once for each enabled proxy rule that has passed its
+/// `expires_at`, checking every minute while the server runs.
+async fn warn_on_rule_expiry(pool: db::DbPool) {
+    let mut warned = std::collections::HashSet::new();
+    let mut interval = tokio::time::interval(Duration::from_secs(60));
+
+    loop {
+        interval.tick().await;
+
+        let expired = match expired_rules(&pool) {
+            Ok(expired) => expired,
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    format!("Failed to check proxy rule expiry: {}", e).red()
+                );
+                continue;
+            }
+        };
+
+        for (id, tool_pattern, expires_at) in expired {
+            if warned.insert(id.clone()) {
+                eprintln!(
+                    "{}",
+                    format!(
+                        "Proxy rule {} ({}) expired at {} and no longer applies",
+                        id, tool_pattern, expires_at
+                    )
+                    .yellow()
+                );
+            }
+        }
+    }
+}
+
+fn expired_rules(pool: &db::DbPool) -> Result<Vec<(String, String, String)>> {
+    let conn = pool.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, tool_pattern, expires_at FROM proxy_rules WHERE enabled = 1 AND expires_at IS NOT NULL",
+    )?;
+    let now = Utc::now();
+
+    let rules = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .filter_map(|r| r.ok())
+        .filter(|(_, _, expires_at): &(String, String, String)| {
+            DateTime::parse_from_rfc3339(expires_at).is_ok_and(|t| t < now)
+        })
+        .collect();
+    Ok(rules)
+}
+
+async fn run_startup_scan(pool: &db::DbPool) {
+    let servers = match discover_all() {
+        Ok(servers) => servers,
+        Err(e) => {
+            eprintln!("{}", format!("Initial scan failed: {}", e).red());
+            return;
+        }
+    };
+
+    let scanner = Scanner::new()
+        .with_timeout(Duration::from_secs(30))
+        .with_snapshot_backend(SnapshotBackend::Database(pool.clone()));
+    let store = db::ScanResultStore::new(pool.clone());
+    let outcome = scanner.scan_all(&servers).await;
+    for result in &outcome.results {
+        if let Err(e) = store.insert(result) {
+            tracing::error!(server = %result.server.name, error = %e, "Failed to save scan result");
+        }
+    }
+    for (server, e) in &outcome.errors {
+        tracing::error!(server = %server.name, error = %e, "Initial scan failed");
+    }
+
+    println!(
+        "{}",
+        format!(
+            "Initial scan complete: {} servers scanned",
+            outcome.results.len()
+        )
+        .green()
+    );
+}
+
+fn cmd_init(
+    force: bool,
+    api_key: Option<String>,
+    client: Option<String>,
+    project: bool,
+    from_remediation: Option<std::path::PathBuf>,
+) -> Result<()> {
+    let config_file = config::config_path()
+        .ok_or_else(|| error::Error::Other("No home directory".to_string()))?;
+    let config_dir = config_file
+        .parent()
+        .expect("config path has a parent directory");
+
+    if let Some(client) = client {
+        write_proxy_client_config(&client, config_dir, force)?;
+    }
+
+    if project {
+        return write_project_config(force);
+    }
+
+    // An existing config is kept, but still gets the API key if one is given
+    if config_file.exists() && !force {
         println!(
             "{}",
             format!(
@@ -488,37 +1457,353 @@ fn cmd_init(force: bool) -> Result<()> {
             )
             .yellow()
         );
-        return Ok(());
+    } else {
+        write_user_config(&config_file)?;
+    }
+
+    if let Some(key) = api_key {
+        config::store_api_key_hash(&config_file, &api::auth::hash_api_key(&key)?)?;
+        println!("{}", "Stored API key hash for `serve`".green());
+    }
+
+    if let Some(path) = from_remediation {
+        apply_remediation(&path, &config_file)?;
+    }
+
+    Ok(())
+}
+
+/// Append the `[[ignore]]` entries of a `scan --remediation-file` to the
+/// allowlist at `[scan] allowlist`, and its `[[rules]]` to the proxy rules
+/// file at `[proxy] rules_path`. An unset path is pointed at a new file
+/// next to `config_file`.
+fn apply_remediation(path: &std::path::Path, config_file: &std::path::Path) -> Result<()> {
+    // Loaded by both consumers first, so a file either would reject changes nothing
+    scanner::Allowlist::load(path)?;
+    proxy::ProxyConfig::load(path)?;
+    let mut remediation = std::fs::read_to_string(path)?
+        .parse::<toml::Table>()
+        .map_err(|e| error::Error::config_parse(path, e.to_string()))?;
+
+    let config = config::Config::load(config_file)?;
+    let config_dir = config_file
+        .parent()
+        .expect("config path has a parent directory");
+
+    if let Some(entries) = remediation.remove("ignore") {
+        let target = match config.scan.allowlist {
+            Some(target) => target,
+            None => {
+                let target = config_dir.join("allowlist.toml");
+                config::store_setting(config_file, "scan", "allowlist", &target.to_string_lossy())?;
+                target
+            }
+        };
+        let added = append_entries(&target, "ignore", entries, path)?;
+        println!(
+            "{}",
+            format!("Added {} suppression(s) to {}", added, target.display()).green()
+        );
     }
 
-    std::fs::create_dir_all(&config_dir)?;
+    if let Some(entries) = remediation.remove("rules") {
+        let target = match config.proxy.rules_path {
+            Some(target) => target,
+            None => {
+                let target = config_dir.join("rules.toml");
+                config::store_setting(
+                    config_file,
+                    "proxy",
+                    "rules_path",
+                    &target.to_string_lossy(),
+                )?;
+                target
+            }
+        };
+        let added = append_entries(&target, "rules", entries, path)?;
+        println!(
+            "{}",
+            format!("Added {} proxy rule(s) to {}", added, target.display()).green()
+        );
+    }
+
+    Ok(())
+}
+
+/// Append `entries`, an array of tables, to the TOML file at `target` as
+/// `[[key]]` tables, skipping any it already has. Returns how many were
+/// added.
+fn append_entries(
+    target: &std::path::Path,
+    key: &str,
+    entries: toml::Value,
+    source: &std::path::Path,
+) -> Result<usize> {
+    let content = if target.exists() {
+        std::fs::read_to_string(target)?
+    } else {
+        String::new()
+    };
+    let existing = content
+        .parse::<toml::Table>()
+        .map_err(|e| error::Error::config_parse(target, e.to_string()))?
+        .remove(key)
+        .and_then(|v| v.as_array().cloned())
+        .unwrap_or_default();
+    let new: Vec<toml::Value> = entries
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|entry| !existing.contains(entry))
+        .cloned()
+        .collect();
+    if new.is_empty() {
+        return Ok(0);
+    }
+
+    let added = new.len();
+    let fragment = toml::to_string(&toml::Table::from_iter([(
+        key.to_string(),
+        toml::Value::Array(new),
+    )]))
+    .map_err(|e| error::Error::Other(format!("Failed to write {}: {}", key, e)))?;
+
+    let mut content = content;
+    if !content.is_empty() {
+        content.push_str(if content.ends_with('\n') {
+            "\n"
+        } else {
+            "\n\n"
+        });
+    }
+    content.push_str(&format!("# Added from {}\n{}", source.display(), fragment));
+
+    if let Some(dir) = target.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(target, content)?;
+    Ok(added)
+}
+
+/// Write the default user config to `config_file`.
+fn write_user_config(config_file: &std::path::Path) -> Result<()> {
+    if let Some(config_dir) = config_file.parent() {
+        std::fs::create_dir_all(config_dir)?;
+    }
 
     let default_config = r#"# mcp-scanner configuration
 
+# Settings in .mcp-guard.toml in the current directory override these.
+
 [scan]
 timeout = 30  # seconds
-# concurrency = 4  # parallel scans
+concurrency = 4  # servers scanned in parallel
+max_description_length = 2000  # longer tool descriptions are flagged
+min_severity = "info"  # critical, high, medium, low, info
 
 [output]
-format = "table"  # table, json, sarif
+format = "table"  # table, json, sarif, github, junit, markdown
+show_info = false  # report info threats; `scan --include-info` shows them anyway
+
+# While `mcp-scanner serve` runs, older records are deleted once a day.
+# Remove a setting to keep records forever.
+[audit]
+retention_days = 90
+
+[scan_results]
+retention_days = 365
 
+# Proxy rules go in their own file, passed with `mcp-scanner proxy --config <file>`
+# or set here:
+#
 # [proxy]
-# Example proxy rules:
-# [[proxy.rules]]
+# rules_path = "/path/to/rules.toml"
+#
+# Actions: allow, block (with reason), rate_limit (with max_calls,
+# window_secs, and optionally per_client), log. Higher priority rules are
+# checked first.
+#
+# [[rules]]
+# id = "block-dangerous"
 # tool_pattern = "dangerous_*"
 # action = "block"
 # reason = "Blocked by policy"
+# priority = 10
+#
+# [[rules]]
+# id = "limit-api"
+# tool_pattern = "api_*"
+# action = "rate_limit"
+# max_calls = 10
+# window_secs = 60
 "#;
 
-    std::fs::write(&config_file, default_config)?;
+    std::fs::write(config_file, default_config)?;
     println!(
         "{}",
         format!("Created config at {}", config_file.display()).green()
     );
+    Ok(())
+}
+
+/// Write `.mcp-guard.toml` to the current directory, with every setting
+/// commented out so the user config still applies until one is set.
+fn write_project_config(force: bool) -> Result<()> {
+    let path = std::path::Path::new(config::PROJECT_CONFIG_FILE);
+    if path.exists() && !force {
+        println!(
+            "{}",
+            format!(
+                "{} already exists. Use --force to overwrite.",
+                path.display()
+            )
+            .yellow()
+        );
+        return Ok(());
+    }
+
+    let project_config = r#"# mcp-scanner project configuration
+#
+# Settings here override ~/.mcp-scanner/config.toml when running from this
+# directory. Command-line flags override both. Only the settings below can be
+# set here; the API key, notifications, proxy rules, severity filter, and
+# retention only come from ~/.mcp-scanner/config.toml.
+
+[scan]
+# timeout = 30
+# concurrency = 4
+# max_description_length = 2000
+
+[output]
+# format = "sarif"
+# show_info = true
+"#;
 
+    std::fs::write(path, project_config)?;
+    println!(
+        "{}",
+        format!("Created project config at {}", path.display()).green()
+    );
     Ok(())
 }
 
+/// Write `<client>_proxy.json` to `config_dir`: the client's servers in the
+/// `mcpServers` format, with each local server started through `proxy`.
+fn write_proxy_client_config(
+    client: &str,
+    config_dir: &std::path::Path,
+    force: bool,
+) -> Result<()> {
+    let discovery = discovery::find_client(client)?;
+    let servers = discovery.discover()?;
+    if servers.is_empty() {
+        return Err(error::Error::Other(format!(
+            "No MCP servers found for {}",
+            discovery.name()
+        )));
+    }
+
+    let file_name = format!(
+        "{}_proxy.json",
+        discovery.name().to_lowercase().replace(' ', "_")
+    );
+    let proxy_file = config_dir.join(file_name);
+    if proxy_file.exists() && !force {
+        println!(
+            "{}",
+            format!(
+                "{} already exists. Use --force to overwrite.",
+                proxy_file.display()
+            )
+            .yellow()
+        );
+        return Ok(());
+    }
+
+    // Clients often start servers with a minimal PATH, so use the full path
+    let exe = std::env::current_exe()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| "mcp-scanner".to_string());
+    let mut skipped = Vec::new();
+    let wrapped: Vec<ServerConfig> = servers
+        .iter()
+        .map(|server| {
+            if server.transport != discovery::TransportType::Stdio {
+                skipped.push(server.name.as_str());
+                return server.clone();
+            }
+            let command = std::iter::once(&server.command)
+                .chain(&server.args)
+                .map(String::as_str);
+            let mut proxied = server.clone();
+            proxied.command = exe.clone();
+            proxied.args = vec![
+                "proxy".to_string(),
+                "--server".to_string(),
+                shell_words::join(command),
+                "--server-name".to_string(),
+                server.name.clone(),
+            ];
+            proxied
+        })
+        .collect();
+
+    // Env values are copied as written, so inline secrets end up here too
+    std::fs::create_dir_all(config_dir)?;
+    let json = discovery::clients::to_mcp_servers_json(&wrapped);
+    write_private_file(&proxy_file, &(serde_json::to_string_pretty(&json)? + "\n"))?;
+
+    println!(
+        "{}",
+        format!(
+            "Wrote {} proxied server(s) for {} to {}",
+            wrapped.len() - skipped.len(),
+            discovery.name(),
+            proxy_file.display()
+        )
+        .green()
+    );
+    if !skipped.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "Remote servers are copied unchanged; the proxy only wraps local servers: {}",
+                skipped.join(", ")
+            )
+            .yellow()
+        );
+    }
+    let original = servers
+        .iter()
+        .find_map(|s| s.config_path())
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "its config file".to_string());
+    println!(
+        "To route {} through the proxy, replace the \"mcpServers\" section of {} with the one in {}, then restart {}.",
+        discovery.name(),
+        original,
+        proxy_file.display(),
+        discovery.name()
+    );
+    Ok(())
+}
+
+/// Write `contents` to `path`, readable and writable only by the current
+/// user on Unix.
+fn write_private_file(path: &std::path::Path, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    // The mode only applies to new files
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+    file.write_all(contents.as_bytes())
+}
+
 fn cmd_completions(shell: cli::Shell) {
     use clap::CommandFactory;
     use clap_complete::generate;
@@ -533,13 +1818,32 @@ fn cmd_completions(shell: cli::Shell) {
     );
 }
 
-fn cmd_list(client: Option<String>) -> Result<()> {
+fn cmd_list(client: Option<String>, merge: bool, output: OutputFormat) -> Result<()> {
     let servers = if let Some(client_name) = client {
         discover_from_client(&client_name)?
+    } else if merge {
+        discovery::discover_merged()?
     } else {
         discover_all()?
     };
 
+    // The JSON is what `scan --config` reads back
+    match output {
+        _ if merge => {}
+        OutputFormat::Json => {}
+        OutputFormat::Table => return print_server_list(&servers),
+        _ => {
+            return Err(error::Error::Other(
+                "list supports table and json output".to_string(),
+            ))
+        }
+    }
+    let json = discovery::clients::to_mcp_servers_json(&servers);
+    println!("{}", serde_json::to_string_pretty(&json)?);
+    Ok(())
+}
+
+fn print_server_list(servers: &[ServerConfig]) -> Result<()> {
     if servers.is_empty() {
         println!("{}", "No MCP servers found.".yellow());
         return Ok(());
@@ -547,7 +1851,7 @@ fn cmd_list(client: Option<String>) -> Result<()> {
 
     println!("{}", format!("Found {} server(s):\n", servers.len()).cyan());
 
-    for server in &servers {
+    for server in servers {
         println!("  {} {}", "•".blue(), server.name.bold());
         println!("    Command: {} {}", server.command, server.args.join(" "));
         println!("    Source: {}", server.display_source().dimmed());
@@ -561,3 +1865,1018 @@ fn cmd_list(client: Option<String>) -> Result<()> {
 
     Ok(())
 }
+
+fn cmd_rules(action: RulesAction, output: OutputFormat) -> Result<()> {
+    use proxy::rules::{ProxyRule, RuleAction, RuleEngine, RuleResult};
+
+    let store = proxy::RuleStore::new(db::create_pool(&db::default_db_path()?)?);
+    let not_found = |id: &str| error::Error::Other(format!("No rule with ID '{}'", id));
+
+    match action {
+        RulesAction::List => {
+            let rules = store.list()?;
+            match output {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&rules)?);
+                    return Ok(());
+                }
+                OutputFormat::Table => {}
+                _ => {
+                    return Err(error::Error::Other(
+                        "rules supports table and json output".to_string(),
+                    ))
+                }
+            }
+
+            if rules.is_empty() {
+                println!("{}", "No proxy rules stored.".yellow());
+                return Ok(());
+            }
+
+            for stored in &rules {
+                let rule = &stored.rule;
+                let status = if !stored.enabled {
+                    " [disabled]".dimmed()
+                } else if rule.is_expired() {
+                    " [expired]".dimmed()
+                } else {
+                    "".normal()
+                };
+                println!(
+                    "  {} {} {} → {} (priority {}){}",
+                    "•".blue(),
+                    rule.id.dimmed(),
+                    rule.tool_pattern.bold(),
+                    rule.action,
+                    rule.priority,
+                    status
+                );
+            }
+        }
+        RulesAction::Add {
+            pattern,
+            action,
+            reason,
+            priority,
+        } => {
+            let mut action: RuleAction = action.parse()?;
+            if let (RuleAction::Block { reason: r }, Some(reason)) = (&mut action, reason) {
+                *r = reason;
+            }
+            let rule = ProxyRule {
+                id: uuid::Uuid::new_v4().to_string(),
+                tool_pattern: pattern,
+                action,
+                priority: priority.unwrap_or(0),
+                expires_at: None,
+            };
+            // Compiling the rule rejects bad patterns before they're stored
+            RuleEngine::new().add_rule(rule.clone())?;
+            store.insert(&rule)?;
+            println!(
+                "{} Added rule {}: {} → {}",
+                "✓".green(),
+                rule.id,
+                rule.tool_pattern.bold(),
+                rule.action
+            );
+        }
+        RulesAction::Remove { id } => {
+            if !store.delete(&id)? {
+                return Err(not_found(&id));
+            }
+            println!("{} Removed rule {}", "✓".green(), id);
+        }
+        RulesAction::Enable { id } => {
+            if !store.set_enabled(&id, true)? {
+                return Err(not_found(&id));
+            }
+            println!("{} Enabled rule {}", "✓".green(), id);
+        }
+        RulesAction::Disable { id } => {
+            if !store.set_enabled(&id, false)? {
+                return Err(not_found(&id));
+            }
+            println!("{} Disabled rule {}", "✓".green(), id);
+        }
+        RulesAction::Test { tool } => {
+            let rules = store.list()?;
+            let mut engine = RuleEngine::new();
+            let mut matched = false;
+
+            for stored in rules.iter().filter(|r| r.rule.matches(&tool)) {
+                matched = true;
+                let rule = &stored.rule;
+                let status = if !stored.enabled {
+                    " [disabled, ignored]".dimmed()
+                } else if rule.is_expired() {
+                    " [expired, ignored]".dimmed()
+                } else {
+                    "".normal()
+                };
+                println!(
+                    "  {} {} {} → {} (priority {}){}",
+                    "•".blue(),
+                    rule.id.dimmed(),
+                    rule.tool_pattern.bold(),
+                    rule.action,
+                    rule.priority,
+                    status
+                );
+                if stored.enabled {
+                    engine.add_rule(rule.clone())?;
+                }
+            }
+
+            if !matched {
+                println!("{}", format!("No rules match '{}'.", tool).dimmed());
+            }
+
+            let verdict = match engine.evaluate(&tool).swap_remove(0) {
+                RuleResult::Block { reason } => format!("blocked: {}", reason).red(),
+                RuleResult::RateLimited { .. } => "rate limited".yellow(),
+                _ => "allowed".green(),
+            };
+            println!("\n{} would be {}", tool.bold(), verdict);
+        }
+    }
+
+    Ok(())
+}
+
+fn cmd_snapshot(action: SnapshotAction) -> Result<()> {
+    // Same store `scan` uses: the database, or files if it can't be opened
+    let backend = match db::default_db_path().and_then(|path| db::create_pool(&path)) {
+        Ok(pool) => SnapshotBackend::Database(pool),
+        Err(e) => {
+            tracing::warn!(error = %e, "Could not open database, using file snapshots");
+            SnapshotBackend::File
+        }
+    };
+    let store = scanner::SnapshotStorage::open(backend)?;
+
+    let load = |server: &str| {
+        store.load(server)?.ok_or_else(|| {
+            error::Error::Other(format!("No snapshot stored for server '{}'", server))
+        })
+    };
+
+    match action {
+        SnapshotAction::List => {
+            let snapshots = store.list()?;
+            if snapshots.is_empty() {
+                println!("{}", "No snapshots stored.".yellow());
+                return Ok(());
+            }
+
+            for (server, snapshot) in &snapshots {
+                println!(
+                    "  {} {} ({} tools, {})",
+                    "•".blue(),
+                    server.bold(),
+                    snapshot.tools.len(),
+                    snapshot
+                        .created_at
+                        .format("%Y-%m-%d %H:%M:%S UTC")
+                        .to_string()
+                        .dimmed()
+                );
+            }
+        }
+        SnapshotAction::Show { server } => {
+            let snapshot = load(&server)?;
+            println!(
+                "{} ({} tools, {})\n",
+                server.bold(),
+                snapshot.tools.len(),
+                snapshot.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+            );
+
+            let mut tools: Vec<_> = snapshot.tools.iter().collect();
+            tools.sort_by_key(|(name, _)| name.as_str());
+            for (name, tool) in tools {
+                println!("  {} {}", "•".blue(), name.bold());
+                println!("    Hash: {}", tool.description_hash.dimmed());
+                if let Some(description) = &tool.description {
+                    println!("    {}", description.dimmed());
+                }
+            }
+        }
+        SnapshotAction::Delete { server } => {
+            if store.delete(&server)? {
+                println!("{}", format!("Deleted snapshot for '{}'", server).green());
+            } else {
+                println!(
+                    "{}",
+                    format!("No snapshot stored for server '{}'", server).yellow()
+                );
+            }
+        }
+        SnapshotAction::Export { server, file } => {
+            let snapshot = load(&server)?;
+            std::fs::write(&file, serde_json::to_string_pretty(&snapshot)?)?;
+            println!(
+                "{}",
+                format!("Exported snapshot for '{}' to {}", server, file.display()).green()
+            );
+        }
+        SnapshotAction::Import { file, server } => {
+            let server = match server {
+                Some(server) => server,
+                None => file
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .map(str::to_string)
+                    .ok_or_else(|| {
+                        error::Error::Other(
+                            "Could not derive a server name from the file name; pass --server"
+                                .to_string(),
+                        )
+                    })?,
+            };
+            let content = std::fs::read_to_string(&file)?;
+            let snapshot: scanner::Snapshot = serde_json::from_str(&content)?;
+            store.import(&server, &snapshot)?;
+            println!(
+                "{}",
+                format!(
+                    "Imported snapshot for '{}' ({} tools)",
+                    server,
+                    snapshot.tools.len()
+                )
+                .green()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn cmd_audit_export(path: &std::path::Path, server: Option<String>, blocked: bool) -> Result<()> {
+    let pool = db::create_pool(&db::default_db_path()?)?;
+    let log = db::AuditLog::new(pool);
+
+    let file = std::fs::File::create(path)?;
+    let count = log.export_csv(std::io::BufWriter::new(file), server.as_deref(), blocked)?;
+    println!(
+        "{} Exported {} audit entries to {}",
+        "✓".green(),
+        count,
+        path.display()
+    );
+    Ok(())
+}
+
+fn cmd_audit(
+    server: Option<String>,
+    tool: Option<String>,
+    blocked: bool,
+    limit: usize,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    output: OutputFormat,
+) -> Result<()> {
+    let pool = db::create_pool(&db::default_db_path()?)?;
+    let log = db::AuditLog::new(pool);
+
+    let entries = if since.is_some() || until.is_some() {
+        log.search_with_time_range(
+            since.unwrap_or(DateTime::UNIX_EPOCH),
+            until.unwrap_or_else(Utc::now),
+            server.as_deref(),
+            tool.as_deref(),
+            blocked,
+            limit,
+        )?
+    } else {
+        log.search(server.as_deref(), tool.as_deref(), blocked, limit)?
+    };
+
+    match output {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+            return Ok(());
+        }
+        OutputFormat::Table => {}
+        _ => {
+            return Err(error::Error::Other(
+                "audit supports table and json output".to_string(),
+            ))
+        }
+    }
+
+    if entries.is_empty() {
+        println!("{}", "No matching audit entries.".yellow());
+        return Ok(());
+    }
+
+    let server_width = entries
+        .iter()
+        .map(|e| e.server_name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("SERVER".len());
+    let tool_width = entries
+        .iter()
+        .map(|e| e.tool_name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("TOOL".len());
+
+    println!(
+        "{}",
+        format!(
+            "{:>6}  {:<19}  {:<server_width$}  {:<tool_width$}  {:<7}  {:>8}",
+            "ID", "TIMESTAMP", "SERVER", "TOOL", "BLOCKED", "DURATION"
+        )
+        .bold()
+    );
+    for entry in &entries {
+        let row = format!(
+            "{:>6}  {:<19}  {:<server_width$}  {:<tool_width$}  {:<7}  {:>6}ms",
+            entry.id,
+            entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            entry.server_name,
+            entry.tool_name,
+            if entry.blocked { "yes" } else { "no" },
+            entry.duration_ms
+        );
+        if entry.blocked {
+            println!("{}", row.red());
+            if let Some(reason) = &entry.block_reason {
+                println!("{:>8}{}", "", reason.dimmed());
+            }
+        } else {
+            println!("{}", row);
+        }
+    }
+
+    Ok(())
+}
+
+/// Number of tools listed in each ranking printed by `audit --stats`.
+const AUDIT_STATS_TOP: usize = 5;
+
+fn cmd_audit_purge(cutoff: DateTime<Utc>) -> Result<()> {
+    let pool = db::create_pool(&db::default_db_path()?)?;
+    let deleted = db::AuditLog::new(pool).purge_before(cutoff)?;
+    println!(
+        "{}",
+        format!(
+            "Deleted {} audit entries logged before {}",
+            deleted,
+            cutoff.format("%Y-%m-%d %H:%M:%S UTC")
+        )
+        .green()
+    );
+    Ok(())
+}
+
+fn cmd_audit_stats(since: Option<DateTime<Utc>>, output: OutputFormat) -> Result<()> {
+    let pool = db::create_pool(&db::default_db_path()?)?;
+    let stats = db::AuditLog::new(pool).stats(since)?;
+
+    match output {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&stats)?);
+            return Ok(());
+        }
+        OutputFormat::Table => {}
+        _ => {
+            return Err(error::Error::Other(
+                "audit --stats supports table and json output".to_string(),
+            ))
+        }
+    }
+
+    match since {
+        Some(since) => println!(
+            "{}",
+            format!("Audit log since {}", since.format("%Y-%m-%d %H:%M:%S UTC")).bold()
+        ),
+        None => println!("{}", "Audit log".bold()),
+    }
+    println!("  Calls:     {}", stats.total_calls);
+    println!(
+        "  Blocked:   {}",
+        if stats.blocked_calls > 0 {
+            stats.blocked_calls.to_string().red()
+        } else {
+            stats.blocked_calls.to_string().normal()
+        }
+    );
+    println!("  Tools:     {}", stats.unique_tools);
+    println!("  Servers:   {}", stats.unique_servers);
+    println!(
+        "  Duration:  {:.1}ms average, {}ms p99",
+        stats.avg_duration_ms, stats.p99_duration_ms
+    );
+
+    print_top_tools(
+        &format!("Top {} most-called tools", AUDIT_STATS_TOP),
+        &stats.calls_per_tool,
+        "calls",
+    );
+    print_top_tools(
+        &format!("Top {} most-blocked tools", AUDIT_STATS_TOP),
+        &stats.blocked_per_tool,
+        "blocked",
+    );
+    Ok(())
+}
+
+fn print_top_tools(title: &str, counts: &HashMap<String, u64>, label: &str) {
+    let mut top: Vec<_> = counts.iter().collect();
+    top.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    top.truncate(AUDIT_STATS_TOP);
+
+    println!("\n{}", title.bold());
+    if top.is_empty() {
+        println!("  {}", "None".dimmed());
+        return;
+    }
+    let width = top
+        .iter()
+        .map(|(tool, _)| tool.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (tool, count) in top {
+        println!("  {:<width$}  {:>6} {}", tool, count, label);
+    }
+}
+
+async fn cmd_check(server: String, timeout: u64, output: OutputFormat) -> Result<()> {
+    if !matches!(output, OutputFormat::Table | OutputFormat::Json) {
+        return Err(error::Error::Other(
+            "check supports table and json output".to_string(),
+        ));
+    }
+
+    let config = server_from_command(&server)?;
+    let scanner = Scanner::new().with_timeout(Duration::from_secs(timeout));
+    let check = match scanner.check(&config).await {
+        Ok(check) => check,
+        Err(e) => {
+            eprintln!("{} {}: {}", "FAIL".red().bold(), server, e);
+            telemetry::exit(1);
+        }
+    };
+
+    match output {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&check)?),
+        _ => {
+            let yes_no = |supported: bool| {
+                if supported {
+                    "yes".green()
+                } else {
+                    "no".dimmed()
+                }
+            };
+            println!(
+                "{} {} {}",
+                "OK".green().bold(),
+                check.server_name.bold(),
+                check.server_version.dimmed()
+            );
+            println!("  Protocol version: {}", check.protocol_version);
+            println!(
+                "  Tools:            {} ({})",
+                yes_no(check.tools),
+                check.tool_count
+            );
+            println!("  Resources:        {}", yes_no(check.resources));
+            println!("  Prompts:          {}", yes_no(check.prompts));
+            println!("  Responded in {} ms", check.duration_ms);
+        }
+    }
+    Ok(())
+}
+
+fn cmd_diff(
+    server: String,
+    run1: Option<i64>,
+    run2: Option<i64>,
+    output: OutputFormat,
+) -> Result<()> {
+    if !matches!(output, OutputFormat::Table | OutputFormat::Json) {
+        return Err(error::Error::Other(
+            "diff supports table and json output".to_string(),
+        ));
+    }
+
+    let store = db::ScanResultStore::new(db::create_pool(&db::default_db_path()?)?);
+    let (run1, run2) = match (run1, run2) {
+        (Some(run1), Some(run2)) => (run1, run2),
+        (run1, run2) => {
+            // Newest first
+            let total = store.count(Some(&server))? as usize;
+            let ids: Vec<i64> = store
+                .list(Some(&server), total, 0)?
+                .iter()
+                .map(|r| r.id)
+                .collect();
+            let run2 = match run2 {
+                Some(id) => id,
+                None => *ids.first().ok_or_else(|| {
+                    error::Error::Other(format!("No stored scans for {}", server))
+                })?,
+            };
+            let run1 = match run1 {
+                Some(id) => id,
+                None => ids
+                    .iter()
+                    .skip_while(|&&id| id != run2)
+                    .nth(1)
+                    .copied()
+                    .ok_or_else(|| {
+                        error::Error::Other(format!(
+                            "No scan of {} before #{} to compare with",
+                            server, run2
+                        ))
+                    })?,
+            };
+            (run1, run2)
+        }
+    };
+
+    let from = store.get(run1)?;
+    let to = store.get(run2)?;
+    for (id, result) in [(run1, &from), (run2, &to)] {
+        if result.server.name != server {
+            return Err(error::Error::Other(format!(
+                "Scan #{} is of {}, not {}",
+                id, result.server.name, server
+            )));
+        }
+    }
+    let diff = scanner::RunDiff::new(&from, &to);
+
+    match output {
+        OutputFormat::Json => {
+            let mut value = serde_json::to_value(&diff)?;
+            value["run1"] = run1.into();
+            value["run2"] = run2.into();
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        }
+        _ => {
+            println!(
+                "{} #{} ({}) → #{} ({})",
+                diff.server.bold(),
+                run1,
+                diff.from_scanned_at.to_rfc3339().dimmed(),
+                run2,
+                diff.to_scanned_at.to_rfc3339().dimmed()
+            );
+
+            for threat in &diff.new_threats {
+                println!(
+                    "  {} {}",
+                    format!("+ [{}]", threat.severity).red().bold(),
+                    threat.title.red()
+                );
+            }
+            for threat in &diff.resolved_threats {
+                println!(
+                    "  {} {}",
+                    format!("- [{}]", threat.severity).green(),
+                    threat.title.strikethrough()
+                );
+            }
+            for tool in &diff.added_tools {
+                println!("  {} tool {}", "+".yellow().bold(), tool.bold());
+            }
+            for tool in &diff.removed_tools {
+                println!("  {} tool {}", "-".yellow(), tool);
+            }
+            if !diff.has_changes() {
+                println!("  {}", "No changes".dimmed());
+            }
+
+            println!(
+                "\n{} new, {} resolved threats; {} tools added, {} removed",
+                diff.new_threats.len().to_string().red(),
+                diff.resolved_threats.len().to_string().green(),
+                diff.added_tools.len(),
+                diff.removed_tools.len()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Build a server config from a command line such as `npx -y some-server`.
+fn server_from_command(command: &str) -> Result<ServerConfig> {
+    let parts: Vec<String> = shell_words::split(command)
+        .map_err(|e| error::Error::Other(format!("Invalid server command: {}", e)))?;
+    let (program, args) = parts
+        .split_first()
+        .ok_or_else(|| error::Error::Other("Empty server command".to_string()))?;
+    Ok(
+        ServerConfig::new(proxy::interceptor::default_server_name(program), program)
+            .with_args(args.to_vec()),
+    )
+}
+
+async fn cmd_bench(
+    server: String,
+    requests: usize,
+    warmup: usize,
+    timeout: u64,
+    output: OutputFormat,
+) -> Result<()> {
+    if !matches!(output, OutputFormat::Table | OutputFormat::Json) {
+        return Err(error::Error::Other(
+            "bench supports table and json output".to_string(),
+        ));
+    }
+
+    // A discovered server name takes precedence over a command line
+    let config = match discover_all()?.into_iter().find(|s| s.name == server) {
+        Some(config) => config,
+        None => server_from_command(&server)?,
+    };
+
+    let scanner = Scanner::new().with_timeout(Duration::from_secs(timeout));
+    let bench = match scanner.bench(&config, requests, warmup).await {
+        Ok(bench) => bench,
+        Err(e) => {
+            eprintln!("{} {}: {}", "FAIL".red().bold(), server, e);
+            telemetry::exit(1);
+        }
+    };
+
+    match output {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&bench)?),
+        _ => {
+            println!(
+                "{} {} ({} requests, {} warmup)",
+                "tools/list".bold(),
+                bench.server,
+                bench.requests,
+                warmup
+            );
+            println!("  Initialization: {:>9.2} ms", bench.init_ms);
+            println!("  min:            {:>9.2} ms", bench.min_ms);
+            println!("  p50:            {:>9.2} ms", bench.p50_ms);
+            println!("  p90:            {:>9.2} ms", bench.p90_ms);
+            println!("  p99:            {:>9.2} ms", bench.p99_ms);
+            println!("  max:            {:>9.2} ms", bench.max_ms);
+        }
+    }
+    Ok(())
+}
+
+async fn cmd_server_info(server: String, timeout: u64, output: OutputFormat) -> Result<()> {
+    if !matches!(output, OutputFormat::Table | OutputFormat::Json) {
+        return Err(error::Error::Other(
+            "server-info supports table and json output".to_string(),
+        ));
+    }
+
+    // A discovered server name takes precedence over a command line
+    let config = match discover_all()?.into_iter().find(|s| s.name == server) {
+        Some(config) => config,
+        None => server_from_command(&server)?,
+    };
+
+    let scanner = Scanner::new().with_timeout(Duration::from_secs(timeout));
+    let info = match scanner.inspect(&config).await {
+        Ok(info) => info,
+        Err(e) => {
+            eprintln!("{} {}: {}", "FAIL".red().bold(), server, e);
+            telemetry::exit(1);
+        }
+    };
+
+    if let OutputFormat::Json = output {
+        println!("{}", serde_json::to_string_pretty(&info)?);
+        return Ok(());
+    }
+
+    println!(
+        "{} {} {}",
+        info.server.bold(),
+        info.server_name,
+        info.server_version.dimmed()
+    );
+    println!("  Protocol version: {}", info.protocol_version);
+    let capabilities: Vec<String> = info
+        .capabilities
+        .as_object()
+        .map(|caps| {
+            caps.iter()
+                .map(|(name, value)| match value.as_object() {
+                    Some(options) if !options.is_empty() => {
+                        format!("{} {}", name, value)
+                    }
+                    _ => name.clone(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    println!(
+        "  Capabilities:     {}",
+        if capabilities.is_empty() {
+            "none".to_string()
+        } else {
+            capabilities.join(", ")
+        }
+    );
+    if let Some(instructions) = &info.instructions {
+        println!("  Instructions:     {}", instructions);
+    }
+
+    println!("\n{} ({})", "Tools".bold(), info.tools.len());
+    for tool in &info.tools {
+        println!("\n  {}", tool.name.cyan().bold());
+        if let Some(description) = &tool.description {
+            println!("  {}", description.dimmed());
+        }
+        print_schema_table(&tool.input_schema);
+    }
+
+    println!("\n{} ({})", "Resources".bold(), info.resources.len());
+    for resource in &info.resources {
+        println!(
+            "  {}  {}  {}",
+            resource.name.bold(),
+            resource.uri,
+            resource.mime_type.as_deref().unwrap_or("-").dimmed()
+        );
+        if let Some(description) = &resource.description {
+            println!("    {}", description.dimmed());
+        }
+    }
+
+    println!("\n{} ({})", "Prompts".bold(), info.prompts.len());
+    for prompt in &info.prompts {
+        let arguments: Vec<String> = prompt
+            .arguments
+            .iter()
+            .map(|a| {
+                if a.required {
+                    a.name.clone()
+                } else {
+                    format!("{}?", a.name)
+                }
+            })
+            .collect();
+        println!("  {}({})", prompt.name.bold(), arguments.join(", "));
+        if let Some(description) = &prompt.description {
+            println!("    {}", description.dimmed());
+        }
+    }
+    Ok(())
+}
+
+/// Print the properties of a tool's input schema as a table.
+fn print_schema_table(schema: &serde_json::Value) {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+    let rows: Vec<[String; 5]> = schema
+        .get("properties")
+        .and_then(|p| p.as_object())
+        .map(|properties| {
+            properties
+                .iter()
+                .map(|(name, property)| {
+                    let kind = match property.get("type") {
+                        Some(serde_json::Value::String(kind)) => kind.clone(),
+                        Some(kind) => kind.to_string(),
+                        None => "-".to_string(),
+                    };
+                    [
+                        name.clone(),
+                        kind,
+                        if required.contains(&name.as_str()) {
+                            "yes".to_string()
+                        } else {
+                            "no".to_string()
+                        },
+                        property
+                            .get("default")
+                            .map_or_else(|| "-".to_string(), |d| d.to_string()),
+                        property
+                            .get("description")
+                            .and_then(|d| d.as_str())
+                            .unwrap_or("")
+                            .to_string(),
+                    ]
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if rows.is_empty() {
+        println!("    {}", "No parameters".dimmed());
+        return;
+    }
+
+    let headers = ["PARAMETER", "TYPE", "REQUIRED", "DEFAULT", "DESCRIPTION"];
+    let widths: Vec<usize> = (0..4)
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([headers[i].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let line = |cells: [&str; 5]| {
+        format!(
+            "    {:<w0$}  {:<w1$}  {:<w2$}  {:<w3$}  {}",
+            cells[0],
+            cells[1],
+            cells[2],
+            cells[3],
+            cells[4],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3]
+        )
+    };
+    println!("{}", line(headers).bold());
+    for row in &rows {
+        println!("{}", line([&row[0], &row[1], &row[2], &row[3], &row[4]]));
+    }
+}
+
+fn cmd_report(
+    server: Option<String>,
+    file: std::path::PathBuf,
+    since: Option<DateTime<Utc>>,
+) -> Result<()> {
+    let pool = db::create_pool(&db::default_db_path()?)?;
+    let history = db::ScanResultStore::new(pool).history(server.as_deref(), since)?;
+
+    if history.is_empty() {
+        eprintln!(
+            "{}",
+            "No matching scans in the history. Run `mcp-scanner scan` first.".yellow()
+        );
+    }
+
+    let html = scanner::html_report::html_report(&history, Utc::now());
+    std::fs::write(&file, html)?;
+
+    println!(
+        "{}",
+        format!(
+            "Wrote report covering {} scan(s) to {}",
+            history.len(),
+            file.display()
+        )
+        .green()
+    );
+    Ok(())
+}
+
+async fn cmd_replay(
+    id: Option<i64>,
+    server: Option<String>,
+    last: Option<usize>,
+    command: Option<String>,
+) -> Result<()> {
+    let pool = db::create_pool(&db::default_db_path()?)?;
+    let log = db::AuditLog::new(pool);
+
+    let mut entries = match id {
+        Some(id) => vec![log.get(id)?],
+        None => log.search(server.as_deref(), None, false, last.unwrap_or(1))?,
+    };
+    // Replay oldest first, in the order the calls were made
+    entries.reverse();
+
+    if entries.is_empty() {
+        println!("{}", "No matching audit entries.".yellow());
+        return Ok(());
+    }
+
+    let override_server = command
+        .map(|cmd| -> Result<ServerConfig> {
+            let parts = shell_words::split(&cmd)
+                .map_err(|e| error::Error::Other(format!("Invalid server command: {}", e)))?;
+            let (program, args) = parts
+                .split_first()
+                .ok_or_else(|| error::Error::Other("Empty server command".to_string()))?;
+            Ok(ServerConfig::new("replay", program).with_args(args.to_vec()))
+        })
+        .transpose()?;
+    let discovered = if override_server.is_none() {
+        discover_all()?
+    } else {
+        Vec::new()
+    };
+
+    for entry in &entries {
+        println!(
+            "{} #{} {} on {} ({})",
+            "▶".cyan(),
+            entry.id,
+            entry.tool_name.bold(),
+            entry.server_name,
+            entry.timestamp.to_rfc3339().dimmed()
+        );
+
+        if entry.blocked {
+            println!(
+                "  {}\n",
+                format!(
+                    "Skipped: originally blocked ({})",
+                    entry.block_reason.as_deref().unwrap_or("no reason")
+                )
+                .yellow()
+            );
+            continue;
+        }
+
+        let target = match &override_server {
+            Some(server) => server,
+            None => match discovered.iter().find(|s| s.name == entry.server_name) {
+                Some(server) => server,
+                None => {
+                    println!(
+                        "  {}\n",
+                        format!(
+                            "Skipped: no discovered server named '{}'; pass --command",
+                            entry.server_name
+                        )
+                        .red()
+                    );
+                    continue;
+                }
+            },
+        };
+
+        match replay_call(target, entry).await {
+            Ok(result) => print_replay_diff(entry.result.as_ref(), &result),
+            Err(e) => println!("  {}", format!("Replay failed: {}", e).red()),
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Start `server` and send the logged `tools/call`.
+async fn replay_call(server: &ServerConfig, entry: &db::AuditEntry) -> Result<serde_json::Value> {
+    if !matches!(server.transport, discovery::TransportType::Stdio) {
+        return Err(error::Error::Other(
+            "Replay only supports stdio servers".to_string(),
+        ));
+    }
+
+    let arguments = entry
+        .tool_args
+        .clone()
+        .unwrap_or_else(|| serde_json::json!({}));
+    Scanner::new()
+        .with_timeout(Duration::from_secs(30))
+        .call_tool(server, &entry.tool_name, arguments)
+        .await
+}
+
+/// Print a line diff of the pretty-printed original and replayed results.
+fn print_replay_diff(original: Option<&serde_json::Value>, replayed: &serde_json::Value) {
+    use similar::{ChangeTag, TextDiff};
+
+    let Some(original) = original else {
+        println!(
+            "  {}",
+            "No stored result to compare; replayed result:".dimmed()
+        );
+        for line in to_pretty_lines(replayed) {
+            println!("  {}", line);
+        }
+        return;
+    };
+
+    if original == replayed {
+        println!("  {}", "✓ Result unchanged".green());
+        return;
+    }
+
+    println!("  {}", "Result changed:".yellow());
+    let old = serde_json::to_string_pretty(original).unwrap_or_default();
+    let new = serde_json::to_string_pretty(replayed).unwrap_or_default();
+    for change in TextDiff::from_lines(&old, &new).iter_all_changes() {
+        let line = change.value().trim_end_matches('\n');
+        match change.tag() {
+            ChangeTag::Equal => println!("    {}", line.dimmed()),
+            ChangeTag::Delete => println!("  {} {}", "-".red(), line.red()),
+            ChangeTag::Insert => println!("  {} {}", "+".green(), line.green()),
+        }
+    }
+}
+
+fn to_pretty_lines(value: &serde_json::Value) -> Vec<String> {
+    serde_json::to_string_pretty(value)
+        .unwrap_or_default()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,63 @@
+//! OpenTelemetry export of the scanner's tracing spans.
+//!
+//! Spans are sent over OTLP/HTTP when `--trace` is passed or
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` is set. The exporter reads the standard
+//! `OTEL_EXPORTER_OTLP_*` variables; without an endpoint it sends to a
+//! collector on `http://localhost:4318`.
+
+use mcp_guard_core::error::{Error, Result};
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::SpanExporter;
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use std::sync::OnceLock;
+
+const SERVICE_NAME: &str = "mcp-scanner";
+
+static PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
+
+/// Whether spans should be exported.
+pub fn enabled(trace: bool) -> bool {
+    trace || std::env::var_os("OTEL_EXPORTER_OTLP_ENDPOINT").is_some()
+}
+
+/// Start the OTLP exporter and return a tracer for the tracing layer.
+/// Spans are batched in the background until [`shutdown`].
+pub fn init_tracer() -> Result<SdkTracer> {
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .build()
+        .map_err(|e| Error::Other(format!("Failed to start OTLP exporter: {}", e)))?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+        .build();
+    let tracer = provider.tracer(SERVICE_NAME);
+    let _ = PROVIDER.set(provider);
+    Ok(tracer)
+}
+
+/// Send any spans not yet exported. Does nothing if export isn't enabled.
+pub fn shutdown() {
+    if let Some(provider) = PROVIDER.get() {
+        if let Err(e) = provider.shutdown() {
+            eprintln!("Failed to export traces: {}", e);
+        }
+    }
+}
+
+/// Calls [`shutdown`] when dropped, so spans are exported however `main`
+/// returns.
+pub struct ShutdownOnDrop;
+
+impl Drop for ShutdownOnDrop {
+    fn drop(&mut self) {
+        shutdown();
+    }
+}
+
+/// Exit with `code` once pending spans are exported.
+pub fn exit(code: i32) -> ! {
+    shutdown();
+    std::process::exit(code)
+}
@@ -0,0 +1,258 @@
+//! End-to-end tests of the `mcp-scanner` binary.
+
+use assert_cmd::Command;
+use std::path::PathBuf;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// The binary with its home directory, and so its config and database, in
+/// `home`.
+fn mcp_scanner(home: &tempfile::TempDir) -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_mcp-scanner"));
+    cmd.env("HOME", home.path())
+        .env("USERPROFILE", home.path())
+        .env_remove("NO_COLOR")
+        .env_remove("OTEL_EXPORTER_OTLP_ENDPOINT");
+    cmd
+}
+
+/// `scan --mock` of `mock_server.json`, which has a critical threat and
+/// several lower ones, with extra `args`.
+fn scan_mock(home: &tempfile::TempDir, args: &[&str]) -> Command {
+    let mut cmd = mcp_scanner(home);
+    cmd.arg("scan")
+        .arg("--mock")
+        .arg(fixture("mock_server.json"))
+        .args(args);
+    cmd
+}
+
+#[test]
+fn severity_filter_hides_lower_threats_but_keeps_exit_code() {
+    let home = tempfile::tempdir().unwrap();
+
+    let output = scan_mock(&home, &["--output", "json", "--severity", "high"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let severities: Vec<&str> = results[0]["threats"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["severity"].as_str().unwrap())
+        .collect();
+    assert_eq!(severities, ["critical"]);
+}
+
+#[test]
+fn scan_without_high_threats_exits_zero() {
+    let home = tempfile::tempdir().unwrap();
+
+    let output = mcp_scanner(&home)
+        .arg("scan")
+        .arg("--mock")
+        .arg(fixture("mock_server_low.json"))
+        .args(["--output", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn github_output_annotates_threats_by_severity() {
+    let home = tempfile::tempdir().unwrap();
+
+    let output = scan_mock(&home, &["--output", "github"]).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    assert!(lines.contains(
+        &"::error title=PRIVKEY-EMBEDDED-deploy,file=mock::Private key embedded in tool definition: Tool 'deploy' ships a PEM private key in its definition, where every client and model that lists the tool can read it"
+    ));
+    assert!(lines
+        .iter()
+        .any(|l| l.starts_with("::warning title=PATH-TRAV-SCHEMA-read_file,file=mock::")));
+    assert!(lines
+        .iter()
+        .any(|l| l.starts_with("::notice title=NO-AUTH-LOCAL,file=mock::")));
+    assert!(lines.iter().all(|l| l.starts_with("::")), "{}", stdout);
+}
+
+#[test]
+fn watch_rejects_zero_interval() {
+    let home = tempfile::tempdir().unwrap();
+
+    let output = mcp_scanner(&home)
+        .args(["watch", "--interval", "0"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--interval"));
+}
+
+#[cfg(unix)]
+#[test]
+fn diff_stores_allowlisted_threats_as_suppressed() {
+    let home = tempfile::tempdir().unwrap();
+    let allowlist = home.path().join("allowlist.toml");
+    std::fs::write(
+        &allowlist,
+        "[[ignore]]\nid = \"NO-AUTH-LOCAL\"\nserver = \"manual\"\n",
+    )
+    .unwrap();
+    let server = format!(
+        "sh {}/crates/mcp-guard-core/tests/fixtures/mock_server.sh",
+        env!("CARGO_MANIFEST_DIR")
+    );
+
+    mcp_scanner(&home)
+        .args(["scan", "--diff", "--server", &server, "--allowlist"])
+        .arg(&allowlist)
+        .output()
+        .unwrap();
+
+    let db = rusqlite::Connection::open(home.path().join(".mcp-guard/mcp-guard.db")).unwrap();
+    let json: String = db
+        .query_row("SELECT result_json FROM scan_results", [], |row| row.get(0))
+        .unwrap();
+    let stored: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let no_auth = stored["threats"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|t| t["id"] == "NO-AUTH-LOCAL")
+        .unwrap();
+    assert_eq!(no_auth["suppressed"], true);
+}
+
+#[test]
+fn project_config_only_sets_scan_and_output_keys() {
+    let home = tempfile::tempdir().unwrap();
+    let project = tempfile::tempdir().unwrap();
+    std::fs::write(
+        project.path().join(".mcp-guard.toml"),
+        "[output]\nformat = \"json\"\n\n[scan]\nmin_severity = \"critical\"\n\n[serve]\napi_key_hash = \"x\"\n",
+    )
+    .unwrap();
+
+    let output = scan_mock(&home, &[])
+        .current_dir(project.path())
+        .output()
+        .unwrap();
+
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(results[0]["threats"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|t| t["severity"] == "medium"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("scan.min_severity"), "{}", stderr);
+    assert!(stderr.contains("serve"), "{}", stderr);
+}
+
+#[test]
+fn config_errors_name_the_file_at_fault() {
+    let home = tempfile::tempdir().unwrap();
+    let project = tempfile::tempdir().unwrap();
+    let user_config = home.path().join(".mcp-scanner/config.toml");
+    std::fs::create_dir_all(user_config.parent().unwrap()).unwrap();
+    std::fs::write(&user_config, "[scan]\ntimeout = \"soon\"\n").unwrap();
+    std::fs::write(
+        project.path().join(".mcp-guard.toml"),
+        "[output]\nformat = \"json\"\n",
+    )
+    .unwrap();
+
+    let output = scan_mock(&home, &[])
+        .current_dir(project.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("config.toml"), "{}", stderr);
+    assert!(!stderr.contains(".mcp-guard.toml"), "{}", stderr);
+}
+
+#[test]
+fn no_color_output_has_no_escape_codes() {
+    let home = tempfile::tempdir().unwrap();
+    let has_escapes = |output: &std::process::Output| {
+        output.stdout.contains(&0x1b) || output.stderr.contains(&0x1b)
+    };
+
+    // Colors are forced on so that turning them off is what's tested
+    let colored = scan_mock(&home, &[])
+        .env("CLICOLOR_FORCE", "1")
+        .output()
+        .unwrap();
+    assert!(has_escapes(&colored));
+
+    let plain = scan_mock(&home, &["--no-color"])
+        .env("CLICOLOR_FORCE", "1")
+        .output()
+        .unwrap();
+    assert!(
+        !has_escapes(&plain),
+        "{}",
+        String::from_utf8_lossy(&plain.stdout)
+    );
+
+    for (name, value) in [("NO_COLOR", "1"), ("TERM", "dumb")] {
+        let plain = scan_mock(&home, &[])
+            .env("CLICOLOR_FORCE", "1")
+            .env(name, value)
+            .output()
+            .unwrap();
+        assert!(!has_escapes(&plain), "{}={}", name, value);
+    }
+}
+
+#[test]
+fn init_applies_a_remediation_file() {
+    let home = tempfile::tempdir().unwrap();
+    let remediation = home.path().join("remediation.toml");
+    scan_mock(&home, &["--include-info", "--remediation-file"])
+        .arg(&remediation)
+        .output()
+        .unwrap();
+
+    for added in ["Added 1 proxy rule(s)", "Added 0 proxy rule(s)"] {
+        let output = mcp_scanner(&home)
+            .args(["init", "--from-remediation"])
+            .arg(&remediation)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains(added), "{}", stdout);
+    }
+
+    let config_dir = home.path().join(".mcp-scanner");
+    let config = std::fs::read_to_string(config_dir.join("config.toml")).unwrap();
+    assert!(config.contains("allowlist = "), "{}", config);
+    assert!(config.contains("rules_path = "), "{}", config);
+
+    let rules = mcp_guard_core::proxy::ProxyConfig::load(&config_dir.join("rules.toml")).unwrap();
+    assert_eq!(rules.rules.len(), 1);
+    assert!(!rules.rules[0].applies_to("another-server"));
+
+    // The suppressions now apply to every scan
+    let output = scan_mock(&home, &["--include-info", "--output", "json"])
+        .output()
+        .unwrap();
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(!results[0]["threats"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|t| t["id"] == "NO-AUTH-LOCAL"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("1 threats suppressed"), "{}", stderr);
+}